@@ -0,0 +1,22 @@
+//! Feeds malformed configuration files and option values to the parsers of the configuration,
+//! which must reject them without panicking.
+
+#![no_main]
+
+use cli_pong::config::ConfigFile;
+use cli_pong::input::parse_action_binding;
+use cli_pong::state::FieldSize;
+use libfuzzer_sys::fuzz_target;
+use std::path::Path;
+
+fuzz_target!(|text: &str| {
+    if let Ok(file) = ConfigFile::parse(Path::new("fuzz.toml"), text) {
+        let _ = file.global_args();
+        for profile in file.profiles() {
+            let _ = file.profile_args(profile);
+        }
+    }
+
+    let _ = parse_action_binding(text);
+    let _ = text.parse::<FieldSize>();
+});
@@ -0,0 +1,13 @@
+//! Feeds malformed replay files to the replay parser, which must reject them without panicking.
+
+#![no_main]
+
+use cli_pong::replay::Replay;
+use libfuzzer_sys::fuzz_target;
+use std::path::Path;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(replay) = Replay::read(data, Path::new("fuzz.replay")) {
+        let _ = replay.option("seed");
+    }
+});
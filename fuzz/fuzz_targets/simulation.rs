@@ -0,0 +1,139 @@
+//! Plays random key sequences with random frame times against a game with random rules.
+//! The game must neither panic nor let the ball or the paddles leave the field.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use cli_pong::mode::GameMode;
+use cli_pong::mutator::{Mutator, Mutators};
+use cli_pong::state::{step, Arena, GameConfig, GameState, Obstacle, PortalPair};
+use cli_pong::stats::PlayerSide;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use libfuzzer_sys::fuzz_target;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The keys of the default bindings the fuzzer presses, one per bit of a `Frame::keys`.
+const KEYS: [(KeyCode, KeyModifiers); 13] = [
+    (KeyCode::Char('w'), KeyModifiers::NONE),
+    (KeyCode::Char('s'), KeyModifiers::NONE),
+    (KeyCode::Up, KeyModifiers::NONE),
+    (KeyCode::Down, KeyModifiers::NONE),
+    (KeyCode::Char('q'), KeyModifiers::NONE),
+    (KeyCode::Char('e'), KeyModifiers::NONE),
+    (KeyCode::Left, KeyModifiers::NONE),
+    (KeyCode::Right, KeyModifiers::NONE),
+    (KeyCode::Char('r'), KeyModifiers::NONE),
+    (KeyCode::Char('r'), KeyModifiers::CONTROL),
+    (KeyCode::Char('b'), KeyModifiers::NONE),
+    (KeyCode::Char(' '), KeyModifiers::NONE),
+    (KeyCode::F(1), KeyModifiers::NONE),
+];
+
+#[derive(Debug, Arbitrary)]
+struct Game {
+    seed: u64,
+    width: u8,
+    height: u8,
+    extend_up: u8,
+    extend_down: u8,
+    restitution: u8,
+    spin: bool,
+    jitter: u8,
+    gravity: u8,
+    obstacles: bool,
+    portals: bool,
+    shrink_paddles: bool,
+    turn_based: bool,
+    mutators: u8,
+    frames: Vec<Frame>,
+}
+
+/// A mode that places any combination of the features of the field modes.
+#[derive(Debug, Copy, Clone)]
+struct Field {
+    obstacles: bool,
+    portals: bool,
+}
+
+impl GameMode for Field {
+    fn name(&self) -> &str {
+        "field"
+    }
+
+    fn arena(&self, config: &GameConfig) -> Arena {
+        let mut arena = Arena::default();
+        if self.obstacles {
+            arena.obstacles = Obstacle::mid_field(config.width, config.height);
+        }
+        if self.portals {
+            arena.portals = Some(PortalPair::mid_field(config.width, config.height));
+        }
+        arena
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+struct Frame {
+    keys: u16,
+    dt_millis: u8,
+}
+
+fn config(game: &Game) -> GameConfig {
+    let mutators = [
+        Mutator::NoWalls,
+        Mutator::BigBall,
+        Mutator::FastServe,
+        Mutator::InvertP2,
+        Mutator::Mirror,
+        Mutator::Chaos,
+    ]
+    .into_iter()
+    .enumerate()
+    .filter(|(bit, _)| game.mutators & (1 << bit) != 0)
+    .map(|(_, mutator)| mutator);
+
+    GameConfig::builder()
+        .seed(Some(game.seed))
+        .width(10 + usize::from(game.width))
+        .height(4 + usize::from(game.height % 64))
+        .extend_player_height_up(usize::from(game.extend_up % 4))
+        .extend_player_height_down(usize::from(game.extend_down % 4))
+        .restitution(0.5 + f64::from(game.restitution) / 255.0)
+        .spin(game.spin)
+        .jitter(f64::from(game.jitter) / 255.0)
+        .gravity(f64::from(game.gravity % 64))
+        .shrink_paddles(game.shrink_paddles)
+        .turn_based(game.turn_based)
+        .practice(true)
+        .mutators(Mutators::new(mutators))
+        .build()
+}
+
+fn pressed_keys(keys: u16) -> HashMap<KeyCode, KeyEvent> {
+    KEYS.iter()
+        .enumerate()
+        .filter(|(bit, _)| keys & (1 << bit) != 0)
+        .map(|(_, &(code, modifiers))| (code, KeyEvent::new(code, modifiers)))
+        .collect()
+}
+
+fuzz_target!(|game: Game| {
+    let config = config(&game);
+    let (width, height) = (config.width as f64, config.height as f64);
+    let field = Field {
+        obstacles: game.obstacles,
+        portals: game.portals,
+    };
+    let mut state = GameState::with_mode(config, Box::new(field));
+
+    for frame in game.frames.iter().take(2000) {
+        let dt = Duration::from_millis(u64::from(frame.dt_millis));
+        state = step(&state, pressed_keys(frame.keys), dt).0;
+
+        let observation = state.observation(PlayerSide::Left);
+        assert!((0.0..=width).contains(&observation.ball_x));
+        assert!((0.0..=height).contains(&observation.ball_y));
+        assert!((0.0..=height).contains(&observation.paddle_y));
+    }
+});
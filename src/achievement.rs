@@ -0,0 +1,174 @@
+use crate::observer::GameObserver;
+use crate::skin::Skin;
+use crate::state::{Controller, GameEvent, GameState};
+use crate::stats::PlayerSide;
+use crate::storage::{Collection, Storage};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io;
+
+/// The number of returns in a single rally that unlocks `Achievement::LongRally`.
+pub const LONG_RALLY_HITS: u64 = 20;
+
+/// The achievements player1 can unlock by playing with the keyboard.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Achievement {
+    /// Win a game against the computer.
+    FirstWin,
+    /// Win a game against the computer without conceding a goal.
+    Shutout,
+    /// Play a rally of at least `LONG_RALLY_HITS` returns.
+    LongRally,
+}
+
+impl Achievement {
+    /// All achievements.
+    pub const ALL: [Achievement; 3] = [
+        Achievement::FirstWin,
+        Achievement::Shutout,
+        Achievement::LongRally,
+    ];
+
+    /// Returns the name the achievement is shown with.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Achievement::FirstWin => "First win",
+            Achievement::Shutout => "Shutout",
+            Achievement::LongRally => "Long rally",
+        }
+    }
+
+    /// Returns what has to be done to unlock the achievement.
+    pub fn description(&self) -> String {
+        match self {
+            Achievement::FirstWin => "win a game against the computer".to_string(),
+            Achievement::Shutout => {
+                "win a game against the computer without conceding a goal".to_string()
+            }
+            Achievement::LongRally => format!("play a rally of {LONG_RALLY_HITS} returns"),
+        }
+    }
+}
+
+/// The achievements collection as it is stored: the achievements unlocked so far.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Achievements {
+    pub unlocked: BTreeSet<Achievement>,
+}
+
+impl Achievements {
+    /// Reads the unlocked achievements from the given storage.
+    pub fn load(storage: &Storage) -> io::Result<Self> {
+        storage
+            .load(Collection::Achievements)
+            .map(|loaded| loaded.value)
+    }
+
+    /// Returns `true` if the given achievement is unlocked.
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+}
+
+/// # Explanation
+/// The achievement tracker is the observer that watches a game for the achievements player1
+/// earns. Only a player1 on the keyboard earns achievements, so games of the computer against
+/// itself unlock nothing. The achievements are written to the storage when the game ends, and the
+/// new ones are announced on the terminal.
+pub struct AchievementTracker {
+    storage: Storage,
+    earned: BTreeSet<Achievement>,
+    /// The returns of the current rally.
+    rally_hits: u64,
+}
+
+impl AchievementTracker {
+    /// Starts to watch a game for the achievements that are kept in the given storage.
+    pub fn new(storage: Storage) -> Self {
+        AchievementTracker {
+            storage,
+            earned: BTreeSet::new(),
+            rally_hits: 0,
+        }
+    }
+
+    /// Returns the achievements earned in the game so far, including the ones that were already
+    /// unlocked before.
+    pub fn earned(&self) -> &BTreeSet<Achievement> {
+        &self.earned
+    }
+
+    /// Writes the earned achievements to the storage.
+    ///
+    /// # Returns
+    /// The achievements that were not unlocked before, in the order of `Achievement::ALL`.
+    pub fn save(&self) -> io::Result<Vec<Achievement>> {
+        let mut new = Vec::new();
+        self.storage.update(
+            Collection::Achievements,
+            |achievements: &mut Achievements| {
+                for &achievement in &self.earned {
+                    if achievements.unlocked.insert(achievement) {
+                        new.push(achievement);
+                    }
+                }
+            },
+        )?;
+
+        Ok(new)
+    }
+}
+
+impl GameObserver for AchievementTracker {
+    fn on_event(&mut self, state: &GameState, event: GameEvent) -> io::Result<()> {
+        if !matches!(state.controller(PlayerSide::Left), Controller::Keyboard(_)) {
+            return Ok(());
+        }
+        let against_computer = matches!(state.controller(PlayerSide::Right), Controller::Ai(_));
+
+        match event {
+            GameEvent::Hit(_) => {
+                self.rally_hits += 1;
+                if self.rally_hits >= LONG_RALLY_HITS {
+                    self.earned.insert(Achievement::LongRally);
+                }
+            }
+            GameEvent::Goal(_)
+            | GameEvent::PointRestarted
+            | GameEvent::Rewound
+            | GameEvent::NewGame => self.rally_hits = 0,
+            GameEvent::Won(PlayerSide::Left) if against_computer => {
+                self.earned.insert(Achievement::FirstWin);
+                if state.scores().1 == 0 {
+                    self.earned.insert(Achievement::Shutout);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn on_finish(&mut self) -> io::Result<()> {
+        // an achievement that can not be saved must not turn the end of the game into an error
+        let Ok(new) = self.save() else {
+            return Ok(());
+        };
+        for achievement in new {
+            println!(
+                "Achievement unlocked: {} ({})",
+                achievement.title(),
+                achievement.description()
+            );
+            if let Some(skin) = Skin::unlocked_by(achievement) {
+                println!(
+                    "New skin: {}, use it with `--skin {}`",
+                    skin.name, skin.name
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
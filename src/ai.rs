@@ -0,0 +1,434 @@
+use crate::state::{GameState, PaddleCommand};
+use crate::stats::PlayerSide;
+use crate::storage::write_atomically;
+use clap::ValueEnum;
+use rand::rngs::StdRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use std::{fs, io};
+
+/// The number of buckets the distance between ball and paddle is divided into.
+pub const DISTANCE_BUCKETS: usize = 3;
+
+/// The time step the flight of the ball is followed with for the intercept of an `Observation`.
+pub const PREDICTION_STEP: Duration = Duration::from_millis(20);
+
+/// The longest flight of the ball the predictions follow ahead.
+pub const LOOKAHEAD_TIME: Duration = Duration::from_secs(10);
+
+/// The distance in cells to the target at which the built-in strategies stop moving.
+const STRATEGY_TOLERANCE: f64 = 0.25;
+
+/// The number of buckets for the vertical direction of the ball (upwards and downwards).
+pub const DIRECTION_BUCKETS: usize = 2;
+
+/// The view of the game an AI bases its decisions on.
+#[derive(Debug, Copy, Clone)]
+pub struct Observation {
+    pub paddle_x: f64,
+    pub paddle_y: f64,
+    pub ball_x: f64,
+    pub ball_y: f64,
+    pub ball_vx: f64,
+    pub ball_vy: f64,
+    /// The vertical acceleration that curves the flight of a spinning ball.
+    pub ball_ay: f64,
+    /// The height at which the ball will cross the line of the paddle, followed with the physics
+    /// of the game, or `None` while the ball moves away or does not get there within
+    /// `LOOKAHEAD_TIME`.
+    pub intercept_y: Option<f64>,
+    pub field_width: f64,
+    pub field_height: f64,
+}
+
+/// # Explanation
+/// A strategy decides how a computer controlled paddle moves. Custom bots implement this trait and
+/// are added to a `StrategyRegistry`, or handed to `GameState::set_ai_strategy` directly.
+///
+/// # Remarks
+/// The game state is cloned for replays and rewinds, so strategies have to be `Clone`. Anything a
+/// strategy remembers between updates is cloned with it.
+pub trait AiStrategy: AiStrategyClone {
+    /// Decides how the paddle should move in the current update.
+    ///
+    /// # Arguments
+    /// * `state` - The `GameState` before the update.
+    /// * `side` - The `PlayerSide` of the paddle the strategy moves.
+    /// * `dt` - The `Duration` since the last update.
+    fn decide(&mut self, state: &GameState, side: PlayerSide, dt: Duration) -> PaddleCommand;
+}
+
+/// Clones boxed strategies. It is implemented for every strategy that is `Clone`.
+pub trait AiStrategyClone {
+    fn clone_box(&self) -> Box<dyn AiStrategy>;
+}
+
+impl<T: AiStrategy + Clone + 'static> AiStrategyClone for T {
+    fn clone_box(&self) -> Box<dyn AiStrategy> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn AiStrategy> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Creates a new instance of a registered strategy.
+type StrategyFactory = Box<dyn Fn() -> Box<dyn AiStrategy>>;
+
+/// # Explanation
+/// The strategies that can be chosen by name, e.g. with the `--ai-strategy` option. The default
+/// registry contains the built-in strategies, further ones are added with `register`.
+pub struct StrategyRegistry {
+    strategies: Vec<(String, StrategyFactory)>,
+}
+
+impl Default for StrategyRegistry {
+    fn default() -> Self {
+        let mut registry = StrategyRegistry {
+            strategies: Vec::new(),
+        };
+        registry.register("follow-ball", || Box::new(FollowBall));
+        registry.register("predictive", || Box::new(Predictive));
+
+        registry
+    }
+}
+
+impl StrategyRegistry {
+    /// Adds a strategy, replacing a registered strategy of the same name.
+    ///
+    /// # Arguments
+    /// * `name` - The name the strategy is chosen by.
+    /// * `create` - Creates a new instance of the strategy for every paddle it moves.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        create: impl Fn() -> Box<dyn AiStrategy> + 'static,
+    ) {
+        let name = name.into();
+        self.strategies
+            .retain(|(registered, _)| *registered != name);
+        self.strategies.push((name, Box::new(create)));
+    }
+
+    /// Creates a new instance of the strategy with the given name, or `None` if there is none.
+    pub fn create(&self, name: &str) -> Option<Box<dyn AiStrategy>> {
+        self.strategies
+            .iter()
+            .find(|(registered, _)| registered == name)
+            .map(|(_, create)| create())
+    }
+
+    /// Returns the names of all registered strategies in the order they were registered.
+    pub fn names(&self) -> Vec<&str> {
+        self.strategies
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+/// # Explanation
+/// A simple strategy that keeps the paddle at the height of the ball while the ball comes closer
+/// and waits while it moves away.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct FollowBall;
+
+impl AiStrategy for FollowBall {
+    fn decide(&mut self, state: &GameState, side: PlayerSide, _dt: Duration) -> PaddleCommand {
+        let observation = state.observation(side);
+        let heading_towards_paddle =
+            (observation.ball_x - observation.paddle_x) * observation.ball_vx < 0.0;
+
+        match heading_towards_paddle {
+            true => move_towards(observation.paddle_y, observation.ball_y),
+            false => PaddleCommand::Stay,
+        }
+    }
+}
+
+/// # Explanation
+/// A strategy that simulates the flight of the ball with the physics of the game, including the
+/// bounces at the walls, the spin and the speed-up, and waits at the point where the ball will
+/// cross the line of the paddle. While the ball moves away, the paddle returns to the center.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Predictive;
+
+impl AiStrategy for Predictive {
+    fn decide(&mut self, state: &GameState, side: PlayerSide, dt: Duration) -> PaddleCommand {
+        let observation = state.observation(side);
+        let heading_towards_paddle =
+            (observation.ball_x - observation.paddle_x) * observation.ball_vx < 0.0;
+        let center = observation.field_height / 2.0;
+
+        let target_y = match heading_towards_paddle {
+            true => state
+                .ball()
+                .predict_crossing(
+                    state.config(),
+                    state.arena(),
+                    observation.paddle_x,
+                    dt,
+                    LOOKAHEAD_TIME,
+                )
+                .map_or(center, |crossing| crossing.y()),
+            false => center,
+        };
+        move_towards(observation.paddle_y, target_y)
+    }
+}
+
+/// Moves the paddle to the target height, stopping there instead of oscillating around it.
+fn move_towards(paddle_y: f64, target_y: f64) -> PaddleCommand {
+    match (target_y - paddle_y).abs() > STRATEGY_TOLERANCE {
+        true => PaddleCommand::MoveTo(target_y),
+        false => PaddleCommand::Stay,
+    }
+}
+
+/// The named playing styles of the computer controlled player.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum AiStyle {
+    /// Solid all-round play.
+    Balanced,
+    /// Chases every ball early, but does not recover to the center.
+    Aggressive,
+    /// Always returns to the center and waits for the ball.
+    Defensive,
+    /// Reacts late and often misjudges the ball.
+    Rookie,
+}
+
+impl AiStyle {
+    /// Returns the parameter set that defines this style.
+    pub fn profile(self) -> AiProfile {
+        match self {
+            AiStyle::Balanced => AiProfile {
+                reaction_time: 0.15,
+                reaction_distance: 0.7,
+                aim_error: 1.0,
+                predicts_bounces: true,
+                recovers_to_center: true,
+                tolerance: 0.5,
+                target_offsets: [[0.0; DISTANCE_BUCKETS]; DIRECTION_BUCKETS],
+                aim_error_distribution: AimErrorDistribution::Uniform,
+            },
+            AiStyle::Aggressive => AiProfile {
+                reaction_time: 0.1,
+                reaction_distance: 1.0,
+                aim_error: 1.5,
+                predicts_bounces: true,
+                recovers_to_center: false,
+                tolerance: 0.3,
+                target_offsets: [[0.0; DISTANCE_BUCKETS]; DIRECTION_BUCKETS],
+                aim_error_distribution: AimErrorDistribution::Uniform,
+            },
+            AiStyle::Defensive => AiProfile {
+                reaction_time: 0.2,
+                reaction_distance: 0.5,
+                aim_error: 0.5,
+                predicts_bounces: true,
+                recovers_to_center: true,
+                tolerance: 0.5,
+                target_offsets: [[0.0; DISTANCE_BUCKETS]; DIRECTION_BUCKETS],
+                aim_error_distribution: AimErrorDistribution::Uniform,
+            },
+            AiStyle::Rookie => AiProfile {
+                reaction_time: 0.4,
+                reaction_distance: 0.4,
+                aim_error: 2.5,
+                predicts_bounces: false,
+                recovers_to_center: false,
+                tolerance: 1.0,
+                target_offsets: [[0.0; DISTANCE_BUCKETS]; DIRECTION_BUCKETS],
+                aim_error_distribution: AimErrorDistribution::Uniform,
+            },
+        }
+    }
+}
+
+/// How the error of the position an AI aims for is distributed.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AimErrorDistribution {
+    /// Evenly between `-aim_error` and `aim_error`.
+    #[default]
+    Uniform,
+    /// Normally distributed with a standard deviation of `aim_error`. Most aims are close to the
+    /// target, while a few miss it by far, which looks like the misses of a human player.
+    Gaussian,
+}
+
+impl AimErrorDistribution {
+    /// Draws an error of the given size from the distribution.
+    fn sample(self, size: f64, rng: &mut impl Rng) -> f64 {
+        if size <= 0.0 {
+            return 0.0;
+        }
+
+        match self {
+            AimErrorDistribution::Uniform => rng.gen_range(-size..size),
+            AimErrorDistribution::Gaussian => {
+                // the Box-Muller transform turns two uniform samples into a normally distributed one
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen();
+                size * (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+            }
+        }
+    }
+}
+
+/// The parameters that define how an AI controlled player behaves.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct AiProfile {
+    /// Seconds between two looks at the ball.
+    pub reaction_time: f64,
+    /// Share of the field width, measured from the own paddle, in which the AI follows the ball.
+    pub reaction_distance: f64,
+    /// Maximum deviation in cells of the position the AI aims for, or its standard deviation if
+    /// the error is gaussian.
+    pub aim_error: f64,
+    /// How the deviation of the aimed position is distributed.
+    #[serde(default)]
+    pub aim_error_distribution: AimErrorDistribution,
+    /// Whether the AI predicts the wall bounces of the ball or simply follows it.
+    pub predicts_bounces: bool,
+    /// Whether the AI moves back to the center while the ball moves away.
+    pub recovers_to_center: bool,
+    /// Distance in cells to the target at which the AI stops moving.
+    pub tolerance: f64,
+    /// Offsets in cells added to the aimed position, indexed by the trajectory bucket of the ball.
+    /// Profiles that were not fitted to a player have none.
+    #[serde(default)]
+    pub target_offsets: [[f64; DISTANCE_BUCKETS]; DIRECTION_BUCKETS],
+}
+
+impl AiProfile {
+    /// Returns the profile with a humanized reaction and aim: the AI looks at the ball only every
+    /// `reaction_time` and misses the target by a gaussian error of the given standard deviation.
+    ///
+    /// # Arguments
+    /// * `reaction_time` - The time between two looks at the ball, or `None` to keep the current one.
+    /// * `error_sigma` - The standard deviation in cells of the aim, or `None` to keep the current aim.
+    pub fn humanized(mut self, reaction_time: Option<Duration>, error_sigma: Option<f64>) -> Self {
+        if let Some(reaction_time) = reaction_time {
+            self.reaction_time = reaction_time.as_secs_f64();
+        }
+        if let Some(error_sigma) = error_sigma {
+            self.aim_error = error_sigma;
+            self.aim_error_distribution = AimErrorDistribution::Gaussian;
+        }
+
+        self
+    }
+
+    /// Reads a profile from a TOML file.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {error}", path.display()),
+            )
+        })
+    }
+
+    /// Writes the profile to a TOML file.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        write_atomically(path, &content)
+    }
+}
+
+/// # Explanation
+/// The AI controller looks at the ball every `reaction_time` seconds and picks a target height.
+/// In between it moves its paddle towards the last chosen target.
+#[derive(Debug, Clone)]
+pub struct AiController {
+    profile: AiProfile,
+    target_y: Option<f64>,
+    time_until_reaction: f64,
+    rng: StdRng,
+}
+
+impl AiController {
+    pub fn new(profile: AiProfile, rng: StdRng) -> Self {
+        AiController {
+            profile,
+            target_y: None,
+            time_until_reaction: 0.0,
+            rng,
+        }
+    }
+
+    /// Decides how the paddle should move in the current update.
+    ///
+    /// # Arguments
+    /// * `observation` - The current `Observation` of the game.
+    /// * `dt` - The `Duration` since the last update.
+    pub fn command(&mut self, observation: &Observation, dt: Duration) -> PaddleCommand {
+        self.time_until_reaction -= dt.as_secs_f64();
+        if self.time_until_reaction <= 0.0 {
+            self.time_until_reaction = self.profile.reaction_time;
+            self.target_y = self.choose_target(observation);
+        }
+
+        match self.target_y {
+            Some(target_y) if target_y > observation.paddle_y + self.profile.tolerance => {
+                PaddleCommand::Up
+            }
+            Some(target_y) if target_y < observation.paddle_y - self.profile.tolerance => {
+                PaddleCommand::Down
+            }
+            _ => PaddleCommand::Stay,
+        }
+    }
+
+    fn choose_target(&mut self, observation: &Observation) -> Option<f64> {
+        let offset_x = observation.ball_x - observation.paddle_x;
+        let heading_towards_paddle = offset_x * observation.ball_vx < 0.0;
+        let distance = offset_x.abs() / observation.field_width;
+
+        if heading_towards_paddle && distance <= self.profile.reaction_distance {
+            let target_y = match self.profile.predicts_bounces {
+                true => observation.intercept_y.unwrap_or(observation.ball_y),
+                false => observation.ball_y,
+            };
+            let error = self
+                .profile
+                .aim_error_distribution
+                .sample(self.profile.aim_error, &mut self.rng);
+            let (direction, distance) = trajectory_bucket(observation);
+            let offset = self.profile.target_offsets[direction][distance];
+
+            Some(target_y + offset + error)
+        } else if self.profile.recovers_to_center {
+            Some(observation.field_height / 2.0)
+        } else {
+            None
+        }
+    }
+}
+
+impl AiStrategy for AiController {
+    fn decide(&mut self, state: &GameState, side: PlayerSide, dt: Duration) -> PaddleCommand {
+        self.command(&state.observation(side), dt)
+    }
+}
+
+/// Sorts the trajectory of the ball into a bucket of (vertical direction, distance to the paddle).
+pub fn trajectory_bucket(observation: &Observation) -> (usize, usize) {
+    let direction = usize::from(observation.ball_vy < 0.0);
+    let distance = (observation.ball_x - observation.paddle_x).abs() / observation.field_width;
+    let distance_bucket = ((distance * DISTANCE_BUCKETS as f64) as usize).min(DISTANCE_BUCKETS - 1);
+
+    (direction, distance_bucket)
+}
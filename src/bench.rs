@@ -0,0 +1,154 @@
+use crate::ai::{AiProfile, AiStyle};
+use crate::state::{GameConfig, GameState};
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// The simulated time of a single update, matching the frame time of the interactive game.
+const TICK: Duration = Duration::from_millis(100);
+
+/// A participant of the AI benchmark.
+pub struct Contender {
+    pub name: String,
+    pub profile: AiProfile,
+}
+
+impl Contender {
+    /// Resolves a contender from the name of a built-in AI style or the path of an AI profile file.
+    pub fn resolve(name: &str) -> io::Result<Self> {
+        let profile = match AiStyle::from_str(name, true) {
+            Ok(style) => style.profile(),
+            Err(_) => AiProfile::load(Path::new(name))?,
+        };
+
+        Ok(Contender {
+            name: name.to_string(),
+            profile,
+        })
+    }
+}
+
+/// The settings all benchmark matches are played with.
+pub struct BenchSettings {
+    pub width: usize,
+    pub height: usize,
+    pub points_to_win: usize,
+    /// The game time after which a match counts as a draw.
+    pub max_match_time: Duration,
+    /// Every pairing plays one match per seed from each side.
+    pub seeds: Vec<u64>,
+}
+
+/// The accumulated results of one contender.
+#[derive(Debug, Clone, Default)]
+pub struct ContenderResult {
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+    pub rallies: usize,
+    pub rally_hits: usize,
+    pub longest_rally: usize,
+}
+
+impl ContenderResult {
+    fn matches(&self) -> usize {
+        self.wins + self.losses + self.draws
+    }
+}
+
+/// Plays every contender against every other contender and collects the results.
+///
+/// # Returns
+/// The `ContenderResult` of every contender in the order of `contenders`.
+pub fn run_round_robin(contenders: &[Contender], settings: &BenchSettings) -> Vec<ContenderResult> {
+    let mut results = vec![ContenderResult::default(); contenders.len()];
+
+    for left in 0..contenders.len() {
+        for right in 0..contenders.len() {
+            if left == right {
+                continue;
+            }
+
+            for &seed in &settings.seeds {
+                let game_state = play_match(
+                    contenders[left].profile,
+                    contenders[right].profile,
+                    settings,
+                    seed,
+                );
+                let (left_score, right_score) = game_state.scores();
+
+                for index in [left, right] {
+                    let result = &mut results[index];
+                    result.rallies += game_state.stats().rallies();
+                    result.rally_hits += game_state.stats().rally_hits();
+                    result.longest_rally =
+                        result.longest_rally.max(game_state.stats().longest_rally());
+                }
+
+                if left_score >= settings.points_to_win {
+                    results[left].wins += 1;
+                    results[right].losses += 1;
+                } else if right_score >= settings.points_to_win {
+                    results[right].wins += 1;
+                    results[left].losses += 1;
+                } else {
+                    results[left].draws += 1;
+                    results[right].draws += 1;
+                }
+            }
+        }
+    }
+
+    results
+}
+
+fn play_match(left: AiProfile, right: AiProfile, settings: &BenchSettings, seed: u64) -> GameState {
+    let mut game_state = GameState::new(GameConfig {
+        width: settings.width,
+        height: settings.height,
+        player1_ai: Some(left),
+        player2_ai: Some(right),
+        seed: Some(seed),
+        ..GameConfig::default()
+    });
+
+    let mut match_time = Duration::ZERO;
+    while match_time < settings.max_match_time {
+        game_state.update(HashMap::new(), TICK);
+        match_time += TICK;
+
+        let (left_score, right_score) = game_state.scores();
+        if left_score >= settings.points_to_win || right_score >= settings.points_to_win {
+            break;
+        }
+    }
+
+    game_state
+}
+
+/// Prints the results of the benchmark as a table.
+pub fn print_results(contenders: &[Contender], results: &[ContenderResult]) {
+    println!(
+        "{:<24} {:>6} {:>6} {:>6} {:>9} {:>10} {:>8}",
+        "Contender", "Wins", "Losses", "Draws", "Win rate", "Avg rally", "Longest"
+    );
+
+    for (contender, result) in contenders.iter().zip(results) {
+        let win_rate = result.wins as f64 / result.matches().max(1) as f64;
+        let average_rally = result.rally_hits as f64 / result.rallies.max(1) as f64;
+
+        println!(
+            "{:<24} {:>6} {:>6} {:>6} {:>8.1}% {:>10.1} {:>8}",
+            contender.name,
+            result.wins,
+            result.losses,
+            result.draws,
+            win_rate * 100.0,
+            average_rally,
+            result.longest_rally
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use crate::frame::{Frame, Layer};
+use crate::library::{ReplayEntry, ReplayLibrary};
+use crate::utils::format_utc;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The rows above the list: the title, a free row and the column headers.
+const LIST_TOP: usize = 3;
+
+/// What the caller of the browser has to do after a key was handled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BrowserAction {
+    /// Keep showing the browser.
+    Stay,
+    /// Play the replay in the given file, then come back to the browser.
+    Play(PathBuf),
+    /// Close the browser.
+    Quit,
+}
+
+/// A question the browser asks about the selected replay in its last row.
+#[derive(Debug, Clone, PartialEq)]
+enum Prompt {
+    /// Asks for the new name of the replay.
+    Rename(String),
+    /// Asks for the path the replay is copied to.
+    Export(String),
+    /// Asks whether the replay should really be deleted.
+    Delete,
+}
+
+/// # Explanation
+/// The replay browser is the screen of the `replays` command. It lists the replays of a
+/// `ReplayLibrary` and plays, renames, deletes and exports the selected replay. The browser only
+/// handles keys and draws itself, the caller reads the terminal and plays the replays.
+pub struct ReplayBrowser {
+    library: ReplayLibrary,
+    entries: Vec<ReplayEntry>,
+    selected: usize,
+    prompt: Option<Prompt>,
+    message: Option<String>,
+}
+
+impl ReplayBrowser {
+    /// Constructs a browser of the replays in the given library.
+    pub fn open(library: ReplayLibrary) -> io::Result<Self> {
+        let entries = library.entries()?;
+
+        Ok(ReplayBrowser {
+            library,
+            entries,
+            selected: 0,
+            prompt: None,
+            message: None,
+        })
+    }
+
+    /// Returns the listed replays, the newest first.
+    pub fn entries(&self) -> &[ReplayEntry] {
+        &self.entries
+    }
+
+    /// Returns the selected replay, or `None` if the library is empty.
+    pub fn selected(&self) -> Option<&ReplayEntry> {
+        self.entries.get(self.selected)
+    }
+
+    /// Returns the message about the last action, e.g. where a replay was exported to.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Handles a key press.
+    ///
+    /// # Returns
+    /// The `BrowserAction` the caller has to take.
+    ///
+    /// # Remarks
+    /// In the list, the arrow keys select a replay, `enter` plays it, `n` renames it, `x` exports it
+    /// and `d` deletes it after asking. `q` and `esc` close the browser.
+    pub fn handle_key(&mut self, key: KeyEvent) -> BrowserAction {
+        if key.kind == KeyEventKind::Release {
+            return BrowserAction::Stay;
+        }
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return BrowserAction::Quit;
+        }
+
+        match self.prompt.take() {
+            Some(prompt) => {
+                self.answer(prompt, key.code);
+                BrowserAction::Stay
+            }
+            None => self.handle_list_key(key.code),
+        }
+    }
+
+    fn handle_list_key(&mut self, code: KeyCode) -> BrowserAction {
+        self.message = None;
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return BrowserAction::Quit,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.entries.len().saturating_sub(1));
+            }
+            KeyCode::Enter | KeyCode::Char('p') => {
+                if let Some(entry) = self.selected() {
+                    return BrowserAction::Play(entry.path.clone());
+                }
+            }
+            KeyCode::Char('n') => {
+                self.prompt = self
+                    .selected()
+                    .map(|entry| Prompt::Rename(entry.name.clone()));
+            }
+            KeyCode::Char('x') => {
+                self.prompt = self.selected().map(|entry| {
+                    let file_name = entry.path.file_name().unwrap_or_default();
+                    Prompt::Export(file_name.to_string_lossy().into_owned())
+                });
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                self.prompt = self.selected().map(|_| Prompt::Delete);
+            }
+            _ => {}
+        }
+
+        BrowserAction::Stay
+    }
+
+    /// Continues the given prompt with a key. The prompt stays open until it is answered or cancelled.
+    fn answer(&mut self, prompt: Prompt, code: KeyCode) {
+        let Some(entry) = self.selected().cloned() else {
+            return;
+        };
+
+        match (prompt, code) {
+            (_, KeyCode::Esc) => {}
+            (Prompt::Delete, KeyCode::Char('y')) => {
+                let result = self.library.delete(&entry);
+                self.finish(result.map(|_| format!("Deleted {}", entry.name)));
+            }
+            (Prompt::Delete, _) => {}
+            (Prompt::Rename(name), KeyCode::Enter) => {
+                let result = self.library.rename(&entry, &name);
+                self.finish(result.map(|path| format!("Renamed to {}", file_name(&path))));
+            }
+            (Prompt::Export(destination), KeyCode::Enter) => {
+                let result = self.library.export(&entry, Path::new(&destination));
+                self.finish(result.map(|path| format!("Exported to {}", path.display())));
+            }
+            (Prompt::Rename(name), code) => self.prompt = Some(Prompt::Rename(edit(name, code))),
+            (Prompt::Export(destination), code) => {
+                self.prompt = Some(Prompt::Export(edit(destination, code)));
+            }
+        }
+    }
+
+    /// Shows the outcome of an action and lists the replays again.
+    fn finish(&mut self, outcome: io::Result<String>) {
+        self.message = Some(outcome.unwrap_or_else(|error| format!("Failed: {error}")));
+        if let Ok(entries) = self.library.entries() {
+            self.entries = entries;
+        }
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Draws the browser into a `Frame` of the given size: the title, the list of replays with their
+    /// details and a last row with the open prompt, the last message or the keys.
+    pub fn compose_frame(&self, columns: usize, rows: usize) -> Frame {
+        let mut frame = Frame::new(columns, rows);
+        let title = format!("Replays in {}", self.library.dir().display());
+        frame.print(Layer::Hud, 0, 0, &title, None);
+        frame.print(
+            Layer::Hud,
+            0,
+            LIST_TOP - 1,
+            &row("Name", "Date", "Players", "Score", "Duration"),
+            None,
+        );
+
+        if self.entries.is_empty() {
+            let hint = "No replays yet. Save the next game with `--save-replay`.";
+            frame.print(Layer::Hud, 0, LIST_TOP, hint, None);
+        }
+        let visible = rows.saturating_sub(LIST_TOP + 1).max(1);
+        let first = (self.selected + 1).saturating_sub(visible);
+        for (index, entry) in self.entries.iter().enumerate().skip(first).take(visible) {
+            let y = LIST_TOP + index - first;
+            let players = entry
+                .players
+                .as_ref()
+                .map_or("-".to_string(), |(left, right)| {
+                    format!("{left} vs {right}")
+                });
+            let text = row(
+                &entry.name,
+                &format_utc(entry.modified),
+                &players,
+                &format!("{}:{}", entry.score.0, entry.score.1),
+                &format_duration(entry.duration),
+            );
+            frame.print(Layer::Hud, 0, y, &text, None);
+            if index == self.selected {
+                for x in 0..columns {
+                    if let Some(cell) = frame.cell_mut(Layer::Overlay, x, y) {
+                        cell.reverse = true;
+                    }
+                }
+            }
+        }
+
+        let status = match (&self.prompt, &self.message) {
+            (Some(Prompt::Rename(name)), _) => format!("New name: {name}_"),
+            (Some(Prompt::Export(destination)), _) => format!("Export to: {destination}_"),
+            (Some(Prompt::Delete), _) => "Delete this replay? (y/n)".to_string(),
+            (None, Some(message)) => message.clone(),
+            (None, None) => "enter: play  n: rename  d: delete  x: export  q: quit".to_string(),
+        };
+        let last_row = rows.saturating_sub(1);
+        frame.print(Layer::Hud, 0, last_row, &status, None);
+
+        frame
+    }
+}
+
+/// Applies a key to the text of a prompt: characters are appended and `backspace` removes the last one.
+fn edit(mut text: String, code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(character) => text.push(character),
+        KeyCode::Backspace => {
+            text.pop();
+        }
+        _ => {}
+    }
+
+    text
+}
+
+/// Lays out the columns of a row of the list.
+fn row(name: &str, date: &str, players: &str, score: &str, duration: &str) -> String {
+    format!("{name:<24.24} {date:<16} {players:<22.22} {score:>7} {duration:>8}")
+}
+
+/// Formats a duration as minutes and seconds, e.g. `3:07`.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
@@ -0,0 +1,166 @@
+use crate::ai::AiStyle;
+use crate::state::{BallSpeed, GameState};
+use crate::stats::PlayerSide;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The number of serves of a calibration session.
+pub const CALIBRATION_SERVES: usize = 20;
+
+/// # Explanation
+/// A calibration session serves the ball towards player1 again and again. For every serve it measures
+/// how long the player needs to start moving and how far the paddle is off the ball while it approaches.
+/// A serve ends as soon as the ball is returned or missed.
+pub struct Calibration {
+    serves: usize,
+    returns: usize,
+    reaction_times: Vec<f64>,
+    tracking_errors: Vec<f64>,
+    time_since_serve: f64,
+    reacted: bool,
+    last_paddle_y: Option<f64>,
+}
+
+/// The outcome of a calibration session.
+#[derive(Debug, Copy, Clone)]
+pub struct CalibrationResult {
+    /// The share of serves the player returned.
+    pub return_rate: f64,
+    /// The mean time in seconds from the serve until the player started moving.
+    pub reaction_time: f64,
+    /// The mean distance in cells between the paddle and the approaching ball.
+    pub tracking_error: f64,
+}
+
+impl Calibration {
+    /// Starts a calibration session and serves the first ball.
+    pub fn start(game_state: &mut GameState) -> Self {
+        game_state.serve(PlayerSide::Left);
+
+        Calibration {
+            serves: 0,
+            returns: 0,
+            reaction_times: Vec::new(),
+            tracking_errors: Vec::new(),
+            time_since_serve: 0.0,
+            reacted: false,
+            last_paddle_y: None,
+        }
+    }
+
+    /// Updates the game and the measurements of the current serve.
+    ///
+    /// # Arguments
+    /// * `game_state` - The `GameState` the session is played in.
+    /// * `pressed_keys` - A `HashMap` representing the keys currently pressed by the player.
+    /// * `dt` - The `Duration` since the last update.
+    pub fn update(
+        &mut self,
+        game_state: &mut GameState,
+        pressed_keys: HashMap<KeyCode, KeyEvent>,
+        dt: Duration,
+    ) {
+        let scores_before = game_state.scores();
+        game_state.update(pressed_keys, dt);
+        self.time_since_serve += dt.as_secs_f64();
+
+        let observation = game_state.observation(PlayerSide::Left);
+        if !self.reacted
+            && self
+                .last_paddle_y
+                .is_some_and(|y| y != observation.paddle_y)
+        {
+            self.reaction_times.push(self.time_since_serve);
+            self.reacted = true;
+        }
+        self.last_paddle_y = Some(observation.paddle_y);
+
+        let missed = game_state.scores() != scores_before;
+        let returned = observation.ball_vx > 0.0;
+        if observation.ball_vx < 0.0 {
+            let distance = observation.ball_x - observation.paddle_x;
+            if distance < observation.field_width / 3.0 {
+                if let Some(intercept_y) = observation.intercept_y {
+                    self.tracking_errors
+                        .push((observation.paddle_y - intercept_y).abs());
+                }
+            }
+        }
+
+        if missed || returned {
+            self.serves += 1;
+            if returned && !missed {
+                self.returns += 1;
+            }
+            if !self.is_finished() {
+                self.next_serve(game_state);
+            }
+        }
+    }
+
+    fn next_serve(&mut self, game_state: &mut GameState) {
+        game_state.serve(PlayerSide::Left);
+        self.time_since_serve = 0.0;
+        self.reacted = false;
+        self.last_paddle_y = None;
+    }
+
+    /// Returns `true` once all serves have been played.
+    pub fn is_finished(&self) -> bool {
+        self.serves >= CALIBRATION_SERVES
+    }
+
+    /// Returns a short description of the progress of the session.
+    pub fn progress(&self) -> String {
+        format!(
+            "Calibration serve {}/{}, returned {}",
+            (self.serves + 1).min(CALIBRATION_SERVES),
+            CALIBRATION_SERVES,
+            self.returns
+        )
+    }
+
+    /// Returns the measurements of the serves played so far.
+    pub fn result(&self) -> CalibrationResult {
+        CalibrationResult {
+            return_rate: self.returns as f64 / self.serves.max(1) as f64,
+            reaction_time: mean(&self.reaction_times),
+            tracking_error: mean(&self.tracking_errors),
+        }
+    }
+}
+
+impl CalibrationResult {
+    /// Recommends an AI style that is a fair opponent for the measured player.
+    pub fn recommended_ai_style(&self) -> AiStyle {
+        if self.return_rate < 0.4 {
+            AiStyle::Rookie
+        } else if self.return_rate < 0.65 {
+            AiStyle::Defensive
+        } else if self.return_rate < 0.85 {
+            AiStyle::Balanced
+        } else {
+            AiStyle::Aggressive
+        }
+    }
+
+    /// Recommends a ball speed preset that fits the reactions and the tracking of the measured player.
+    pub fn recommended_ball_speed(&self) -> BallSpeed {
+        if self.reaction_time > 0.5 || self.tracking_error > 3.0 {
+            BallSpeed::Slow
+        } else if self.reaction_time < 0.25 && self.return_rate > 0.8 {
+            BallSpeed::Fast
+        } else {
+            BallSpeed::Normal
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
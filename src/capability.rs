@@ -0,0 +1,221 @@
+use clap::ValueEnum;
+use crossterm::style::Color;
+use crossterm::terminal;
+use std::io::IsTerminal;
+
+/// The number of colors a terminal can show.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, ValueEnum)]
+pub enum ColorDepth {
+    /// No colors at all, e.g. with `NO_COLOR` set or in a dumb terminal.
+    #[value(name = "none")]
+    Monochrome,
+    /// The 16 standard colors.
+    #[value(name = "16")]
+    Ansi16,
+    /// The 256 colors of the xterm palette.
+    #[value(name = "256")]
+    Ansi256,
+    /// Any RGB color.
+    #[value(name = "truecolor")]
+    TrueColor,
+}
+
+/// # Explanation
+/// What the terminal the game runs in can show and report. The game is drawn for a modern
+/// terminal with true colors and Unicode, and the frame is adapted to the capabilities when it is
+/// written, e.g. colors are mapped to the closest color the terminal knows and block characters
+/// are replaced by ASCII.
+///
+/// # Remarks
+/// The default capabilities are the ones of a modern terminal, which is what the game assumes
+/// when nothing was detected.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Capabilities {
+    pub color_depth: ColorDepth,
+    /// Whether the terminal shows characters beyond ASCII, like the blocks of the paddles.
+    pub unicode: bool,
+    /// Whether the terminal supports the keyboard protocol of kitty, which reports when a key is
+    /// released.
+    pub keyboard_enhancement: bool,
+    /// The columns and rows of the terminal, or `None` if the output is no terminal.
+    pub size: Option<(u16, u16)>,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Capabilities {
+            color_depth: ColorDepth::TrueColor,
+            unicode: true,
+            keyboard_enhancement: false,
+            size: None,
+        }
+    }
+}
+
+impl Capabilities {
+    /// Probes the terminal the game runs in.
+    ///
+    /// # Remarks
+    /// The colors and Unicode support are read from the environment, e.g. `COLORTERM`, `TERM` and
+    /// the locale. The keyboard protocol is queried from the terminal, which takes a moment for
+    /// terminals that do not answer, so it is only queried if `probe_keyboard` is set.
+    pub fn detect(probe_keyboard: bool) -> Self {
+        let mut capabilities = Self::from_env(|name| std::env::var(name).ok());
+        if std::io::stdout().is_terminal() {
+            capabilities.size = terminal::size().ok();
+            capabilities.keyboard_enhancement =
+                probe_keyboard && terminal::supports_keyboard_enhancement().unwrap_or(false);
+        }
+
+        capabilities
+    }
+
+    /// Derives the colors and Unicode support from environment variables.
+    ///
+    /// # Arguments
+    /// * `var` - Returns the value of the environment variable of the given name, if it is set.
+    pub fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        let term = var("TERM").unwrap_or_default();
+        let color_term = var("COLORTERM").unwrap_or_default().to_lowercase();
+        // https://no-color.org: any non-empty value disables colors
+        let no_color = var("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+        let color_depth = if no_color || term == "dumb" {
+            ColorDepth::Monochrome
+        } else if color_term == "truecolor" || color_term == "24bit" || cfg!(windows) {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        };
+
+        // the first of these variables that is set decides the character set
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .filter_map(&var)
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_lowercase();
+        let unicode = cfg!(windows) || locale.contains("utf-8") || locale.contains("utf8");
+
+        Capabilities {
+            color_depth,
+            unicode,
+            ..Capabilities::default()
+        }
+    }
+
+    /// Returns the color the terminal shows instead of the given one, or `None` for the default
+    /// color if it can not show colors at all.
+    pub fn color(&self, color: Option<Color>) -> Option<Color> {
+        let color = color?;
+        match (self.color_depth, color) {
+            (ColorDepth::Monochrome, _) => None,
+            (ColorDepth::TrueColor, _) => Some(color),
+            (ColorDepth::Ansi256, Color::Rgb { r, g, b }) => {
+                Some(Color::AnsiValue(ansi256(r, g, b)))
+            }
+            (ColorDepth::Ansi256, _) => Some(color),
+            (ColorDepth::Ansi16, Color::Rgb { r, g, b }) => Some(ansi16(r, g, b)),
+            (ColorDepth::Ansi16, Color::AnsiValue(value)) if value >= 16 => {
+                let (r, g, b) = xterm_rgb(value);
+                Some(ansi16(r, g, b))
+            }
+            (ColorDepth::Ansi16, _) => Some(color),
+        }
+    }
+
+    /// Returns the character the terminal shows instead of the given one. Without Unicode, the
+    /// characters the game draws with are replaced by similar ASCII characters.
+    pub fn glyph(&self, character: char) -> char {
+        if self.unicode || character.is_ascii() {
+            return character;
+        }
+
+        match character {
+            '\u{2588}' => '#',
+            '\u{2592}' => ':',
+            '\u{2591}' | '\u{00B7}' => '.',
+            '\u{25CF}' | '\u{25CB}' => 'O',
+            '\u{25C6}' => '*',
+            '\u{25CE}' => '@',
+            '\u{25C9}' => '&',
+            '\u{25D0}'..='\u{25D3}' => 'o',
+            '\u{25D6}' => '(',
+            '\u{25D7}' => ')',
+            _ => '?',
+        }
+    }
+}
+
+/// The 16 standard colors with the RGB values of the xterm palette.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (205, 0, 0)),
+    (Color::DarkGreen, (0, 205, 0)),
+    (Color::DarkYellow, (205, 205, 0)),
+    (Color::DarkBlue, (0, 0, 238)),
+    (Color::DarkMagenta, (205, 0, 205)),
+    (Color::DarkCyan, (0, 205, 205)),
+    (Color::Grey, (229, 229, 229)),
+    (Color::DarkGrey, (127, 127, 127)),
+    (Color::Red, (255, 0, 0)),
+    (Color::Green, (0, 255, 0)),
+    (Color::Yellow, (255, 255, 0)),
+    (Color::Blue, (92, 92, 255)),
+    (Color::Magenta, (255, 0, 255)),
+    (Color::Cyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// The levels of each channel in the 6x6x6 color cube of the xterm palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Returns the closest of the 16 standard colors.
+fn ansi16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
+
+/// Returns the closest color of the xterm palette, either from the color cube or the grays.
+fn ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |channel: u8| {
+        (0..6)
+            .min_by_key(|&index| CUBE_LEVELS[index].abs_diff(channel))
+            .unwrap_or(0) as u8
+    };
+    let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray = 232 + ((average.saturating_sub(3)) / 10).min(23) as u8;
+
+    [cube, gray]
+        .into_iter()
+        .min_by_key(|&value| distance((r, g, b), xterm_rgb(value)))
+        .unwrap_or(cube)
+}
+
+/// Returns the RGB value of a color of the xterm palette from 16 on.
+fn xterm_rgb(value: u8) -> (u8, u8, u8) {
+    if value >= 232 {
+        let level = 8 + 10 * (value - 232);
+        return (level, level, level);
+    }
+
+    let index = value.saturating_sub(16);
+    (
+        CUBE_LEVELS[(index / 36) as usize],
+        CUBE_LEVELS[(index / 6 % 6) as usize],
+        CUBE_LEVELS[(index % 6) as usize],
+    )
+}
+
+/// Returns the squared distance of two colors.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
@@ -0,0 +1,196 @@
+use crate::frame::{Cell, Frame, Layer};
+use crate::replay::ReplayFrame;
+use crate::rewind::RewindBuffer;
+use crate::state::{GameEvent, GameState};
+use crate::stats::PlayerSide;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// The game time before a goal the replay of the goal shows.
+pub const GOAL_REPLAY_TIME: Duration = Duration::from_secs(2);
+
+/// The speed the replay of a goal is shown with, relative to the speed of the game.
+pub const SLOW_MOTION: f64 = 0.5;
+
+/// The rows above the court: the big score with the names and the line below it.
+const HEADER_ROWS: usize = 8;
+
+/// The row of the header that shows the rally counter or the replay banner.
+const STATUS_ROW: usize = 7;
+
+/// The digits of the big score, five rows of three columns each.
+const DIGITS: [[&str; 5]; 10] = [
+    ["███", "█ █", "█ █", "█ █", "███"],
+    [" █ ", "██ ", " █ ", " █ ", "███"],
+    ["███", "  █", "███", "█  ", "███"],
+    ["███", "  █", "███", "  █", "███"],
+    ["█ █", "█ █", "███", "  █", "  █"],
+    ["███", "█  ", "███", "  █", "███"],
+    ["███", "█  ", "███", "█ █", "███"],
+    ["███", "  █", "  █", "  █", "  █"],
+    ["███", "█ █", "███", "█ █", "███"],
+    ["███", "█ █", "███", "  █", "███"],
+];
+
+/// The colon between the two scores.
+const COLON: [&str; 5] = [" ", "█", " ", "█", " "];
+
+/// Returns the rows of the given number in the digits of the big score.
+fn big_number(number: usize) -> [String; 5] {
+    let digits: Vec<usize> = number
+        .to_string()
+        .chars()
+        .filter_map(|digit| digit.to_digit(10))
+        .map(|digit| digit as usize)
+        .collect();
+
+    std::array::from_fn(|row| {
+        digits
+            .iter()
+            .map(|&digit| DIGITS[digit][row])
+            .collect::<Vec<_>>()
+            .join(" ")
+    })
+}
+
+/// The replay of a goal that is shown in slow motion.
+struct GoalReplay {
+    /// A copy of the game the recorded frames are shown in.
+    state: GameState,
+    frames: VecDeque<ReplayFrame>,
+    /// The replay time that passed since the frame shown last.
+    elapsed: Duration,
+    scorer: PlayerSide,
+}
+
+/// # Explanation
+/// The caster presents a game for an audience, e.g. on a projector at an office tournament: a big
+/// score between the names of the players, a rally counter and the court below. It keeps the last
+/// seconds of the game, and after every goal it shows them again in slow motion before it returns
+/// to the live game. The caster only watches the game, so nobody has to operate it.
+pub struct Caster {
+    names: [String; 2],
+    history: RewindBuffer<ReplayFrame>,
+    replay: Option<GoalReplay>,
+}
+
+impl Caster {
+    /// Creates a caster that shows the given names of player1 and player2.
+    pub fn new(names: [String; 2]) -> Self {
+        Caster {
+            names,
+            history: RewindBuffer::new(GOAL_REPLAY_TIME),
+            replay: None,
+        }
+    }
+
+    /// Returns `true` while the replay of a goal is shown.
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Follows an update of the game: it advances the replay that is shown, keeps the new frame of
+    /// the game and starts the replay of a goal.
+    ///
+    /// # Arguments
+    /// * `state` - The `GameState` after the update.
+    /// * `events` - The `GameEvent`s the update returned.
+    /// * `dt` - The `Duration` the update advanced the game by.
+    pub fn update(&mut self, state: &GameState, events: &[GameEvent], dt: Duration) {
+        if let Some(replay) = &mut self.replay {
+            replay.elapsed += dt.mul_f64(SLOW_MOTION);
+            while let Some(frame) = replay.frames.front() {
+                if frame.dt > replay.elapsed {
+                    break;
+                }
+                replay.elapsed -= frame.dt;
+                replay.state.show_replay_frame(frame);
+                replay.frames.pop_front();
+            }
+            if replay.frames.is_empty() {
+                self.replay = None;
+            }
+        }
+
+        for &event in events {
+            match event {
+                GameEvent::Goal(scorer) => {
+                    // the ball of the frame after the goal is already served again
+                    let frames: VecDeque<ReplayFrame> = self.history.take_all().into();
+                    if let Some(first) = frames.front() {
+                        let mut replay_state = state.clone();
+                        replay_state.show_replay_frame(first);
+                        self.replay = Some(GoalReplay {
+                            state: replay_state,
+                            frames,
+                            elapsed: Duration::ZERO,
+                            scorer,
+                        });
+                    }
+                    return;
+                }
+                GameEvent::PointRestarted | GameEvent::Rewound | GameEvent::NewGame => {
+                    self.history.take_all();
+                }
+                _ => {}
+            }
+        }
+        self.history.push(dt, state.replay_frame(dt));
+    }
+
+    /// Draws the view of the caster: the live game or the replay of the last goal.
+    pub fn compose(&self, state: &GameState) -> Frame {
+        let shown = self.replay.as_ref().map_or(state, |replay| &replay.state);
+        let court = shown.compose_court();
+        let mut frame = Frame::new(court.width(), HEADER_ROWS + court.height());
+        frame.copy_from(&court, 0, 0, 0, HEADER_ROWS, court.width(), court.height());
+
+        let theme = &state.config().theme;
+        let (player1, player2) = state.scores();
+        let (left, right) = (big_number(player1), big_number(player2));
+        let score_width = left[0].chars().count() + 6 + right[0].chars().count();
+        let score_start = frame.width().saturating_sub(score_width) / 2;
+        for row in 0..5 {
+            let line = format!("{}  {}  {}", left[row], COLON[row], right[row]);
+            for (offset, character) in line.chars().enumerate() {
+                if character != ' ' {
+                    let cell = Cell::new(character, theme.text);
+                    frame.set(Layer::Hud, score_start + offset, 1 + row, cell);
+                }
+            }
+        }
+
+        let [name1, name2] = &self.names;
+        let name1_start = score_start.saturating_sub(name1.chars().count() + 3);
+        frame.print(Layer::Hud, name1_start, 3, name1, theme.paddle1);
+        frame.print(
+            Layer::Hud,
+            score_start + score_width + 3,
+            3,
+            name2,
+            theme.paddle2,
+        );
+
+        let status = match &self.replay {
+            Some(replay) => {
+                let scorer = match replay.scorer {
+                    PlayerSide::Left => name1,
+                    PlayerSide::Right => name2,
+                };
+                format!("REPLAY  goal for {scorer}")
+            }
+            None => format!("Rally: {}", state.rally_hits()),
+        };
+        let status_start = frame.width().saturating_sub(status.chars().count()) / 2;
+        frame.print(Layer::Hud, status_start, STATUS_ROW, &status, theme.text);
+        if self.is_replaying() {
+            for column in status_start..status_start + status.chars().count() {
+                if let Some(cell) = frame.cell_mut(Layer::Hud, column, STATUS_ROW) {
+                    cell.reverse = true;
+                }
+            }
+        }
+
+        frame
+    }
+}
@@ -0,0 +1,313 @@
+use crate::ai::AiStyle;
+use crate::hud::{Diagnostics, Hud, HudAlign, HudSlot, ModeStatus, Score};
+use crate::mode::GameMode;
+use crate::state::{GameConfig, GameState, Position2D, Velocity2D};
+use crate::stats::PlayerSide;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// Where the ball of the first serve of a challenge starts and how it flies.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BallStart {
+    pub x: f64,
+    pub y: f64,
+    pub vx: f64,
+    pub vy: f64,
+}
+
+/// What player1 has to achieve to complete a challenge.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChallengeGoal {
+    /// Reach the given number of goals before the opponent does.
+    Win { goals: usize },
+    /// Return the given number of balls that fly at least `min_speed` cells per second. The
+    /// challenge fails once player1 missed more than `allowed_misses` balls.
+    Returns {
+        count: usize,
+        #[serde(default)]
+        min_speed: f64,
+        #[serde(default)]
+        allowed_misses: usize,
+    },
+    /// Keep the ball out of the own goal for the given number of seconds.
+    Survive { seconds: u64 },
+}
+
+/// # Explanation
+/// A scenario describes a challenge: the state the game starts in, e.g. the score, the ball and
+/// the length of the paddles, the opponent, an optional time limit and the goal player1 has to
+/// reach. Scenarios are written as TOML files and played with `cli_pong challenge <file>`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    /// The name of the challenge.
+    pub name: String,
+    /// A sentence that tells the player what to do.
+    #[serde(default)]
+    pub description: String,
+    /// The playing style of the computer that controls player2.
+    #[serde(default = "default_opponent")]
+    pub opponent: AiStyle,
+    /// The goals player1 starts with.
+    #[serde(default)]
+    pub player1_score: usize,
+    /// The goals player2 starts with.
+    #[serde(default)]
+    pub player2_score: usize,
+    /// How much longer the paddle of player1 is in both directions, or `None` for the settings.
+    pub player1_extend: Option<usize>,
+    /// How much longer the paddle of player2 is in both directions, or `None` for the settings.
+    pub player2_extend: Option<usize>,
+    /// The ball of the first serve, or `None` for a usual serve.
+    pub ball: Option<BallStart>,
+    /// The seconds of play player1 has to reach the goal, or `None` without a time limit.
+    pub time_limit: Option<u64>,
+    /// What player1 has to achieve.
+    pub goal: ChallengeGoal,
+}
+
+fn default_opponent() -> AiStyle {
+    AiStyle::Balanced
+}
+
+impl Scenario {
+    /// Reads a scenario from a TOML file.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let scenario: Scenario = toml::from_str(&content).map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {error}", path.display()),
+            )
+        })?;
+        if let ChallengeGoal::Win { goals } = scenario.goal {
+            if goals <= scenario.player1_score.max(scenario.player2_score) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{}: the goal of {goals} goals is reached before the challenge starts",
+                        path.display()
+                    ),
+                ));
+            }
+        }
+
+        Ok(scenario)
+    }
+
+    /// Describes the goal of the challenge in a few words, e.g. `reach 11 goals first`.
+    pub fn goal_description(&self) -> String {
+        let goal = match self.goal {
+            ChallengeGoal::Win { goals } => format!("reach {goals} goals first"),
+            ChallengeGoal::Returns {
+                count, min_speed, ..
+            } if min_speed > 0.0 => {
+                format!("return {count} balls faster than {min_speed} cells/s")
+            }
+            ChallengeGoal::Returns { count, .. } => format!("return {count} balls"),
+            ChallengeGoal::Survive { seconds } => format!("survive {seconds}s"),
+        };
+
+        match self.time_limit {
+            Some(limit) => format!("{goal} within {limit}s"),
+            None => goal,
+        }
+    }
+}
+
+/// The outcome of a challenge.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChallengeOutcome {
+    /// Player1 reached the goal.
+    Success,
+    /// Player1 failed for the given reason.
+    Failure(String),
+}
+
+/// # Explanation
+/// The challenge mode plays a `Scenario`. It sets up the start of the game, counts the time of
+/// play and the returns that count for the goal, and decides the game as soon as player1 reached
+/// the goal or can no longer reach it: a success is won by player1, a failure by player2.
+#[derive(Debug, Clone)]
+pub struct Challenge {
+    scenario: Scenario,
+    elapsed: Duration,
+    returns: usize,
+    /// Whether the ball of the scenario was served in the current game.
+    ball_served: bool,
+}
+
+impl Challenge {
+    /// Creates the challenge of the given scenario.
+    pub fn new(scenario: Scenario) -> Self {
+        Challenge {
+            scenario,
+            elapsed: Duration::ZERO,
+            returns: 0,
+            ball_served: false,
+        }
+    }
+
+    /// Returns the scenario of the challenge.
+    pub fn scenario(&self) -> &Scenario {
+        &self.scenario
+    }
+
+    /// Returns the outcome of the challenge in the given game, or `None` while it is open.
+    pub fn outcome(&self, state: &GameState) -> Option<ChallengeOutcome> {
+        let (player1, player2) = state.scores();
+        let conceded = player2.saturating_sub(self.scenario.player2_score);
+        let failure = |reason: &str| Some(ChallengeOutcome::Failure(reason.to_string()));
+
+        match self.scenario.goal {
+            ChallengeGoal::Win { goals } if player1 >= goals => Some(ChallengeOutcome::Success),
+            ChallengeGoal::Win { goals } if player2 >= goals => {
+                failure("the opponent got there first")
+            }
+            ChallengeGoal::Returns { count, .. } if self.returns >= count => {
+                Some(ChallengeOutcome::Success)
+            }
+            ChallengeGoal::Returns { allowed_misses, .. } if conceded > allowed_misses => {
+                failure("too many balls got past you")
+            }
+            ChallengeGoal::Survive { .. } if conceded > 0 => failure("a ball got past you"),
+            ChallengeGoal::Survive { seconds } if self.elapsed.as_secs() >= seconds => {
+                Some(ChallengeOutcome::Success)
+            }
+            _ if self
+                .scenario
+                .time_limit
+                .is_some_and(|limit| self.elapsed.as_secs() >= limit) =>
+            {
+                failure("time is up")
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the time of play left until the time limit, or `None` without a time limit.
+    fn time_left(&self) -> Option<Duration> {
+        self.scenario
+            .time_limit
+            .map(|limit| Duration::from_secs(limit).saturating_sub(self.elapsed))
+    }
+}
+
+impl GameMode for Challenge {
+    fn name(&self) -> &str {
+        "challenge"
+    }
+
+    fn mutate_config(&self, config: &mut GameConfig) {
+        let scenario = &self.scenario;
+        config.player2_ai = Some(scenario.opponent.profile());
+        config.player1_handicap.start_score = scenario.player1_score;
+        config.player2_handicap.start_score = scenario.player2_score;
+        if let Some(extend) = scenario.player1_extend {
+            config.player1_handicap.extend = Some(extend);
+        }
+        if let Some(extend) = scenario.player2_extend {
+            config.player2_handicap.extend = Some(extend);
+        }
+        // only the goal of the scenario decides the game
+        config.score_limit = None;
+        config.match_format = None;
+    }
+
+    fn build_hud(&self, hud: &mut Hud) {
+        *hud = Hud::default();
+        hud.add(HudSlot::new(0, HudAlign::Left), Diagnostics);
+        hud.add(HudSlot::new(1, HudAlign::Left), Score);
+        hud.add(HudSlot::new(2, HudAlign::Left), ModeStatus);
+    }
+
+    fn on_serve(&mut self, state: &mut GameState) {
+        if self.ball_served {
+            return;
+        }
+        if let Some(ball) = self.scenario.ball {
+            state.launch_ball(
+                Position2D::new(ball.x, ball.y),
+                Velocity2D::new(ball.vx, ball.vy),
+            );
+        }
+        let task = match self.scenario.description.is_empty() {
+            true => self.scenario.goal_description(),
+            false => self.scenario.description.clone(),
+        };
+        state.notify(task);
+        self.ball_served = true;
+    }
+
+    fn on_hit(&mut self, state: &mut GameState, hitter: PlayerSide) {
+        let ChallengeGoal::Returns { min_speed, .. } = self.scenario.goal else {
+            return;
+        };
+        if hitter != PlayerSide::Left {
+            return;
+        }
+        // a return hardly changes the speed of the ball, so it tells how fast the ball came in
+        let observation = state.observation(PlayerSide::Left);
+        if observation.ball_vx.hypot(observation.ball_vy) >= min_speed {
+            self.returns += 1;
+        }
+    }
+
+    fn on_tick(&mut self, _state: &mut GameState, dt: Duration) {
+        self.elapsed += dt;
+    }
+
+    fn on_new_game(&mut self, _state: &mut GameState) {
+        self.elapsed = Duration::ZERO;
+        self.returns = 0;
+        self.ball_served = false;
+    }
+
+    fn win_condition(&self, state: &GameState) -> Option<PlayerSide> {
+        match self.outcome(state)? {
+            ChallengeOutcome::Success => Some(PlayerSide::Left),
+            ChallengeOutcome::Failure(_) => Some(PlayerSide::Right),
+        }
+    }
+
+    fn status(&self, state: &GameState) -> Option<String> {
+        let mut status = match self.scenario.goal {
+            ChallengeGoal::Returns { count, .. } => format!("Returns: {}/{count}", self.returns),
+            ChallengeGoal::Survive { seconds } => format!(
+                "Survived: {}/{seconds}s",
+                self.elapsed.as_secs().min(seconds)
+            ),
+            ChallengeGoal::Win { goals } => format!("First to {goals}"),
+        };
+        if let Some(left) = self.time_left() {
+            status += &format!(",  time left: {}s", left.as_secs_f64().ceil());
+        }
+        if let Some(ChallengeOutcome::Failure(reason)) = self.outcome(state) {
+            status = format!("Failed: {reason}");
+        } else if state.is_over() {
+            status = "Challenge complete!".to_string();
+        }
+
+        Some(status)
+    }
+
+    fn summary(&self, state: &GameState) -> Vec<String> {
+        let title = format!(
+            "Challenge `{}`: {}",
+            self.scenario.name,
+            self.scenario.goal_description()
+        );
+        let result = match self.outcome(state) {
+            Some(ChallengeOutcome::Success) => {
+                format!("Complete after {:.1}s of play", self.elapsed.as_secs_f64())
+            }
+            Some(ChallengeOutcome::Failure(reason)) => format!("Failed: {reason}"),
+            None => "Not finished".to_string(),
+        };
+
+        vec![title, result]
+    }
+}
@@ -0,0 +1,91 @@
+use crate::timer::Timer;
+use rand::Rng;
+use std::time::Duration;
+
+/// The time of play between two chaos events by default.
+pub const DEFAULT_CHAOS_INTERVAL: Duration = Duration::from_secs(8);
+
+/// The factor the speed of the ball is scaled with by a speed burst.
+pub const SPEED_BURST_FACTOR: f64 = 1.5;
+
+/// The number of cells a paddle loses to a paddle shrink.
+pub const CHAOS_SHRINK_CELLS: usize = 2;
+
+/// The random events of the `chaos` mutator.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ChaosEvent {
+    /// The ball jumps to a random place in the middle of the field and keeps its velocity.
+    Teleport,
+    /// The ball gets one and a half times as fast.
+    SpeedBurst,
+    /// The paddle of a random player loses two cells until the next serve.
+    PaddleShrink,
+    /// A second ball is served, which scores like the first one until the next serve.
+    SecondBall,
+}
+
+impl ChaosEvent {
+    /// All chaos events.
+    pub const ALL: [ChaosEvent; 4] = [
+        ChaosEvent::Teleport,
+        ChaosEvent::SpeedBurst,
+        ChaosEvent::PaddleShrink,
+        ChaosEvent::SecondBall,
+    ];
+
+    /// Returns the short notice shown on the screen when the event fires.
+    pub fn notice(&self) -> &'static str {
+        match self {
+            ChaosEvent::Teleport => "Chaos: the ball teleports!",
+            ChaosEvent::SpeedBurst => "Chaos: speed burst!",
+            ChaosEvent::PaddleShrink => "Chaos: a paddle shrinks!",
+            ChaosEvent::SecondBall => "Chaos: a second ball!",
+        }
+    }
+}
+
+/// # Explanation
+/// The chaos scheduler fires a random chaos event each time the given interval of play has
+/// passed. It runs on the simulated time of the updates and draws the events from the random
+/// number generator of the game, so a seeded game sees the same events at the same moments.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ChaosScheduler {
+    timer: Timer,
+}
+
+impl ChaosScheduler {
+    /// Creates a scheduler that fires an event every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        ChaosScheduler {
+            timer: Timer::repeating(interval.max(Duration::from_millis(1))),
+        }
+    }
+
+    /// Advances the scheduler by the given time of play.
+    ///
+    /// # Arguments
+    /// * `dt` - The `Duration` of play since the last update.
+    /// * `allowed` - The events that can fire in the current situation of the game.
+    /// * `rng` - The random number generator the event is drawn from.
+    ///
+    /// # Returns
+    /// The event that fires, or `None` while the interval has not passed. An update never fires
+    /// more than one event.
+    pub fn advance(
+        &mut self,
+        dt: Duration,
+        allowed: &[ChaosEvent],
+        rng: &mut impl Rng,
+    ) -> Option<ChaosEvent> {
+        if self.timer.advance(dt) == 0 || allowed.is_empty() {
+            return None;
+        }
+
+        Some(allowed[rng.gen_range(0..allowed.len())])
+    }
+
+    /// Returns the time of play until the next event.
+    pub fn time_to_next_event(&self) -> Duration {
+        self.timer.remaining()
+    }
+}
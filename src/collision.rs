@@ -0,0 +1,145 @@
+//! The collision detection runs in two phases. The broadphase compares cheap bounding boxes of
+//! everything that moves or can be hit in an update and keeps only the pairs that may touch. The
+//! narrowphase then runs the exact swept test of the entities, e.g. `Ball::update_position`, on
+//! these pairs alone, so the cost of an update grows with the number of entities and not with the
+//! number of pairs.
+
+use crate::state::{Position2D, Velocity2D};
+
+/// An axis-aligned bounding box in field coordinates.
+#[derive(Debug, Copy, Clone)]
+pub struct Aabb {
+    min: Position2D,
+    max: Position2D,
+}
+
+impl Aabb {
+    /// Creates the smallest box that contains both corners, given in any order.
+    pub fn new(corner1: Position2D, corner2: Position2D) -> Self {
+        Aabb {
+            min: Position2D::new(corner1.x().min(corner2.x()), corner1.y().min(corner2.y())),
+            max: Position2D::new(corner1.x().max(corner2.x()), corner1.y().max(corner2.y())),
+        }
+    }
+
+    /// Creates the box that covers the path from `from` to `to` of an entity that reaches
+    /// `half_height` above and below its center.
+    pub fn swept(from: Position2D, to: Position2D, half_height: f64) -> Self {
+        Aabb::new(
+            Position2D::new(from.x(), from.y().min(to.y()) - half_height),
+            Position2D::new(to.x(), from.y().max(to.y()) + half_height),
+        )
+    }
+
+    pub fn min(&self) -> Position2D {
+        self.min
+    }
+
+    pub fn max(&self) -> Position2D {
+        self.max
+    }
+
+    /// Returns `true` if the boxes share at least one point, including their borders.
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.x() <= other.max.x()
+            && other.min.x() <= self.max.x()
+            && self.min.y() <= other.max.y()
+            && other.min.y() <= self.max.y()
+    }
+}
+
+/// Finds the pairs of boxes that overlap by sorting the boxes along the x-axis and sweeping over
+/// them, so only boxes whose x-ranges overlap are compared with each other.
+///
+/// # Arguments
+/// * `boxes` - The bounding boxes of all entities of the update.
+///
+/// # Returns
+/// The pairs of indices `(i, j)` with `i < j` whose boxes overlap, sorted by `i` and then `j`.
+pub fn broadphase(boxes: &[Aabb]) -> Vec<(usize, usize)> {
+    let mut order: Vec<usize> = (0..boxes.len()).collect();
+    order.sort_by(|&a, &b| boxes[a].min.x().total_cmp(&boxes[b].min.x()));
+
+    let mut active: Vec<usize> = Vec::new();
+    let mut pairs = Vec::new();
+    for index in order {
+        let current = &boxes[index];
+        active.retain(|&other| boxes[other].max.x() >= current.min.x());
+        for &other in &active {
+            if current.overlaps(&boxes[other]) {
+                pairs.push((index.min(other), index.max(other)));
+            }
+        }
+        active.push(index);
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Returns the point at which an entity moving in a straight line crosses the vertical line at `x`.
+///
+/// # Arguments
+/// * `position` - The current `Position2D` of the entity.
+/// * `velocity` - The `Velocity2D` of the entity, which has to move horizontally.
+/// * `x` - The x-coordinate of the vertical line, e.g. the column of a paddle.
+///
+/// # Remarks
+/// The point may lie behind the entity if it already passed the line.
+pub fn crossing_point(position: Position2D, velocity: Velocity2D, x: f64) -> Position2D {
+    let time = (x - position.x()) / velocity.vx();
+
+    Position2D::new(x, position.y() + velocity.vy() * time)
+}
+
+/// Finds a spawn point for a ball that neither overlaps one of the blocked boxes, e.g. the paddles,
+/// nor reaches into a wall or over a goal line. The preferred point is taken if it is free, otherwise
+/// the closest free point that lies a whole number of cells away from it.
+///
+/// # Arguments
+/// * `preferred` - The `Position2D` the ball should spawn at, usually the center of the field.
+/// * `(width, height)` - The size of the field.
+/// * `radius` - How many cells the ball reaches above and below its center.
+/// * `blocked` - The bounding boxes of everything the ball must not spawn in.
+///
+/// # Returns
+/// The spawn point, or `preferred` if there is no free point in the whole field.
+pub fn safe_spawn_point(
+    preferred: Position2D,
+    (width, height): (f64, f64),
+    radius: f64,
+    blocked: &[Aabb],
+) -> Position2D {
+    let is_free = |point: Position2D| {
+        let inside = point.x() >= 1.0
+            && point.x() <= width - 1.0
+            && point.y() - radius >= 0.0
+            && point.y() + radius <= height;
+        let cells = Aabb::new(
+            Position2D::new(point.x() - 0.5, point.y() - radius - 0.5),
+            Position2D::new(point.x() + 0.5, point.y() + radius + 0.5),
+        );
+
+        inside && !blocked.iter().any(|other| cells.overlaps(other))
+    };
+
+    let max_distance = width.max(height).ceil() as i64;
+    for distance in 0..=max_distance {
+        // the points of the square ring around the preferred point, closest first
+        let mut ring: Vec<(i64, i64)> = (-distance..=distance)
+            .flat_map(|dx| (-distance..=distance).map(move |dy| (dx, dy)))
+            .filter(|(dx, dy)| dx.abs().max(dy.abs()) == distance)
+            .collect();
+        ring.sort_by_key(|(dx, dy)| (dx * dx + dy * dy, dx.abs()));
+
+        let free = ring
+            .into_iter()
+            .map(|(dx, dy)| Position2D::new(preferred.x() + dx as f64, preferred.y() + dy as f64))
+            .find(|&point| is_free(point));
+        if let Some(point) = free {
+            return point;
+        }
+    }
+
+    preferred
+}
@@ -0,0 +1,569 @@
+use crate::storage::write_atomically;
+use crate::utils::config_dir;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+use toml::{Table, Value};
+use toml_edit::{DocumentMut, Item, Key};
+
+/// The table of a configuration file that holds the named profiles.
+const PROFILE_TABLE: &str = "profile";
+
+/// The key of a configuration file that holds the version of its format.
+const VERSION_KEY: &str = "version";
+
+/// The version of the format of the written configuration files. Files without a version are of
+/// version 1.
+///
+/// Version 2 spells the options like on the command line, e.g. `win-meter` instead of `win_meter`.
+const CONFIG_VERSION: i64 = 2;
+
+/// The migrations of the configuration files, where the first one migrates a file of version 1 to
+/// version 2 and so on. A renamed option needs a migration, so the users keep their settings.
+/// Each migration returns `true` if it changed the file.
+const MIGRATIONS: &[fn(&mut toml_edit::Table) -> bool] = &[dash_option_names];
+
+/// The name of the configuration file that applies to the games started in its directory.
+pub const WORKSPACE_CONFIG_FILE: &str = ".cli_pong.toml";
+
+/// The option that turns unknown and deprecated options in the configuration files into errors.
+const STRICT_OPTION: &str = "strict-config";
+
+/// The options that were renamed, as pairs of the old and the new name.
+/// The old names are still accepted with a warning unless the configuration is strict.
+const DEPRECATED_OPTIONS: &[(&str, &str)] = &[];
+
+/// The prefix of the environment variables that set options, e.g. `CLI_PONG_WIDTH=80`.
+pub const ENV_PREFIX: &str = "CLI_PONG_";
+
+/// Returns the path of the configuration file of the user.
+pub fn user_config_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// Returns the path of the file of the preset with the given name.
+pub fn preset_file(name: &str) -> io::Result<PathBuf> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|character| character.is_alphanumeric() || character == '-' || character == '_');
+    if !valid {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid preset name `{name}`, use letters, digits, `-` and `_`"),
+        ));
+    }
+
+    presets_dir()
+        .map(|dir| dir.join(format!("{name}.toml")))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no configuration directory"))
+}
+
+/// Returns the names of the saved presets in alphabetical order.
+pub fn preset_names() -> Vec<String> {
+    let Some(entries) = presets_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "toml")
+        })
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+        .collect();
+    names.sort();
+
+    names
+}
+
+/// Saves the given options as a preset, which replaces an earlier preset with the same name.
+///
+/// # Arguments
+/// * `name` - The name the preset is selected with, e.g. `--preset tournament`.
+/// * `options` - The options in the format of a configuration file, e.g. `width = "80"`.
+///
+/// # Returns
+/// The path of the written file.
+pub fn save_preset(name: &str, options: Table) -> io::Result<PathBuf> {
+    let path = preset_file(name)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut table = Table::new();
+    table.insert(VERSION_KEY.to_string(), Value::Integer(CONFIG_VERSION));
+    table.extend(options);
+    write_atomically(&path, &table.to_string())?;
+
+    Ok(path)
+}
+
+fn presets_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("presets"))
+}
+
+/// # Explanation
+/// The configuration consists of all configuration files that exist. Their options are applied
+/// in the following order, where every step overrides the previous ones:
+///
+/// 1. the defaults of the game,
+/// 2. the configuration file of the user (`~/.config/cli_pong/config.toml`),
+/// 3. the `.cli_pong.toml` in the current directory, e.g. to pin the settings of a tournament,
+/// 4. the preset selected with `--preset`, which is stored in `~/.config/cli_pong/presets/`,
+/// 5. the environment variables starting with `CLI_PONG_`,
+/// 6. the command line.
+///
+/// Within a file, the options of the selected profile override its global options.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    files: Vec<ConfigFile>,
+    env_args: Vec<String>,
+}
+
+/// # Explanation
+/// A layer holds the command-line arguments converted from one source of the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigLayer {
+    /// Describes where the arguments come from, e.g. the path of the file.
+    pub origin: String,
+    pub args: Vec<String>,
+}
+
+impl Config {
+    /// Reads all configuration files that exist.
+    pub fn load() -> io::Result<Self> {
+        let paths = [
+            user_config_file(),
+            Some(PathBuf::from(WORKSPACE_CONFIG_FILE)),
+        ];
+
+        let mut files = Vec::new();
+        for path in paths.into_iter().flatten() {
+            if !path.exists() {
+                continue;
+            }
+            // the file of a project may be shared, so it is only migrated in memory
+            let file = match path.as_path() == Path::new(WORKSPACE_CONFIG_FILE) {
+                true => ConfigFile::parse(&path, &fs::read_to_string(&path)?)?,
+                false => ConfigFile::load(&path)?,
+            };
+            files.push(file);
+        }
+
+        Ok(Config {
+            files,
+            env_args: env_args(std::env::vars()),
+        })
+    }
+
+    /// Adds the preset with the given name, which is applied after the configuration files.
+    pub fn add_preset(&mut self, name: &str) -> io::Result<()> {
+        let path = preset_file(name)?;
+        if !path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "unknown preset `{name}`, presets: {}",
+                    preset_names().join(", ")
+                ),
+            ));
+        }
+
+        self.files.push(ConfigFile::load(&path)?);
+        Ok(())
+    }
+
+    /// Returns `true` if one of the files switches on the strict mode with `strict-config = true`.
+    pub fn is_strict(&self) -> bool {
+        self.files.iter().any(|file| {
+            file.table.iter().any(|(key, value)| {
+                option_name(key) == STRICT_OPTION && value.as_bool() == Some(true)
+            })
+        })
+    }
+
+    /// Checks that the files only contain the given options.
+    ///
+    /// # Arguments
+    /// * `known_options` - The names of the command-line options, e.g. `width`.
+    /// * `strict` - Whether unknown and deprecated options are errors.
+    ///
+    /// # Returns
+    /// A warning for every unknown or deprecated option. In the permissive mode unknown options are
+    /// removed and deprecated options are renamed, so the game still starts.
+    pub fn check(&mut self, known_options: &[&str], strict: bool) -> io::Result<Vec<String>> {
+        let mut warnings = Vec::new();
+        for file in &mut self.files {
+            file.check(known_options, strict, &mut warnings)?;
+        }
+
+        Ok(warnings)
+    }
+
+    /// Returns the arguments set by the environment variables.
+    pub fn env_args(&self) -> &[String] {
+        &self.env_args
+    }
+
+    /// Converts the options of all files and of the environment into command-line arguments
+    /// in the order they are applied.
+    ///
+    /// # Arguments
+    /// * `profile` - The name of the selected profile. It has to exist in at least one of the files.
+    pub fn args(&self, profile: Option<&str>) -> io::Result<Vec<String>> {
+        Ok(self
+            .layers(profile)?
+            .into_iter()
+            .flat_map(|layer| layer.args)
+            .collect())
+    }
+
+    /// Converts the options of all files and of the environment into layers of command-line
+    /// arguments in the order they are applied. Sources without options are left out.
+    ///
+    /// # Arguments
+    /// * `profile` - The name of the selected profile. It has to exist in at least one of the files.
+    pub fn layers(&self, profile: Option<&str>) -> io::Result<Vec<ConfigLayer>> {
+        let mut layers = Vec::new();
+        let mut profile_found = false;
+
+        for file in &self.files {
+            let origin = file.path.display().to_string();
+            layers.push(ConfigLayer {
+                origin: origin.clone(),
+                args: file.global_args()?,
+            });
+            if let Some(name) = profile {
+                if let Some(profile_args) = file.profile_args(name)? {
+                    layers.push(ConfigLayer {
+                        origin: format!("{origin} [{PROFILE_TABLE}.{name}]"),
+                        args: profile_args,
+                    });
+                    profile_found = true;
+                }
+            }
+        }
+        layers.push(ConfigLayer {
+            origin: "environment".to_string(),
+            args: self.env_args.clone(),
+        });
+        layers.retain(|layer| !layer.args.is_empty());
+
+        match profile {
+            Some(name) if !profile_found => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "unknown profile `{name}`, profiles: {}",
+                    self.profiles().join(", ")
+                ),
+            )),
+            _ => Ok(layers),
+        }
+    }
+
+    /// Returns the names of the profiles of all files.
+    pub fn profiles(&self) -> Vec<&str> {
+        let mut profiles: Vec<&str> = self.files.iter().flat_map(ConfigFile::profiles).collect();
+        profiles.sort();
+        profiles.dedup();
+
+        profiles
+    }
+}
+
+/// # Explanation
+/// A configuration file sets the command-line options of the game, e.g. `width = 80` or
+/// `bind = ["player1-up=shift+w"]`. Flags are switched on with `true`.
+///
+/// Besides the global options, the file can contain named profiles in `[profile.<name>]` tables.
+/// The options of a selected profile are applied after the global options, so they override them.
+/// Options that may be given several times, like `bind`, are merged instead.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigFile {
+    path: PathBuf,
+    table: Table,
+}
+
+impl ConfigFile {
+    /// Reads a configuration file.
+    ///
+    /// # Remarks
+    /// A file of an older format version is migrated to the current version. If the migration
+    /// changed the options, the migrated file replaces the old one, which is kept as a backup next
+    /// to it, e.g. `config.toml.v1.bak`. The comments and the order of the options are kept. The
+    /// file is only replaced once the backup is written. If either can not be written, the old file
+    /// stays as it is and the migrated options are only used for this game.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let (file, migrated) = Self::migrate(path, &content)?;
+
+        if let Some((version, migrated)) = migrated {
+            let backup = path.with_extension(format!("toml.v{version}.bak"));
+            // a file that can not be replaced, e.g. in a read-only directory, is migrated again next time
+            if write_atomically(&backup, &content).is_ok() {
+                let _ = write_atomically(path, &migrated);
+            }
+        }
+
+        Ok(file)
+    }
+
+    /// Parses the content of a configuration file and migrates it to the current format version
+    /// in memory. Nothing is written to the disk.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the file, which is only used in error messages.
+    /// * `content` - The TOML content of the file.
+    pub fn parse(path: &Path, content: &str) -> io::Result<Self> {
+        Self::migrate(path, content).map(|(file, _)| file)
+    }
+
+    /// Parses and migrates the content of a configuration file.
+    ///
+    /// # Returns
+    /// The migrated file, and if a migration changed it, the format version of the content before
+    /// the migration together with the migrated content.
+    fn migrate(path: &Path, content: &str) -> io::Result<(Self, Option<(i64, String)>)> {
+        let mut document: DocumentMut =
+            content.parse().map_err(|error| invalid_data(path, error))?;
+
+        let version = match document.get(VERSION_KEY) {
+            Some(item) => item
+                .as_integer()
+                .ok_or_else(|| invalid_data(path, "the version has to be a number"))?,
+            None => 1,
+        };
+        if !(1..=CONFIG_VERSION).contains(&version) {
+            return Err(invalid_data(
+                path,
+                format!("format version {version} is not supported, this game reads up to version {CONFIG_VERSION}"),
+            ));
+        }
+
+        let mut migrated = None;
+        if version < CONFIG_VERSION {
+            let mut changed = false;
+            for migration in &MIGRATIONS[version as usize - 1..] {
+                changed |= migration(document.as_table_mut());
+            }
+            if changed {
+                document.insert(VERSION_KEY, toml_edit::value(CONFIG_VERSION));
+                migrated = Some((version, document.to_string()));
+            }
+        }
+
+        let table: Table =
+            toml::from_str(&document.to_string()).map_err(|error| invalid_data(path, error))?;
+        let file = ConfigFile {
+            path: path.to_path_buf(),
+            table,
+        };
+
+        Ok((file, migrated))
+    }
+
+    /// Returns the names of the profiles in the file.
+    pub fn profiles(&self) -> Vec<&str> {
+        match self.table.get(PROFILE_TABLE) {
+            Some(Value::Table(profiles)) => profiles.keys().map(String::as_str).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn check(
+        &mut self,
+        known_options: &[&str],
+        strict: bool,
+        warnings: &mut Vec<String>,
+    ) -> io::Result<()> {
+        let location = self.path.display().to_string();
+        check_table(&mut self.table, &location, known_options, strict, warnings)?;
+
+        if let Some(Value::Table(profiles)) = self.table.get_mut(PROFILE_TABLE) {
+            for (name, profile) in profiles.iter_mut() {
+                if let Value::Table(profile) = profile {
+                    let location = format!("{location} [{PROFILE_TABLE}.{name}]");
+                    check_table(profile, &location, known_options, strict, warnings)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Converts the global options of the file into command-line arguments.
+    pub fn global_args(&self) -> io::Result<Vec<String>> {
+        let mut args = Vec::new();
+        self.table_args(&self.table, &mut args)?;
+
+        Ok(args)
+    }
+
+    /// Converts the options of the given profile into command-line arguments.
+    ///
+    /// # Returns
+    /// The arguments, or `None` if the file has no profile with that name.
+    pub fn profile_args(&self, name: &str) -> io::Result<Option<Vec<String>>> {
+        let profile = match self.table.get(PROFILE_TABLE) {
+            Some(Value::Table(profiles)) => profiles.get(name),
+            _ => None,
+        };
+
+        match profile {
+            Some(Value::Table(profile)) => {
+                let mut args = Vec::new();
+                self.table_args(profile, &mut args)?;
+                Ok(Some(args))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn table_args(&self, table: &Table, args: &mut Vec<String>) -> io::Result<()> {
+        for (key, value) in table {
+            if key == PROFILE_TABLE || key == VERSION_KEY {
+                continue;
+            }
+
+            let flag = format!("--{}", option_name(key));
+            let values = match value {
+                Value::Array(values) => values.iter().collect(),
+                value => vec![value],
+            };
+            for value in values {
+                match value {
+                    Value::Boolean(true) => args.push(flag.clone()),
+                    // a switched off flag is the default
+                    Value::Boolean(false) => {}
+                    Value::String(text) => args.extend([flag.clone(), text.clone()]),
+                    Value::Integer(number) => args.extend([flag.clone(), number.to_string()]),
+                    Value::Float(number) => args.extend([flag.clone(), number.to_string()]),
+                    _ => {
+                        return Err(invalid_data(
+                            &self.path,
+                            format!("unsupported value of `{key}`"),
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks the options of a single table of a configuration file, see [`Config::check`].
+fn check_table(
+    table: &mut Table,
+    location: &str,
+    known_options: &[&str],
+    strict: bool,
+    warnings: &mut Vec<String>,
+) -> io::Result<()> {
+    let keys: Vec<String> = table.keys().cloned().collect();
+    for key in keys {
+        let name = option_name(&key);
+        if key == PROFILE_TABLE || key == VERSION_KEY || known_options.contains(&name.as_str()) {
+            continue;
+        }
+
+        let deprecated = DEPRECATED_OPTIONS
+            .iter()
+            .find(|(old_name, _)| *old_name == name);
+        let message = match deprecated {
+            Some((_, new_name)) => format!("{location}: `{key}` is deprecated, use `{new_name}`"),
+            None => format!("{location}: unknown option `{key}`"),
+        };
+        if strict {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        }
+
+        let value = table.remove(&key);
+        if let (Some((_, new_name)), Some(value)) = (deprecated, value) {
+            table.insert(new_name.to_string(), value);
+        }
+        warnings.push(message);
+    }
+
+    Ok(())
+}
+
+/// Migrates a configuration file from version 1 to version 2 by replacing the underscores in the
+/// names of the options with dashes.
+fn dash_option_names(table: &mut toml_edit::Table) -> bool {
+    fn dash_keys(table: &mut toml_edit::Table) -> bool {
+        let keys: Vec<String> = table.iter().map(|(key, _)| key.to_string()).collect();
+        if !keys.iter().any(|key| key.contains('_')) {
+            return false;
+        }
+
+        // all options are inserted again, so the renamed ones keep their place
+        let entries: Vec<(Key, Item)> = keys
+            .iter()
+            .filter_map(|key| table.remove_entry(key))
+            .collect();
+        for (key, item) in entries {
+            let name = option_name(key.get());
+            // an option that is also spelled with dashes keeps that value
+            if name != key.get() && keys.contains(&name) {
+                continue;
+            }
+            let renamed = Key::new(name).with_leaf_decor(key.leaf_decor().clone());
+            table.insert_formatted(&renamed, item);
+        }
+
+        true
+    }
+
+    let mut changed = dash_keys(table);
+    if let Some(profiles) = table.get_mut(PROFILE_TABLE).and_then(Item::as_table_mut) {
+        for (_, profile) in profiles.iter_mut() {
+            if let Some(profile) = profile.as_table_mut() {
+                changed |= dash_keys(profile);
+            }
+        }
+    }
+
+    changed
+}
+
+/// Returns the name of the command-line option a key of a configuration file stands for.
+/// Both `win-meter` and `win_meter` stand for `--win-meter`.
+fn option_name(key: &str) -> String {
+    key.replace('_', "-")
+}
+
+/// Converts the environment variables starting with [`ENV_PREFIX`] into command-line arguments,
+/// e.g. `CLI_PONG_BALL_GLOW=true` into `--ball-glow`.
+fn env_args(vars: impl IntoIterator<Item = (String, String)>) -> Vec<String> {
+    let mut vars: Vec<_> = vars
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let option = key.strip_prefix(ENV_PREFIX)?;
+            Some((option.to_lowercase().replace('_', "-"), value))
+        })
+        .collect();
+    // the order of the environment is arbitrary
+    vars.sort();
+
+    let mut args = Vec::new();
+    for (option, value) in vars {
+        let flag = format!("--{option}");
+        match value.as_str() {
+            "true" => args.push(flag),
+            "false" => {}
+            _ => args.extend([flag, value]),
+        }
+    }
+
+    args
+}
+
+fn invalid_data(path: &Path, error: impl ToString) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {}", path.display(), error.to_string()),
+    )
+}
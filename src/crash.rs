@@ -0,0 +1,106 @@
+use crate::debug::{create_timestamped, DebugHistory};
+use crossterm::terminal;
+use std::backtrace::Backtrace;
+use std::io;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// The first line of every crash report.
+const REPORT_HEADER: &str = "cli_pong crash report";
+
+/// # Explanation
+/// A crash report bundles what a bug report about a crash needs into a single file: why the game
+/// crashed and the backtrace, the platform and the terminal, the options from every source of the
+/// configuration and the debug history of the last updates. It is written once the game panicked,
+/// so the player can attach one file to an issue instead of copying a backtrace from the terminal.
+#[derive(Debug, Clone)]
+pub struct CrashReport {
+    reason: String,
+    backtrace: String,
+    sources: Vec<(String, String)>,
+    platform: Vec<(&'static str, String)>,
+}
+
+impl CrashReport {
+    /// Captures the backtrace of the current thread and describes the platform.
+    ///
+    /// # Arguments
+    /// * `reason` - Why the game crashed, e.g. the message and the location of a panic.
+    /// * `sources` - The origin and the arguments of every source of the configuration, e.g.
+    ///   `command line` and `--mode single`.
+    pub fn capture(reason: impl Into<String>, sources: Vec<(String, String)>) -> Self {
+        CrashReport {
+            reason: reason.into(),
+            backtrace: Backtrace::force_capture().to_string(),
+            sources,
+            platform: platform_info(),
+        }
+    }
+
+    /// Returns why the game crashed.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// Writes the report in a readable form into the given writer.
+    ///
+    /// # Arguments
+    /// * `writer` - The writer the report is written into.
+    /// * `history` - The `DebugHistory` of the crashed game, or `None` if it was not available.
+    pub fn write(&self, mut writer: impl Write, history: Option<&DebugHistory>) -> io::Result<()> {
+        writeln!(writer, "{REPORT_HEADER}")?;
+        writeln!(writer, "engine {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(writer, "reason {}", self.reason.replace('\n', " "))?;
+
+        writeln!(writer, "\n== platform ==")?;
+        for (name, value) in &self.platform {
+            writeln!(writer, "{name} {value}")?;
+        }
+
+        writeln!(writer, "\n== configuration ==")?;
+        for (origin, args) in &self.sources {
+            writeln!(writer, "{origin}: {args}")?;
+        }
+
+        writeln!(writer, "\n== backtrace ==")?;
+        writeln!(writer, "{}", self.backtrace.trim_end())?;
+
+        writeln!(writer, "\n== recent updates ==")?;
+        match history {
+            Some(history) if !history.is_empty() => history.write(&mut writer, &self.reason)?,
+            Some(_) => writeln!(writer, "the debug history is turned off")?,
+            None => writeln!(writer, "the debug history was not available")?,
+        }
+
+        writer.flush()
+    }
+
+    /// Writes the report into a new file in the given directory, e.g. `crash-1700000000.txt`.
+    /// The directory is created if needed and existing reports are never overwritten.
+    ///
+    /// # Returns
+    /// The path of the written file.
+    pub fn save(&self, dir: &Path, history: Option<&DebugHistory>) -> io::Result<PathBuf> {
+        let (path, file) = create_timestamped(dir, "crash")?;
+        self.write(BufWriter::new(file), history)?;
+
+        Ok(path)
+    }
+}
+
+/// Describes the operating system and the terminal the game runs in.
+fn platform_info() -> Vec<(&'static str, String)> {
+    let variable = |name: &str| std::env::var(name).unwrap_or_else(|_| "-".to_string());
+    let size = terminal::size().map_or("unknown".to_string(), |(columns, rows)| {
+        format!("{columns}x{rows}")
+    });
+
+    vec![
+        ("os", std::env::consts::OS.to_string()),
+        ("arch", std::env::consts::ARCH.to_string()),
+        ("term", variable("TERM")),
+        ("colorterm", variable("COLORTERM")),
+        ("term-program", variable("TERM_PROGRAM")),
+        ("terminal-size", size),
+    ]
+}
@@ -0,0 +1,216 @@
+use crate::input::KeyBinding;
+use crate::observer::GameObserver;
+use crate::replay::ReplayFrame;
+use crate::state::{GameEvent, GameState};
+use crossterm::event::{KeyCode, KeyEvent};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The first line of every debug dump.
+const DUMP_HEADER: &str = "cli_pong debug dump";
+
+/// The number of updates the debug history keeps by default, 30 seconds at 10 updates per second.
+pub const DEFAULT_HISTORY_LENGTH: usize = 300;
+
+/// A single update of the game as it is kept in the debug history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// The state of the game after the update.
+    pub frame: ReplayFrame,
+    /// The keys that were pressed during the update, e.g. `ctrl+r`, sorted by name.
+    pub keys: Vec<String>,
+    /// The `GameEvent`s of the update.
+    pub events: Vec<GameEvent>,
+}
+
+/// # Explanation
+/// The debug history is a ring buffer of the most recent updates of a game: the state after every
+/// update together with the pressed keys and the events. Once a game panics or a player asks for it,
+/// the history is dumped into a file, so a report of a collision or physics bug comes with the
+/// moments that led to it.
+#[derive(Debug, Clone)]
+pub struct DebugHistory {
+    entries: VecDeque<HistoryEntry>,
+    capacity: usize,
+    width: usize,
+    height: usize,
+    options: Vec<(String, String)>,
+    /// The keys and the events of the running update, which are recorded once the update ends.
+    pending_keys: HashMap<KeyCode, KeyEvent>,
+    pending_events: Vec<GameEvent>,
+}
+
+impl DebugHistory {
+    /// Constructs an empty `DebugHistory` for the given game.
+    ///
+    /// # Arguments
+    /// * `capacity` - The number of updates that are kept. Older updates are dropped.
+    /// * `state` - The `GameState` whose field size and options are written into the dumps.
+    pub fn new(capacity: usize, state: &GameState) -> Self {
+        DebugHistory {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            width: state.config().width,
+            height: state.config().height,
+            options: state.replay_options(),
+            pending_keys: HashMap::new(),
+            pending_events: Vec::new(),
+        }
+    }
+
+    /// Returns the kept updates, from the oldest to the newest.
+    pub fn entries(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter()
+    }
+
+    /// Returns the number of kept updates.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no update is kept.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Keeps an update of the game and drops the oldest update if the history is full.
+    ///
+    /// # Arguments
+    /// * `state` - The `GameState` after the update.
+    /// * `pressed_keys` - The keys that were passed to the update.
+    /// * `events` - The `GameEvent`s the update returned.
+    /// * `dt` - The `Duration` the update advanced the game by.
+    pub fn record(
+        &mut self,
+        state: &GameState,
+        pressed_keys: &HashMap<KeyCode, KeyEvent>,
+        events: &[GameEvent],
+        dt: Duration,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+
+        let mut keys: Vec<String> = pressed_keys
+            .values()
+            .map(|event| KeyBinding::with_modifiers(event.code, event.modifiers).to_string())
+            .collect();
+        keys.sort();
+        self.entries.push_back(HistoryEntry {
+            frame: state.replay_frame(dt),
+            keys,
+            events: events.to_vec(),
+        });
+    }
+
+    /// Writes the history in a readable form into the given writer.
+    ///
+    /// # Arguments
+    /// * `writer` - The writer the dump is written into.
+    /// * `reason` - Why the dump was taken, e.g. the message of a panic.
+    pub fn write(&self, mut writer: impl Write, reason: &str) -> io::Result<()> {
+        writeln!(writer, "{DUMP_HEADER}")?;
+        writeln!(writer, "engine {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(writer, "reason {}", reason.replace('\n', " "))?;
+        writeln!(writer, "field {} {}", self.width, self.height)?;
+        for (name, value) in &self.options {
+            writeln!(writer, "option {name} {value}")?;
+        }
+        writeln!(writer, "updates {}", self.entries.len())?;
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            let frame = entry.frame;
+            writeln!(
+                writer,
+                "{index} dt={}us player1_y={} player2_y={} ball=({}, {}) velocity=({}, {}) score={}:{} keys=[{}] events={:?}",
+                frame.dt.as_micros(),
+                frame.player1_y,
+                frame.player2_y,
+                frame.ball_x,
+                frame.ball_y,
+                frame.ball_vx,
+                frame.ball_vy,
+                frame.player1_score,
+                frame.player2_score,
+                entry.keys.join(", "),
+                entry.events
+            )?;
+        }
+
+        writer.flush()
+    }
+
+    /// Writes the history into a new file in the given directory. The directory is created if needed.
+    ///
+    /// # Arguments
+    /// * `dir` - The directory the dump is written into.
+    /// * `reason` - Why the dump was taken, e.g. the message of a panic.
+    ///
+    /// # Returns
+    /// The path of the written file.
+    ///
+    /// # Remarks
+    /// Existing dumps are never overwritten. The files are named after the time they were taken,
+    /// e.g. `dump-1700000000.txt`, with a counter appended for several dumps in the same second.
+    pub fn dump(&self, dir: &Path, reason: &str) -> io::Result<PathBuf> {
+        let (path, file) = create_timestamped(dir, "dump")?;
+        self.write(BufWriter::new(file), reason)?;
+
+        Ok(path)
+    }
+}
+
+impl GameObserver for DebugHistory {
+    fn on_input(
+        &mut self,
+        _state: &GameState,
+        pressed_keys: &HashMap<KeyCode, KeyEvent>,
+    ) -> io::Result<()> {
+        self.pending_keys = pressed_keys.clone();
+        Ok(())
+    }
+
+    fn on_event(&mut self, _state: &GameState, event: GameEvent) -> io::Result<()> {
+        self.pending_events.push(event);
+        Ok(())
+    }
+
+    fn on_tick(&mut self, state: &GameState, dt: Duration) -> io::Result<()> {
+        let pressed_keys = std::mem::take(&mut self.pending_keys);
+        let events = std::mem::take(&mut self.pending_events);
+        self.record(state, &pressed_keys, &events, dt);
+        Ok(())
+    }
+}
+
+/// Creates a new file in the given directory that is named after the current time, e.g.
+/// `dump-1700000000.txt` for the prefix `dump`, with a counter appended for several files in the
+/// same second. The directory is created if needed and existing files are never overwritten.
+pub(crate) fn create_timestamped(dir: &Path, prefix: &str) -> io::Result<(PathBuf, File)> {
+    std::fs::create_dir_all(dir)?;
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+
+    (0..)
+        .map(|counter| match counter {
+            0 => dir.join(format!("{prefix}-{seconds}.txt")),
+            _ => dir.join(format!("{prefix}-{seconds}-{counter}.txt")),
+        })
+        .find_map(|path| match create_new(&path) {
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => None,
+            result => Some(result.map(|file| (path, file))),
+        })
+        .expect("the counter of the file names is unbounded")
+}
+
+fn create_new(path: &Path) -> io::Result<File> {
+    OpenOptions::new().write(true).create_new(true).open(path)
+}
@@ -0,0 +1,170 @@
+use crate::mode::{EntityColor, ModeEntity};
+use crate::state::{GameConfig, Position2D, Velocity2D, MAX_BALL_SPEED};
+use clap::ValueEnum;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The number of serves a sweeping serve machine needs from one end of its angles to the other.
+const SWEEP_SERVES: usize = 6;
+
+/// The glyph the serve machine is drawn with.
+const MACHINE_GLYPH: char = '\u{25C4}';
+
+/// How a serve machine varies the serves of a drill.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServePattern {
+    /// Every serve flies at the same angle and speed.
+    Fixed,
+    /// The angle moves from the lowest to the highest angle and back, one step per serve.
+    Sweep,
+    /// Every serve flies at a random angle between the lowest and the highest angle.
+    Random,
+    /// Every serve flies at the same angle, each one faster than the one before.
+    Accelerating,
+}
+
+/// # Explanation
+/// A drill describes the serves a serve machine launches at player1: how they vary, at which
+/// angles and speeds, and how many of them there are. Drills can be written as TOML files, in
+/// which every setting that is left out keeps its default.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Drill {
+    /// How the serves vary.
+    pub pattern: ServePattern,
+    /// The angle in degrees above the horizontal of fixed and accelerating serves. Negative angles
+    /// aim below the horizontal.
+    pub angle: f64,
+    /// The lowest angle in degrees of sweeping and random serves.
+    pub min_angle: f64,
+    /// The highest angle in degrees of sweeping and random serves.
+    pub max_angle: f64,
+    /// The speed of the first serve in cells per second.
+    pub speed: f64,
+    /// The speed in cells per second each serve of an accelerating drill adds to the one before.
+    pub speed_step: f64,
+    /// The number of serves, after which the drill is over.
+    pub serves: usize,
+}
+
+impl Default for Drill {
+    fn default() -> Self {
+        Drill {
+            pattern: ServePattern::Sweep,
+            angle: 15.0,
+            min_angle: -35.0,
+            max_angle: 35.0,
+            speed: 15.0,
+            speed_step: 1.5,
+            serves: 20,
+        }
+    }
+}
+
+impl Drill {
+    /// Returns the default drill with the given pattern.
+    pub fn new(pattern: ServePattern) -> Self {
+        Drill {
+            pattern,
+            ..Drill::default()
+        }
+    }
+
+    /// Reads a drill from a TOML file.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {error}", path.display()),
+            )
+        })
+    }
+
+    /// Returns the angle in degrees and the speed in cells per second of a serve.
+    ///
+    /// # Arguments
+    /// * `index` - The number of serves before this one.
+    /// * `seed` - The seed of the game, which the random angles are drawn from.
+    pub fn serve(&self, index: usize, seed: u64) -> (f64, f64) {
+        let (low, high) = (
+            self.min_angle.min(self.max_angle),
+            self.min_angle.max(self.max_angle),
+        );
+        let angle = match self.pattern {
+            ServePattern::Fixed | ServePattern::Accelerating => self.angle,
+            ServePattern::Sweep => {
+                let step = index % (2 * SWEEP_SERVES);
+                let progress = step.min(2 * SWEEP_SERVES - step) as f64 / SWEEP_SERVES as f64;
+                low + (high - low) * progress
+            }
+            ServePattern::Random => {
+                // every serve draws from a generator of its own, so a serve does not depend on
+                // how often the ones before were served again
+                let mut rng = StdRng::seed_from_u64(seed.wrapping_add(index as u64));
+                rng.gen_range(low..=high)
+            }
+        };
+        let speed = match self.pattern {
+            ServePattern::Accelerating => self.speed + self.speed_step * index as f64,
+            _ => self.speed,
+        };
+
+        (angle, speed.clamp(1.0, MAX_BALL_SPEED))
+    }
+}
+
+/// # Explanation
+/// The serve machine stands in front of the right wall of a drill, halfway up the field, and
+/// launches the serves of its drill at player1. It is drawn as an entity of the game mode, so the
+/// ball is never served into it.
+#[derive(Debug, Copy, Clone)]
+pub struct ServeMachine {
+    drill: Drill,
+    position: Position2D,
+}
+
+impl ServeMachine {
+    /// Places the serve machine of the given drill on a field of the given config.
+    pub fn new(drill: Drill, config: &GameConfig) -> Self {
+        ServeMachine {
+            drill,
+            position: Position2D::new(
+                config.width as f64 - 2.0,
+                (config.height as f64 / 2.0).round(),
+            ),
+        }
+    }
+
+    /// Returns the drill of the machine.
+    pub fn drill(&self) -> &Drill {
+        &self.drill
+    }
+
+    /// Returns the entity the machine is drawn as.
+    pub fn entity(&self) -> ModeEntity {
+        ModeEntity {
+            position: self.position,
+            glyph: MACHINE_GLYPH,
+            color: EntityColor::Text,
+        }
+    }
+
+    /// Returns where the ball of a serve starts and how it flies towards player1.
+    ///
+    /// # Arguments
+    /// * `index` - The number of serves before this one.
+    /// * `seed` - The seed of the game.
+    pub fn launch(&self, index: usize, seed: u64) -> (Position2D, Velocity2D) {
+        let (angle, speed) = self.drill.serve(index, seed);
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let start = Position2D::new(self.position.x() - 1.0, self.position.y());
+
+        (start, Velocity2D::new(-speed * cos, speed * sin))
+    }
+}
@@ -0,0 +1,109 @@
+use crate::ai::{AiProfile, Observation};
+use crate::state::{Controller, GameConfig, GameState, PaddleCommand};
+use crate::stats::PlayerSide;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The reward the agent receives after a step.
+pub type Reward = f64;
+
+/// # Explanation
+/// A gym-style environment around the headless game. The agent controls the left paddle
+/// and plays against an AI opponent on the right.
+///
+/// Each step simulates `dt` of game time. The agent receives a reward of `1.0` for every goal
+/// it scores and `-1.0` for every goal it concedes. An episode is done once one side reached
+/// `points_to_win` or the episode ran for `max_steps` steps.
+pub struct PongEnv {
+    config: GameConfig,
+    opponent: AiProfile,
+    points_to_win: usize,
+    max_steps: usize,
+    dt: Duration,
+    episode: u64,
+    steps: usize,
+    game_state: GameState,
+}
+
+impl PongEnv {
+    /// Constructs a new `PongEnv`.
+    ///
+    /// # Arguments
+    /// * `config` - The `GameConfig` of every episode. If it contains a seed, episode `n` uses the seed plus `n`.
+    /// * `opponent` - The `AiProfile` of the opponent.
+    /// * `points_to_win` - The number of goals that ends an episode.
+    /// * `max_steps` - The number of steps after which an episode ends regardless of the score.
+    pub fn new(
+        config: GameConfig,
+        opponent: AiProfile,
+        points_to_win: usize,
+        max_steps: usize,
+    ) -> Self {
+        let game_state = Self::new_game(&config, opponent, 0);
+
+        PongEnv {
+            config,
+            opponent,
+            points_to_win,
+            max_steps,
+            dt: Duration::from_millis(100),
+            episode: 0,
+            steps: 0,
+            game_state,
+        }
+    }
+
+    /// Starts a new episode.
+    ///
+    /// # Returns
+    /// The first `Observation` of the episode.
+    pub fn reset(&mut self) -> Observation {
+        self.episode += 1;
+        self.steps = 0;
+        self.game_state = Self::new_game(&self.config, self.opponent, self.episode);
+
+        self.game_state.observation(PlayerSide::Left)
+    }
+
+    /// Moves the agent's paddle according to `action` and simulates the game for one step.
+    /// An agent can either send single steps (`Up`, `Down`, `Stay`) or a target position (`MoveTo`).
+    /// A target that is not a finite number, e.g. the NaN of a diverged agent, leaves the paddle
+    /// where it is.
+    ///
+    /// # Returns
+    /// The new `Observation`, the `Reward` of the step and whether the episode is done.
+    pub fn step(&mut self, action: PaddleCommand) -> (Observation, Reward, bool) {
+        let (agent_score_before, opponent_score_before) = self.game_state.scores();
+
+        self.game_state
+            .set_controller(PlayerSide::Left, Controller::External(action));
+        self.game_state.update(HashMap::new(), self.dt);
+        self.steps += 1;
+
+        let (agent_score, opponent_score) = self.game_state.scores();
+        let reward = (agent_score - agent_score_before) as f64
+            - (opponent_score - opponent_score_before) as f64;
+        let done = agent_score >= self.points_to_win
+            || opponent_score >= self.points_to_win
+            || self.steps >= self.max_steps;
+
+        (self.game_state.observation(PlayerSide::Left), reward, done)
+    }
+
+    /// Returns the underlying game, e.g. to render it.
+    pub fn game_state(&self) -> &GameState {
+        &self.game_state
+    }
+
+    fn new_game(config: &GameConfig, opponent: AiProfile, episode: u64) -> GameState {
+        let mut game_state = GameState::new(GameConfig {
+            player1_ai: None,
+            player2_ai: Some(opponent),
+            seed: config.seed.map(|seed| seed.wrapping_add(episode)),
+            ..config.clone()
+        });
+        game_state.set_controller(PlayerSide::Left, Controller::External(PaddleCommand::Stay));
+
+        game_state
+    }
+}
@@ -0,0 +1,414 @@
+use crate::capability::Capabilities;
+use crossterm::style::{
+    Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+};
+use crossterm::terminal::ClearType;
+use crossterm::{cursor, terminal, QueueableCommand};
+use std::io;
+use std::io::Write;
+
+/// The layers of a frame in the order they are composed, from the bottom to the top.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Layer {
+    /// The court: borders, background colors and patterns.
+    Background,
+    /// The paddles and the ball.
+    Entities,
+    /// Visual effects that tint or shade what lies beneath them.
+    Effects,
+    /// The score and other information about the game.
+    Hud,
+    /// Messages and menus drawn on top of everything else.
+    Overlay,
+}
+
+impl Layer {
+    const ALL: [Layer; 5] = [
+        Layer::Background,
+        Layer::Entities,
+        Layer::Effects,
+        Layer::Hud,
+        Layer::Overlay,
+    ];
+
+    fn index(self) -> usize {
+        match self {
+            Layer::Background => 0,
+            Layer::Entities => 1,
+            Layer::Effects => 2,
+            Layer::Hud => 3,
+            Layer::Overlay => 4,
+        }
+    }
+}
+
+/// # Explanation
+/// A cell of a layer. Everything a cell leaves unset is transparent and shows the layers beneath it.
+///
+/// A cell with a `character` covers the character and the foreground color of the layers beneath it,
+/// even if its own `foreground` is `None`, i.e. the default color of the terminal.
+/// The background color only covers the layers beneath it if it is set.
+/// `dim` and `reverse` are added to the attributes of the layers beneath it.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Cell {
+    pub character: Option<char>,
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub dim: bool,
+    pub reverse: bool,
+}
+
+impl Cell {
+    /// Constructs a cell showing the given character in the given color.
+    pub fn new(character: char, foreground: Option<Color>) -> Self {
+        Cell {
+            character: Some(character),
+            foreground,
+            ..Cell::default()
+        }
+    }
+
+    /// Constructs a cell that only changes the background color of the layers beneath it.
+    pub fn background(color: Color) -> Self {
+        Cell {
+            background: Some(color),
+            ..Cell::default()
+        }
+    }
+
+    /// Constructs a cell that only dims the layers beneath it.
+    pub fn dim() -> Self {
+        Cell {
+            dim: true,
+            ..Cell::default()
+        }
+    }
+
+    fn is_transparent(&self) -> bool {
+        *self == Cell::default()
+    }
+
+    /// Places this cell on top of the given cell.
+    fn over(self, below: Cell) -> Cell {
+        let (character, foreground) = match self.character {
+            Some(_) => (self.character, self.foreground),
+            None => (below.character, below.foreground),
+        };
+
+        Cell {
+            character,
+            foreground,
+            background: self.background.or(below.background),
+            dim: self.dim || below.dim,
+            reverse: self.reverse || below.reverse,
+        }
+    }
+}
+
+/// # Explanation
+/// A frame is a grid of cells with one grid per `Layer`. The render systems write into the layers
+/// independently of each other, then the frame composes the layers and writes the result to the
+/// terminal at once.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    layers: Vec<Vec<Cell>>,
+}
+
+impl Frame {
+    /// Constructs an empty `Frame` with the given size in terminal cells.
+    pub fn new(width: usize, height: usize) -> Self {
+        Frame {
+            width,
+            height,
+            layers: vec![vec![Cell::default(); width * height]; Layer::ALL.len()],
+        }
+    }
+
+    /// Returns the width of the frame.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the frame.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns a mutable reference to a cell of a layer, or `None` if the position is outside the frame.
+    ///
+    /// # Arguments
+    /// * `layer` - The `Layer` of the cell.
+    /// * `x` - The column of the cell, starting at the left.
+    /// * `y` - The row of the cell, starting at the top.
+    pub fn cell_mut(&mut self, layer: Layer, x: usize, y: usize) -> Option<&mut Cell> {
+        if x < self.width && y < self.height {
+            Some(&mut self.layers[layer.index()][y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// Replaces a cell of a layer. Cells outside the frame are ignored.
+    pub fn set(&mut self, layer: Layer, x: usize, y: usize, cell: Cell) {
+        if let Some(target) = self.cell_mut(layer, x, y) {
+            *target = cell;
+        }
+    }
+
+    /// Writes text into a row of a layer. Characters outside the frame are cut off.
+    ///
+    /// # Returns
+    /// The column after the last character of the text.
+    pub fn print(
+        &mut self,
+        layer: Layer,
+        x: usize,
+        y: usize,
+        text: &str,
+        foreground: Option<Color>,
+    ) -> usize {
+        let mut column = x;
+        for character in text.chars() {
+            self.set(layer, column, y, Cell::new(character, foreground));
+            column += 1;
+        }
+
+        column
+    }
+
+    /// Copies a rectangle of all layers of another frame into this frame.
+    ///
+    /// # Arguments
+    /// * `source` - The `Frame` to copy from.
+    /// * `source_x` - The left column of the rectangle in the source.
+    /// * `source_y` - The top row of the rectangle in the source.
+    /// * `x` - The left column the rectangle is copied to.
+    /// * `y` - The top row the rectangle is copied to.
+    /// * `width` - The width of the rectangle.
+    /// * `height` - The height of the rectangle.
+    ///
+    /// # Remarks
+    /// Parts of the rectangle outside of one of the frames are left out.
+    #[allow(clippy::too_many_arguments)]
+    pub fn copy_from(
+        &mut self,
+        source: &Frame,
+        source_x: usize,
+        source_y: usize,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) {
+        for row in 0..height.min(source.height.saturating_sub(source_y)) {
+            for column in 0..width.min(source.width.saturating_sub(source_x)) {
+                let index = (source_y + row) * source.width + source_x + column;
+                for layer in Layer::ALL {
+                    let cell = source.layers[layer.index()][index];
+                    self.set(layer, x + column, y + row, cell);
+                }
+            }
+        }
+    }
+
+    /// Composes the layers into the cell that is shown at the given position.
+    pub fn composed(&self, x: usize, y: usize) -> Cell {
+        let index = y * self.width + x;
+
+        self.layers
+            .iter()
+            .fold(Cell::default(), |below, layer| layer[index].over(below))
+    }
+
+    /// Composes a row of the frame without the transparent cells at its end, so short rows stay short.
+    pub fn composed_row(&self, y: usize) -> Vec<Cell> {
+        let mut row: Vec<Cell> = (0..self.width).map(|x| self.composed(x, y)).collect();
+        let length = row
+            .iter()
+            .rposition(|cell| !cell.is_transparent())
+            .map_or(0, |last| last + 1);
+        row.truncate(length);
+
+        row
+    }
+
+    /// Returns the characters of the composed frame without colors, one row per entry.
+    /// Cells without a character are spaces and every row is as wide as the frame.
+    pub fn chars(&self) -> Vec<Vec<char>> {
+        (0..self.height)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| self.composed(x, y).character.unwrap_or(' '))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Clears the terminal and writes the composed frame into the given writer.
+    pub fn flush(&self, stdout: &mut impl Write) -> io::Result<()> {
+        self.flush_adapted(stdout, &Capabilities::default())
+    }
+
+    /// Clears the terminal and writes the composed frame into the given writer, adapted to what
+    /// the terminal can show.
+    ///
+    /// # Arguments
+    /// * `stdout` - The writer the commands are queued in.
+    /// * `capabilities` - The `Capabilities` of the terminal the frame is shown in.
+    pub fn flush_adapted(
+        &self,
+        stdout: &mut impl Write,
+        capabilities: &Capabilities,
+    ) -> io::Result<()> {
+        stdout.queue(terminal::Clear(ClearType::All))?;
+        stdout.queue(cursor::Hide)?;
+        stdout.queue(cursor::MoveTo(0, 0))?;
+
+        let mut pen = Pen::new(stdout, capabilities);
+        for y in 0..self.height {
+            for cell in &self.composed_row(y) {
+                pen.print(cell)?;
+            }
+            pen.end_line()?;
+        }
+
+        stdout.queue(cursor::Show)?;
+
+        Ok(())
+    }
+
+    /// Writes only the cells that differ from the previous frame into the given writer, which is
+    /// far less output than a whole frame while little moves, e.g. over a slow connection.
+    ///
+    /// # Arguments
+    /// * `stdout` - The writer the commands are queued in.
+    /// * `previous` - The `Frame` the terminal shows right now.
+    /// * `capabilities` - The `Capabilities` of the terminal the frame is shown in.
+    ///
+    /// # Remarks
+    /// If the previous frame has another size, the whole frame is written.
+    pub fn flush_changes(
+        &self,
+        stdout: &mut impl Write,
+        previous: &Frame,
+        capabilities: &Capabilities,
+    ) -> io::Result<()> {
+        if previous.width != self.width || previous.height != self.height {
+            return self.flush_adapted(stdout, capabilities);
+        }
+
+        stdout.queue(cursor::Hide)?;
+        let mut pen = Pen::new(stdout, capabilities);
+        for y in 0..self.height {
+            let previous_row = previous.composed_row(y);
+            // the column the cursor stands in after the last printed cell of the row
+            let mut cursor_column = None;
+            for (x, (cell, before)) in self.composed_row(y).iter().zip(&previous_row).enumerate() {
+                if cell == before {
+                    continue;
+                }
+                if cursor_column != Some(x) {
+                    pen.move_to(x, y)?;
+                }
+                pen.print(cell)?;
+                cursor_column = Some(x + 1);
+            }
+        }
+        // the cursor ends up below the frame, like after writing the whole frame
+        pen.reset()?;
+        pen.move_to(0, self.height)?;
+        stdout.queue(cursor::Show)?;
+
+        Ok(())
+    }
+}
+
+/// # Explanation
+/// The pen prints cells into a writer. It only emits a change of the colors or attributes when
+/// they actually differ from the previous cell, which keeps the written frames small.
+struct Pen<'a, W: Write> {
+    stdout: &'a mut W,
+    capabilities: &'a Capabilities,
+    current: Cell,
+}
+
+impl<'a, W: Write> Pen<'a, W> {
+    fn new(stdout: &'a mut W, capabilities: &'a Capabilities) -> Self {
+        Pen {
+            stdout,
+            capabilities,
+            current: Cell::default(),
+        }
+    }
+
+    fn print(&mut self, cell: &Cell) -> io::Result<()> {
+        let cell = &Cell {
+            character: cell
+                .character
+                .map(|character| self.capabilities.glyph(character)),
+            foreground: self.capabilities.color(cell.foreground),
+            background: self.capabilities.color(cell.background),
+            ..*cell
+        };
+        if cell.foreground != self.current.foreground {
+            self.stdout
+                .queue(SetForegroundColor(cell.foreground.unwrap_or(Color::Reset)))?;
+        }
+        if cell.background != self.current.background {
+            self.stdout
+                .queue(SetBackgroundColor(cell.background.unwrap_or(Color::Reset)))?;
+        }
+        if cell.dim != self.current.dim {
+            let attribute = if cell.dim {
+                Attribute::Dim
+            } else {
+                Attribute::NormalIntensity
+            };
+            self.stdout.queue(SetAttribute(attribute))?;
+        }
+        if cell.reverse != self.current.reverse {
+            let attribute = if cell.reverse {
+                Attribute::Reverse
+            } else {
+                Attribute::NoReverse
+            };
+            self.stdout.queue(SetAttribute(attribute))?;
+        }
+        self.current = *cell;
+
+        self.stdout.queue(Print(cell.character.unwrap_or(' ')))?;
+
+        Ok(())
+    }
+
+    /// Resets all colors and attributes, so they do not bleed into the rest of the line, and starts a new line.
+    fn end_line(&mut self) -> io::Result<()> {
+        self.reset()?;
+        self.stdout.queue(Print("\r\n"))?;
+
+        Ok(())
+    }
+
+    /// Resets all colors and attributes if any are set.
+    fn reset(&mut self) -> io::Result<()> {
+        let styled = self.current.foreground.is_some()
+            || self.current.background.is_some()
+            || self.current.dim
+            || self.current.reverse;
+        if styled {
+            self.stdout.queue(ResetColor)?;
+        }
+        self.current = Cell::default();
+
+        Ok(())
+    }
+
+    /// Moves the cursor to the given cell. The colors and attributes are kept.
+    fn move_to(&mut self, x: usize, y: usize) -> io::Result<()> {
+        self.stdout.queue(cursor::MoveTo(x as u16, y as u16))?;
+
+        Ok(())
+    }
+}
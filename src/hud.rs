@@ -0,0 +1,438 @@
+use crate::frame::{Frame, Layer};
+use crate::mode::{Breakout, BREAKOUT_LIVES};
+use crate::state::{side_name, Edge, GameState};
+use crate::stats::PlayerSide;
+use crossterm::style::Color;
+use std::rc::Rc;
+
+/// The number of rows above the field the HUD is drawn into.
+pub const HUD_ROWS: usize = 3;
+
+/// The space between two widgets that share a slot.
+const WIDGET_GAP: &str = "  ";
+
+/// The number of cells of the bar of the win meter.
+const WIN_METER_WIDTH: usize = 20;
+
+/// The number of cells of the bar of a paddle.
+const PADDLE_BAR_WIDTH: usize = 10;
+
+/// Where the widgets of a slot are placed within their row.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HudAlign {
+    Left,
+    Center,
+    Right,
+}
+
+/// A place in the HUD a widget is drawn into: one of the `HUD_ROWS` rows above the field and
+/// the alignment within it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct HudSlot {
+    row: usize,
+    align: HudAlign,
+}
+
+impl HudSlot {
+    pub fn new(row: usize, align: HudAlign) -> Self {
+        HudSlot { row, align }
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn align(&self) -> HudAlign {
+        self.align
+    }
+}
+
+/// A piece of text of a widget, which may be highlighted, e.g. a score right after a goal.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HudSpan {
+    pub text: String,
+    pub highlighted: bool,
+}
+
+impl HudSpan {
+    pub fn plain(text: impl Into<String>) -> Self {
+        HudSpan {
+            text: text.into(),
+            highlighted: false,
+        }
+    }
+
+    pub fn highlighted(text: impl Into<String>, highlighted: bool) -> Self {
+        HudSpan {
+            text: text.into(),
+            highlighted,
+        }
+    }
+}
+
+/// # Explanation
+/// A widget shows one piece of information about the game in the HUD. It is asked for its text
+/// in every frame, so it always shows the current state.
+pub trait HudWidget {
+    /// Returns the text of the widget in the current frame, or no spans to hide the widget.
+    fn spans(&self, state: &GameState) -> Vec<HudSpan>;
+}
+
+/// # Explanation
+/// The HUD is the area above the field. It consists of widgets that are placed into slots, so
+/// game modes and features add their own information by adding a widget. Widgets that share a
+/// slot are drawn next to each other in the order they were added.
+#[derive(Clone, Default)]
+pub struct Hud {
+    widgets: Vec<(HudSlot, Rc<dyn HudWidget>)>,
+}
+
+impl Hud {
+    /// Returns the HUD every game starts with: the diagnostics in the first row, the score in the
+    /// second and, if enabled, the win meter in the third.
+    pub fn standard(win_meter: bool) -> Self {
+        let mut hud = Hud::default();
+        hud.add(HudSlot::new(0, HudAlign::Left), Diagnostics);
+        hud.add(HudSlot::new(1, HudAlign::Left), Score);
+        if win_meter {
+            hud.add(HudSlot::new(2, HudAlign::Left), WinMeter);
+        }
+
+        hud
+    }
+
+    /// Adds a widget to the given slot, after the widgets already in it.
+    pub fn add(&mut self, slot: HudSlot, widget: impl HudWidget + 'static) {
+        self.widgets.push((slot, Rc::new(widget)));
+    }
+
+    /// Returns the number of widgets.
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    /// Returns `true` if the HUD has no widgets.
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    /// Draws all widgets into the rows at the top of the frame.
+    ///
+    /// # Arguments
+    /// * `state` - The `GameState` the widgets show.
+    /// * `frame` - The `Frame` to draw into.
+    /// * `color` - The color of the text.
+    ///
+    /// # Remarks
+    /// Slots in rows from `HUD_ROWS` on are not drawn, as these rows belong to the field.
+    pub fn draw(&self, state: &GameState, frame: &mut Frame, color: Option<Color>) {
+        let mut slots: Vec<HudSlot> = Vec::new();
+        for (slot, _) in &self.widgets {
+            if slot.row < HUD_ROWS && !slots.contains(slot) {
+                slots.push(*slot);
+            }
+        }
+
+        for slot in slots {
+            let spans = self.slot_spans(slot, state);
+            let width: usize = spans.iter().map(|span| span.text.chars().count()).sum();
+            let mut x = match slot.align {
+                HudAlign::Left => 0,
+                HudAlign::Center => frame.width().saturating_sub(width) / 2,
+                HudAlign::Right => frame.width().saturating_sub(width),
+            };
+
+            for span in spans {
+                let end = frame.print(Layer::Hud, x, slot.row, &span.text, color);
+                if span.highlighted {
+                    for column in x..end {
+                        if let Some(cell) = frame.cell_mut(Layer::Hud, column, slot.row) {
+                            cell.reverse = true;
+                        }
+                    }
+                }
+                x = end;
+            }
+        }
+    }
+
+    /// Returns the spans of all visible widgets of a slot, separated by gaps.
+    fn slot_spans(&self, slot: HudSlot, state: &GameState) -> Vec<HudSpan> {
+        let mut spans = Vec::new();
+        for (_, widget) in self.widgets.iter().filter(|(other, _)| *other == slot) {
+            let widget_spans = widget.spans(state);
+            if widget_spans.is_empty() {
+                continue;
+            }
+            if !spans.is_empty() {
+                spans.push(HudSpan::plain(WIDGET_GAP));
+            }
+            spans.extend(widget_spans);
+        }
+
+        spans
+    }
+}
+
+/// Shows the line of diagnostic information set with `GameState::set_diagnostics`, e.g. the
+/// input latency.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Diagnostics;
+
+impl HudWidget for Diagnostics {
+    fn spans(&self, state: &GameState) -> Vec<HudSpan> {
+        state
+            .diagnostics()
+            .map(|diagnostics| vec![HudSpan::plain(diagnostics)])
+            .unwrap_or_default()
+    }
+}
+
+/// Shows the goals of both players, or the games and points of a match. A score flashes for a
+/// moment after a goal.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Score;
+
+impl HudWidget for Score {
+    fn spans(&self, state: &GameState) -> Vec<HudSpan> {
+        if let Some(score) = state.match_score() {
+            let format = score.format();
+            let (games1, games2) = score.games();
+            let (points1, points2) = score.points();
+
+            return vec![
+                HudSpan::plain(format!(
+                    "Game {} of best of {}  Games: {games1}-{games2}  Points: ",
+                    score.current_game(),
+                    format.best_of
+                )),
+                HudSpan::highlighted(points1.to_string(), state.score_flashes(PlayerSide::Left)),
+                HudSpan::plain("-"),
+                HudSpan::highlighted(points2.to_string(), state.score_flashes(PlayerSide::Right)),
+                HudSpan::plain(format!(" (to {})", format.points_to_win)),
+            ];
+        }
+
+        if state.arena().edge_players {
+            let scores = state.edge_scores();
+            let mut spans = vec![HudSpan::plain("Goals: ")];
+            for (index, (edge, score)) in Edge::ALL.into_iter().zip(scores).enumerate() {
+                let flashes = edge.side().is_some_and(|side| state.score_flashes(side));
+                if index > 0 {
+                    spans.push(HudSpan::plain(",  "));
+                }
+                spans.push(HudSpan::plain(format!("{} ", edge.player_name())));
+                spans.push(HudSpan::highlighted(score.to_string(), flashes));
+            }
+
+            return spans;
+        }
+
+        let (player1_score, player2_score) = state.scores();
+
+        let mut spans = vec![
+            HudSpan::plain("Goals of player1: "),
+            HudSpan::highlighted(
+                player1_score.to_string(),
+                state.score_flashes(PlayerSide::Left),
+            ),
+            HudSpan::plain(",  Goals of player2: "),
+            HudSpan::highlighted(
+                player2_score.to_string(),
+                state.score_flashes(PlayerSide::Right),
+            ),
+        ];
+        if let Some(limit) = state.config().score_limit {
+            spans.push(HudSpan::plain(format!(",  First to {limit}")));
+        }
+
+        spans
+    }
+}
+
+/// Shows the probability that each player wins as a bar.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct WinMeter;
+
+impl HudWidget for WinMeter {
+    fn spans(&self, state: &GameState) -> Vec<HudSpan> {
+        let probability = state.player1_win_probability();
+
+        vec![HudSpan::plain(format!(
+            "Win probability: player1 {:>3.0}% {} {:>3.0}% player2",
+            probability * 100.0,
+            bar(probability, WIN_METER_WIDTH),
+            (1.0 - probability) * 100.0
+        ))]
+    }
+}
+
+/// Shows how often the ball was hit in the current rally.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct RallyCounter;
+
+impl HudWidget for RallyCounter {
+    fn spans(&self, state: &GameState) -> Vec<HudSpan> {
+        vec![HudSpan::plain(format!("Rally: {}", state.rally_hits()))]
+    }
+}
+
+/// Shows the returns in a row of a single player against a wall, the best streak and the misses.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ReturnStreak;
+
+impl HudWidget for ReturnStreak {
+    fn spans(&self, state: &GameState) -> Vec<HudSpan> {
+        let (_, misses) = state.scores();
+        let best = state.stats().longest_rally().max(state.rally_hits());
+
+        vec![
+            HudSpan::plain(format!("Returns in a row: {}", state.rally_hits())),
+            HudSpan::plain(format!(",  Best: {best},  Misses: ")),
+            HudSpan::highlighted(misses.to_string(), state.score_flashes(PlayerSide::Right)),
+        ]
+    }
+}
+
+/// Shows the bricks that are left in a breakout game and the lives of the player.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct BreakoutStatus;
+
+impl HudWidget for BreakoutStatus {
+    fn spans(&self, state: &GameState) -> Vec<HudSpan> {
+        let (_, lost_balls) = state.scores();
+        let lives = BREAKOUT_LIVES.saturating_sub(lost_balls);
+        let bricks = state
+            .mode_as::<Breakout>()
+            .map_or(0, |breakout| breakout.bricks().len());
+
+        vec![
+            HudSpan::plain(format!("Bricks: {bricks},  Lives: ")),
+            HudSpan::highlighted(lives.to_string(), state.score_flashes(PlayerSide::Right)),
+        ]
+    }
+}
+
+/// Shows the line about the progress of the game the game mode reports, if any.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ModeStatus;
+
+impl HudWidget for ModeStatus {
+    fn spans(&self, state: &GameState) -> Vec<HudSpan> {
+        state
+            .mode()
+            .status(state)
+            .map(HudSpan::plain)
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Shows the progress of a drill: the current serve and how many serves player1 returned.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct DrillStatus;
+
+impl HudWidget for DrillStatus {
+    fn spans(&self, state: &GameState) -> Vec<HudSpan> {
+        let serves = state.config().drill.unwrap_or_default().serves;
+        let stats = state.stats();
+        let returns = stats.returns(PlayerSide::Left);
+        let served = returns + stats.misses(PlayerSide::Left);
+        let rate = match served {
+            0 => String::new(),
+            served => format!(" ({:.0}%)", 100.0 * returns as f64 / served as f64),
+        };
+
+        vec![HudSpan::plain(format!(
+            "Serve {} of {serves},  returned: {returns}{rate}",
+            (served + 1).min(serves)
+        ))]
+    }
+}
+
+/// Shows how long player1 survived in a game with a difficulty ramp, the current level and the
+/// time until the next level.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SurvivalClock;
+
+impl HudWidget for SurvivalClock {
+    fn spans(&self, state: &GameState) -> Vec<HudSpan> {
+        let Some(ramp) = state.difficulty() else {
+            return Vec::new();
+        };
+        let seconds = ramp.elapsed().as_secs();
+        let mut spans = vec![HudSpan::plain(format!(
+            "Survived: {:02}:{:02},  Level: {}",
+            seconds / 60,
+            seconds % 60,
+            ramp.level()
+        ))];
+        if !state.is_over() {
+            spans.push(HudSpan::plain(format!(
+                ",  next in {}s",
+                ramp.time_to_next_level().as_secs_f64().ceil()
+            )));
+        }
+
+        spans
+    }
+}
+
+/// Shows the simulated time since the start of the game.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct MatchClock;
+
+impl HudWidget for MatchClock {
+    fn spans(&self, state: &GameState) -> Vec<HudSpan> {
+        let seconds = state.clock().elapsed().as_secs();
+
+        vec![HudSpan::plain(format!(
+            "Time: {:02}:{:02}",
+            seconds / 60,
+            seconds % 60
+        ))]
+    }
+}
+
+/// Shows the current speed of the ball.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Speedometer;
+
+impl HudWidget for Speedometer {
+    fn spans(&self, state: &GameState) -> Vec<HudSpan> {
+        let observation = state.observation(PlayerSide::Left);
+        let speed = observation.ball_vx.hypot(observation.ball_vy);
+
+        vec![HudSpan::plain(format!("Speed: {speed:.1} cells/s"))]
+    }
+}
+
+/// Shows how much of its length the paddle of a player has left, which drops during long rallies
+/// with shrinking paddles.
+#[derive(Debug, Copy, Clone)]
+pub struct PaddleBar {
+    pub side: PlayerSide,
+}
+
+impl HudWidget for PaddleBar {
+    fn spans(&self, state: &GameState) -> Vec<HudSpan> {
+        let (length, full_length) = state.paddle_length(self.side);
+        let name = side_name(self.side);
+
+        vec![HudSpan::plain(format!(
+            "{name} {}",
+            bar(length as f64 / full_length as f64, PADDLE_BAR_WIDTH)
+        ))]
+    }
+}
+
+/// Draws a bar of the given number of cells that is filled by the given share.
+fn bar(share: f64, width: usize) -> String {
+    let filled = (share.clamp(0.0, 1.0) * width as f64).round() as usize;
+
+    format!(
+        "[{}{}]",
+        "\u{2588}".repeat(filled),
+        "\u{2591}".repeat(width - filled)
+    )
+}
@@ -0,0 +1,607 @@
+use crate::state::PaddleCommand;
+use clap::ValueEnum;
+use crossterm::event::{
+    poll, read, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEventKind,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use std::{fmt, io};
+
+/// Defines how many events are handled per frame. Further events of the frame are read but
+/// dropped, so a flood of events, e.g. text pasted without bracketed paste, can not stall the game.
+pub const MAX_EVENTS_PER_FRAME: usize = 64;
+
+/// The input received since the previous frame.
+#[derive(Debug, Clone, Default)]
+pub struct PolledInput {
+    pub pressed_keys: HashMap<KeyCode, KeyEvent>,
+    /// The time the first key event was received.
+    pub first_key_received: Option<Instant>,
+    /// The notches the mouse wheel was scrolled, upwards if positive.
+    pub wheel_notches: i32,
+    /// The number of events that were dropped, because the game does not use them
+    /// or because there were too many of them.
+    pub dropped_events: usize,
+    handled_events: usize,
+}
+
+impl PolledInput {
+    /// Collects the given events of a frame.
+    ///
+    /// # Remarks
+    /// Pasted text, focus changes, resizes and all other events the game does not use are dropped,
+    /// just like every event after the first `MAX_EVENTS_PER_FRAME` events.
+    pub fn collect(events: impl IntoIterator<Item = Event>) -> Self {
+        let mut input = PolledInput::default();
+        for event in events {
+            input.handle(event);
+        }
+
+        input
+    }
+
+    /// Reads the pending events of the terminal until no event arrives for the given time.
+    /// Reading stops at the latest after twice that time, even if events keep arriving.
+    pub fn poll(wait: Duration) -> io::Result<Self> {
+        let deadline = Instant::now() + wait * 2;
+        let mut input = PolledInput::default();
+
+        while poll(wait.min(deadline.saturating_duration_since(Instant::now())))? {
+            input.handle(read()?);
+            if Instant::now() >= deadline {
+                break;
+            }
+        }
+
+        Ok(input)
+    }
+
+    fn handle(&mut self, event: Event) {
+        if self.handled_events == MAX_EVENTS_PER_FRAME {
+            self.dropped_events += 1;
+            return;
+        }
+        self.handled_events += 1;
+
+        match event {
+            // a key that was tapped within the frame still counts as pressed
+            Event::Key(key_event)
+                if key_event.kind == KeyEventKind::Release
+                    && self.pressed_keys.contains_key(&key_event.code) => {}
+            Event::Key(key_event) => {
+                if key_event.kind != KeyEventKind::Release {
+                    self.first_key_received.get_or_insert_with(Instant::now);
+                }
+                self.pressed_keys.insert(key_event.code, key_event);
+            }
+            Event::Mouse(mouse_event) if mouse_event.kind == MouseEventKind::ScrollUp => {
+                self.wheel_notches += 1;
+            }
+            Event::Mouse(mouse_event) if mouse_event.kind == MouseEventKind::ScrollDown => {
+                self.wheel_notches -= 1;
+            }
+            _ => self.dropped_events += 1,
+        }
+    }
+}
+
+/// A key together with the modifier keys that have to be held with it, e.g. `ctrl+r`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Constructs a binding of a key without modifiers.
+    pub fn key(code: KeyCode) -> Self {
+        KeyBinding {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    /// Constructs a binding of a key that is pressed with the given modifiers.
+    ///
+    /// # Remarks
+    /// Terminals report a character typed with shift as the shifted character, so shift is
+    /// folded into the character, e.g. `shift+w` becomes `W`.
+    pub fn with_modifiers(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        match code {
+            KeyCode::Char(character) if modifiers.contains(KeyModifiers::SHIFT) => KeyBinding {
+                code: KeyCode::Char(character.to_ascii_uppercase()),
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            _ => KeyBinding { code, modifiers },
+        }
+    }
+
+    /// Returns the key of the binding without its modifiers.
+    pub fn code(&self) -> KeyCode {
+        self.code
+    }
+
+    /// Returns `true` if the key of the binding is pressed together with exactly its modifiers.
+    pub fn is_pressed(&self, pressed_keys: &HashMap<KeyCode, KeyEvent>) -> bool {
+        pressed_keys.get(&self.code).is_some_and(|event| {
+            KeyBinding::with_modifiers(event.code, event.modifiers).modifiers == self.modifiers
+        })
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    /// Parses a binding like `w`, `up`, `f1`, `shift+up` or `ctrl+r`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = text.split('+').collect();
+        let key = parts
+            .pop()
+            .filter(|key| !key.is_empty())
+            .ok_or("missing key")?;
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "shift" => KeyModifiers::SHIFT,
+                "alt" => KeyModifiers::ALT,
+                _ => return Err(format!("unknown modifier `{modifier}`")),
+            };
+        }
+
+        let lowercase = key.to_lowercase();
+        let code = match lowercase.as_str() {
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "space" => KeyCode::Char(' '),
+            "enter" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "esc" => KeyCode::Esc,
+            "backspace" => KeyCode::Backspace,
+            _ => match lowercase.strip_prefix('f').map(u8::from_str) {
+                Some(Ok(number)) if (1..=12).contains(&number) => KeyCode::F(number),
+                _ => {
+                    let mut characters = key.chars();
+                    match (characters.next(), characters.next()) {
+                        (Some(character), None) => KeyCode::Char(character),
+                        _ => return Err(format!("unknown key `{key}`")),
+                    }
+                }
+            },
+        };
+
+        Ok(KeyBinding::with_modifiers(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl+"),
+            (KeyModifiers::ALT, "alt+"),
+            (KeyModifiers::SHIFT, "shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+
+        match self.code {
+            KeyCode::Up => f.write_str("up"),
+            KeyCode::Down => f.write_str("down"),
+            KeyCode::Left => f.write_str("left"),
+            KeyCode::Right => f.write_str("right"),
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(character) => write!(f, "{character}"),
+            KeyCode::Enter => f.write_str("enter"),
+            KeyCode::Tab => f.write_str("tab"),
+            KeyCode::Esc => f.write_str("esc"),
+            KeyCode::Backspace => f.write_str("backspace"),
+            KeyCode::F(number) => write!(f, "f{number}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// The actions that can be bound to keys.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum Action {
+    Player1Up,
+    Player1Down,
+    Player2Up,
+    Player2Down,
+    /// Moves the lower paddle of the left team up in the doubles mode.
+    Teammate1Up,
+    /// Moves the lower paddle of the left team down in the doubles mode.
+    Teammate1Down,
+    /// Moves the lower paddle of the right team up in the doubles mode.
+    Teammate2Up,
+    /// Moves the lower paddle of the right team down in the doubles mode.
+    Teammate2Down,
+    /// Moves the paddle of player3 at the top edge to the left in the four-player mode.
+    Player3Left,
+    /// Moves the paddle of player3 at the top edge to the right in the four-player mode.
+    Player3Right,
+    /// Moves the paddle of player4 at the bottom edge to the left in the four-player mode.
+    Player4Left,
+    /// Moves the paddle of player4 at the bottom edge to the right in the four-player mode.
+    Player4Right,
+    /// Tilts the paddle of player1 a few degrees, so it returns the ball more upwards.
+    Player1TiltUp,
+    /// Tilts the paddle of player1 a few degrees, so it returns the ball more downwards.
+    Player1TiltDown,
+    /// Tilts the paddle of player2 a few degrees, so it returns the ball more upwards.
+    Player2TiltUp,
+    /// Tilts the paddle of player2 a few degrees, so it returns the ball more downwards.
+    Player2TiltDown,
+    /// Shows the first taunt next to the paddle of player1.
+    Player1Taunt1,
+    /// Shows the second taunt next to the paddle of player1.
+    Player1Taunt2,
+    /// Shows the third taunt next to the paddle of player1.
+    Player1Taunt3,
+    /// Shows the fourth taunt next to the paddle of player1.
+    Player1Taunt4,
+    /// Shows the first taunt next to the paddle of player2.
+    Player2Taunt1,
+    /// Shows the second taunt next to the paddle of player2.
+    Player2Taunt2,
+    /// Shows the third taunt next to the paddle of player2.
+    Player2Taunt3,
+    /// Shows the fourth taunt next to the paddle of player2.
+    Player2Taunt4,
+    /// Serves the current point again.
+    RestartPoint,
+    /// Starts the whole game again with a score of zero.
+    RestartGame,
+    /// Jumps back a few seconds in practice games.
+    Rewind,
+    /// Pauses the game or continues it.
+    Pause,
+    /// Swaps the controller of player1 between the keyboard and the computer.
+    TogglePlayer1,
+    /// Swaps the controller of player2 between the keyboard and the computer.
+    TogglePlayer2,
+    /// Swaps the controller of player3 between the keyboard and the computer.
+    TogglePlayer3,
+    /// Swaps the controller of player4 between the keyboard and the computer.
+    TogglePlayer4,
+    CycleTheme,
+    /// Starts a new game once the game is over.
+    Rematch,
+    /// Quits once the game is over.
+    Leave,
+    /// Writes the recent updates of the game into a file for a bug report.
+    DumpState,
+    Quit,
+}
+
+/// The keys bound to the actions of the game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyBindings {
+    pub player1_up: KeyBinding,
+    pub player1_down: KeyBinding,
+    pub player2_up: KeyBinding,
+    pub player2_down: KeyBinding,
+    pub teammate1_up: KeyBinding,
+    pub teammate1_down: KeyBinding,
+    pub teammate2_up: KeyBinding,
+    pub teammate2_down: KeyBinding,
+    pub player3_left: KeyBinding,
+    pub player3_right: KeyBinding,
+    pub player4_left: KeyBinding,
+    pub player4_right: KeyBinding,
+    pub player1_tilt_up: KeyBinding,
+    pub player1_tilt_down: KeyBinding,
+    pub player2_tilt_up: KeyBinding,
+    pub player2_tilt_down: KeyBinding,
+    pub player1_taunt1: KeyBinding,
+    pub player1_taunt2: KeyBinding,
+    pub player1_taunt3: KeyBinding,
+    pub player1_taunt4: KeyBinding,
+    pub player2_taunt1: KeyBinding,
+    pub player2_taunt2: KeyBinding,
+    pub player2_taunt3: KeyBinding,
+    pub player2_taunt4: KeyBinding,
+    pub restart_point: KeyBinding,
+    pub restart_game: KeyBinding,
+    pub rewind: KeyBinding,
+    pub pause: KeyBinding,
+    pub toggle_player1: KeyBinding,
+    pub toggle_player2: KeyBinding,
+    pub toggle_player3: KeyBinding,
+    pub toggle_player4: KeyBinding,
+    pub cycle_theme: KeyBinding,
+    pub rematch: KeyBinding,
+    pub leave: KeyBinding,
+    pub dump_state: KeyBinding,
+    pub quit: KeyBinding,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            player1_up: KeyBinding::key(KeyCode::Char('w')),
+            player1_down: KeyBinding::key(KeyCode::Char('s')),
+            player2_up: KeyBinding::key(KeyCode::Up),
+            player2_down: KeyBinding::key(KeyCode::Down),
+            teammate1_up: KeyBinding::key(KeyCode::Char('f')),
+            teammate1_down: KeyBinding::key(KeyCode::Char('v')),
+            teammate2_up: KeyBinding::key(KeyCode::Char('i')),
+            teammate2_down: KeyBinding::key(KeyCode::Char('k')),
+            player3_left: KeyBinding::key(KeyCode::Char('z')),
+            player3_right: KeyBinding::key(KeyCode::Char('x')),
+            player4_left: KeyBinding::key(KeyCode::Char(',')),
+            player4_right: KeyBinding::key(KeyCode::Char('.')),
+            player1_tilt_up: KeyBinding::key(KeyCode::Char('q')),
+            player1_tilt_down: KeyBinding::key(KeyCode::Char('e')),
+            player2_tilt_up: KeyBinding::key(KeyCode::Left),
+            player2_tilt_down: KeyBinding::key(KeyCode::Right),
+            player1_taunt1: KeyBinding::key(KeyCode::Char('1')),
+            player1_taunt2: KeyBinding::key(KeyCode::Char('2')),
+            player1_taunt3: KeyBinding::key(KeyCode::Char('3')),
+            player1_taunt4: KeyBinding::key(KeyCode::Char('4')),
+            player2_taunt1: KeyBinding::key(KeyCode::Char('7')),
+            player2_taunt2: KeyBinding::key(KeyCode::Char('8')),
+            player2_taunt3: KeyBinding::key(KeyCode::Char('9')),
+            player2_taunt4: KeyBinding::key(KeyCode::Char('0')),
+            restart_point: KeyBinding::key(KeyCode::Char('r')),
+            restart_game: KeyBinding::with_modifiers(KeyCode::Char('r'), KeyModifiers::CONTROL),
+            rewind: KeyBinding::key(KeyCode::Char('b')),
+            pause: KeyBinding::key(KeyCode::Char(' ')),
+            toggle_player1: KeyBinding::key(KeyCode::F(1)),
+            toggle_player2: KeyBinding::key(KeyCode::F(2)),
+            toggle_player3: KeyBinding::key(KeyCode::F(3)),
+            toggle_player4: KeyBinding::key(KeyCode::F(4)),
+            cycle_theme: KeyBinding::key(KeyCode::Char('t')),
+            // only used once the game is over, so they may share keys with the actions of the game
+            rematch: KeyBinding::key(KeyCode::Char('r')),
+            leave: KeyBinding::key(KeyCode::Char('q')),
+            dump_state: KeyBinding::key(KeyCode::F(9)),
+            quit: KeyBinding::with_modifiers(KeyCode::Char('c'), KeyModifiers::CONTROL),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Binds the given action to another key.
+    pub fn bind(&mut self, action: Action, binding: KeyBinding) {
+        *self.binding_mut(action) = binding;
+    }
+
+    fn binding_mut(&mut self, action: Action) -> &mut KeyBinding {
+        match action {
+            Action::Player1Up => &mut self.player1_up,
+            Action::Player1Down => &mut self.player1_down,
+            Action::Player2Up => &mut self.player2_up,
+            Action::Player2Down => &mut self.player2_down,
+            Action::Teammate1Up => &mut self.teammate1_up,
+            Action::Teammate1Down => &mut self.teammate1_down,
+            Action::Teammate2Up => &mut self.teammate2_up,
+            Action::Teammate2Down => &mut self.teammate2_down,
+            Action::Player3Left => &mut self.player3_left,
+            Action::Player3Right => &mut self.player3_right,
+            Action::Player4Left => &mut self.player4_left,
+            Action::Player4Right => &mut self.player4_right,
+            Action::Player1TiltUp => &mut self.player1_tilt_up,
+            Action::Player1TiltDown => &mut self.player1_tilt_down,
+            Action::Player2TiltUp => &mut self.player2_tilt_up,
+            Action::Player2TiltDown => &mut self.player2_tilt_down,
+            Action::Player1Taunt1 => &mut self.player1_taunt1,
+            Action::Player1Taunt2 => &mut self.player1_taunt2,
+            Action::Player1Taunt3 => &mut self.player1_taunt3,
+            Action::Player1Taunt4 => &mut self.player1_taunt4,
+            Action::Player2Taunt1 => &mut self.player2_taunt1,
+            Action::Player2Taunt2 => &mut self.player2_taunt2,
+            Action::Player2Taunt3 => &mut self.player2_taunt3,
+            Action::Player2Taunt4 => &mut self.player2_taunt4,
+            Action::RestartPoint => &mut self.restart_point,
+            Action::RestartGame => &mut self.restart_game,
+            Action::Rewind => &mut self.rewind,
+            Action::Pause => &mut self.pause,
+            Action::TogglePlayer1 => &mut self.toggle_player1,
+            Action::TogglePlayer2 => &mut self.toggle_player2,
+            Action::TogglePlayer3 => &mut self.toggle_player3,
+            Action::TogglePlayer4 => &mut self.toggle_player4,
+            Action::CycleTheme => &mut self.cycle_theme,
+            Action::Rematch => &mut self.rematch,
+            Action::Leave => &mut self.leave,
+            Action::DumpState => &mut self.dump_state,
+            Action::Quit => &mut self.quit,
+        }
+    }
+
+    /// Returns the keys of the taunts of player1 and of player2, in the order of `Taunt::ALL`.
+    pub fn taunt_keys(&self) -> [[KeyBinding; 4]; 2] {
+        [
+            [
+                self.player1_taunt1,
+                self.player1_taunt2,
+                self.player1_taunt3,
+                self.player1_taunt4,
+            ],
+            [
+                self.player2_taunt1,
+                self.player2_taunt2,
+                self.player2_taunt3,
+                self.player2_taunt4,
+            ],
+        ]
+    }
+
+    /// Returns the keys that move the paddles and are held down during the game.
+    pub fn direction_keys(&self) -> [KeyCode; 12] {
+        [
+            self.player1_up.code(),
+            self.player1_down.code(),
+            self.player2_up.code(),
+            self.player2_down.code(),
+            self.teammate1_up.code(),
+            self.teammate1_down.code(),
+            self.teammate2_up.code(),
+            self.teammate2_down.code(),
+            self.player3_left.code(),
+            self.player3_right.code(),
+            self.player4_left.code(),
+            self.player4_right.code(),
+        ]
+    }
+}
+
+/// Parses a key binding argument of the form `action=key`, e.g. `restart-game=ctrl+r`.
+pub fn parse_action_binding(text: &str) -> Result<(Action, KeyBinding), String> {
+    let (action, key) = text
+        .split_once('=')
+        .ok_or("expected a binding of the form `action=key`")?;
+    let action = Action::from_str(action.trim(), true)?;
+    let binding = key.trim().parse()?;
+
+    Ok((action, binding))
+}
+
+/// Defines how a paddle moves while both of its direction keys are held.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, ValueEnum)]
+pub enum OppositeKeys {
+    /// The keys cancel each other out and the paddle stays.
+    #[default]
+    Neutral,
+    /// The key pressed last decides the direction.
+    LatestWins,
+    /// The key pressed first decides the direction until it is released.
+    FirstWins,
+}
+
+/// # Explanation
+/// The keyboard input maps the pressed keys of a player to the movement of its paddle.
+/// It remembers which direction key was held on its own, so it can tell which of both
+/// held keys was pressed first.
+#[derive(Debug, Clone)]
+pub struct KeyboardInput {
+    key_up: KeyBinding,
+    key_down: KeyBinding,
+    opposite_keys: OppositeKeys,
+    held_alone: PaddleCommand,
+}
+
+impl KeyboardInput {
+    /// Constructs a new `KeyboardInput`.
+    ///
+    /// # Arguments
+    /// * `key_up` - The key that moves the paddle up.
+    /// * `key_down` - The key that moves the paddle down.
+    /// * `opposite_keys` - How the paddle moves while both keys are held.
+    pub fn new(key_up: KeyBinding, key_down: KeyBinding, opposite_keys: OppositeKeys) -> Self {
+        KeyboardInput {
+            key_up,
+            key_down,
+            opposite_keys,
+            held_alone: PaddleCommand::Stay,
+        }
+    }
+
+    /// Swaps the key that moves the paddle up with the one that moves it down.
+    pub fn mirror(&mut self) {
+        std::mem::swap(&mut self.key_up, &mut self.key_down);
+        self.held_alone = PaddleCommand::Stay;
+    }
+
+    /// Decides how the paddle should move in the current update.
+    ///
+    /// # Arguments
+    /// * `pressed_keys` - A reference to a `HashMap` containing `KeyCode`s of currently pressed keys.
+    pub fn command(&mut self, pressed_keys: &HashMap<KeyCode, KeyEvent>) -> PaddleCommand {
+        match (
+            self.key_up.is_pressed(pressed_keys),
+            self.key_down.is_pressed(pressed_keys),
+        ) {
+            (true, false) => {
+                self.held_alone = PaddleCommand::Up;
+                PaddleCommand::Up
+            }
+            (false, true) => {
+                self.held_alone = PaddleCommand::Down;
+                PaddleCommand::Down
+            }
+            (false, false) => {
+                self.held_alone = PaddleCommand::Stay;
+                PaddleCommand::Stay
+            }
+            (true, true) => match (self.opposite_keys, self.held_alone) {
+                (OppositeKeys::Neutral, _) => PaddleCommand::Stay,
+                (OppositeKeys::FirstWins, first) => first,
+                (OppositeKeys::LatestWins, PaddleCommand::Up) => PaddleCommand::Down,
+                (OppositeKeys::LatestWins, PaddleCommand::Down) => PaddleCommand::Up,
+                // both keys were pressed at once, so neither of them came first
+                (OppositeKeys::LatestWins, _) => PaddleCommand::Stay,
+            },
+        }
+    }
+}
+
+/// # Explanation
+/// The key tracker bridges the gaps between the repeated events of a held key. Many terminals
+/// do not report when a key is released, so a key counts as held as long as the operating system
+/// repeats its event. Before the first repetition there is a delay that would stop the paddle.
+/// The tracker therefore keeps a key held for a number of frames after its last event.
+///
+/// Only the given keys are tracked, keys that trigger a single action are passed through unchanged.
+#[derive(Debug, Clone)]
+pub struct KeyTracker {
+    hold_frames: usize,
+    tracked_keys: Vec<KeyCode>,
+    held: HashMap<KeyCode, (KeyEvent, usize)>,
+}
+
+impl KeyTracker {
+    /// Constructs a new `KeyTracker`.
+    ///
+    /// # Arguments
+    /// * `hold_frames` - For how many frames without an event a key still counts as held.
+    /// * `tracked_keys` - The keys that are held down during the game, e.g. the direction keys.
+    pub fn new(hold_frames: usize, tracked_keys: impl IntoIterator<Item = KeyCode>) -> Self {
+        KeyTracker {
+            hold_frames,
+            tracked_keys: tracked_keys.into_iter().collect(),
+            held: HashMap::new(),
+        }
+    }
+
+    /// Adds the tracked keys that are still held to the keys pressed in the current frame.
+    ///
+    /// # Remarks
+    /// Terminals that do report released keys end the hold right away. Released keys are removed
+    /// from the pressed keys, so an action does not trigger a second time when its key is released.
+    pub fn update(
+        &mut self,
+        mut pressed_keys: HashMap<KeyCode, KeyEvent>,
+    ) -> HashMap<KeyCode, KeyEvent> {
+        for key in &self.tracked_keys {
+            match pressed_keys.get(key) {
+                Some(event) if event.kind == KeyEventKind::Release => {
+                    pressed_keys.remove(key);
+                    self.held.remove(key);
+                }
+                Some(event) => {
+                    self.held.insert(*key, (*event, self.hold_frames));
+                }
+                None => {
+                    if let Some((event, frames)) = self.held.get_mut(key) {
+                        if *frames > 0 {
+                            *frames -= 1;
+                            pressed_keys.insert(*key, *event);
+                        } else {
+                            self.held.remove(key);
+                        }
+                    }
+                }
+            }
+        }
+        pressed_keys.retain(|_, event| event.kind != KeyEventKind::Release);
+
+        pressed_keys
+    }
+}
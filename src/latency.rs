@@ -0,0 +1,66 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Defines how many of the latest measurements the averages are based on.
+const LATENCY_SAMPLES: usize = 20;
+
+/// Defines after which time a key press that did not move a paddle is no longer measured,
+/// e.g. because the paddle already was at the border of the field.
+const LATENCY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// # Explanation
+/// The latency meter measures the time from receiving a key event until the frame in which a
+/// paddle visibly moved has been written to the terminal.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyMeter {
+    pending: Option<Instant>,
+    samples: VecDeque<Duration>,
+}
+
+impl LatencyMeter {
+    /// Registers that a key event was received at the given time.
+    /// While an earlier key event is still waiting for its frame, the new one is ignored.
+    pub fn key_received(&mut self, time: Instant) {
+        if self.pending.is_none() {
+            self.pending = Some(time);
+        }
+    }
+
+    /// Registers that a frame has been written to the terminal.
+    ///
+    /// # Arguments
+    /// * `paddle_moved` - Whether a paddle is drawn in another cell than in the previous frame.
+    pub fn frame_shown(&mut self, paddle_moved: bool) {
+        let Some(received) = self.pending else {
+            return;
+        };
+
+        let latency = received.elapsed();
+        if paddle_moved {
+            if self.samples.len() == LATENCY_SAMPLES {
+                self.samples.pop_front();
+            }
+            self.samples.push_back(latency);
+            self.pending = None;
+        } else if latency > LATENCY_TIMEOUT {
+            self.pending = None;
+        }
+    }
+
+    /// Returns a line describing the latest measurements.
+    pub fn summary(&self) -> String {
+        let Some(last) = self.samples.back() else {
+            return "Input latency: press a key to move a paddle".to_string();
+        };
+
+        let average = self.samples.iter().sum::<Duration>() / self.samples.len() as u32;
+        let max = self.samples.iter().max().copied().unwrap_or_default();
+
+        format!(
+            "Input latency: last {} ms, avg {} ms, max {} ms",
+            last.as_millis(),
+            average.as_millis(),
+            max.as_millis()
+        )
+    }
+}
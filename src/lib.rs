@@ -0,0 +1,55 @@
+//! The game logic of cli_pong, a classic Pong game for the command line interface.
+//!
+//! The simulation in [`state`] runs independently of the terminal, so it can also be driven
+//! headless, e.g. by the [`env::PongEnv`] for reinforcement learning experiments.
+//!
+//! The crate follows semantic versioning. Settings, events and variants of the rule enums are
+//! added in minor versions, which is why [`state::GameConfig`], [`state::GameEvent`],
+//! [`state::BallSpeed`], [`mutator::Mutator`] and [`ai::AiStyle`] are `#[non_exhaustive]`.
+//! Embedding code creates a config with [`state::GameConfig::builder`].
+
+pub mod achievement;
+pub mod ai;
+pub mod bench;
+pub mod browser;
+pub mod calibration;
+pub mod capability;
+pub mod caster;
+pub mod challenge;
+pub mod chaos;
+pub mod collision;
+pub mod config;
+pub mod crash;
+pub mod debug;
+pub mod drill;
+pub mod env;
+pub mod frame;
+pub mod hud;
+pub mod input;
+pub mod latency;
+pub mod library;
+pub mod mimic;
+pub mod mode;
+pub mod mutator;
+pub mod observer;
+pub mod ramp;
+pub mod replay;
+pub mod rewind;
+pub mod scoring;
+pub mod season;
+pub mod shot_chart;
+pub mod skin;
+#[cfg(unix)]
+pub mod spectate;
+pub mod state;
+pub mod stats;
+pub mod storage;
+pub mod taunt;
+pub mod telemetry;
+pub mod theme;
+pub mod timeline;
+pub mod timer;
+pub mod toast;
+pub mod units;
+pub mod utils;
+pub mod watchdog;
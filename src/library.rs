@@ -0,0 +1,197 @@
+use crate::replay::Replay;
+use crate::utils::data_dir;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The extension of the replay files the library names itself.
+pub const REPLAY_EXTENSION: &str = "replay";
+
+/// The details of a saved replay that the list of replays shows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayEntry {
+    /// The path of the replay file.
+    pub path: PathBuf,
+    /// The file name without the extension.
+    pub name: String,
+    /// The time the replay file was written last.
+    pub modified: SystemTime,
+    /// Who played on the left and on the right, e.g. `human` and `computer`,
+    /// or `None` for replays that do not record it.
+    pub players: Option<(String, String)>,
+    /// The goals of player1 and player2 at the end of the replay.
+    pub score: (usize, usize),
+    /// The game time the replay spans.
+    pub duration: Duration,
+}
+
+impl ReplayEntry {
+    /// Reads the details of the replay in the given file.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let replay = Replay::load(path)?;
+        let players = replay
+            .option("player1")
+            .zip(replay.option("player2"))
+            .map(|(left, right)| (left.to_string(), right.to_string()));
+        let score = replay
+            .frames
+            .last()
+            .map_or((0, 0), |frame| (frame.player1_score, frame.player2_score));
+
+        Ok(ReplayEntry {
+            path: path.to_path_buf(),
+            name: file_stem(path),
+            modified: fs::metadata(path)?.modified()?,
+            players,
+            score,
+            duration: replay.frames.iter().map(|frame| frame.dt).sum(),
+        })
+    }
+}
+
+/// # Explanation
+/// The replay library is the directory the replays of the player are saved in. It lists the replays
+/// with their details and renames, deletes and exports them. Files in the directory that are no
+/// replays are left alone.
+#[derive(Debug, Clone)]
+pub struct ReplayLibrary {
+    dir: PathBuf,
+}
+
+impl ReplayLibrary {
+    /// Constructs a `ReplayLibrary` of the replays in the given directory.
+    /// The directory is created when the first replay is saved.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        ReplayLibrary { dir: dir.into() }
+    }
+
+    /// Constructs the `ReplayLibrary` in the `replays` directory of the data directory,
+    /// or `None` if the data directory is unknown.
+    pub fn open_default() -> Option<Self> {
+        data_dir().map(|dir| ReplayLibrary::new(dir.join("replays")))
+    }
+
+    /// Returns the directory of the replays.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns a path for a new replay that is named after the current time, e.g.
+    /// `replay-1700000000.replay`. The directory of the library is created if needed.
+    pub fn new_replay_path(&self) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+
+        Ok((0..)
+            .map(|counter| match counter {
+                0 => format!("replay-{seconds}"),
+                _ => format!("replay-{seconds}-{counter}"),
+            })
+            .map(|name| self.dir.join(name).with_extension(REPLAY_EXTENSION))
+            .find(|path| !path.exists())
+            .expect("the counter of the file names is unbounded"))
+    }
+
+    /// Returns the details of all replays in the library, the newest first.
+    ///
+    /// # Remarks
+    /// A missing directory is an empty library. Files that can not be read as replays are skipped.
+    pub fn entries(&self) -> io::Result<Vec<ReplayEntry>> {
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+
+        let mut entries = Vec::new();
+        for file in dir {
+            let path = file?.path();
+            if path.is_file() {
+                if let Ok(entry) = ReplayEntry::read(&path) {
+                    entries.push(entry);
+                }
+            }
+        }
+        entries.sort_by(|a, b| b.modified.cmp(&a.modified).then(a.name.cmp(&b.name)));
+
+        Ok(entries)
+    }
+
+    /// Gives a replay another name. The extension of the file is kept.
+    ///
+    /// # Arguments
+    /// * `entry` - The `ReplayEntry` of the replay.
+    /// * `name` - The new name of the replay without the extension.
+    ///
+    /// # Returns
+    /// The new path of the replay.
+    ///
+    /// # Remarks
+    /// Names with path separators and names of existing files are refused, so a rename neither moves
+    /// a replay out of the library nor overwrites another replay.
+    pub fn rename(&self, entry: &ReplayEntry, name: &str) -> io::Result<PathBuf> {
+        let name = name.trim();
+        if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("`{name}` is not a valid name for a replay"),
+            ));
+        }
+
+        let target = match entry.path.extension() {
+            Some(extension) => entry
+                .path
+                .with_file_name(format!("{name}.{}", extension.to_string_lossy())),
+            None => entry.path.with_file_name(name),
+        };
+        refuse_existing(&target)?;
+        fs::rename(&entry.path, &target)?;
+
+        Ok(target)
+    }
+
+    /// Deletes a replay.
+    pub fn delete(&self, entry: &ReplayEntry) -> io::Result<()> {
+        fs::remove_file(&entry.path)
+    }
+
+    /// Copies a replay out of the library, e.g. to share it.
+    ///
+    /// # Arguments
+    /// * `entry` - The `ReplayEntry` of the replay.
+    /// * `destination` - The path of the copy, or a directory the copy is placed in under the
+    ///   file name of the replay.
+    ///
+    /// # Returns
+    /// The path of the copy. Existing files are never overwritten.
+    pub fn export(&self, entry: &ReplayEntry, destination: &Path) -> io::Result<PathBuf> {
+        let target = match entry.path.file_name() {
+            Some(file_name) if destination.is_dir() => destination.join(file_name),
+            _ => destination.to_path_buf(),
+        };
+        refuse_existing(&target)?;
+        fs::copy(&entry.path, &target)?;
+
+        Ok(target)
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn refuse_existing(path: &Path) -> io::Result<()> {
+    if path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", path.display()),
+        ));
+    }
+
+    Ok(())
+}
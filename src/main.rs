@@ -1,25 +1,112 @@
-use crate::state::GameState;
-use crate::utils::GameLoop;
-use clap::Parser;
-use crossterm::event::{poll, read, Event, KeyCode, KeyEvent, KeyModifiers};
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
-use std::collections::HashMap;
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgMatches, CommandFactory, Parser, Subcommand, ValueEnum};
+use cli_pong::achievement::{AchievementTracker, Achievements};
+use cli_pong::ai::{AiProfile, AiStyle, StrategyRegistry};
+use cli_pong::bench::{print_results, run_round_robin, BenchSettings, Contender};
+use cli_pong::browser::{BrowserAction, ReplayBrowser};
+use cli_pong::calibration::Calibration;
+use cli_pong::capability::{Capabilities, ColorDepth};
+use cli_pong::challenge::{Challenge, Scenario};
+use cli_pong::chaos::DEFAULT_CHAOS_INTERVAL;
+use cli_pong::config::{save_preset, Config};
+use cli_pong::crash::CrashReport;
+use cli_pong::debug::{DebugHistory, DEFAULT_HISTORY_LENGTH};
+use cli_pong::drill::{Drill, ServePattern};
+use cli_pong::hud::{Hud, HudAlign, HudSlot, MatchClock, PaddleBar, RallyCounter, Speedometer};
+use cli_pong::input::{
+    parse_action_binding, Action, KeyBinding, KeyBindings, KeyTracker, OppositeKeys, PolledInput,
+};
+use cli_pong::latency::LatencyMeter;
+use cli_pong::library::ReplayLibrary;
+use cli_pong::mimic::fit_mimic_profile;
+use cli_pong::mode::{GameMode, ModeRegistry};
+use cli_pong::mutator::{Mutator, Mutators, DEFAULT_MIRROR_INTERVAL};
+use cli_pong::observer::Observers;
+use cli_pong::replay::{Replay, ReplayFrame, ReplayRecorder};
+use cli_pong::scoring::MatchFormat;
+use cli_pong::season::{no_season, Season, SeasonGame, SeasonRecorder, Seasons};
+use cli_pong::shot_chart::ShotChart;
+use cli_pong::skin::Skin;
+#[cfg(unix)]
+use cli_pong::spectate::{default_socket_path, spectate, SpectatorServer};
+use cli_pong::state::{
+    BallSpeed, Controller, FieldSize, GameConfig, GameState, Handicap, DEFAULT_PADDLE_SPEED,
+};
+use cli_pong::stats::PlayerSide;
+use cli_pong::storage::Storage;
+use cli_pong::telemetry::{Telemetry, TelemetryRecorder};
+use cli_pong::theme::Themes;
+use cli_pong::timeline::{PointTimeline, TimelineAction, TimelineView};
+use cli_pong::utils::{data_dir, GameLoop};
+use cli_pong::watchdog::FrameWatchdog;
+use crossterm::cursor;
+use crossterm::event::{
+    read, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+    Event, KeyCode, KeyModifiers, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
+};
+use crossterm::terminal;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, ClearType};
+use crossterm::ExecutableCommand;
+use std::cell::RefCell;
+use std::ffi::OsString;
+use std::fs;
 use std::io;
-use std::time::Duration;
+use std::io::{IsTerminal, Write};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-mod state;
-mod utils;
+/// The terminal size percentages of the field refer to if the size of the terminal is unknown.
+const FALLBACK_TERMINAL_SIZE: (u16, u16) = (80, 24);
+
+/// The time writing a frame to the terminal may take before visual features are turned off.
+/// Half of a frame at 10 fps leaves enough time for reading the input.
+const FRAME_BUDGET: Duration = Duration::from_millis(50);
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    disable_help_flag = true,
+    args_override_self = true
+)]
 struct Args {
-    /// Width of the game window
-    #[arg(short, long, default_value_t = 60)]
-    width: usize,
+    /// Print help
+    #[arg(long, action = clap::ArgAction::Help)]
+    help: Option<bool>,
+
+    /// Applies the options of the given profile of the configuration files.
+    /// Options given on the command line override the configuration file.
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Applies the options of a preset saved with `config save-preset`.
+    /// Options given on the command line override the preset.
+    #[arg(long)]
+    preset: Option<String>,
+
+    /// Treats unknown and deprecated options in the configuration files as errors instead of warnings,
+    /// e.g. to make sure a tournament is played with the intended settings.
+    /// Can also be switched on with `strict-config = true` in a configuration file.
+    #[arg(long)]
+    strict_config: bool,
+
+    /// Width of the game window in cells, or relative to the terminal like `80%`
+    #[arg(short, long, default_value_t = FieldSize::Cells(60))]
+    width: FieldSize,
+
+    /// Height of the game window in cells, or relative to the terminal like `90%`
+    #[arg(short, long, default_value_t = FieldSize::Cells(18))]
+    height: FieldSize,
 
-    /// Height of the game window
-    #[arg(short, long, default_value_t = 18)]
-    height: usize,
+    /// Resizes the field with the terminal if its size is given as a percentage.
+    #[arg(long, conflicts_with_all = ["record", "save_replay", "timeline"])]
+    fit: bool,
 
     /// Defines how much longer the player should be in the top direction.
     #[arg(short, long, default_value_t = 1)]
@@ -28,46 +115,1656 @@ struct Args {
     /// Defines how much longer the player should be in the bottom direction.
     #[arg(short, long, default_value_t = 1)]
     down_extend_player_height: usize,
+
+    /// The speed preset of the ball.
+    #[arg(long, value_enum, default_value_t = BallSpeed::Normal)]
+    ball_speed: BallSpeed,
+
+    /// The maximal speed of the paddles in cells per second.
+    #[arg(long, default_value_t = DEFAULT_PADDLE_SPEED)]
+    paddle_speed: f64,
+
+    /// Sets the paddle speed relative to the serve speed of the ball, so a faster ball stays returnable.
+    /// Overrides `--paddle-speed`.
+    #[arg(long)]
+    balance: bool,
+
+    /// Handicap: how much longer the paddle of player1 is in both directions. Overrides
+    /// `--up-extend-player-height` and `--down-extend-player-height` for player1.
+    #[arg(long, value_name = "CELLS")]
+    p1_extend: Option<usize>,
+
+    /// Handicap: how much longer the paddle of player2 is in both directions.
+    #[arg(long, value_name = "CELLS")]
+    p2_extend: Option<usize>,
+
+    /// Handicap: the maximal speed of the paddle of player1 in cells per second. Overrides
+    /// `--paddle-speed` and `--balance` for player1.
+    #[arg(long, value_name = "CELLS_PER_SECOND")]
+    p1_speed: Option<f64>,
+
+    /// Handicap: the maximal speed of the paddle of player2 in cells per second.
+    #[arg(long, value_name = "CELLS_PER_SECOND")]
+    p2_speed: Option<f64>,
+
+    /// Handicap: the goals player1 starts each game with.
+    #[arg(long, value_name = "GOALS", default_value_t = 0)]
+    p1_start_score: usize,
+
+    /// Handicap: the goals player2 starts each game with.
+    #[arg(long, value_name = "GOALS", default_value_t = 0)]
+    p2_start_score: usize,
+
+    /// The factor the speed of the ball is multiplied with when a paddle hits it, e.g. `0.95` or `95%`.
+    /// Below 100% defensive returns slow the ball down, above 100% every return is faster.
+    #[arg(long, default_value_t = 1.0, value_parser = parse_restitution)]
+    restitution: f64,
+
+    /// Lets a moving paddle put spin on the ball, which curves its flight towards the direction
+    /// the paddle moved in. The spin fades with every bounce at a wall.
+    #[arg(long)]
+    spin: bool,
+
+    /// Turns every bounce at the walls and the paddles by a small random angle, from `0` (off) to `1`.
+    /// Breaks up rallies that would repeat forever, e.g. between two computer players.
+    #[arg(long, default_value_t = 0.0, value_parser = parse_jitter)]
+    jitter: f64,
+
+    /// Pulls the ball down with the given acceleration in cells per second squared, e.g. `20`, so
+    /// the rallies fly in arcs. The bounces at the walls are damped a little under gravity.
+    #[arg(long, value_name = "CELLS_PER_S2", default_value_t = 0.0, value_parser = parse_gravity)]
+    gravity: f64,
+
+    /// Shrinks both paddles by one cell every 20 hits within the same rally.
+    #[arg(long)]
+    shrink_paddles: bool,
+
+    /// Plays a match in which a game is won with the given number of goals. Defaults to 11 if
+    /// only `--best-of` is given.
+    #[arg(long, value_name = "POINTS", value_parser = clap::value_parser!(u64).range(1..))]
+    points_to_win: Option<u64>,
+
+    /// Plays a match of the given odd number of games, which the first player to win the
+    /// majority of them wins. Defaults to a single game if only `--points-to-win` is given.
+    #[arg(long, value_name = "GAMES", value_parser = parse_best_of)]
+    best_of: Option<usize>,
+
+    /// Ends the game once a player scored the given number of goals. Press `r` for a rematch or
+    /// `q` to quit.
+    #[arg(
+        long,
+        value_name = "GOALS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with_all = ["points_to_win", "best_of"]
+    )]
+    score_limit: Option<u64>,
+
+    /// Shows a live estimate of each player's chance to win.
+    #[arg(long)]
+    win_meter: bool,
+
+    /// Binds an action to another key, e.g. `--bind player1-up=shift+w` or `--bind restart-game=ctrl+n`.
+    /// Keys can be combined with `ctrl`, `shift` and `alt`. Can be given several times.
+    ///
+    /// Actions: player1-up, player1-down, player2-up, player2-down, teammate1-up, teammate1-down,
+    /// teammate2-up, teammate2-down, player3-left, player3-right, player4-left, player4-right,
+    /// player1-tilt-up, player1-tilt-down, player2-tilt-up, player2-tilt-down, player1-taunt1,
+    /// player1-taunt2, player1-taunt3, player1-taunt4, player2-taunt1, player2-taunt2,
+    /// player2-taunt3, player2-taunt4, restart-point, restart-game, rewind, pause, toggle-player1,
+    /// toggle-player2, toggle-player3, toggle-player4, cycle-theme, rematch, leave, dump-state, quit.
+    #[arg(long = "bind", value_name = "ACTION=KEY", value_parser = parse_action_binding)]
+    bindings: Vec<(Action, KeyBinding)>,
+
+    /// For how many frames a direction key counts as held after its last event.
+    /// Bridges the delay before the terminal repeats a held key.
+    #[arg(long, default_value_t = 0)]
+    key_hold_frames: usize,
+
+    /// How a paddle moves while both of its direction keys are held.
+    #[arg(long, value_enum, default_value_t = OppositeKeys::Neutral)]
+    opposite_keys: OppositeKeys,
+
+    /// The theme the game is drawn with. Press `t` during the game to cycle through the installed themes.
+    #[arg(long)]
+    theme: Option<String>,
+
+    /// The look of your paddle and the ball. New skins are unlocked by achievements, see `skins`.
+    #[arg(long)]
+    skin: Option<String>,
+
+    /// Lights up the cells around the ball, which makes it easier to follow on large fields.
+    #[arg(long)]
+    ball_glow: bool,
+
+    /// Picks the glyph of the ball by its position within the cell, which smooths its motion.
+    #[arg(long)]
+    smooth_ball: bool,
+
+    /// Draws every cell two terminal columns wide, which makes the paddles and the ball easier to see.
+    #[arg(long)]
+    double_width: bool,
+
+    /// Lets the mouse wheel nudge the paddle of player1 by one cell per notch.
+    #[arg(long)]
+    mouse: bool,
+
+    /// Draws with the given number of colors instead of the detected one.
+    #[arg(long, value_enum, value_name = "COLORS")]
+    force_color: Option<ColorDepth>,
+
+    /// Draws with ASCII characters only, even if the terminal seems to support Unicode.
+    #[arg(long, conflicts_with = "force_unicode")]
+    force_ascii: bool,
+
+    /// Draws with Unicode characters, even if the locale does not announce UTF-8.
+    #[arg(long)]
+    force_unicode: bool,
+
+    /// Uses the keyboard protocol of kitty, which reports released keys, without asking the terminal.
+    #[arg(long, conflicts_with = "force_legacy_keyboard")]
+    force_kitty_keyboard: bool,
+
+    /// Never uses the keyboard protocol of kitty, even if the terminal supports it.
+    #[arg(long)]
+    force_legacy_keyboard: bool,
+
+    /// Shows the time from a key press until the paddle visibly moves, to tune the terminal for responsiveness.
+    #[arg(long)]
+    latency_overlay: bool,
+
+    /// Keeps all visual features on, even if the terminal is too slow to show them in time.
+    /// By default, effects, patterns and colors are turned off one after the other on slow terminals.
+    #[arg(long)]
+    no_auto_degrade: bool,
+
+    /// Does not print the chart of where the goals crossed the goal lines after the game.
+    #[arg(long)]
+    no_shot_chart: bool,
+
+    /// Shows the points of the match on a timeline after the game and plays the part of the
+    /// replay of the chosen point. Without `--record` or `--save-replay`, the game is recorded to a
+    /// temporary replay that is removed afterwards.
+    #[arg(long)]
+    timeline: bool,
+
+    /// Draws the field with the scanlines and the flicker of an old CRT screen.
+    #[arg(long)]
+    crt: bool,
+
+    /// Stops the ball each time it crosses midfield, so you can move your paddle in time.
+    /// Press space to continue.
+    #[arg(long)]
+    turn_based: bool,
+
+    /// Enables the practice rewind: press `b` to jump back a few seconds.
+    #[arg(long)]
+    practice: bool,
+
+    /// Changes rules of the game, e.g. `--mutator no-walls,big-ball`. Can be combined with all other options.
+    #[arg(
+        long = "mutator",
+        visible_alias = "modifier",
+        value_name = "MUTATOR",
+        value_enum,
+        value_delimiter = ','
+    )]
+    mutators: Vec<Mutator>,
+
+    /// Moves the `mirror` mutator on to the next player every given number of seconds.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = DEFAULT_MIRROR_INTERVAL.as_secs(),
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    mirror_interval: u64,
+
+    /// Fires a random event of the `chaos` mutator every given number of seconds.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = DEFAULT_CHAOS_INTERVAL.as_secs(),
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    chaos_interval: u64,
+
+    /// Adds widgets to the HUD above the field, e.g. `--hud clock,rally`.
+    #[arg(long, value_name = "WIDGET", value_enum, value_delimiter = ',')]
+    hud: Vec<HudExtra>,
+
+    /// The game mode, e.g. `versus` for two players or `single` to play against the computer,
+    /// which controls player2 with the balanced style unless `--ai-style`, `--ai-profile` or
+    /// `--ai-strategy` choose another one. In `demo`, the computer plays against itself, in
+    /// `squash`, player1 rallies against a wall, and in `breakout`, player1 breaks the bricks in
+    /// place of player2 with three lives. In `survival`, player1 holds out as long as possible
+    /// against a computer that, like the ball, gets faster every 30 seconds, and the first goal
+    /// against player1 ends the game. In `drill`, a serve machine serves the balls of `--drill`
+    /// at player1 and counts the returns. In `doubles`, two teammates share each side, and in
+    /// `four-player`, player3 and player4 guard the top and the bottom edge. F3 and F4 let the
+    /// computer move them. In `obstacles`, the ball bounces off blocks in the middle of the field,
+    /// and in `portals`, a ball that enters one of two portals leaves the other one.
+    #[arg(long, default_value = "versus")]
+    mode: String,
+
+    /// The pattern of the serves in `--mode drill`: the same serve again and again, serves that
+    /// sweep from low to high angles, random angles or faster and faster serves.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "PATTERN",
+        conflicts_with = "drill_file"
+    )]
+    drill: Option<ServePattern>,
+
+    /// Reads the drill of `--mode drill` from the given TOML file, e.g. with `pattern = "random"`,
+    /// `min_angle = -20.0`, `max_angle = 40.0`, `speed = 18.0` and `serves = 30`.
+    #[arg(long, value_name = "PATH")]
+    drill_file: Option<PathBuf>,
+
+    /// The number of serves of a drill. Overrides the number of the drill file.
+    #[arg(long, value_name = "SERVES")]
+    drill_serves: Option<usize>,
+
+    /// Lets the computer control player2 with the given playing style.
+    #[arg(long, value_enum, conflicts_with = "ai_profile")]
+    ai_style: Option<AiStyle>,
+
+    /// Lets the computer control player2 with the AI profile stored in the given file.
+    #[arg(long)]
+    ai_profile: Option<PathBuf>,
+
+    /// Lets the computer control player2 with the named strategy, e.g. `follow-ball` or `predictive`.
+    #[arg(long, conflicts_with_all = ["ai_style", "ai_profile"])]
+    ai_strategy: Option<String>,
+
+    /// Lets the AI of player2 look at the ball only every given number of milliseconds. Starts
+    /// from the balanced style unless `--ai-style` or `--ai-profile` choose another one.
+    #[arg(long, value_name = "MS", conflicts_with = "ai_strategy")]
+    ai_reaction_ms: Option<u64>,
+
+    /// Lets the AI of player2 miss its target by a gaussian error with the given standard
+    /// deviation in cells. Starts from the balanced style unless `--ai-style` or `--ai-profile`
+    /// choose another one.
+    #[arg(long, value_name = "SIGMA", conflicts_with = "ai_strategy")]
+    ai_error_sigma: Option<f64>,
+
+    /// Records the game to the given replay file.
+    #[arg(long)]
+    record: Option<PathBuf>,
+
+    /// Records the game into the replay library, which the `replays` command browses.
+    #[arg(long, conflicts_with = "record")]
+    save_replay: bool,
+
+    /// The number of recent updates kept for debug dumps, which are written when F9 is pressed,
+    /// and for the crash reports the game offers when it crashes. 0 turns the dumps off and leaves
+    /// the updates out of the crash reports.
+    #[arg(long, value_name = "UPDATES", default_value_t = DEFAULT_HISTORY_LENGTH)]
+    debug_history: usize,
+
+    /// The directory debug dumps and crash reports are written into. Defaults to `dumps` in the
+    /// data directory.
+    #[arg(long)]
+    dump_dir: Option<PathBuf>,
+
+    /// Lets other terminals watch the game with the `spectate` command.
+    #[cfg(unix)]
+    #[arg(long)]
+    allow_spectators: bool,
+
+    /// The socket spectators connect to. Defaults to `cli_pong.sock` in the runtime directory.
+    #[cfg(unix)]
+    #[arg(long)]
+    spectator_socket: Option<PathBuf>,
+
+    /// The names of player1 and player2 that spectators watching with `spectate --caster` see.
+    /// Defaults to the players of the fixture in a season game.
+    #[cfg(unix)]
+    #[arg(long, value_name = "NAME,NAME", value_delimiter = ',')]
+    player_names: Vec<String>,
+
+    /// Seeds the random number generator to make the game reproducible.
+    #[arg(long)]
+    seed: Option<u64>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Fits an AI profile that imitates a human player from recorded replays.
+    FitMimic {
+        /// The replay files recorded with `--record`.
+        #[arg(required = true)]
+        replays: Vec<PathBuf>,
+
+        /// The side the human player played on in the replays.
+        #[arg(long, value_enum, default_value_t = PlayerSide::Left)]
+        side: PlayerSide,
+
+        /// The file the fitted AI profile is written to. Use it with `--ai-profile`.
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+
+    /// Runs headless round-robin matches between AI profiles and prints their results.
+    AiBench {
+        /// Names of built-in AI styles or paths to AI profile files. Defaults to all built-in styles.
+        contenders: Vec<String>,
+
+        /// The number of seeds every pairing plays. Each seed is played once from each side.
+        #[arg(long, default_value_t = 10)]
+        games: u64,
+
+        /// The first seed of the seed set.
+        #[arg(long, default_value_t = 0)]
+        first_seed: u64,
+
+        /// The number of points needed to win a match.
+        #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+        points: u64,
+    },
+
+    /// Measures your reactions over a series of serves and recommends fitting settings.
+    Calibrate,
+
+    /// Plays the challenge described by a scenario file, e.g. coming back from 0-9, and tells
+    /// whether you mastered it.
+    Challenge {
+        /// The TOML file of the scenario.
+        scenario: PathBuf,
+    },
+
+    /// Lists the skins and the achievements that unlock them.
+    Skins,
+
+    /// Plays a season for local club play: a round robin among the profiles of the configuration
+    /// over as many sessions as you like, with a table and a champion at the end.
+    Season {
+        #[command(subcommand)]
+        command: SeasonCommand,
+    },
+
+    /// Inspects the configuration.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Collects anonymous gameplay metrics on this computer if you turn it on, e.g. the modes you
+    /// play and the average rally length. Nothing leaves your computer unless you export it.
+    Telemetry {
+        #[command(subcommand)]
+        command: TelemetryCommand,
+    },
+
+    /// Lists the saved replays to play, rename, delete or export them.
+    Replays {
+        /// The directory of the replays. Defaults to `replays` in the data directory.
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+
+    /// Watches a game running in another terminal that was started with `--allow-spectators`.
+    #[cfg(unix)]
+    Spectate {
+        /// The socket of the running game. Defaults to `cli_pong.sock` in the runtime directory.
+        #[arg(long)]
+        socket: Option<PathBuf>,
+
+        /// Watches the game presented for an audience, e.g. on a projector: a big score, the
+        /// names of the players, a rally counter and a slow-motion replay of every goal.
+        #[arg(long)]
+        caster: bool,
+    },
+}
+
+/// The optional widgets of the HUD.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+enum HudExtra {
+    /// The simulated time since the start of the game.
+    Clock,
+    /// The number of hits in the current rally.
+    Rally,
+    /// The speed of the ball.
+    Speed,
+    /// The remaining length of both paddles.
+    Paddles,
+}
+
+impl HudExtra {
+    /// Adds the widget to the HUD of the game.
+    fn add_to(self, hud: &mut Hud) {
+        match self {
+            HudExtra::Clock => hud.add(HudSlot::new(0, HudAlign::Right), MatchClock),
+            HudExtra::Rally => hud.add(HudSlot::new(1, HudAlign::Right), RallyCounter),
+            HudExtra::Speed => hud.add(HudSlot::new(2, HudAlign::Right), Speedometer),
+            HudExtra::Paddles => {
+                let slot = HudSlot::new(0, HudAlign::Center);
+                hud.add(
+                    slot,
+                    PaddleBar {
+                        side: PlayerSide::Left,
+                    },
+                );
+                hud.add(
+                    slot,
+                    PaddleBar {
+                        side: PlayerSide::Right,
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Prints the effective value of every option and whether it comes from the defaults,
+    /// a configuration file, the environment or the command line.
+    Show,
+
+    /// Saves the effective options, except for the defaults, as a preset that can be selected
+    /// later with `--preset <name>`, e.g. `cli_pong --width 80 --crt config save-preset retro`.
+    SavePreset {
+        /// The name of the preset.
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SeasonCommand {
+    /// Schedules a new season among the given profiles, e.g. `season start alice bob carol`.
+    /// A finished season is kept with the past ones.
+    Start {
+        /// The profiles of the configuration that play the season.
+        #[arg(required = true, num_args = 2..)]
+        players: Vec<String>,
+
+        /// The name of the season. Defaults to `Season <number>`.
+        #[arg(long)]
+        name: Option<String>,
+
+        /// The number of goals needed to win a fixture.
+        #[arg(long, default_value_t = 5)]
+        points: usize,
+
+        /// Drops the current season if it is not finished yet.
+        #[arg(long)]
+        replace: bool,
+    },
+
+    /// Plays the next fixture of the season. The home player plays on the left.
+    Play,
+
+    /// Prints the table of the season and the fixtures that are left.
+    Table,
+}
+
+#[derive(Subcommand, Debug)]
+enum TelemetryCommand {
+    /// Starts to collect the metrics of the following games.
+    Enable,
+
+    /// Stops to collect metrics. The metrics collected so far are kept until `reset`.
+    Disable,
+
+    /// Prints whether the telemetry is on and the metrics collected so far.
+    Show,
+
+    /// Writes the metrics collected so far as a JSON report to the given file.
+    Export {
+        /// The file the report is written to.
+        path: PathBuf,
+    },
+
+    /// Deletes the metrics collected so far.
+    Reset,
 }
 
 fn main() -> io::Result<()> {
-    let args = Args::parse();
+    let (binary, layers) = argument_layers()?;
+    let args = parse_args(&binary, &layers);
+
+    match args.command {
+        Some(Command::FitMimic {
+            ref replays,
+            side,
+            ref output,
+        }) => fit_mimic(replays, side, output),
+        Some(Command::AiBench {
+            ref contenders,
+            games,
+            first_seed,
+            points,
+        }) => ai_bench(&args, contenders, games, first_seed, points),
+        Some(Command::Calibrate) => calibrate(&args),
+        Some(Command::Challenge { ref scenario }) => {
+            let challenge = Challenge::new(Scenario::load(scenario)?);
+            play(
+                args,
+                &layers,
+                Some(Box::new(challenge)),
+                Observers::default(),
+            )
+        }
+        Some(Command::Config {
+            command: ConfigCommand::Show,
+        }) => config_show(&binary, &layers),
+        Some(Command::Config {
+            command: ConfigCommand::SavePreset { ref name },
+        }) => config_save_preset(&binary, &layers, name),
+        Some(Command::Skins) => skins(),
+        Some(Command::Season { ref command }) => match command {
+            SeasonCommand::Start {
+                players,
+                name,
+                points,
+                replace,
+            } => season_start(players, name.as_deref(), *points, *replace),
+            SeasonCommand::Play => season_play(args, &layers),
+            SeasonCommand::Table => season_table(),
+        },
+        Some(Command::Telemetry { ref command }) => telemetry(command),
+        Some(Command::Replays { ref dir }) => replays(&args, dir.as_deref()),
+        #[cfg(unix)]
+        Some(Command::Spectate { ref socket, caster }) => {
+            spectate(&socket.clone().unwrap_or_else(default_socket_path), caster)
+        }
+        None => play(args, &layers, None, Observers::default()),
+    }
+}
+
+/// Parses the command-line arguments on top of the options of the configuration.
+fn parse_args(binary: &OsString, layers: &[ArgumentLayer]) -> Args {
+    let args = std::iter::once(binary).chain(layers.iter().flat_map(|(_, args)| args));
+
+    Args::parse_from(args)
+}
+
+/// The origin and the arguments of one source of the configuration.
+type ArgumentLayer = (String, Vec<OsString>);
+
+/// Returns the binary and the arguments of every source of the configuration in the order they are
+/// applied. The command line is the last source.
+fn argument_layers() -> io::Result<(OsString, Vec<ArgumentLayer>)> {
+    let mut cli_args: Vec<OsString> = std::env::args_os().collect();
+    let binary = cli_args.remove(0);
+
+    let mut config = Config::load()?;
+    // the environment and the command line decide which profile of the configuration files is used
+    let early_args = Args::parse_from(
+        std::iter::once(binary.clone())
+            .chain(config.env_args().iter().map(OsString::from))
+            .chain(cli_args.iter().cloned()),
+    );
+    let profile = early_args.profile;
+    if let Some(name) = &early_args.preset {
+        config.add_preset(name)?;
+    }
+
+    let command = Args::command();
+    let known_options: Vec<&str> = command
+        .get_arguments()
+        .filter_map(|arg| arg.get_long())
+        .collect();
+    let strict = early_args.strict_config || config.is_strict();
+    for warning in config.check(&known_options, strict)? {
+        eprintln!("Configuration warning: {warning}");
+    }
+
+    let mut layers: Vec<_> = config
+        .layers(profile.as_deref())?
+        .into_iter()
+        .map(|layer| {
+            let args = layer.args.into_iter().map(OsString::from).collect();
+            (layer.origin, args)
+        })
+        .collect();
+    layers.push(("command line".to_string(), cli_args));
+
+    Ok((binary, layers))
+}
+
+/// Returns the matches of the arguments of all sources of the configuration.
+fn merged_matches(binary: &OsString, layers: &[ArgumentLayer]) -> ArgMatches {
+    Args::command()
+        .get_matches_from(std::iter::once(binary).chain(layers.iter().flat_map(|(_, args)| args)))
+}
+
+/// Saves the options that differ from the defaults as the preset with the given name.
+fn config_save_preset(binary: &OsString, layers: &[ArgumentLayer], name: &str) -> io::Result<()> {
+    let matches = merged_matches(binary, layers);
+
+    let mut options = toml::Table::new();
+    for arg in Args::command().get_arguments() {
+        let (Some(option), id) = (arg.get_long(), arg.get_id().as_str()) else {
+            continue;
+        };
+        // a preset must not depend on the other sources it could be combined with
+        if matches!(option, "help" | "profile" | "preset") {
+            continue;
+        }
+        if !matches!(
+            matches.value_source(id),
+            Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+        ) {
+            continue;
+        }
+
+        let values: Vec<String> = matches
+            .get_raw(id)
+            .into_iter()
+            .flatten()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect();
+        let value = match arg.get_action() {
+            ArgAction::SetTrue => toml::Value::Boolean(true),
+            ArgAction::Append => {
+                toml::Value::Array(values.into_iter().map(toml::Value::String).collect())
+            }
+            _ => match values.into_iter().next() {
+                Some(value) => toml::Value::String(value),
+                None => continue,
+            },
+        };
+        options.insert(option.to_string(), value);
+    }
+
+    let path = save_preset(name, options)?;
+    println!("Saved preset `{name}` to {}", path.display());
+    Ok(())
+}
+
+/// Prints the effective value of every option together with the source it comes from.
+fn config_show(binary: &OsString, layers: &[ArgumentLayer]) -> io::Result<()> {
+    let command = Args::command();
+    let matches = merged_matches(binary, layers);
+    let layer_matches: Vec<_> = layers
+        .iter()
+        .map(|(origin, args)| {
+            let matches = command
+                .clone()
+                .get_matches_from(std::iter::once(binary.clone()).chain(args.iter().cloned()));
+            (origin, matches)
+        })
+        .collect();
+
+    for arg in command.get_arguments() {
+        let (Some(name), id) = (arg.get_long(), arg.get_id().as_str()) else {
+            continue;
+        };
+        if matches!(arg.get_action(), ArgAction::Help) {
+            continue;
+        }
+
+        let values: Vec<_> = matches
+            .get_raw(id)
+            .into_iter()
+            .flatten()
+            .map(|value| value.to_string_lossy())
+            .collect();
+        let value = match values.as_slice() {
+            [] => "-".to_string(),
+            [value] => value.to_string(),
+            values => values.join(", "),
+        };
+
+        let mut sources: Vec<&str> = layer_matches
+            .iter()
+            .filter(|(_, matches)| matches.value_source(id) == Some(ValueSource::CommandLine))
+            .map(|(origin, _)| origin.as_str())
+            .collect();
+        // only the values of repeated options are merged
+        if !matches!(arg.get_action(), ArgAction::Append) {
+            sources = sources.split_off(sources.len().saturating_sub(1));
+        }
+        let source = if sources.is_empty() {
+            "default".to_string()
+        } else {
+            sources.join(" + ")
+        };
+
+        println!("{name:<26} {value:<20} {source}");
+    }
+
+    Ok(())
+}
+
+fn ai_bench(
+    args: &Args,
+    contenders: &[String],
+    games: u64,
+    first_seed: u64,
+    points: u64,
+) -> io::Result<()> {
+    let contenders = if contenders.is_empty() {
+        AiStyle::value_variants()
+            .iter()
+            .filter_map(|style| style.to_possible_value())
+            .map(|value| Contender::resolve(value.get_name()))
+            .collect::<io::Result<Vec<_>>>()?
+    } else {
+        contenders
+            .iter()
+            .map(|name| Contender::resolve(name))
+            .collect::<io::Result<Vec<_>>>()?
+    };
+
+    let (width, height) = field_size(args);
+    let settings = BenchSettings {
+        width,
+        height,
+        points_to_win: points as usize,
+        max_match_time: Duration::from_secs(600),
+        seeds: (first_seed..first_seed + games).collect(),
+    };
+
+    let results = run_round_robin(&contenders, &settings);
+    print_results(&contenders, &results);
+    Ok(())
+}
+
+fn fit_mimic(replays: &[PathBuf], side: PlayerSide, output: &Path) -> io::Result<()> {
+    let replays = replays
+        .iter()
+        .map(|path| Replay::load(path))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let profile = fit_mimic_profile(&replays, side);
+    profile.save(output)?;
+
+    println!("Wrote mimic AI profile to {}", output.display());
+    Ok(())
+}
+
+fn calibrate(args: &Args) -> io::Result<()> {
+    let mut game_state = GameState::new(game_config(args)?);
+    game_state.set_capabilities(capabilities(args, false));
+    let mut calibration = Calibration::start(&mut game_state);
+    let bindings = key_bindings(args);
 
     enable_raw_mode()?;
 
-    let mut game_state = GameState::new(
+    for _ in GameLoop::from_fps(10) {
+        let key_events = PolledInput::poll(Duration::from_millis(20))
+            .unwrap_or_default()
+            .pressed_keys;
+
+        if bindings.quit.is_pressed(&key_events) {
+            break;
+        }
+
+        calibration.update(&mut game_state, key_events, Duration::from_millis(100));
+        if calibration.is_finished() {
+            break;
+        }
+
+        game_state
+            .display()
+            .unwrap_or_else(|_| println!("Failed to display!"));
+        print!("{}\r\n", calibration.progress());
+        io::stdout().flush()?;
+    }
+
+    disable_raw_mode()?;
+
+    let result = calibration.result();
+    println!();
+    println!("Returned serves: {:.0}%", result.return_rate * 100.0);
+    println!("Reaction time:   {:.2}s", result.reaction_time);
+    println!("Tracking error:  {:.1} cells", result.tracking_error);
+    println!(
+        "Recommended settings: --ai-style {} --ball-speed {}",
+        value_name(result.recommended_ai_style()),
+        value_name(result.recommended_ball_speed())
+    );
+    Ok(())
+}
+
+fn value_name(value: impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn key_bindings(args: &Args) -> KeyBindings {
+    let mut bindings = KeyBindings::default();
+    for &(action, binding) in &args.bindings {
+        bindings.bind(action, binding);
+    }
+
+    bindings
+}
+
+/// Detects what the terminal can show and report and applies the `--force-*` options.
+///
+/// # Arguments
+/// * `args` - The command-line arguments.
+/// * `probe_keyboard` - Whether to ask the terminal for the keyboard protocol of kitty.
+fn capabilities(args: &Args, probe_keyboard: bool) -> Capabilities {
+    let forced_keyboard = args.force_kitty_keyboard || args.force_legacy_keyboard;
+    let mut capabilities = Capabilities::detect(probe_keyboard && !forced_keyboard);
+
+    if let Some(color_depth) = args.force_color {
+        capabilities.color_depth = color_depth;
+    }
+    if args.force_ascii || args.force_unicode {
+        capabilities.unicode = args.force_unicode;
+    }
+    if forced_keyboard {
+        capabilities.keyboard_enhancement = args.force_kitty_keyboard;
+    }
+
+    capabilities
+}
+
+fn game_config(args: &Args) -> io::Result<GameConfig> {
+    let drill = match (args.drill, &args.drill_file) {
+        (Some(pattern), _) => Some(Drill::new(pattern)),
+        (None, Some(path)) => Some(Drill::load(path)?),
+        (None, None) => None,
+    };
+    let drill = match args.drill_serves {
+        Some(serves) => Some(Drill {
+            serves,
+            ..drill.unwrap_or_default()
+        }),
+        None => drill,
+    };
+    let player2_ai = match (args.ai_style, &args.ai_profile) {
+        (Some(style), _) => Some(style.profile()),
+        (None, Some(path)) => Some(AiProfile::load(path)?),
+        (None, None) => None,
+    };
+    let player2_ai = match (args.ai_reaction_ms, args.ai_error_sigma) {
+        (None, None) => player2_ai,
+        (reaction_ms, error_sigma) => {
+            if error_sigma.is_some_and(|sigma| !(sigma >= 0.0 && sigma.is_finite())) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "the error sigma of the AI has to be a finite number of at least 0",
+                ));
+            }
+
+            Some(
+                player2_ai
+                    .unwrap_or_else(|| AiStyle::Balanced.profile())
+                    .humanized(reaction_ms.map(Duration::from_millis), error_sigma),
+            )
+        }
+    };
+
+    let (width, height) = field_size(args);
+    let mut config = GameConfig::builder()
+        .width(width)
+        .height(height)
+        .extend_player_height_up(args.up_extend_player_height)
+        .extend_player_height_down(args.down_extend_player_height)
+        .ball_speed(args.ball_speed)
+        .paddle_speed(args.paddle_speed)
+        .restitution(args.restitution)
+        .spin(args.spin)
+        .jitter(args.jitter)
+        .gravity(args.gravity)
+        .shrink_paddles(args.shrink_paddles)
+        .match_format(match_format(args))
+        .score_limit(args.score_limit.map(|goals| goals as usize))
+        .win_meter(args.win_meter)
+        .key_bindings(key_bindings(args))
+        .opposite_keys(args.opposite_keys)
+        .ball_glow(args.ball_glow)
+        .smooth_ball(args.smooth_ball)
+        .double_width(args.double_width)
+        .crt(args.crt)
+        .turn_based(args.turn_based)
+        .practice(args.practice)
+        .player2_ai(player2_ai)
+        .player1_handicap(Handicap {
+            extend: args.p1_extend,
+            paddle_speed: args.p1_speed,
+            start_score: args.p1_start_score,
+        })
+        .player2_handicap(Handicap {
+            extend: args.p2_extend,
+            paddle_speed: args.p2_speed,
+            start_score: args.p2_start_score,
+        })
+        .seed(args.seed)
+        .drill(drill)
+        .mutators(Mutators::new(args.mutators.iter().copied()))
+        .mirror_interval(Duration::from_secs(args.mirror_interval))
+        .chaos_interval(Duration::from_secs(args.chaos_interval))
+        .build();
+    if args.balance {
+        config.paddle_speed = config.balanced_paddle_speed();
+    }
+
+    Ok(config)
+}
+
+/// Parses a restitution given as a factor like `1.05` or as a percentage like `105%`.
+fn parse_restitution(text: &str) -> Result<f64, String> {
+    let restitution = match text.strip_suffix('%') {
+        Some(percent) => percent.parse::<f64>().map(|percent| percent / 100.0),
+        None => text.parse(),
+    }
+    .map_err(|_| format!("invalid restitution `{text}`, expected a factor like `0.95` or `95%`"))?;
+
+    match restitution > 0.0 {
+        true => Ok(restitution),
+        false => Err("the restitution has to be positive".to_string()),
+    }
+}
+
+/// Parses a jitter between `0` and `1`.
+fn parse_jitter(text: &str) -> Result<f64, String> {
+    let jitter: f64 = text
+        .parse()
+        .map_err(|_| format!("invalid jitter `{text}`, expected a number from 0 to 1"))?;
+
+    match (0.0..=1.0).contains(&jitter) {
+        true => Ok(jitter),
+        false => Err("the jitter has to be between 0 and 1".to_string()),
+    }
+}
+
+/// Parses a gravity that is not negative.
+fn parse_gravity(text: &str) -> Result<f64, String> {
+    let gravity: f64 = text
+        .parse()
+        .map_err(|_| format!("invalid gravity `{text}`, expected a number like `20`"))?;
+
+    match gravity >= 0.0 && gravity.is_finite() {
+        true => Ok(gravity),
+        false => Err("the gravity can not be negative".to_string()),
+    }
+}
+
+fn parse_best_of(text: &str) -> Result<usize, String> {
+    let best_of: usize = text.parse().map_err(|_| {
+        format!("invalid number of games `{text}`, expected an odd number like `3`")
+    })?;
+
+    match best_of % 2 == 1 {
+        true => Ok(best_of),
+        false => Err("a match has to be played over an odd number of games".to_string()),
+    }
+}
+
+/// Returns the match the players play, or `None` if neither `--points-to-win` nor `--best-of` is given.
+fn match_format(args: &Args) -> Option<MatchFormat> {
+    if args.points_to_win.is_none() && args.best_of.is_none() {
+        return None;
+    }
+
+    let default = MatchFormat::default();
+    Some(MatchFormat {
+        points_to_win: args
+            .points_to_win
+            .map_or(default.points_to_win, |points| points as usize),
+        best_of: args.best_of.unwrap_or(1),
+    })
+}
+
+/// Returns the width and the height of the field, where percentages refer to the current terminal.
+fn field_size(args: &Args) -> (usize, usize) {
+    let (columns, rows) = terminal::size().unwrap_or(FALLBACK_TERMINAL_SIZE);
+    GameState::field_size(
         args.width,
         args.height,
-        args.up_extend_player_height,
-        args.down_extend_player_height,
-    );
-    for _ in GameLoop::from_fps(10) {
-        let key_events = get_pressed_keys().unwrap_or(HashMap::new());
+        args.double_width,
+        (columns as usize, rows as usize),
+    )
+}
 
-        if let Some(key_event) = key_events.get(&KeyCode::Char('c')) {
-            if key_event.modifiers.contains(KeyModifiers::CONTROL) {
-                break;
+/// Plays a game with the given options.
+///
+/// # Arguments
+/// * `args` - The options of the game.
+/// * `layers` - The sources of the options.
+/// * `mode` - The game mode, or `None` for the mode chosen by `--mode`.
+/// * `observers` - The observers of the game besides the ones the options ask for.
+fn play(
+    args: Args,
+    layers: &[ArgumentLayer],
+    mode: Option<Box<dyn GameMode>>,
+    mut observers: Observers,
+) -> io::Result<()> {
+    let (mut themes, theme_errors) = Themes::load_installed();
+    for error in theme_errors {
+        eprintln!("Skipping theme: {error}");
+    }
+    if let Some(name) = &args.theme {
+        if !themes.select(name) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "unknown theme `{name}`, installed themes: {}",
+                    themes.names().join(", ")
+                ),
+            ));
+        }
+    }
+
+    let mut config = game_config(&args)?;
+    config.theme = themes.current().clone();
+    if let Some(name) = &args.skin {
+        config.skin = unlocked_skin(name)?;
+    }
+    let modes = ModeRegistry::default();
+    let mode = match mode {
+        Some(mode) => mode,
+        None => modes.create(&args.mode).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "unknown game mode `{}`, available modes: {}",
+                    args.mode,
+                    modes.names().join(", ")
+                ),
+            )
+        })?,
+    };
+    let mut game_state = GameState::with_mode(config, mode);
+    if let Some(name) = &args.ai_strategy {
+        let strategies = StrategyRegistry::default();
+        let strategy = strategies.create(name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "unknown AI strategy `{name}`, available strategies: {}",
+                    strategies.names().join(", ")
+                ),
+            )
+        })?;
+        game_state.set_ai_strategy(PlayerSide::Right, strategy);
+    }
+    for extra in &args.hud {
+        extra.add_to(game_state.hud_mut());
+    }
+    let record = match &args.record {
+        Some(path) => Some(path.clone()),
+        None if args.save_replay => Some(replay_library(None)?.new_replay_path()?),
+        None if args.timeline => Some(temporary_replay_path()),
+        None => None,
+    };
+    if let Some(path) = &record {
+        let (width, height) = field_size(&args);
+        let options = game_state.replay_options();
+        observers.register(ReplayRecorder::create(path, width, height, &options)?);
+    }
+    #[cfg(unix)]
+    if args.allow_spectators {
+        let path = args
+            .spectator_socket
+            .clone()
+            .unwrap_or_else(default_socket_path);
+        let names = match args.player_names.as_slice() {
+            [] => ["player1".to_string(), "player2".to_string()],
+            [player1, player2] => [player1.clone(), player2.clone()],
+            names => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("--player-names takes two names, not {}", names.len()),
+                ))
             }
+        };
+        observers.register(SpectatorServer::bind(&path, names)?);
+    }
+
+    // the telemetry is optional, so a storage that can not be read does not stop the game
+    if let Some(Ok(Some(recorder))) = Storage::open_default()
+        .map(|storage| TelemetryRecorder::start(storage, game_state.mode().name()))
+    {
+        observers.register(recorder);
+    }
+    if let Some(storage) = Storage::open_default() {
+        observers.register(AchievementTracker::new(storage));
+    }
+
+    let shot_chart = Rc::new(RefCell::new(ShotChart::default()));
+    observers.register(Rc::clone(&shot_chart));
+    let timeline = Rc::new(RefCell::new(PointTimeline::default()));
+    observers.register(Rc::clone(&timeline));
+    let mut latency_meter = args.latency_overlay.then(LatencyMeter::default);
+    let mut watchdog = (!args.no_auto_degrade).then(|| FrameWatchdog::new(FRAME_BUDGET));
+    let bindings = key_bindings(&args);
+    let mut key_tracker = KeyTracker::new(args.key_hold_frames, bindings.direction_keys());
+    let capabilities = capabilities(&args, true);
+    game_state.set_capabilities(capabilities);
+    let dump_dir = args
+        .dump_dir
+        .clone()
+        .or_else(|| data_dir().map(|dir| dir.join("dumps")))
+        .unwrap_or_else(|| std::env::temp_dir().join("cli_pong_dumps"));
+    let history = Arc::new(Mutex::new(DebugHistory::new(
+        args.debug_history,
+        &game_state,
+    )));
+    let sources = layers
+        .iter()
+        .map(|(origin, args)| {
+            let args: Vec<_> = args.iter().map(|arg| arg.to_string_lossy()).collect();
+            (origin.clone(), args.join(" "))
+        })
+        .collect();
+    observers.register(Arc::clone(&history));
+    report_crashes(Arc::clone(&history), dump_dir.clone(), sources);
+
+    enable_raw_mode()?;
+    // pasted text arrives as a single event instead of a flood of key events
+    io::stdout().execute(EnableBracketedPaste)?;
+    if args.mouse {
+        io::stdout().execute(EnableMouseCapture)?;
+    }
+    if capabilities.keyboard_enhancement {
+        // released keys end the hold of the key tracker right away
+        io::stdout().execute(PushKeyboardEnhancementFlags(
+            KeyboardEnhancementFlags::REPORT_EVENT_TYPES,
+        ))?;
+    }
+
+    // an observer that fails ends the game, but the terminal is restored first
+    let mut result = Ok(());
+    for _ in GameLoop::from_fps(10) {
+        let input = PolledInput::poll(Duration::from_millis(20)).unwrap_or_default();
+        let mut key_events = key_tracker.update(input.pressed_keys);
+        if let (Some(meter), Some(time)) = (&mut latency_meter, input.first_key_received) {
+            meter.key_received(time);
+        }
+
+        if bindings.quit.is_pressed(&key_events)
+            || (game_state.is_over() && bindings.leave.is_pressed(&key_events))
+        {
+            break;
+        }
+        let accepts_input = game_state.mode().accepts_input();
+        // a finished game still waits for the rematch key
+        if !accepts_input && !game_state.is_over() {
+            key_events.clear();
+        }
+
+        if bindings.cycle_theme.is_pressed(&key_events) {
+            let theme = themes.cycle().clone();
+            game_state.notify(format!("Theme: {}", theme.name));
+            game_state.set_theme(theme);
+            // a theme that can not be remembered is still used for this game
+            let _ = themes.save_choice();
         }
 
-        game_state.update(key_events, Duration::from_millis(100));
+        if args.fit {
+            let (width, height) = field_size(&args);
+            game_state.resize_field(width, height);
+        }
+
+        let paddles_before = paddle_cells(&game_state);
+        if args.mouse && accepts_input && input.wheel_notches != 0 {
+            game_state.nudge_paddle(PlayerSide::Left, input.wheel_notches);
+        }
+        let dt = Duration::from_millis(100);
+        let outcome = observers.input(&game_state, &key_events).and_then(|_| {
+            let events = game_state.update(key_events.clone(), dt);
+            observers.notify(&game_state, &events, dt)
+        });
+        if let Err(error) = outcome {
+            result = Err(error);
+            break;
+        }
+        if args.debug_history > 0 && bindings.dump_state.is_pressed(&key_events) {
+            let history = history
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            match history.dump(&dump_dir, "dump-state key") {
+                Ok(path) => game_state.notify(format!("Debug dump: {}", path.display())),
+                Err(error) => game_state.notify(format!("Debug dump failed: {error}")),
+            }
+        }
+        let render_start = Instant::now();
         game_state
             .display()
             .unwrap_or_else(|_| println!("Failed to display!"));
+        if let Some(degradation) = watchdog
+            .as_mut()
+            .and_then(|watchdog| watchdog.frame_rendered(render_start.elapsed()))
+        {
+            game_state.degrade(degradation);
+            game_state.notify(degradation.message());
+        }
+
+        if let Some(meter) = &mut latency_meter {
+            meter.frame_shown(paddle_cells(&game_state) != paddles_before);
+            // the measurement of this frame is shown with the next frame
+            game_state.set_diagnostics(Some(meter.summary()));
+        }
     }
 
+    if capabilities.keyboard_enhancement {
+        io::stdout().execute(PopKeyboardEnhancementFlags)?;
+    }
+    if args.mouse {
+        io::stdout().execute(DisableMouseCapture)?;
+    }
+    io::stdout().execute(DisableBracketedPaste)?;
     disable_raw_mode()?;
+    if let Err(error) = result {
+        // the other observers still write what they collected
+        let _ = observers.finish();
+        return Err(error);
+    }
+    let shot_chart = shot_chart.borrow();
+    if !args.no_shot_chart && !shot_chart.is_empty() {
+        println!();
+        for line in shot_chart.render() {
+            println!("{line}");
+        }
+    }
+    let summary = game_state.mode().summary(&game_state);
+    if !summary.is_empty() {
+        println!();
+        for line in summary {
+            println!("{line}");
+        }
+    }
+    observers.finish()?;
+
+    let (Some(path), true) = (record, args.timeline) else {
+        return Ok(());
+    };
+    let timeline = timeline.take();
+    let result = if timeline.is_empty() {
+        Ok(())
+    } else {
+        enable_raw_mode()?;
+        let result = show_timeline(TimelineView::new(timeline), &path, &capabilities);
+        disable_raw_mode()?;
+        println!();
+        result
+    };
+    if args.record.is_none() && !args.save_replay {
+        let _ = fs::remove_file(&path);
+    }
+
+    result
+}
+
+/// Returns a path in the temporary directory for the replay the timeline plays the points from.
+fn temporary_replay_path() -> PathBuf {
+    std::env::temp_dir().join(format!("cli_pong-timeline-{}.replay", std::process::id()))
+}
+
+/// Offers a crash report once the game panics. The terminal is restored and cleared first, so the
+/// message of the panic and the question are not drawn over the field. The report holds the
+/// backtrace, so it is not printed.
+///
+/// # Arguments
+/// * `history` - The `DebugHistory` of the game, which the report ends with.
+/// * `dir` - The directory the report is written into.
+/// * `sources` - The origin and the arguments of every source of the configuration.
+fn report_crashes(history: Arc<Mutex<DebugHistory>>, dir: PathBuf, sources: Vec<(String, String)>) {
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        let report = CrashReport::capture(format!("panic: {info}"), sources.clone());
+        eprintln!("cli_pong crashed, sorry about that.\n{info}\n");
+        if !confirm_crash_report() {
+            return;
+        }
+
+        // the history is locked if the panic happened while recording, then it is left out
+        let history = history.try_lock().ok();
+        match report.save(&dir, history.as_deref()) {
+            Ok(path) => eprintln!(
+                "Wrote a crash report to {}\nPlease attach it to an issue.",
+                path.display()
+            ),
+            Err(error) => eprintln!("Failed to write a crash report: {error}"),
+        }
+    }));
+}
+
+/// Turns off everything the game turned on in the terminal and clears the half drawn field.
+/// Errors are ignored, as the terminal is restored while the game crashes.
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let mut stdout = io::stdout();
+    let _ = stdout
+        .execute(PopKeyboardEnhancementFlags)
+        .and_then(|stdout| stdout.execute(DisableMouseCapture))
+        .and_then(|stdout| stdout.execute(DisableBracketedPaste))
+        .and_then(|stdout| stdout.execute(terminal::Clear(ClearType::All)))
+        .and_then(|stdout| stdout.execute(cursor::MoveTo(0, 0)))
+        .and_then(|stdout| stdout.execute(cursor::Show));
+}
+
+/// Asks whether a crash report should be written. Without a terminal to answer in, the report is
+/// always written.
+fn confirm_crash_report() -> bool {
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        return true;
+    }
+
+    eprint!("Write a crash report with the backtrace, the options and the last updates? [Y/n] ");
+    let mut answer = String::new();
+    match stdin.read_line(&mut answer) {
+        Ok(_) => !answer.trim().to_lowercase().starts_with('n'),
+        Err(_) => false,
+    }
+}
+
+/// Returns the skin with the given name if its achievement is unlocked.
+fn unlocked_skin(name: &str) -> io::Result<Skin> {
+    let skin = Skin::find(name).ok_or_else(|| {
+        let names: Vec<&str> = Skin::built_in().iter().map(|skin| skin.name).collect();
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("unknown skin `{name}`, skins: {}", names.join(", ")),
+        )
+    })?;
+    // without a data directory only the skins that are always available can be used
+    let achievements = match Storage::open_default() {
+        Some(storage) => Achievements::load(&storage)?,
+        None => Achievements::default(),
+    };
+    match skin.unlocked_by {
+        Some(achievement) if !skin.is_unlocked(&achievements) => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "the skin `{name}` is locked, unlock it with the achievement `{}`: {}",
+                achievement.title(),
+                achievement.description()
+            ),
+        )),
+        _ => Ok(skin),
+    }
+}
+
+fn skins() -> io::Result<()> {
+    let achievements = match Storage::open_default() {
+        Some(storage) => Achievements::load(&storage)?,
+        None => Achievements::default(),
+    };
+
+    for skin in Skin::built_in() {
+        let state = match skin.unlocked_by {
+            None => "always available".to_string(),
+            Some(achievement) if skin.is_unlocked(&achievements) => {
+                format!("unlocked by `{}`", achievement.title())
+            }
+            Some(achievement) => format!("locked, {} to unlock it", achievement.description()),
+        };
+        println!("{:<8} {} {}  {state}", skin.name, skin.paddle, skin.ball);
+    }
+
+    Ok(())
+}
+
+/// Returns the storage in the data directory.
+fn default_storage() -> io::Result<Storage> {
+    Storage::open_default().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "the data directory is unknown, set `XDG_DATA_HOME` or `HOME`",
+        )
+    })
+}
+
+/// Schedules a new season among the given profiles of the configuration.
+fn season_start(
+    players: &[String],
+    name: Option<&str>,
+    points: usize,
+    replace: bool,
+) -> io::Result<()> {
+    let storage = default_storage()?;
+    let config = Config::load()?;
+    let profiles = config.profiles();
+    if let Some(player) = players
+        .iter()
+        .find(|player| !profiles.contains(&player.as_str()))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "unknown profile `{player}`, profiles: {}",
+                profiles.join(", ")
+            ),
+        ));
+    }
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => format!("Season {}", Seasons::load(&storage)?.finished.len() + 1),
+    };
+    let season = Season::new(&name, players.to_vec(), points)?;
+    let (fixtures, rounds) = (
+        season.fixtures.len(),
+        season.fixtures.last().map_or(0, |fixture| fixture.round),
+    );
+    Seasons::start(&storage, season, replace)?;
+    println!("Started {name}: {fixtures} fixtures in {rounds} rounds.");
+
+    Ok(())
+}
+
+fn season_table() -> io::Result<()> {
+    let season = Seasons::load(&default_storage()?)?
+        .current
+        .ok_or_else(no_season)?;
+    println!("{}, first to {} goals", season.name, season.points_to_win);
+    for line in season.table() {
+        println!("{line}");
+    }
+    if let Some(champion) = season.champion() {
+        println!("{champion} is the champion of {}!", season.name);
+    }
+    for fixture in season
+        .fixtures
+        .iter()
+        .filter(|fixture| fixture.score.is_none())
+    {
+        println!(
+            "Round {}: {} vs {}",
+            fixture.round, fixture.home, fixture.away
+        );
+    }
+
+    Ok(())
+}
+
+/// Plays the next fixture of the current season and records its result.
+fn season_play(mut args: Args, layers: &[ArgumentLayer]) -> io::Result<()> {
+    let storage = default_storage()?;
+    let season = Seasons::load(&storage)?.current.ok_or_else(no_season)?;
+    let Some(index) = season.next_fixture() else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "all fixtures of {} are played, start a new season with `season start`",
+                season.name
+            ),
+        ));
+    };
+    let game = SeasonGame::new(&season, index).ok_or_else(no_season)?;
+    #[cfg(unix)]
+    if args.player_names.is_empty() {
+        args.player_names = vec![game.fixture().home.clone(), game.fixture().away.clone()];
+    }
+
+    let mut observers = Observers::default();
+    observers.register(SeasonRecorder::new(storage, index));
+    play(args, layers, Some(Box::new(game)), observers)
+}
+
+fn telemetry(command: &TelemetryCommand) -> io::Result<()> {
+    let storage = default_storage()?;
+
+    match command {
+        TelemetryCommand::Enable => {
+            Telemetry::set_enabled(&storage, true)?;
+            println!("Telemetry is on. The metrics stay on this computer unless you export them.");
+        }
+        TelemetryCommand::Disable => {
+            Telemetry::set_enabled(&storage, false)?;
+            println!(
+                "Telemetry is off. Delete the metrics collected so far with `telemetry reset`."
+            );
+        }
+        TelemetryCommand::Show => {
+            let telemetry = Telemetry::load(&storage)?;
+            let state = if telemetry.enabled { "on" } else { "off" };
+            println!("Telemetry is {state}.");
+            for line in telemetry.metrics.summary() {
+                println!("{line}");
+            }
+        }
+        TelemetryCommand::Export { path } => {
+            Telemetry::load(&storage)?.export(path)?;
+            println!("Wrote the telemetry report to {}", path.display());
+        }
+        TelemetryCommand::Reset => {
+            Telemetry::reset(&storage)?;
+            println!("Deleted the collected metrics.");
+        }
+    }
+
     Ok(())
 }
 
-fn get_pressed_keys() -> io::Result<HashMap<KeyCode, KeyEvent>> {
-    let mut pressed_keys = HashMap::new();
+/// Returns the replay library in the given directory, or in the data directory by default.
+fn replay_library(dir: Option<&Path>) -> io::Result<ReplayLibrary> {
+    match dir {
+        Some(dir) => Ok(ReplayLibrary::new(dir)),
+        None => ReplayLibrary::open_default().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "the data directory is unknown, choose the directory of the replays with `--dir`",
+            )
+        }),
+    }
+}
 
-    while poll(Duration::from_millis(20))? {
-        if let Event::Key(key_event) = read()? {
-            pressed_keys.insert(key_event.code, key_event);
+fn replays(args: &Args, dir: Option<&Path>) -> io::Result<()> {
+    let mut browser = ReplayBrowser::open(replay_library(dir)?)?;
+    let capabilities = capabilities(args, false);
+
+    enable_raw_mode()?;
+    let result = browse_replays(&mut browser, &capabilities);
+    disable_raw_mode()?;
+    println!();
+
+    result
+}
+
+/// Shows the replay browser until it is closed and plays the replays chosen in it.
+fn browse_replays(browser: &mut ReplayBrowser, capabilities: &Capabilities) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    loop {
+        let (columns, rows) = terminal::size().unwrap_or(FALLBACK_TERMINAL_SIZE);
+        // the last row stays free, so the line break after the frame does not scroll the terminal
+        browser
+            .compose_frame(columns as usize, (rows as usize).saturating_sub(1))
+            .flush_adapted(&mut stdout, capabilities)?;
+        stdout.flush()?;
+
+        let Event::Key(key) = read()? else {
+            continue;
+        };
+        match browser.handle_key(key) {
+            BrowserAction::Stay => {}
+            BrowserAction::Play(path) => play_replay(&path, capabilities)?,
+            BrowserAction::Quit => return Ok(()),
         }
     }
+}
+
+/// Shows the timeline of a match until it is closed and plays the points chosen in it from the
+/// replay in the given file.
+fn show_timeline(
+    mut view: TimelineView,
+    path: &Path,
+    capabilities: &Capabilities,
+) -> io::Result<()> {
+    let replay = Replay::load(path)?;
+    let mut stdout = io::stdout();
+    loop {
+        let (columns, rows) = terminal::size().unwrap_or(FALLBACK_TERMINAL_SIZE);
+        view.compose_frame(columns as usize, (rows as usize).saturating_sub(1))
+            .flush_adapted(&mut stdout, capabilities)?;
+        stdout.flush()?;
+
+        let Event::Key(key) = read()? else {
+            continue;
+        };
+        match view.handle_key(key) {
+            TimelineAction::Stay => {}
+            TimelineAction::Play(frames) => {
+                let end = frames.end.min(replay.frames.len());
+                let start = frames.start.min(end);
+                play_frames(&replay, &replay.frames[start..end], capabilities)?;
+            }
+            TimelineAction::Quit => return Ok(()),
+        }
+    }
+}
+
+/// Plays a replay at the speed it was recorded. Space pauses it, `q` and `esc` stop it.
+fn play_replay(path: &Path, capabilities: &Capabilities) -> io::Result<()> {
+    let replay = Replay::load(path)?;
+    play_frames(&replay, &replay.frames, capabilities)
+}
+
+/// Plays the given frames of a replay at the speed they were recorded.
+fn play_frames(
+    replay: &Replay,
+    frames: &[ReplayFrame],
+    capabilities: &Capabilities,
+) -> io::Result<()> {
+    let mut game_state = replay.game_state();
+    game_state.set_capabilities(*capabilities);
+    if replay.is_from_other_engine() {
+        game_state.notify(format!(
+            "Recorded with version {} - the replay may differ",
+            replay.engine.as_deref().unwrap_or_default()
+        ));
+    }
+    game_state.notify("Replay - press space to pause, q to stop");
+
+    let mut frames = frames.iter().peekable();
+    let mut paused = false;
+    while let Some(&&frame) = frames.peek() {
+        let wait = if paused {
+            Duration::from_millis(100)
+        } else {
+            game_state.show_replay_frame(&frame);
+            frames.next();
+            game_state.display()?;
+            frame.dt
+        };
+
+        let keys = PolledInput::poll(wait)?.pressed_keys;
+        if [KeyCode::Char('q'), KeyCode::Esc]
+            .iter()
+            .any(|code| keys.contains_key(code))
+            || KeyBinding::with_modifiers(KeyCode::Char('c'), KeyModifiers::CONTROL)
+                .is_pressed(&keys)
+        {
+            break;
+        }
+        if keys.contains_key(&KeyCode::Char(' ')) {
+            paused = !paused;
+        }
+    }
+
+    Ok(())
+}
 
-    Ok(pressed_keys)
+/// Returns the rows of the cells the paddles of player1 and player2 are drawn in.
+/// Paddles that are not moved by the keyboard are left out, so they do not distort the latency.
+fn paddle_cells(game_state: &GameState) -> [Option<i64>; 2] {
+    [PlayerSide::Left, PlayerSide::Right].map(|side| {
+        matches!(game_state.controller(side), Controller::Keyboard(_))
+            .then(|| game_state.observation(side).paddle_y.round() as i64)
+    })
 }
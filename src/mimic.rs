@@ -0,0 +1,164 @@
+use crate::ai::{
+    trajectory_bucket, AiProfile, AiStyle, AimErrorDistribution, DIRECTION_BUCKETS,
+    DISTANCE_BUCKETS,
+};
+use crate::replay::{Replay, ReplayFrame};
+use crate::stats::PlayerSide;
+
+/// Fits an AI profile that imitates the player on the given side of the recorded replays.
+///
+/// # Arguments
+/// * `replays` - The recorded games of the human player.
+/// * `side` - The side the human player played on.
+///
+/// # Returns
+/// An `AiProfile` that mimics the player. Parameters without enough data keep the values of the balanced style.
+///
+/// # Remarks
+/// The aimed position is modeled as the predicted intercept of the ball plus an offset per trajectory bucket.
+/// The spread of the offsets becomes the aim error, while the time and distance at which the player starts
+/// to move after the ball turned towards them become the reaction time and distance.
+pub fn fit_mimic_profile(replays: &[Replay], side: PlayerSide) -> AiProfile {
+    let mut profile = AiStyle::Balanced.profile();
+
+    let mut offsets = OffsetStatistics::default();
+    let mut reactions = Vec::new();
+    let mut recovering_frames = 0;
+    let mut drifting_frames = 0;
+
+    for replay in replays {
+        // runs while the ball heads towards the player and they did not move yet
+        let mut reaction_timer = None;
+        let mut was_heading_towards_paddle = false;
+        // the frames are shown in a game with the recorded options, so the intercepts follow the
+        // same physics as the recorded game
+        let mut state = replay.game_state();
+
+        for (previous, frame) in replay.frames.iter().zip(replay.frames.iter().skip(1)) {
+            state.show_replay_frame(frame);
+            let observation = state.observation(side);
+            let previous_y = paddle_y(previous, side);
+            let offset_x = observation.ball_x - observation.paddle_x;
+            let heading_towards_paddle = offset_x * observation.ball_vx < 0.0;
+
+            if heading_towards_paddle {
+                if let Some(intercept_y) = observation.intercept_y {
+                    let offset = observation.paddle_y - intercept_y;
+                    offsets.add(trajectory_bucket(&observation), offset);
+                }
+
+                if !was_heading_towards_paddle {
+                    reaction_timer = Some(0.0);
+                }
+                if let Some(elapsed) = reaction_timer {
+                    if observation.paddle_y != previous_y {
+                        let distance = offset_x.abs() / observation.field_width;
+                        reactions.push((elapsed, distance));
+                        reaction_timer = None;
+                    } else {
+                        reaction_timer = Some(elapsed + frame.dt.as_secs_f64());
+                    }
+                }
+            } else {
+                reaction_timer = None;
+
+                let center = observation.field_height / 2.0;
+                let previous_distance = (previous_y - center).abs();
+                let distance = (observation.paddle_y - center).abs();
+                if distance < previous_distance {
+                    recovering_frames += 1;
+                } else if distance > previous_distance {
+                    drifting_frames += 1;
+                }
+            }
+
+            was_heading_towards_paddle = heading_towards_paddle;
+        }
+    }
+
+    if let Some(target_offsets) = offsets.means() {
+        profile.target_offsets = target_offsets;
+        profile.aim_error = offsets.standard_deviation();
+        profile.aim_error_distribution = AimErrorDistribution::Gaussian;
+    }
+    if !reactions.is_empty() {
+        let count = reactions.len() as f64;
+        profile.reaction_time = reactions.iter().map(|(elapsed, _)| elapsed).sum::<f64>() / count;
+        profile.reaction_distance =
+            (reactions.iter().map(|(_, distance)| distance).sum::<f64>() / count).clamp(0.1, 1.0);
+    }
+    profile.recovers_to_center = recovering_frames > drifting_frames;
+
+    profile
+}
+
+fn paddle_y(frame: &ReplayFrame, side: PlayerSide) -> f64 {
+    match side {
+        PlayerSide::Left => frame.player1_y,
+        PlayerSide::Right => frame.player2_y,
+    }
+}
+
+/// Accumulates the offsets between paddle and predicted intercept per trajectory bucket.
+#[derive(Default)]
+struct OffsetStatistics {
+    buckets: [[BucketStatistics; DISTANCE_BUCKETS]; DIRECTION_BUCKETS],
+}
+
+impl OffsetStatistics {
+    fn add(&mut self, (direction, distance): (usize, usize), offset: f64) {
+        let bucket = &mut self.buckets[direction][distance];
+        bucket.sum += offset;
+        bucket.squared_sum += offset * offset;
+        bucket.count += 1;
+    }
+
+    fn means(&self) -> Option<[[f64; DISTANCE_BUCKETS]; DIRECTION_BUCKETS]> {
+        if self.total_count() == 0 {
+            return None;
+        }
+
+        Some(self.buckets.map(|row| row.map(|bucket| bucket.mean())))
+    }
+
+    /// The standard deviation of the offsets around the mean of their bucket.
+    fn standard_deviation(&self) -> f64 {
+        let squared_deviations: f64 = self
+            .buckets
+            .iter()
+            .flatten()
+            .map(BucketStatistics::squared_deviations)
+            .sum();
+
+        (squared_deviations.max(0.0) / self.total_count() as f64).sqrt()
+    }
+
+    fn total_count(&self) -> usize {
+        self.buckets
+            .iter()
+            .flatten()
+            .map(|bucket| bucket.count)
+            .sum()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+struct BucketStatistics {
+    sum: f64,
+    squared_sum: f64,
+    count: usize,
+}
+
+impl BucketStatistics {
+    fn mean(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum / self.count as f64
+        }
+    }
+
+    fn squared_deviations(&self) -> f64 {
+        self.squared_sum - self.sum * self.mean()
+    }
+}
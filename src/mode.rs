@@ -0,0 +1,651 @@
+use crate::ai::AiStyle;
+use crate::drill::{Drill, ServeMachine};
+use crate::hud::{
+    BreakoutStatus, Diagnostics, DrillStatus, Hud, HudAlign, HudSlot, ReturnStreak, SurvivalClock,
+};
+use crate::ramp::SURVIVAL_RAMP_INTERVAL;
+use crate::state::{
+    Arena, DiscretePosition2D, GameConfig, GameEvent, GameState, Obstacle, PortalPair, Position2D,
+};
+use crate::stats::PlayerSide;
+use crate::theme::Theme;
+use clap::ValueEnum;
+use crossterm::style::Color;
+use std::any::Any;
+use std::time::Duration;
+
+/// The number of lives of player1 in a breakout game. Every ball that gets past the paddle costs one.
+pub const BREAKOUT_LIVES: usize = 3;
+
+/// The number of columns of bricks in front of the right wall in a breakout game.
+const BRICK_COLUMNS: usize = 3;
+
+/// The glyph a brick of a breakout game is drawn with.
+const BRICK_GLYPH: char = '\u{2593}';
+
+/// # Explanation
+/// A game mode changes the rules of a game through hooks that `GameState` calls at fixed points,
+/// so a new mode does not need its own copy of the game loop. All hooks do nothing by default, and
+/// a mode only implements the ones it needs.
+///
+/// # Remarks
+/// The game state is cloned for replays and rewinds, so modes have to be `Clone`.
+pub trait GameMode: GameModeClone {
+    /// Returns the name the mode is chosen by with `--mode`.
+    fn name(&self) -> &str;
+
+    /// Changes the configuration before the game is created, e.g. to let the computer play.
+    fn mutate_config(&self, _config: &mut GameConfig) {}
+
+    /// Returns what the mode adds to the field, e.g. further goals. It is built from the
+    /// configuration after `mutate_config` changed it, and again when the field is resized.
+    fn arena(&self, _config: &GameConfig) -> Arena {
+        Arena::default()
+    }
+
+    /// Changes the standard HUD before the game starts, e.g. to show a score of its own.
+    fn build_hud(&self, _hud: &mut Hud) {}
+
+    /// Called each time the ball was placed for a serve, e.g. to change its velocity.
+    fn on_serve(&mut self, _state: &mut GameState) {}
+
+    /// Called after a goal was counted and before the ball is served again.
+    fn on_goal(&mut self, _state: &mut GameState, _scorer: PlayerSide) {}
+
+    /// Called after the player on the given side returned the ball, e.g. to serve the next ball
+    /// right away. The mode is taken out of the state while the hook runs, so a serve started
+    /// from here does not call `on_serve` of this mode.
+    fn on_hit(&mut self, _state: &mut GameState, _hitter: PlayerSide) {}
+
+    /// Called at the end of each update in which the ball was in play, e.g. to run a time limit.
+    /// Paused time is not passed to the hook.
+    fn on_tick(&mut self, _state: &mut GameState, _dt: Duration) {}
+
+    /// Called when a game starts, the first one as well as e.g. a rematch, before its first serve.
+    fn on_new_game(&mut self, _state: &mut GameState) {}
+
+    /// Called for each ball after it moved in an update, e.g. to let it break the bricks on its
+    /// way with `GameState::bounce_ball_off_cells`.
+    ///
+    /// # Arguments
+    /// * `state` - The state of the game, whose balls have moved already.
+    /// * `ball` - The index of the ball in `GameState::balls`.
+    /// * `from` - The position of the ball before the update.
+    ///
+    /// # Returns
+    /// The `GameEvent`s the rules of the mode caused. The game asks `win_condition` right after
+    /// a hook that returned events.
+    fn on_ball_moved(
+        &mut self,
+        _state: &mut GameState,
+        _ball: usize,
+        _from: Position2D,
+    ) -> Vec<GameEvent> {
+        Vec::new()
+    }
+
+    /// Called after the field was resized, e.g. to move the entities of the mode along with the
+    /// walls. The arena was already rebuilt for the new size.
+    fn on_resize(&mut self, _state: &mut GameState, _previous_size: (usize, usize)) {}
+
+    /// Returns the winner once the game is decided, or `None` while it goes on. The game stops
+    /// until a new game is started.
+    fn win_condition(&self, _state: &GameState) -> Option<PlayerSide> {
+        None
+    }
+
+    /// Returns the entities the mode adds to the field. They are drawn and the ball is never
+    /// served into them. By default, these are the obstacles and the portals of the arena.
+    fn extra_entities(&self, state: &GameState) -> Vec<ModeEntity> {
+        state.arena().entities()
+    }
+
+    /// Returns `false` if the players do not take part in the game, so the game loop ignores all
+    /// keys except the ones that quit the game.
+    fn accepts_input(&self) -> bool {
+        true
+    }
+
+    /// Returns a line about the progress of the game under the rules of the mode, which the
+    /// `ModeStatus` widget shows in the HUD, or `None` if there is nothing to show.
+    fn status(&self, _state: &GameState) -> Option<String> {
+        None
+    }
+
+    /// Returns the lines that sum up the game once it is over, e.g. to print them after the
+    /// terminal was restored. Modes without a summary of their own return no lines.
+    fn summary(&self, _state: &GameState) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Clones boxed modes and looks at their concrete type. It is implemented for every mode that
+/// is `Clone`.
+pub trait GameModeClone {
+    fn clone_box(&self) -> Box<dyn GameMode>;
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: GameMode + Clone + 'static> GameModeClone for T {
+    fn clone_box(&self) -> Box<dyn GameMode> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Clone for Box<dyn GameMode> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// A cell of the field a game mode draws something into.
+#[derive(Debug, Copy, Clone)]
+pub struct ModeEntity {
+    pub position: Position2D,
+    pub glyph: char,
+    pub color: EntityColor,
+}
+
+/// The color of the theme an entity of a game mode is drawn in.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum EntityColor {
+    #[default]
+    Text,
+    Border,
+    Paddle1,
+    Paddle2,
+}
+
+impl EntityColor {
+    /// Returns the color of the given theme, or `None` for the default color of the terminal.
+    pub fn of(self, theme: &Theme) -> Option<Color> {
+        match self {
+            EntityColor::Text => theme.text,
+            EntityColor::Border => theme.border,
+            EntityColor::Paddle1 => theme.paddle1,
+            EntityColor::Paddle2 => theme.paddle2,
+        }
+    }
+}
+
+/// Creates a new instance of a registered mode.
+type ModeFactory = Box<dyn Fn() -> Box<dyn GameMode>>;
+
+/// # Explanation
+/// The game modes that can be chosen by name with `--mode`. The default registry contains the
+/// built-in modes, further ones are added with `register`.
+pub struct ModeRegistry {
+    modes: Vec<(String, ModeFactory)>,
+}
+
+impl Default for ModeRegistry {
+    fn default() -> Self {
+        let mut registry = ModeRegistry { modes: Vec::new() };
+        registry.register("versus", || Box::new(Versus));
+        registry.register("single", || Box::new(Single));
+        registry.register("demo", || Box::new(Demo));
+        registry.register("squash", || Box::new(Squash));
+        registry.register("four-player", || Box::new(FourPlayer));
+        registry.register("doubles", || Box::new(Doubles));
+        registry.register("breakout", || Box::new(Breakout::default()));
+        registry.register("survival", || Box::new(Survival));
+        registry.register("drill", || Box::new(ServeDrill));
+        registry.register("obstacles", || Box::new(Obstacles));
+        registry.register("portals", || Box::new(Portals));
+
+        registry
+    }
+}
+
+impl ModeRegistry {
+    /// Adds a mode, replacing a registered mode of the same name.
+    ///
+    /// # Arguments
+    /// * `name` - The name the mode is chosen by.
+    /// * `create` - Creates a new instance of the mode for every game.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        create: impl Fn() -> Box<dyn GameMode> + 'static,
+    ) {
+        let name = name.into();
+        self.modes.retain(|(registered, _)| *registered != name);
+        self.modes.push((name, Box::new(create)));
+    }
+
+    /// Creates a new instance of the mode with the given name, or `None` if there is none.
+    pub fn create(&self, name: &str) -> Option<Box<dyn GameMode>> {
+        self.modes
+            .iter()
+            .find(|(registered, _)| registered == name)
+            .map(|(_, create)| create())
+    }
+
+    /// Returns the names of all registered modes in the order they were registered.
+    pub fn names(&self) -> Vec<&str> {
+        self.modes.iter().map(|(name, _)| name.as_str()).collect()
+    }
+}
+
+/// The classic game: two players share the keyboard and the game never ends.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Versus;
+
+impl GameMode for Versus {
+    fn name(&self) -> &str {
+        "versus"
+    }
+}
+
+/// A single player plays against the computer, which controls player2 with the balanced style
+/// unless another AI was chosen.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Single;
+
+impl GameMode for Single {
+    fn name(&self) -> &str {
+        "single"
+    }
+
+    fn mutate_config(&self, config: &mut GameConfig) {
+        if config.player2_ai.is_none() {
+            config.player2_ai = Some(AiStyle::Balanced.profile());
+        }
+    }
+}
+
+/// The computer plays against itself, e.g. as a screensaver or to check the rendering. Both
+/// players use the balanced style unless another AI was chosen, and the keys only quit the game.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Demo;
+
+impl GameMode for Demo {
+    fn name(&self) -> &str {
+        "demo"
+    }
+
+    fn mutate_config(&self, config: &mut GameConfig) {
+        config
+            .player1_ai
+            .get_or_insert_with(|| AiStyle::Balanced.profile());
+        config
+            .player2_ai
+            .get_or_insert_with(|| AiStyle::Balanced.profile());
+    }
+
+    fn accepts_input(&self) -> bool {
+        false
+    }
+}
+
+/// A single player rallies against a wall in place of player2 and tries to return the ball as
+/// often as possible in a row. A miss ends the streak and counts for the wall.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Squash;
+
+impl GameMode for Squash {
+    fn name(&self) -> &str {
+        "squash"
+    }
+
+    fn arena(&self, _config: &GameConfig) -> Arena {
+        Arena {
+            right_wall: true,
+            ..Arena::default()
+        }
+    }
+
+    fn build_hud(&self, hud: &mut Hud) {
+        // goals and win chances of two players mean nothing against a wall
+        *hud = Hud::default();
+        hud.add(HudSlot::new(0, HudAlign::Left), Diagnostics);
+        hud.add(HudSlot::new(1, HudAlign::Left), ReturnStreak);
+    }
+}
+
+/// Four players guard the four edges of the field: player1 and player2 the left and the right
+/// edge as usual, player3 and player4 the top and the bottom edge with horizontal paddles. A goal
+/// on any edge counts for the player who touched the ball last. The game is free play and never ends.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct FourPlayer;
+
+impl GameMode for FourPlayer {
+    fn name(&self) -> &str {
+        "four-player"
+    }
+
+    fn mutate_config(&self, config: &mut GameConfig) {
+        // four-player games are free play
+        config.score_limit = None;
+        config.match_format = None;
+    }
+
+    fn arena(&self, _config: &GameConfig) -> Arena {
+        Arena {
+            edge_players: true,
+            ..Arena::default()
+        }
+    }
+}
+
+/// Two teams of two players share the keyboard. On each side, one player moves the upper paddle
+/// and a teammate the lower paddle, and the ball bounces off whichever paddle it reaches.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Doubles;
+
+impl GameMode for Doubles {
+    fn name(&self) -> &str {
+        "doubles"
+    }
+
+    fn arena(&self, _config: &GameConfig) -> Arena {
+        Arena {
+            doubles: true,
+            ..Arena::default()
+        }
+    }
+}
+
+/// A single player breaks the bricks that fill the right side of the field in place of player2.
+/// Breaking all bricks wins the game. Each ball that gets past the paddle costs one of the
+/// `BREAKOUT_LIVES` lives and counts as a goal of the right side, and the game is lost with the
+/// last life.
+#[derive(Debug, Clone, Default)]
+pub struct Breakout {
+    /// The cells of the bricks that are left.
+    bricks: Vec<DiscretePosition2D>,
+}
+
+impl Breakout {
+    /// Returns the cells of the bricks that are left.
+    pub fn bricks(&self) -> &[DiscretePosition2D] {
+        &self.bricks
+    }
+
+    /// Returns the bricks a game on a field of the given size starts with: whole columns in front
+    /// of the right wall, with a free column between the bricks and the wall.
+    fn wall_of_bricks(width: usize, height: usize) -> Vec<DiscretePosition2D> {
+        let last_column = width as i64 - 2;
+        (last_column + 1 - BRICK_COLUMNS as i64..=last_column)
+            .flat_map(|x| (0..=height as i64).map(move |y| DiscretePosition2D::new(x, y)))
+            .collect()
+    }
+}
+
+impl GameMode for Breakout {
+    fn name(&self) -> &str {
+        "breakout"
+    }
+
+    fn mutate_config(&self, config: &mut GameConfig) {
+        // only the bricks and the lives decide the game
+        config.score_limit = None;
+        config.match_format = None;
+    }
+
+    fn arena(&self, _config: &GameConfig) -> Arena {
+        Arena {
+            right_wall: true,
+            ..Arena::default()
+        }
+    }
+
+    fn build_hud(&self, hud: &mut Hud) {
+        *hud = Hud::default();
+        hud.add(HudSlot::new(0, HudAlign::Left), Diagnostics);
+        hud.add(HudSlot::new(1, HudAlign::Left), BreakoutStatus);
+    }
+
+    fn on_new_game(&mut self, state: &mut GameState) {
+        let config = state.config();
+        self.bricks = Self::wall_of_bricks(config.width, config.height);
+    }
+
+    fn on_ball_moved(
+        &mut self,
+        state: &mut GameState,
+        ball: usize,
+        from: Position2D,
+    ) -> Vec<GameEvent> {
+        if self.bricks.is_empty() {
+            return Vec::new();
+        }
+
+        let bricks = &self.bricks;
+        let Some(cell) = state.bounce_ball_off_cells(ball, from, |cell| bricks.contains(&cell))
+        else {
+            return Vec::new();
+        };
+        if let Some(index) = self.bricks.iter().position(|&brick| brick == cell) {
+            self.bricks.swap_remove(index);
+        }
+
+        vec![GameEvent::BrickBroken]
+    }
+
+    fn on_resize(&mut self, state: &mut GameState, (previous_width, _): (usize, usize)) {
+        // the bricks keep their distance to the right wall
+        let config = state.config();
+        let shift = config.width as i64 - previous_width as i64;
+        self.bricks = self
+            .bricks
+            .iter()
+            .map(|brick| DiscretePosition2D::new(brick.x() + shift, brick.y()))
+            .filter(|brick| brick.in_field(config.width, config.height).is_some())
+            .collect();
+    }
+
+    fn win_condition(&self, state: &GameState) -> Option<PlayerSide> {
+        let (_, lost_balls) = state.scores();
+        if self.bricks.is_empty() {
+            Some(PlayerSide::Left)
+        } else if lost_balls >= BREAKOUT_LIVES {
+            Some(PlayerSide::Right)
+        } else {
+            None
+        }
+    }
+
+    fn extra_entities(&self, _state: &GameState) -> Vec<ModeEntity> {
+        self.bricks
+            .iter()
+            .map(|brick| ModeEntity {
+                position: brick.to_continuous(),
+                glyph: BRICK_GLYPH,
+                color: EntityColor::Paddle2,
+            })
+            .collect()
+    }
+}
+
+/// A single player tries to survive against the computer for as long as possible. Every
+/// `SURVIVAL_RAMP_INTERVAL` of play the ball gets faster and the computer, which starts with the
+/// rookie style unless another AI was chosen, gets sharper. The first goal against player1 ends
+/// the game, and the summary tells how long player1 survived.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Survival;
+
+impl GameMode for Survival {
+    fn name(&self) -> &str {
+        "survival"
+    }
+
+    fn mutate_config(&self, config: &mut GameConfig) {
+        config
+            .player2_ai
+            .get_or_insert_with(|| AiStyle::Rookie.profile());
+        config.difficulty_ramp = Some(SURVIVAL_RAMP_INTERVAL);
+        // only the first goal against player1 decides the game
+        config.score_limit = None;
+        config.match_format = None;
+    }
+
+    fn build_hud(&self, hud: &mut Hud) {
+        hud.add(HudSlot::new(2, HudAlign::Left), SurvivalClock);
+    }
+
+    fn win_condition(&self, state: &GameState) -> Option<PlayerSide> {
+        let (_, goals_against) = state.scores();
+        (goals_against > 0).then_some(PlayerSide::Right)
+    }
+
+    fn summary(&self, state: &GameState) -> Vec<String> {
+        let Some(ramp) = state.difficulty() else {
+            return Vec::new();
+        };
+        let seconds = ramp.elapsed().as_secs();
+        let stats = state.stats();
+
+        vec![
+            format!(
+                "Survived {:02}:{:02} and reached level {}",
+                seconds / 60,
+                seconds % 60,
+                ramp.level()
+            ),
+            format!(
+                "Rallies: {},  returns: {},  longest rally: {}",
+                stats.rallies(),
+                stats.rally_hits(),
+                stats.longest_rally()
+            ),
+        ]
+    }
+}
+
+/// A single player returns the serves of a serve machine in front of the right wall. The machine
+/// serves the next ball as soon as player1 returned or missed the last one, following the drill
+/// of `GameConfig::drill`, and the drill is over after its last serve.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ServeDrill;
+
+impl ServeDrill {
+    /// Returns the serve machine of the drill of the given game.
+    fn machine(state: &GameState) -> ServeMachine {
+        let config = state.config();
+        ServeMachine::new(config.drill.unwrap_or_default(), config)
+    }
+
+    /// Returns the number of serves player1 returned or missed so far.
+    fn served(state: &GameState) -> usize {
+        let stats = state.stats();
+        stats.returns(PlayerSide::Left) + stats.misses(PlayerSide::Left)
+    }
+
+    /// Lets the serve machine launch the next serve, unless the drill is over.
+    fn serve_next(state: &mut GameState) {
+        let machine = Self::machine(state);
+        let served = Self::served(state);
+        if served < machine.drill().serves {
+            let (position, velocity) =
+                machine.launch(served, state.config().seed.unwrap_or_default());
+            state.launch_ball(position, velocity);
+        }
+    }
+}
+
+impl GameMode for ServeDrill {
+    fn name(&self) -> &str {
+        "drill"
+    }
+
+    fn mutate_config(&self, config: &mut GameConfig) {
+        config.drill.get_or_insert_with(Drill::default);
+        // only the serves of the drill decide when it is over
+        config.score_limit = None;
+        config.match_format = None;
+    }
+
+    fn arena(&self, _config: &GameConfig) -> Arena {
+        Arena {
+            right_wall: true,
+            ..Arena::default()
+        }
+    }
+
+    fn build_hud(&self, hud: &mut Hud) {
+        *hud = Hud::default();
+        hud.add(HudSlot::new(0, HudAlign::Left), Diagnostics);
+        hud.add(HudSlot::new(1, HudAlign::Left), DrillStatus);
+    }
+
+    fn on_serve(&mut self, state: &mut GameState) {
+        Self::serve_next(state);
+    }
+
+    fn on_hit(&mut self, state: &mut GameState, hitter: PlayerSide) {
+        if hitter == PlayerSide::Left {
+            state.serve(PlayerSide::Left);
+            Self::serve_next(state);
+        }
+    }
+
+    fn win_condition(&self, state: &GameState) -> Option<PlayerSide> {
+        let serves = Self::machine(state).drill().serves;
+        (Self::served(state) >= serves).then_some(PlayerSide::Left)
+    }
+
+    fn extra_entities(&self, state: &GameState) -> Vec<ModeEntity> {
+        vec![Self::machine(state).entity()]
+    }
+
+    fn summary(&self, state: &GameState) -> Vec<String> {
+        let served = Self::served(state);
+        let returns = state.stats().returns(PlayerSide::Left);
+        let drill = *Self::machine(state).drill();
+        let pattern = drill
+            .pattern
+            .to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default();
+
+        vec![
+            format!(
+                "Returned {returns} of {served} serves ({:.0}%)",
+                100.0 * returns as f64 / served.max(1) as f64
+            ),
+            format!("Drill: {pattern} serves at {:.1} cells/s", drill.speed),
+        ]
+    }
+}
+
+/// Two players rally around static blocks above and below the middle of the field, which the ball
+/// bounces off like off the walls. The lane through the middle stays free for the serves.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Obstacles;
+
+impl GameMode for Obstacles {
+    fn name(&self) -> &str {
+        "obstacles"
+    }
+
+    fn arena(&self, config: &GameConfig) -> Arena {
+        Arena {
+            obstacles: Obstacle::mid_field(config.width, config.height),
+            ..Arena::default()
+        }
+    }
+}
+
+/// Two players rally across a field with a pair of portals. A ball that enters one portal leaves
+/// the other one with the same velocity, so a return can come out far from where it was aimed.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Portals;
+
+impl GameMode for Portals {
+    fn name(&self) -> &str {
+        "portals"
+    }
+
+    fn arena(&self, config: &GameConfig) -> Arena {
+        Arena {
+            portals: Some(PortalPair::mid_field(config.width, config.height)),
+            ..Arena::default()
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::chaos::ChaosScheduler;
+use crate::stats::PlayerSide;
+use clap::ValueEnum;
+use std::time::Duration;
+
+/// The factor the serve velocity of the ball is scaled with by the `fast-serve` mutator.
+const FAST_SERVE_FACTOR: f64 = 1.5;
+
+/// How long the controls of a player stay mirrored by the `mirror` mutator by default.
+pub const DEFAULT_MIRROR_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The mutators change a rule of the game. Any number of them can be combined with each other
+/// and with all other options, e.g. `--mutator no-walls,big-ball`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, ValueEnum)]
+#[non_exhaustive]
+pub enum Mutator {
+    /// The ball passes through the top and the bottom wall and comes back in on the other side.
+    NoWalls,
+    /// The ball is three cells tall, so it is easier to hit.
+    BigBall,
+    /// The ball is served one and a half times as fast.
+    FastServe,
+    /// The up and down keys of player2 are swapped.
+    InvertP2,
+    /// The up and down keys of one player after the other are swapped for a while.
+    Mirror,
+    /// Random events like a teleporting ball or a second ball fire every few seconds.
+    Chaos,
+}
+
+/// # Explanation
+/// The registry of the active mutators. The simulation asks it at a few hook points how the rules
+/// are changed, e.g. whether the ball bounces off the walls. A new mutator only has to extend the
+/// hooks it affects.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Mutators {
+    active: Vec<Mutator>,
+}
+
+impl Mutators {
+    /// Creates the registry of the given mutators. Mutators given several times are applied once.
+    pub fn new(mutators: impl IntoIterator<Item = Mutator>) -> Self {
+        let mut active = Vec::new();
+        for mutator in mutators {
+            if !active.contains(&mutator) {
+                active.push(mutator);
+            }
+        }
+
+        Mutators { active }
+    }
+
+    /// Returns `true` if the given mutator is active.
+    pub fn is_active(&self, mutator: Mutator) -> bool {
+        self.active.contains(&mutator)
+    }
+
+    /// Returns the active mutators in the order they were given.
+    pub fn active(&self) -> &[Mutator] {
+        &self.active
+    }
+
+    /// Returns whether the ball bounces off the top and the bottom wall.
+    pub fn walls(&self) -> bool {
+        !self.is_active(Mutator::NoWalls)
+    }
+
+    /// Returns how many cells the ball reaches above and below its center.
+    pub fn ball_radius(&self) -> usize {
+        if self.is_active(Mutator::BigBall) {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Returns the factor the serve velocity of the ball is scaled with.
+    pub fn serve_factor(&self) -> f64 {
+        if self.is_active(Mutator::FastServe) {
+            FAST_SERVE_FACTOR
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns whether the up and down keys of player2 are swapped.
+    pub fn invert_player2(&self) -> bool {
+        self.is_active(Mutator::InvertP2)
+    }
+
+    /// Returns the schedule of the mirrored controls if the `mirror` mutator is active.
+    ///
+    /// # Arguments
+    /// * `interval` - How long the controls of a player stay mirrored.
+    pub fn mirror_schedule(&self, interval: Duration) -> Option<MirrorSchedule> {
+        self.is_active(Mutator::Mirror)
+            .then(|| MirrorSchedule::new(interval))
+    }
+
+    /// Returns the scheduler of the chaos events if the `chaos` mutator is active.
+    ///
+    /// # Arguments
+    /// * `interval` - The time of play between two chaos events.
+    pub fn chaos_scheduler(&self, interval: Duration) -> Option<ChaosScheduler> {
+        self.is_active(Mutator::Chaos)
+            .then(|| ChaosScheduler::new(interval))
+    }
+}
+
+/// # Explanation
+/// The mirror schedule decides whose controls the `mirror` mutator swaps. Both players start with
+/// their usual controls. After each interval of play the mirror moves on: first to player1, then
+/// to player2, then back to player1 and so on.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MirrorSchedule {
+    interval: Duration,
+    until_switch: Duration,
+    switches: u64,
+}
+
+impl MirrorSchedule {
+    /// Creates a schedule that moves the mirror on after every interval of the given length.
+    pub fn new(interval: Duration) -> Self {
+        // an empty interval would switch endlessly
+        let interval = interval.max(Duration::from_millis(1));
+        MirrorSchedule {
+            interval,
+            until_switch: interval,
+            switches: 0,
+        }
+    }
+
+    /// Advances the schedule by the given time of play.
+    ///
+    /// # Returns
+    /// `true` if the mirror moved on to another player.
+    pub fn advance(&mut self, dt: Duration) -> bool {
+        let before = self.mirrored();
+        let mut left = dt;
+        while left >= self.until_switch {
+            left -= self.until_switch;
+            self.until_switch = self.interval;
+            self.switches += 1;
+        }
+        self.until_switch -= left;
+
+        self.mirrored() != before
+    }
+
+    /// Returns the player whose controls are mirrored, or `None` before the first switch.
+    pub fn mirrored(&self) -> Option<PlayerSide> {
+        match self.switches {
+            0 => None,
+            switches if switches % 2 == 1 => Some(PlayerSide::Left),
+            _ => Some(PlayerSide::Right),
+        }
+    }
+
+    /// Returns the time of play until the mirror moves on.
+    pub fn time_to_switch(&self) -> Duration {
+        self.until_switch
+    }
+}
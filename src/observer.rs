@@ -0,0 +1,190 @@
+use crate::state::{GameEvent, GameState};
+use crossterm::event::{KeyCode, KeyEvent};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// # Explanation
+/// An observer reacts to a running game without being part of the simulation, e.g. by recording it,
+/// sending it to spectators or playing sounds. It receives the events of every update and the state
+/// after the update. All methods do nothing by default, so an observer only implements what it needs.
+pub trait GameObserver {
+    /// Called once before every update with the keys that are passed to the update.
+    ///
+    /// # Arguments
+    /// * `state` - The `GameState` before the update.
+    /// * `pressed_keys` - The keys that were pressed for the update.
+    fn on_input(
+        &mut self,
+        _state: &GameState,
+        _pressed_keys: &HashMap<KeyCode, KeyEvent>,
+    ) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called for every event of an update, in the order the events happened.
+    ///
+    /// # Arguments
+    /// * `state` - The `GameState` after the update.
+    /// * `event` - The `GameEvent` that happened.
+    fn on_event(&mut self, _state: &GameState, _event: GameEvent) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called once after every update, after the events of the update.
+    ///
+    /// # Arguments
+    /// * `state` - The `GameState` after the update.
+    /// * `dt` - The `Duration` the update advanced the game by.
+    fn on_tick(&mut self, _state: &GameState, _dt: Duration) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called once when the game ends, e.g. to write buffered data.
+    fn on_finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// An observer that is shared with the code that reads what it collected, e.g. a chart that is
+/// shown after the game.
+impl<T: GameObserver> GameObserver for Rc<RefCell<T>> {
+    fn on_input(
+        &mut self,
+        state: &GameState,
+        pressed_keys: &HashMap<KeyCode, KeyEvent>,
+    ) -> io::Result<()> {
+        self.borrow_mut().on_input(state, pressed_keys)
+    }
+
+    fn on_event(&mut self, state: &GameState, event: GameEvent) -> io::Result<()> {
+        self.borrow_mut().on_event(state, event)
+    }
+
+    fn on_tick(&mut self, state: &GameState, dt: Duration) -> io::Result<()> {
+        self.borrow_mut().on_tick(state, dt)
+    }
+
+    fn on_finish(&mut self) -> io::Result<()> {
+        self.borrow_mut().on_finish()
+    }
+}
+
+/// An observer that is shared with another thread, e.g. the debug history that the crash report
+/// is written from. A poisoned lock is used anyway, as the observer only collects data.
+impl<T: GameObserver> GameObserver for Arc<Mutex<T>> {
+    fn on_input(
+        &mut self,
+        state: &GameState,
+        pressed_keys: &HashMap<KeyCode, KeyEvent>,
+    ) -> io::Result<()> {
+        lock(self).on_input(state, pressed_keys)
+    }
+
+    fn on_event(&mut self, state: &GameState, event: GameEvent) -> io::Result<()> {
+        lock(self).on_event(state, event)
+    }
+
+    fn on_tick(&mut self, state: &GameState, dt: Duration) -> io::Result<()> {
+        lock(self).on_tick(state, dt)
+    }
+
+    fn on_finish(&mut self) -> io::Result<()> {
+        lock(self).on_finish()
+    }
+}
+
+fn lock<T>(observer: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    observer
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// # Explanation
+/// The registry of the observers of a game. The game loop passes the events of every update to
+/// it, and it forwards them to all observers in the order the observers were registered.
+#[derive(Default)]
+pub struct Observers {
+    observers: Vec<Box<dyn GameObserver>>,
+}
+
+impl Observers {
+    /// Adds an observer that is notified about all following updates.
+    pub fn register(&mut self, observer: impl GameObserver + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Returns the number of registered observers.
+    pub fn len(&self) -> usize {
+        self.observers.len()
+    }
+
+    /// Returns `true` if no observer is registered.
+    pub fn is_empty(&self) -> bool {
+        self.observers.is_empty()
+    }
+
+    /// Tells all observers which keys are passed to the next update of the game.
+    ///
+    /// # Arguments
+    /// * `state` - The `GameState` before the update.
+    /// * `pressed_keys` - The keys that were pressed for the update.
+    ///
+    /// # Returns
+    /// The first error of an observer. The remaining observers are still told.
+    pub fn input(
+        &mut self,
+        state: &GameState,
+        pressed_keys: &HashMap<KeyCode, KeyEvent>,
+    ) -> io::Result<()> {
+        let mut result = Ok(());
+        for observer in &mut self.observers {
+            result = result.and(observer.on_input(state, pressed_keys));
+        }
+
+        result
+    }
+
+    /// Notifies all observers about an update of the game.
+    ///
+    /// # Arguments
+    /// * `state` - The `GameState` after the update.
+    /// * `events` - The `GameEvent`s the update returned.
+    /// * `dt` - The `Duration` the update advanced the game by.
+    ///
+    /// # Returns
+    /// The first error of an observer. The remaining observers are still notified.
+    pub fn notify(
+        &mut self,
+        state: &GameState,
+        events: &[GameEvent],
+        dt: Duration,
+    ) -> io::Result<()> {
+        let mut result = Ok(());
+        for observer in &mut self.observers {
+            let outcome = events
+                .iter()
+                .try_for_each(|&event| observer.on_event(state, event))
+                .and_then(|_| observer.on_tick(state, dt));
+            result = result.and(outcome);
+        }
+
+        result
+    }
+
+    /// Tells all observers that the game ended.
+    ///
+    /// # Returns
+    /// The first error of an observer. The remaining observers are still told.
+    pub fn finish(&mut self) -> io::Result<()> {
+        let mut result = Ok(());
+        for observer in &mut self.observers {
+            result = result.and(observer.on_finish());
+        }
+
+        result
+    }
+}
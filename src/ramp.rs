@@ -0,0 +1,96 @@
+use crate::ai::AiProfile;
+use crate::timer::Timer;
+use std::time::Duration;
+
+/// The time of play between two levels of a survival game.
+pub const SURVIVAL_RAMP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The factor the speed of the ball grows by with each level.
+pub const BALL_SPEED_STEP: f64 = 1.15;
+
+/// The factor the reaction time, the aim error and the tolerance of the AI shrink by with each level.
+const AI_SHARPENING_STEP: f64 = 0.8;
+
+/// The share of the field width the AI follows the ball in grows by this much with each level.
+const REACTION_DISTANCE_STEP: f64 = 0.1;
+
+/// The shortest reaction time in seconds the AI is sharpened to.
+const MIN_REACTION_TIME: f64 = 0.05;
+
+/// # Explanation
+/// The difficulty ramp raises the level of a game each time the given interval of play has passed.
+/// It runs on the simulated time of the updates it is advanced with, so pauses do not count and a
+/// replay reaches each level at the same moment as the game. The game speeds the ball up and
+/// sharpens the computer player with each level, by the factors the ramp returns for the level.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DifficultyRamp {
+    timer: Timer,
+    level: u32,
+    elapsed: Duration,
+}
+
+impl DifficultyRamp {
+    /// Creates a ramp at level zero that raises the level every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        DifficultyRamp {
+            timer: Timer::repeating(interval),
+            level: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Advances the ramp by the given time of play.
+    ///
+    /// # Returns
+    /// The number of levels the ramp rose by during this time, usually zero or one.
+    pub fn advance(&mut self, dt: Duration) -> u32 {
+        self.elapsed += dt;
+        let levels = self.timer.advance(dt);
+        self.level = self.level.saturating_add(levels);
+
+        levels
+    }
+
+    /// Returns the current level, which starts at zero.
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    /// Returns the time of play the ramp was advanced by so far.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the time of play until the next level.
+    pub fn time_to_next_level(&self) -> Duration {
+        self.timer.remaining()
+    }
+
+    /// Returns the factor the speed of the ball at level zero is multiplied with at the current level.
+    pub fn ball_speed_factor(&self) -> f64 {
+        BALL_SPEED_STEP.powi(self.level as i32)
+    }
+
+    /// Returns the given AI profile sharpened to the current level: the AI looks at the ball more
+    /// often and from farther away, aims better, and from the first levels on recovers to the
+    /// center and predicts the bounces of the ball.
+    ///
+    /// # Arguments
+    /// * `base` - The `AiProfile` of the computer player at level zero.
+    pub fn ai_profile(&self, base: AiProfile) -> AiProfile {
+        let sharpening = AI_SHARPENING_STEP.powi(self.level as i32);
+
+        AiProfile {
+            reaction_time: (base.reaction_time * sharpening)
+                .max(MIN_REACTION_TIME.min(base.reaction_time)),
+            reaction_distance: (base.reaction_distance
+                + REACTION_DISTANCE_STEP * self.level as f64)
+                .min(1.0),
+            aim_error: base.aim_error * sharpening,
+            recovers_to_center: base.recovers_to_center || self.level >= 1,
+            predicts_bounces: base.predicts_bounces || self.level >= 2,
+            tolerance: base.tolerance * sharpening,
+            ..base
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use crate::mode::ModeRegistry;
+use crate::mutator::{Mutator, Mutators};
+use crate::observer::GameObserver;
+use crate::scoring::MatchFormat;
+use crate::state::{BallSpeed, GameConfig, GameState};
+use clap::ValueEnum;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// The start of the first line of every replay file, which continues with the format version.
+const REPLAY_HEADER: &str = "cli_pong replay v";
+
+/// The format version of the written replay files.
+///
+/// Version 1 only stores the field size and the frames. Version 2 adds the version of the game
+/// that recorded the replay and the options the game was played with.
+const REPLAY_VERSION: u32 = 2;
+
+/// The prefixes of the lines that describe the recorded game in front of the frames.
+const ENGINE_PREFIX: &str = "engine ";
+const OPTION_PREFIX: &str = "option ";
+
+/// The state of the game in a single frame of a replay.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ReplayFrame {
+    /// The game time that passed since the previous frame.
+    pub dt: Duration,
+    pub player1_y: f64,
+    pub player2_y: f64,
+    pub ball_x: f64,
+    pub ball_y: f64,
+    pub ball_vx: f64,
+    pub ball_vy: f64,
+    pub player1_score: usize,
+    pub player2_score: usize,
+}
+
+impl ReplayFrame {
+    fn to_line(self) -> String {
+        format!(
+            "{} {} {} {} {} {} {} {} {}",
+            self.dt.as_micros(),
+            self.player1_y,
+            self.player2_y,
+            self.ball_x,
+            self.ball_y,
+            self.ball_vx,
+            self.ball_vy,
+            self.player1_score,
+            self.player2_score
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+
+        let frame = ReplayFrame {
+            dt: Duration::from_micros(fields.next()?.parse().ok()?),
+            player1_y: fields.next()?.parse().ok()?,
+            player2_y: fields.next()?.parse().ok()?,
+            ball_x: fields.next()?.parse().ok()?,
+            ball_y: fields.next()?.parse().ok()?,
+            ball_vx: fields.next()?.parse().ok()?,
+            ball_vy: fields.next()?.parse().ok()?,
+            player1_score: fields.next()?.parse().ok()?,
+            player2_score: fields.next()?.parse().ok()?,
+        };
+
+        match fields.next() {
+            Some(_) => None,
+            None => Some(frame),
+        }
+    }
+}
+
+/// A recorded game consisting of the field size, the options of the game and all frames.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub width: usize,
+    pub height: usize,
+    /// The version of the game that recorded the replay, or `None` for replays of format version 1.
+    pub engine: Option<String>,
+    /// The options the game was played with as pairs of the option name and its value,
+    /// e.g. `("seed", "42")`. Replays of format version 1 have no options.
+    pub options: Vec<(String, String)>,
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    /// Reads a replay from the given file.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::read(BufReader::new(File::open(path)?), path)
+    }
+
+    /// Reads a replay from the given reader, e.g. a file or a buffer in memory.
+    ///
+    /// # Arguments
+    /// * `reader` - The reader of the content of the replay.
+    /// * `path` - The path of the replay, which is only used in error messages.
+    pub fn read(reader: impl BufRead, path: &Path) -> io::Result<Self> {
+        let mut lines = reader.lines();
+
+        let header = lines.next().transpose()?.unwrap_or_default();
+        let fields = header.strip_prefix(REPLAY_HEADER).map(|fields| {
+            fields
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Vec<_>>()
+        });
+        let (version, width, height) = match fields.as_deref() {
+            Some([Ok(version), Ok(width), Ok(height)]) => (*version, *width, *height),
+            _ => return Err(invalid_data(path, "missing or malformed replay header")),
+        };
+        if version == 0 || version > REPLAY_VERSION as usize {
+            return Err(invalid_data(
+                path,
+                &format!(
+                    "replay format v{version} is not supported, this game reads up to v{REPLAY_VERSION}"
+                ),
+            ));
+        }
+
+        let mut engine = None;
+        let mut options = Vec::new();
+        let mut frames = Vec::new();
+        for line in lines {
+            let line = line?;
+            if let Some(version) = line.strip_prefix(ENGINE_PREFIX) {
+                engine = Some(version.to_string());
+            } else if let Some(option) = line.strip_prefix(OPTION_PREFIX) {
+                let (name, value) = option.split_once(' ').unwrap_or((option, ""));
+                options.push((name.to_string(), value.to_string()));
+            } else {
+                let frame = ReplayFrame::from_line(&line)
+                    .ok_or_else(|| invalid_data(path, &format!("malformed frame `{line}`")))?;
+                frames.push(frame);
+            }
+        }
+
+        Ok(Replay {
+            width,
+            height,
+            engine,
+            options,
+            frames,
+        })
+    }
+
+    /// Returns the value of the given option of the recorded game.
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns `true` if the replay was recorded by another version of the game, whose physics and
+    /// modes may differ from this one. Replays of format version 1 do not know the version.
+    pub fn is_from_other_engine(&self) -> bool {
+        self.engine
+            .as_deref()
+            .is_some_and(|engine| engine != env!("CARGO_PKG_VERSION"))
+    }
+
+    /// Returns the configuration of the recorded game: the field size and the recorded options.
+    ///
+    /// # Remarks
+    /// Options that this version of the game does not know or can not read keep their defaults.
+    pub fn config(&self) -> GameConfig {
+        let mut config = GameConfig::builder()
+            .width(self.width)
+            .height(self.height)
+            .build();
+        for (name, value) in &self.options {
+            apply_option(&mut config, name, value);
+        }
+
+        config
+    }
+
+    /// Creates a game like the recorded one, so the frames of the replay can be shown in it.
+    /// The game follows the recorded options and is played in the recorded game mode.
+    pub fn game_state(&self) -> GameState {
+        let mode = self
+            .option("mode")
+            .and_then(|name| ModeRegistry::default().create(name));
+
+        match mode {
+            Some(mode) => GameState::with_mode(self.config(), mode),
+            None => GameState::new(self.config()),
+        }
+    }
+}
+
+/// Applies a recorded option to the configuration of the game.
+///
+/// # Returns
+/// `None` if the value of the option can not be read. Unknown options are skipped.
+fn apply_option(config: &mut GameConfig, name: &str, value: &str) -> Option<()> {
+    match name {
+        "ball-speed" => config.ball_speed = BallSpeed::from_str(value, false).ok()?,
+        "paddle-speed" => config.paddle_speed = value.parse().ok()?,
+        "restitution" => config.restitution = value.parse().ok()?,
+        "spin" => config.spin = value.parse().ok()?,
+        "jitter" => config.jitter = value.parse().ok()?,
+        "gravity" => config.gravity = value.parse().ok()?,
+        "up-extend-player-height" => config.extend_player_height_up = value.parse().ok()?,
+        "down-extend-player-height" => config.extend_player_height_down = value.parse().ok()?,
+        "shrink-paddles" => config.shrink_paddles = value.parse().ok()?,
+        "turn-based" => config.turn_based = value.parse().ok()?,
+        "mutator" => {
+            let mutators = value
+                .split(',')
+                .filter(|mutator| !mutator.is_empty())
+                .map(|mutator| Mutator::from_str(mutator, false))
+                .collect::<Result<Vec<_>, _>>()
+                .ok()?;
+            config.mutators = Mutators::new(mutators);
+        }
+        "mirror-interval" => {
+            config.mirror_interval = Duration::try_from_secs_f64(value.parse().ok()?).ok()?
+        }
+        "chaos-interval" => {
+            config.chaos_interval = Duration::try_from_secs_f64(value.parse().ok()?).ok()?
+        }
+        "seed" => config.seed = Some(value.parse().ok()?),
+        "score-limit" => config.score_limit = Some(value.parse().ok()?),
+        "points-to-win" => {
+            config
+                .match_format
+                .get_or_insert_with(MatchFormat::default)
+                .points_to_win = value.parse().ok()?
+        }
+        "best-of" => {
+            config
+                .match_format
+                .get_or_insert_with(MatchFormat::default)
+                .best_of = value.parse().ok()?
+        }
+        "p1-extend" => config.player1_handicap.extend = Some(value.parse().ok()?),
+        "p1-speed" => config.player1_handicap.paddle_speed = Some(value.parse().ok()?),
+        "p1-start-score" => config.player1_handicap.start_score = value.parse().ok()?,
+        "p2-extend" => config.player2_handicap.extend = Some(value.parse().ok()?),
+        "p2-speed" => config.player2_handicap.paddle_speed = Some(value.parse().ok()?),
+        "p2-start-score" => config.player2_handicap.start_score = value.parse().ok()?,
+        // the game mode is applied by `Replay::game_state`, and the recorded frames move the paddles
+        _ => {}
+    }
+
+    Some(())
+}
+
+/// # Explanation
+/// The replay recorder writes every frame of a running game to a replay file.
+pub struct ReplayRecorder {
+    writer: BufWriter<File>,
+}
+
+impl ReplayRecorder {
+    /// Creates the replay file and writes its header.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the replay file.
+    /// * `width` - The width of the field.
+    /// * `height` - The height of the field.
+    /// * `options` - The options of the game as pairs of the option name and its value,
+    ///   so the replay describes the game it was recorded from.
+    pub fn create(
+        path: &Path,
+        width: usize,
+        height: usize,
+        options: &[(String, String)],
+    ) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{REPLAY_HEADER}{REPLAY_VERSION} {width} {height}")?;
+        writeln!(writer, "{ENGINE_PREFIX}{}", env!("CARGO_PKG_VERSION"))?;
+        for (name, value) in options {
+            writeln!(writer, "{OPTION_PREFIX}{name} {value}")?;
+        }
+
+        Ok(ReplayRecorder { writer })
+    }
+
+    /// Appends a frame to the replay file.
+    pub fn record(&mut self, frame: ReplayFrame) -> io::Result<()> {
+        writeln!(self.writer, "{}", frame.to_line())
+    }
+
+    /// Writes all buffered frames to the file.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl GameObserver for ReplayRecorder {
+    fn on_tick(&mut self, state: &GameState, dt: Duration) -> io::Result<()> {
+        self.record(state.replay_frame(dt))
+    }
+
+    fn on_finish(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+fn invalid_data(path: &Path, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {message}", path.display()),
+    )
+}
@@ -0,0 +1,65 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// # Explanation
+/// The rewind buffer is a rolling buffer of snapshots. Each snapshot is stored together with the
+/// game time that passed since the previous one, so the buffer can hold a fixed span of game time
+/// independent of the frame rate.
+#[derive(Clone)]
+pub struct RewindBuffer<T> {
+    snapshots: VecDeque<(Duration, T)>,
+    buffered_time: Duration,
+    capacity: Duration,
+}
+
+impl<T> RewindBuffer<T> {
+    /// Constructs a new `RewindBuffer` that keeps at most `capacity` of game time.
+    pub fn new(capacity: Duration) -> Self {
+        RewindBuffer {
+            snapshots: VecDeque::new(),
+            buffered_time: Duration::ZERO,
+            capacity,
+        }
+    }
+
+    /// Stores a new snapshot that was taken `dt` after the previous one.
+    /// Snapshots that are older than the capacity of the buffer are dropped.
+    pub fn push(&mut self, dt: Duration, snapshot: T) {
+        self.snapshots.push_back((dt, snapshot));
+        self.buffered_time += dt;
+
+        while self.buffered_time > self.capacity {
+            match self.snapshots.pop_front() {
+                Some((oldest_dt, _)) => self.buffered_time -= oldest_dt,
+                None => break,
+            }
+        }
+    }
+
+    /// Goes back `time` in the buffer and returns the snapshot at that point.
+    /// All newer snapshots are discarded. If the buffer holds less than `time`, the oldest snapshot is returned.
+    pub fn rewind(&mut self, time: Duration) -> Option<T> {
+        let mut rewound = Duration::ZERO;
+
+        while rewound < time && self.snapshots.len() > 1 {
+            if let Some((dt, _)) = self.snapshots.pop_back() {
+                rewound += dt;
+                self.buffered_time -= dt;
+            }
+        }
+
+        self.snapshots.pop_back().map(|(dt, snapshot)| {
+            self.buffered_time -= dt;
+            snapshot
+        })
+    }
+
+    /// Removes all snapshots from the buffer and returns them, the oldest first.
+    pub fn take_all(&mut self) -> Vec<T> {
+        self.buffered_time = Duration::ZERO;
+        self.snapshots
+            .drain(..)
+            .map(|(_, snapshot)| snapshot)
+            .collect()
+    }
+}
@@ -0,0 +1,119 @@
+use crate::stats::PlayerSide;
+
+/// How a match is played: a game is won by the first player to reach `points_to_win` goals, and
+/// the match by the first player to win the majority of `best_of` games.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MatchFormat {
+    pub points_to_win: usize,
+    pub best_of: usize,
+}
+
+impl Default for MatchFormat {
+    fn default() -> Self {
+        MatchFormat {
+            points_to_win: 11,
+            best_of: 3,
+        }
+    }
+}
+
+impl MatchFormat {
+    /// Returns the number of games a player has to win to win the match.
+    pub fn games_to_win(&self) -> usize {
+        self.best_of / 2 + 1
+    }
+}
+
+/// What a goal decided besides the point itself.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MatchProgress {
+    /// The goal only counted as a point of the current game.
+    Point,
+    /// The player on the given side won the current game, and the next game starts.
+    Game(PlayerSide),
+    /// The player on the given side won the last game they needed and with it the match.
+    Match(PlayerSide),
+}
+
+/// # Explanation
+/// The score of a match. It sits above the goals `GameState` counts: every goal is a point of
+/// the current game, a game ends once a player reaches the point target, and the match once a
+/// player won enough games.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MatchScore {
+    format: MatchFormat,
+    points: (usize, usize),
+    games: (usize, usize),
+    winner: Option<PlayerSide>,
+}
+
+impl MatchScore {
+    /// Starts a match of the given format with a score of zero.
+    pub fn new(format: MatchFormat) -> Self {
+        MatchScore {
+            format,
+            points: (0, 0),
+            games: (0, 0),
+            winner: None,
+        }
+    }
+
+    /// Counts a goal of the player on the given side. Goals after the match was decided are
+    /// ignored.
+    ///
+    /// # Returns
+    /// The `MatchProgress` the goal made.
+    pub fn point(&mut self, scorer: PlayerSide) -> MatchProgress {
+        if self.winner.is_some() {
+            return MatchProgress::Point;
+        }
+
+        let (points, games) = match scorer {
+            PlayerSide::Left => (&mut self.points.0, &mut self.games.0),
+            PlayerSide::Right => (&mut self.points.1, &mut self.games.1),
+        };
+        *points += 1;
+        if *points < self.format.points_to_win {
+            return MatchProgress::Point;
+        }
+
+        *games += 1;
+        let won_match = *games >= self.format.games_to_win();
+        self.points = (0, 0);
+        if won_match {
+            self.winner = Some(scorer);
+            MatchProgress::Match(scorer)
+        } else {
+            MatchProgress::Game(scorer)
+        }
+    }
+
+    pub fn format(&self) -> MatchFormat {
+        self.format
+    }
+
+    /// Returns the points of both players in the current game.
+    pub fn points(&self) -> (usize, usize) {
+        self.points
+    }
+
+    /// Returns the games both players won.
+    pub fn games(&self) -> (usize, usize) {
+        self.games
+    }
+
+    /// Returns the number of the current game, starting at 1. After the match it is the number
+    /// of the last game.
+    pub fn current_game(&self) -> usize {
+        let played = self.games.0 + self.games.1;
+        match self.winner {
+            Some(_) => played,
+            None => played + 1,
+        }
+    }
+
+    /// Returns the player that won the match, or `None` while it goes on.
+    pub fn winner(&self) -> Option<PlayerSide> {
+        self.winner
+    }
+}
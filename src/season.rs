@@ -0,0 +1,451 @@
+use crate::hud::{Diagnostics, Hud, HudAlign, HudSlot, ModeStatus, Score};
+use crate::mode::GameMode;
+use crate::observer::GameObserver;
+use crate::state::{GameConfig, GameEvent, GameState};
+use crate::storage::{Collection, Storage};
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashSet;
+use std::io;
+
+/// A game of the schedule of a season. The home player plays on the left.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    /// The round of the schedule, starting with `1`. Every player plays at most once per round.
+    pub round: usize,
+    pub home: String,
+    pub away: String,
+    /// The goals of the home and the away player, or `None` while the game is not played.
+    pub score: Option<(usize, usize)>,
+}
+
+impl Fixture {
+    /// Returns the winner of a played fixture.
+    pub fn winner(&self) -> Option<&str> {
+        match self.score? {
+            (home, away) if home > away => Some(&self.home),
+            (home, away) if away > home => Some(&self.away),
+            _ => None,
+        }
+    }
+}
+
+/// The record of a player in the table of a season.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Standing {
+    pub player: String,
+    pub played: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub goals_for: usize,
+    pub goals_against: usize,
+}
+
+impl Standing {
+    /// Returns the goals scored minus the goals conceded.
+    pub fn goal_difference(&self) -> i64 {
+        self.goals_for as i64 - self.goals_against as i64
+    }
+}
+
+/// # Explanation
+/// A season is a round robin among the players of a club, in which every player meets every
+/// other player once. The fixtures are played one after another over as many sessions as the club
+/// likes, and the table is updated after every game. Once all fixtures are played, the leader of
+/// the table is the champion of the season.
+///
+/// # Remarks
+/// The table is ordered by wins, then goal difference, then goals scored, then the wins in the
+/// games among the tied players, and finally by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Season {
+    pub name: String,
+    /// The goals a player needs to win a fixture.
+    pub points_to_win: usize,
+    pub players: Vec<String>,
+    pub fixtures: Vec<Fixture>,
+}
+
+impl Season {
+    /// Schedules a new season with the circle method, so every player plays once per round and,
+    /// with an odd number of players, sits out one round.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the season.
+    /// * `players` - The names of the players, at least two and each one only once.
+    /// * `points_to_win` - The goals a player needs to win a fixture.
+    pub fn new(name: &str, players: Vec<String>, points_to_win: usize) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+        if players.len() < 2 {
+            return Err(invalid("a season needs at least two players".to_string()));
+        }
+        let mut seen = HashSet::new();
+        if let Some(player) = players.iter().find(|player| !seen.insert(player.as_str())) {
+            return Err(invalid(format!("the player `{player}` is listed twice")));
+        }
+        if points_to_win == 0 {
+            return Err(invalid(
+                "a fixture has to be won with at least one goal".to_string(),
+            ));
+        }
+
+        // a bye fills the circle up to an even number of places
+        let mut circle: Vec<Option<&String>> = players.iter().map(Some).collect();
+        if circle.len() % 2 == 1 {
+            circle.push(None);
+        }
+        let places = circle.len();
+        let mut fixtures = Vec::new();
+        for round in 0..places - 1 {
+            for place in 0..places / 2 {
+                let (mut home, mut away) = (circle[place], circle[places - 1 - place]);
+                // the player at the fixed place alternates between home and away
+                if place == 0 && round % 2 == 1 {
+                    (home, away) = (away, home);
+                }
+                if let (Some(home), Some(away)) = (home, away) {
+                    fixtures.push(Fixture {
+                        round: round + 1,
+                        home: home.clone(),
+                        away: away.clone(),
+                        score: None,
+                    });
+                }
+            }
+            circle[1..].rotate_right(1);
+        }
+
+        Ok(Season {
+            name: name.to_string(),
+            points_to_win,
+            players,
+            fixtures,
+        })
+    }
+
+    /// Returns the index of the first fixture that is not played yet.
+    pub fn next_fixture(&self) -> Option<usize> {
+        self.fixtures
+            .iter()
+            .position(|fixture| fixture.score.is_none())
+    }
+
+    /// Returns `true` once all fixtures are played.
+    pub fn is_complete(&self) -> bool {
+        self.next_fixture().is_none()
+    }
+
+    /// Records the result of a fixture.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the fixture in `fixtures`.
+    /// * `score` - The goals of the home and the away player.
+    pub fn record(&mut self, index: usize, score: (usize, usize)) -> io::Result<()> {
+        let fixture = self.fixtures.get_mut(index).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("the season has no fixture {index}"),
+            )
+        })?;
+        if fixture.score.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "{} against {} was already played",
+                    fixture.home, fixture.away
+                ),
+            ));
+        }
+
+        fixture.score = Some(score);
+        Ok(())
+    }
+
+    /// Returns the table of the season, the leader first.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .players
+            .iter()
+            .map(|player| Standing {
+                player: player.clone(),
+                ..Standing::default()
+            })
+            .collect();
+        for fixture in &self.fixtures {
+            let Some((home_goals, away_goals)) = fixture.score else {
+                continue;
+            };
+            for (player, scored, conceded) in [
+                (&fixture.home, home_goals, away_goals),
+                (&fixture.away, away_goals, home_goals),
+            ] {
+                if let Some(standing) = standings.iter_mut().find(|s| &s.player == player) {
+                    standing.played += 1;
+                    standing.goals_for += scored;
+                    standing.goals_against += conceded;
+                    match scored.cmp(&conceded) {
+                        Ordering::Greater => standing.wins += 1,
+                        Ordering::Less => standing.losses += 1,
+                        Ordering::Equal => {}
+                    }
+                }
+            }
+        }
+
+        let key = |standing: &Standing| {
+            (
+                standing.wins,
+                standing.goal_difference(),
+                standing.goals_for,
+            )
+        };
+        // the head-to-head wins only count the games among the players that are tied
+        let head_to_head: Vec<usize> = standings
+            .iter()
+            .map(|standing| {
+                let tied: HashSet<&str> = standings
+                    .iter()
+                    .filter(|other| key(other) == key(standing))
+                    .map(|other| other.player.as_str())
+                    .collect();
+                self.fixtures
+                    .iter()
+                    .filter(|fixture| {
+                        tied.contains(fixture.home.as_str()) && tied.contains(fixture.away.as_str())
+                    })
+                    .filter(|fixture| fixture.winner() == Some(standing.player.as_str()))
+                    .count()
+            })
+            .collect();
+
+        let mut ranked: Vec<(Standing, usize)> = standings.into_iter().zip(head_to_head).collect();
+        ranked.sort_by(|(a, a_head), (b, b_head)| {
+            (Reverse(key(a)), Reverse(a_head), &a.player).cmp(&(
+                Reverse(key(b)),
+                Reverse(b_head),
+                &b.player,
+            ))
+        });
+
+        ranked.into_iter().map(|(standing, _)| standing).collect()
+    }
+
+    /// Returns the champion once all fixtures are played.
+    pub fn champion(&self) -> Option<String> {
+        match self.is_complete() {
+            true => self
+                .standings()
+                .into_iter()
+                .next()
+                .map(|standing| standing.player),
+            false => None,
+        }
+    }
+
+    /// Describes the table of the season as lines of text.
+    pub fn table(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{:>3}  {:<16} {:>6} {:>4} {:>6} {:>7} {:>5}",
+            "#", "Player", "Played", "Won", "Lost", "Goals", "Diff"
+        )];
+        for (rank, standing) in self.standings().iter().enumerate() {
+            lines.push(format!(
+                "{:>3}  {:<16} {:>6} {:>4} {:>6} {:>7} {:>+5}",
+                rank + 1,
+                standing.player,
+                standing.played,
+                standing.wins,
+                standing.losses,
+                format!("{}:{}", standing.goals_for, standing.goals_against),
+                standing.goal_difference()
+            ));
+        }
+
+        lines
+    }
+}
+
+/// The seasons collection as it is stored: the season that is played and the finished ones.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Seasons {
+    pub current: Option<Season>,
+    pub finished: Vec<Season>,
+}
+
+impl Seasons {
+    /// Reads the seasons from the given storage.
+    pub fn load(storage: &Storage) -> io::Result<Self> {
+        storage.load(Collection::Seasons).map(|loaded| loaded.value)
+    }
+
+    /// Starts a new season. A complete season is moved to the finished ones first.
+    ///
+    /// # Arguments
+    /// * `storage` - The `Storage` the seasons are kept in.
+    /// * `season` - The new season.
+    /// * `replace` - Whether a season that is not complete yet is dropped instead of kept.
+    pub fn start(storage: &Storage, season: Season, replace: bool) -> io::Result<()> {
+        let mut seasons = Seasons::load(storage)?;
+        match seasons.current.take() {
+            Some(current) if current.is_complete() => seasons.finished.push(current),
+            Some(current) if !replace => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!(
+                        "the season `{}` is not finished yet, start the new one with `--replace` to drop it",
+                        current.name
+                    ),
+                ))
+            }
+            _ => {}
+        }
+        seasons.current = Some(season);
+
+        storage.save(Collection::Seasons, &seasons)
+    }
+
+    /// Records the result of a fixture of the current season.
+    ///
+    /// # Returns
+    /// The current season with the result.
+    pub fn record(storage: &Storage, index: usize, score: (usize, usize)) -> io::Result<Season> {
+        let mut seasons = Seasons::load(storage)?;
+        let season = seasons.current.as_mut().ok_or_else(no_season)?;
+        season.record(index, score)?;
+        let season = season.clone();
+        storage.save(Collection::Seasons, &seasons)?;
+
+        Ok(season)
+    }
+}
+
+/// Returns the error of a command that needs a season while none was started.
+pub fn no_season() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        "no season was started, start one with `season start <players>`",
+    )
+}
+
+/// # Explanation
+/// The game mode of a fixture of a season: the home player plays on the left and the away player
+/// on the right, and the first to the goals of the season wins.
+#[derive(Debug, Clone)]
+pub struct SeasonGame {
+    season: String,
+    fixture: Fixture,
+    points_to_win: usize,
+}
+
+impl SeasonGame {
+    /// Creates the game of the fixture with the given index of a season.
+    pub fn new(season: &Season, index: usize) -> Option<Self> {
+        Some(SeasonGame {
+            season: season.name.clone(),
+            fixture: season.fixtures.get(index)?.clone(),
+            points_to_win: season.points_to_win,
+        })
+    }
+
+    /// Returns the fixture the game is played for.
+    pub fn fixture(&self) -> &Fixture {
+        &self.fixture
+    }
+}
+
+impl GameMode for SeasonGame {
+    fn name(&self) -> &str {
+        "season"
+    }
+
+    fn mutate_config(&self, config: &mut GameConfig) {
+        config.score_limit = Some(self.points_to_win);
+        config.match_format = None;
+    }
+
+    fn build_hud(&self, hud: &mut Hud) {
+        *hud = Hud::default();
+        hud.add(HudSlot::new(0, HudAlign::Left), Diagnostics);
+        hud.add(HudSlot::new(1, HudAlign::Left), Score);
+        hud.add(HudSlot::new(2, HudAlign::Left), ModeStatus);
+    }
+
+    fn status(&self, _state: &GameState) -> Option<String> {
+        Some(format!(
+            "{}, round {}: {} vs {}, first to {}",
+            self.season,
+            self.fixture.round,
+            self.fixture.home,
+            self.fixture.away,
+            self.points_to_win
+        ))
+    }
+
+    fn summary(&self, state: &GameState) -> Vec<String> {
+        let (home, away) = state.scores();
+        vec![format!(
+            "{}, round {}: {} {home}:{away} {}",
+            self.season, self.fixture.round, self.fixture.home, self.fixture.away
+        )]
+    }
+}
+
+/// # Explanation
+/// The season recorder is the observer that writes the result of a fixture to the storage as soon
+/// as the game is won, so a result is kept even if the game is not quit properly. Only the first
+/// game counts, a rematch is not recorded. When the game ends, it prints the updated table and,
+/// after the last fixture, the champion of the season.
+pub struct SeasonRecorder {
+    storage: Storage,
+    index: usize,
+    recorded: Option<io::Result<Season>>,
+}
+
+impl SeasonRecorder {
+    /// Starts to watch the game of the fixture with the given index of the current season.
+    pub fn new(storage: Storage, index: usize) -> Self {
+        SeasonRecorder {
+            storage,
+            index,
+            recorded: None,
+        }
+    }
+}
+
+impl GameObserver for SeasonRecorder {
+    fn on_event(&mut self, state: &GameState, event: GameEvent) -> io::Result<()> {
+        if let (GameEvent::Won(_), None) = (event, &self.recorded) {
+            self.recorded = Some(Seasons::record(&self.storage, self.index, state.scores()));
+        }
+
+        Ok(())
+    }
+
+    fn on_finish(&mut self) -> io::Result<()> {
+        let season = match self.recorded.take() {
+            Some(result) => result?,
+            None => {
+                println!("The game was not finished, so the fixture stays next in the schedule.");
+                return Ok(());
+            }
+        };
+
+        println!();
+        for line in season.table() {
+            println!("{line}");
+        }
+        match season.champion() {
+            Some(champion) => println!("{champion} is the champion of {}!", season.name),
+            None => {
+                let left = season
+                    .fixtures
+                    .iter()
+                    .filter(|fixture| fixture.score.is_none())
+                    .count();
+                println!("{left} fixtures left in {}.", season.name);
+            }
+        }
+
+        Ok(())
+    }
+}
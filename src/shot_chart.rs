@@ -0,0 +1,168 @@
+use crate::observer::GameObserver;
+use crate::state::{side_name, GameEvent, GameState};
+use crate::stats::PlayerSide;
+use std::io;
+
+/// The number of bands the height of the goal lines is split into.
+pub const SHOT_CHART_BANDS: usize = 6;
+
+/// The number of characters of the longest bar of the chart.
+const BAR_WIDTH: usize = 20;
+
+/// The number of goals a player has to concede before the chart points out a weak half.
+const MIN_GOALS_FOR_TIP: usize = 3;
+
+/// # Explanation
+/// The shot chart collects where the goals of a match crossed the goal lines. It is built from the
+/// goal events of the updates, and after the match it shows for each player how the goals they
+/// conceded are spread over the height of their goal line, so a player can see whether they keep
+/// losing high or low balls.
+///
+/// # Remarks
+/// The goals at the top and the bottom edge of four-player games are left out. A new game or a
+/// rematch starts an empty chart.
+#[derive(Debug, Clone, Default)]
+pub struct ShotChart {
+    /// The heights the goals crossed the goal lines of player1 and player2 at, as shares of the
+    /// height of the field from `0.0` at the bottom to `1.0` at the top.
+    conceded: [Vec<f64>; 2],
+    /// The height of the field at the last goal, which the bands are labelled with.
+    height: usize,
+}
+
+impl ShotChart {
+    /// Adds the goals of an update to the chart.
+    ///
+    /// # Arguments
+    /// * `state` - The `GameState` after the update.
+    /// * `events` - The `GameEvent`s of the update.
+    pub fn record(&mut self, state: &GameState, events: &[GameEvent]) {
+        for event in events {
+            match event {
+                GameEvent::NewGame => *self = ShotChart::default(),
+                GameEvent::Goal(_) | GameEvent::EdgeGoal(_) => {
+                    let Some((edge, crossing)) = state.last_goal_crossing() else {
+                        continue;
+                    };
+                    let Some(side) = edge.side() else {
+                        continue;
+                    };
+                    self.height = state.config().height;
+                    let share = crossing.y() / self.height.max(1) as f64;
+                    self.conceded[index(side)].push(share.clamp(0.0, 1.0));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns `true` if no goal was recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.conceded.iter().all(Vec::is_empty)
+    }
+
+    /// Returns the number of goals the player on the given side conceded.
+    pub fn conceded(&self, side: PlayerSide) -> usize {
+        self.conceded[index(side)].len()
+    }
+
+    /// Returns the number of goals the player on the given side conceded in each band of the
+    /// height of the goal line, from the top band to the bottom band.
+    pub fn bands(&self, side: PlayerSide) -> [usize; SHOT_CHART_BANDS] {
+        let mut bands = [0; SHOT_CHART_BANDS];
+        for share in &self.conceded[index(side)] {
+            let from_bottom =
+                ((share * SHOT_CHART_BANDS as f64) as usize).min(SHOT_CHART_BANDS - 1);
+            bands[SHOT_CHART_BANDS - 1 - from_bottom] += 1;
+        }
+
+        bands
+    }
+
+    /// Draws the chart as lines of text: a bar for each band and player, from the top of the goal
+    /// lines to the bottom, and a tip for each player who concedes most goals in one half.
+    pub fn render(&self) -> Vec<String> {
+        let bands = [self.bands(PlayerSide::Left), self.bands(PlayerSide::Right)];
+        let most = bands.iter().flatten().copied().max().unwrap_or(0).max(1);
+        let column = |side: PlayerSide| format!("{} conceded", side_name(side));
+
+        let mut lines = vec![
+            "Where the goals crossed the goal lines".to_string(),
+            format!(
+                "{:<8}{:<width$}{}",
+                "rows",
+                column(PlayerSide::Left),
+                column(PlayerSide::Right),
+                width = BAR_WIDTH + 6
+            ),
+        ];
+        for band in 0..SHOT_CHART_BANDS {
+            let cells: Vec<String> = bands
+                .iter()
+                .map(|counts| {
+                    let count = counts[band];
+                    let bar = "#".repeat((count * BAR_WIDTH).div_ceil(most));
+                    format!("{bar:<BAR_WIDTH$} {count:>3}  ")
+                })
+                .collect();
+            let line = format!("{:<8}{}", self.band_label(band), cells.concat());
+            lines.push(line.trim_end().to_string());
+        }
+
+        lines.extend(
+            [PlayerSide::Left, PlayerSide::Right]
+                .into_iter()
+                .filter_map(|side| self.tip(side)),
+        );
+
+        lines
+    }
+
+    /// Returns the rows of the field the given band covers, e.g. `15-18` for the top band.
+    fn band_label(&self, band: usize) -> String {
+        let from_bottom = SHOT_CHART_BANDS - 1 - band;
+        let lowest = (from_bottom * self.height).div_ceil(SHOT_CHART_BANDS);
+        let highest = match from_bottom {
+            bottom if bottom == SHOT_CHART_BANDS - 1 => self.height,
+            _ => ((from_bottom + 1) * self.height).div_ceil(SHOT_CHART_BANDS) - 1,
+        };
+
+        format!("{lowest}-{highest}")
+    }
+
+    /// Points out if the player on the given side conceded at least two thirds of their goals in
+    /// the upper or the lower half of the goal line.
+    fn tip(&self, side: PlayerSide) -> Option<String> {
+        let conceded = &self.conceded[index(side)];
+        if conceded.len() < MIN_GOALS_FOR_TIP {
+            return None;
+        }
+
+        let high = conceded.iter().filter(|&&share| share >= 0.5).count();
+        let total = conceded.len();
+        let (half, count) = match (high, total - high) {
+            (high, _) if 3 * high >= 2 * total => ("high", high),
+            (_, low) if 3 * low >= 2 * total => ("low", low),
+            _ => return None,
+        };
+
+        Some(format!(
+            "{} lets in most goals {half}: {count} of {total}",
+            side_name(side)
+        ))
+    }
+}
+
+impl GameObserver for ShotChart {
+    fn on_event(&mut self, state: &GameState, event: GameEvent) -> io::Result<()> {
+        self.record(state, &[event]);
+        Ok(())
+    }
+}
+
+fn index(side: PlayerSide) -> usize {
+    match side {
+        PlayerSide::Left => 0,
+        PlayerSide::Right => 1,
+    }
+}
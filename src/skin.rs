@@ -0,0 +1,90 @@
+use crate::achievement::{Achievement, Achievements};
+use crossterm::style::Color;
+
+/// # Explanation
+/// A skin changes the look of the paddle of player1 and of the balls: the glyph of the full cells
+/// of the paddle, the glyph of a ball in the middle of its cell and their colors. It is purely
+/// visual, so it never changes how a game plays. The skins besides `classic` are unlocked by
+/// achievements.
+///
+/// # Remarks
+/// A color of `None` keeps the color of the theme, so a skin only overrides what it changes.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Skin {
+    pub name: &'static str,
+    pub paddle: char,
+    pub ball: char,
+    pub paddle_color: Option<Color>,
+    pub ball_color: Option<Color>,
+    /// The achievement that unlocks the skin, or `None` if it is always available.
+    pub unlocked_by: Option<Achievement>,
+}
+
+impl Default for Skin {
+    fn default() -> Self {
+        Skin::classic()
+    }
+}
+
+impl Skin {
+    /// The original paddle and ball in the colors of the theme.
+    pub fn classic() -> Self {
+        Skin {
+            name: "classic",
+            paddle: '\u{2588}',
+            ball: '\u{25CF}',
+            paddle_color: None,
+            ball_color: None,
+            unlocked_by: None,
+        }
+    }
+
+    /// Returns all skins that ship with the game.
+    pub fn built_in() -> Vec<Skin> {
+        vec![
+            Skin::classic(),
+            Skin {
+                name: "ember",
+                paddle: '\u{2588}',
+                ball: '\u{25CF}',
+                paddle_color: Some(Color::Red),
+                ball_color: Some(Color::Yellow),
+                unlocked_by: Some(Achievement::FirstWin),
+            },
+            Skin {
+                name: "ghost",
+                paddle: '\u{2592}',
+                ball: '\u{25CB}',
+                paddle_color: Some(Color::Grey),
+                ball_color: Some(Color::White),
+                unlocked_by: Some(Achievement::Shutout),
+            },
+            Skin {
+                name: "comet",
+                paddle: '\u{2588}',
+                ball: '\u{25C6}',
+                paddle_color: Some(Color::Cyan),
+                ball_color: Some(Color::Magenta),
+                unlocked_by: Some(Achievement::LongRally),
+            },
+        ]
+    }
+
+    /// Returns the built-in skin with the given name.
+    pub fn find(name: &str) -> Option<Skin> {
+        Skin::built_in().into_iter().find(|skin| skin.name == name)
+    }
+
+    /// Returns the skin the given achievement unlocks.
+    pub fn unlocked_by(achievement: Achievement) -> Option<Skin> {
+        Skin::built_in()
+            .into_iter()
+            .find(|skin| skin.unlocked_by == Some(achievement))
+    }
+
+    /// Returns `true` if the skin can be used with the given unlocked achievements.
+    pub fn is_unlocked(&self, achievements: &Achievements) -> bool {
+        self.unlocked_by
+            .is_none_or(|achievement| achievements.is_unlocked(achievement))
+    }
+}
@@ -0,0 +1,197 @@
+use crate::caster::Caster;
+use crate::observer::GameObserver;
+use crate::state::{GameEvent, GameState};
+use std::io;
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The byte a spectator sends to watch the game as the players see it.
+const PLAIN_VIEW_REQUEST: u8 = b'p';
+
+/// The byte a spectator sends to watch the game in the view of the caster.
+const CASTER_VIEW_REQUEST: u8 = b'c';
+
+/// The updates the server waits for the view a spectator requests before it sends the view the
+/// players see.
+const VIEW_REQUEST_TICKS: u32 = 10;
+
+/// Returns the socket a running game accepts spectators on if no other path is given.
+pub fn default_socket_path() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("cli_pong.sock")
+}
+
+/// # Explanation
+/// The spectator server listens on a Unix domain socket and sends every rendered frame of the game
+/// to all connected spectators. Spectators only receive frames and can not influence the game.
+/// Spectators that disconnect or can not keep up are dropped.
+///
+/// # Remarks
+/// A spectator chooses its view with the first byte it sends: the game as the players see it or
+/// the view of the `Caster`, which presents the game for an audience.
+pub struct SpectatorServer {
+    path: PathBuf,
+    listener: UnixListener,
+    /// The spectators that did not request a view yet and the updates they are waiting for.
+    pending: Vec<(UnixStream, u32)>,
+    spectators: Vec<UnixStream>,
+    casted: Vec<UnixStream>,
+    caster: Caster,
+    /// The events of the current update, which the caster follows.
+    events: Vec<GameEvent>,
+}
+
+impl SpectatorServer {
+    /// Starts listening for spectators on the given socket.
+    ///
+    /// # Remarks
+    /// A leftover socket file of a game that is no longer running is replaced.
+    /// If another game is still listening on the socket, an `AddrInUse` error is returned. Any
+    /// other file at the path is left alone and an `AlreadyExists` error is returned.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the socket.
+    /// * `names` - The names of player1 and player2 the view of the caster shows.
+    pub fn bind(path: &Path, names: [String; 2]) -> io::Result<Self> {
+        match std::fs::symlink_metadata(path) {
+            Ok(metadata) if !metadata.file_type().is_socket() => {
+                return Err(io::Error::new(
+                    ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a socket", path.display()),
+                ));
+            }
+            Ok(_) => {
+                if UnixStream::connect(path).is_ok() {
+                    return Err(io::Error::new(
+                        ErrorKind::AddrInUse,
+                        format!("another game is already running on {}", path.display()),
+                    ));
+                }
+                std::fs::remove_file(path)?;
+            }
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+
+        let listener = UnixListener::bind(path)?;
+        listener.set_nonblocking(true)?;
+
+        Ok(SpectatorServer {
+            path: path.to_path_buf(),
+            listener,
+            pending: Vec::new(),
+            spectators: Vec::new(),
+            casted: Vec::new(),
+            caster: Caster::new(names),
+            events: Vec::new(),
+        })
+    }
+
+    /// Returns `true` if at least one spectator is connected.
+    pub fn has_spectators(&self) -> bool {
+        !self.spectators.is_empty() || !self.casted.is_empty()
+    }
+
+    /// Accepts all spectators that connected since the last call and sorts the spectators that
+    /// requested a view by the view they requested.
+    pub fn accept_spectators(&mut self) {
+        while let Ok((stream, _)) = self.listener.accept() {
+            if stream.set_nonblocking(true).is_ok() {
+                self.pending.push((stream, 0));
+            }
+        }
+
+        for (mut stream, waited) in std::mem::take(&mut self.pending) {
+            let mut request = [0];
+            match stream.read(&mut request) {
+                Ok(1) if request[0] == CASTER_VIEW_REQUEST => self.casted.push(stream),
+                Ok(1) => self.spectators.push(stream),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                    if waited >= VIEW_REQUEST_TICKS {
+                        self.spectators.push(stream);
+                    } else {
+                        self.pending.push((stream, waited + 1));
+                    }
+                }
+                // the spectator disconnected
+                _ => {}
+            }
+        }
+    }
+
+    /// Sends a rendered frame to all spectators that watch the game as the players see it.
+    pub fn broadcast(&mut self, frame: &[u8]) {
+        self.spectators
+            .retain_mut(|spectator| spectator.write_all(frame).is_ok());
+    }
+
+    /// Sends a rendered frame to all spectators that watch the view of the caster.
+    pub fn broadcast_casted(&mut self, frame: &[u8]) {
+        self.casted
+            .retain_mut(|spectator| spectator.write_all(frame).is_ok());
+    }
+}
+
+impl GameObserver for SpectatorServer {
+    fn on_event(&mut self, _state: &GameState, event: GameEvent) -> io::Result<()> {
+        self.events.push(event);
+
+        Ok(())
+    }
+
+    fn on_tick(&mut self, state: &GameState, dt: Duration) -> io::Result<()> {
+        // the caster follows the game without spectators, so a replay has the seconds before a goal
+        self.caster.update(state, &self.events, dt);
+        self.events.clear();
+
+        self.accept_spectators();
+        // the spectators watch in terminals like the one of the game, e.g. without Unicode
+        let capabilities = state.capabilities();
+        if !self.spectators.is_empty() {
+            let mut frame = Vec::new();
+            state
+                .compose_frame()
+                .flush_adapted(&mut frame, capabilities)?;
+            self.broadcast(&frame);
+        }
+        if !self.casted.is_empty() {
+            let mut frame = Vec::new();
+            self.caster
+                .compose(state)
+                .flush_adapted(&mut frame, capabilities)?;
+            self.broadcast_casted(&frame);
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for SpectatorServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Connects to a running game and mirrors its frames to the terminal until the game ends.
+///
+/// # Arguments
+/// * `path` - The socket of the running game.
+/// * `caster` - Whether to watch the view of the caster instead of the game as the players see it.
+pub fn spectate(path: &Path, caster: bool) -> io::Result<()> {
+    let mut stream = UnixStream::connect(path)?;
+    let request = if caster {
+        CASTER_VIEW_REQUEST
+    } else {
+        PLAIN_VIEW_REQUEST
+    };
+    stream.write_all(&[request])?;
+    let mut stdout = io::stdout();
+
+    io::copy(&mut stream, &mut stdout)?;
+    stdout.flush()
+}
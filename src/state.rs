@@ -1,16 +1,172 @@
+use crate::ai::{
+    AiController, AiProfile, AiStrategy, AiStyle, Observation, LOOKAHEAD_TIME, PREDICTION_STEP,
+};
+use crate::capability::{Capabilities, ColorDepth};
+use crate::chaos::{
+    ChaosEvent, ChaosScheduler, CHAOS_SHRINK_CELLS, DEFAULT_CHAOS_INTERVAL, SPEED_BURST_FACTOR,
+};
+use crate::collision::{broadphase, crossing_point, safe_spawn_point, Aabb};
+use crate::drill::Drill;
+use crate::frame::{Cell, Frame, Layer};
+use crate::hud::{Hud, HUD_ROWS};
+use crate::input::{KeyBindings, KeyboardInput, OppositeKeys};
+use crate::mode::{EntityColor, GameMode, ModeEntity, Versus};
+use crate::mutator::{MirrorSchedule, Mutator, Mutators, DEFAULT_MIRROR_INTERVAL};
+use crate::ramp::{DifficultyRamp, BALL_SPEED_STEP};
+use crate::replay::ReplayFrame;
+use crate::rewind::RewindBuffer;
+use crate::scoring::{MatchFormat, MatchProgress, MatchScore};
+use crate::skin::Skin;
+use crate::stats;
+use crate::stats::{PlayerSide, RallyStats};
+use crate::taunt::{Taunt, Taunts};
+use crate::theme::{Pattern, Theme};
+use crate::timer::{Timer, WorldClock};
+use crate::toast::Toasts;
+use crate::units::{Cells, CellsPerSecond, Extent};
+use crate::watchdog::Degradation;
+use clap::ValueEnum;
 use crossterm::event::{KeyCode, KeyEvent};
-use crossterm::style::Print;
-use crossterm::terminal::ClearType;
-use crossterm::{cursor, terminal, QueueableCommand};
-use rand::{random, Rng};
+use crossterm::style::Color;
+use crossterm::terminal;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::collections::HashMap;
+use std::fmt;
 use std::io;
 use std::io::Write;
+use std::str::FromStr;
 use std::time::Duration;
 
 /// Defines how much the velocity of the ball should increase with each frame.
 const VELOCITY_INCREASE: f64 = 1.003;
 
+/// Defines the speed in cells per second the ball never gets faster than, however long a rally lasts.
+pub const MAX_BALL_SPEED: f64 = 60.0;
+
+/// Defines after how many hits within the same rally the paddles shrink by one cell.
+const SHRINK_HIT_INTERVAL: usize = 20;
+
+/// Defines up to which distance in cells the glow around the ball reaches.
+const GLOW_RADIUS: f64 = 4.0;
+
+/// Defines how many brightness levels the glow around the ball uses.
+const GLOW_LEVELS: f64 = 8.0;
+
+/// Defines the first row of the frame that shows the game field, below the HUD and the top border.
+const FIELD_TOP: usize = HUD_ROWS + 1;
+
+/// Defines the minimal width of the frame, so the widgets of the HUD fit on narrow fields.
+const HUD_WIDTH: usize = 80;
+
+/// Defines the maximal size of the minimap shown on fields larger than the terminal.
+const MINIMAP_WIDTH: usize = 24;
+const MINIMAP_HEIGHT: usize = 8;
+
+/// Defines the background color that separates the minimap from the field beneath it.
+const MINIMAP_BACKGROUND: Color = Color::DarkGrey;
+
+/// Defines how long the score of a player flashes after a goal.
+const SCORE_FLASH_TIME: Duration = Duration::from_millis(1200);
+
+/// Defines how long a single flash of the score lasts.
+const SCORE_FLASH_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Defines the maximal speed of the paddles in cells per second at the normal ball speed.
+pub const DEFAULT_PADDLE_SPEED: f64 = 12.0;
+
+/// Defines by how many degrees a single press of a tilt key tilts a paddle.
+const TILT_STEP_DEGREES: f64 = 10.0;
+
+/// Defines how many steps a paddle can be tilted in each direction.
+const MAX_TILT_STEPS: i32 = 2;
+
+/// Defines the vertical acceleration of a spinning ball per cell per second of spin.
+const MAGNUS_COEFFICIENT: f64 = 0.3;
+
+/// Defines the factor the spin of the ball is multiplied with when it bounces off a wall.
+pub const SPIN_BOUNCE_DECAY: f64 = 0.5;
+
+/// Defines the factor the vertical speed of the ball is multiplied with when it bounces off a wall
+/// under gravity. It takes out the energy the ball gains from the steps of the simulation.
+pub const GRAVITY_BOUNCE_DAMPING: f64 = 0.9;
+
+/// Defines by how many degrees a bounce is turned at most with the full jitter of `1.0`.
+const MAX_JITTER_DEGREES: f64 = 15.0;
+
+/// Defines the minimal size of a field that is given relative to the terminal.
+const MIN_FIELD_WIDTH: usize = 10;
+const MIN_FIELD_HEIGHT: usize = 4;
+
+/// Defines how much game time the rewind buffer keeps.
+const REWIND_CAPACITY: Duration = Duration::from_secs(10);
+
+/// Defines how far the game jumps back when the rewind key is pressed.
+const REWIND_TIME: Duration = Duration::from_secs(3);
+
+/// The distance in cells between the points of the path of the ball that are checked for bricks
+/// and obstacles, so a fast ball can not pass through them between two updates.
+const BRICK_SWEEP_STEP: f64 = 0.25;
+
+/// The number of columns of each obstacle in the middle of the field.
+pub const OBSTACLE_WIDTH: usize = 2;
+
+/// Mirrors a height outside of the field at the walls, like a ball that bounces off them.
+/// Heights from `0.0` up to and including `max_height` are inside the field and kept as they are.
+pub fn fold_into_field(y: f64, max_height: f64) -> f64 {
+    // every bounce mirrors the trajectory, so the movement repeats after twice the field height
+    let period = 2.0 * max_height;
+    let folded = y.rem_euclid(period);
+    if folded > max_height {
+        period - folded
+    } else {
+        folded
+    }
+}
+
+/// Returns the index of the team on the given side, `0` for the left and `1` for the right team.
+fn team_index(side: PlayerSide) -> usize {
+    match side {
+        PlayerSide::Left => 0,
+        PlayerSide::Right => 1,
+    }
+}
+
+/// Returns the name the player on the given side is shown with.
+pub fn side_name(side: PlayerSide) -> &'static str {
+    match side {
+        PlayerSide::Left => "player1",
+        PlayerSide::Right => "player2",
+    }
+}
+
+/// Returns the edge the ball left the field through in a four-player game, if any.
+pub fn edge_conceded(ball: Position2D, field_width: f64, field_height: f64) -> Option<Edge> {
+    if ball.x < 0.0 {
+        Some(Edge::Left)
+    } else if ball.x > field_width {
+        Some(Edge::Right)
+    } else if ball.y < 0.0 {
+        Some(Edge::Bottom)
+    } else if ball.y > field_height {
+        Some(Edge::Top)
+    } else {
+        None
+    }
+}
+
+/// Returns the side that scored if the ball crossed one of the goal lines at `x = 0` and
+/// `x = field_width`, independent of where the paddles are and how the ball moves.
+pub fn goal_scored(ball: Position2D, field_width: f64) -> Option<PlayerSide> {
+    if ball.x < 0.0 {
+        Some(PlayerSide::Right)
+    } else if ball.x > field_width {
+        Some(PlayerSide::Left)
+    } else {
+        None
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct Position2D {
     x: f64,
@@ -21,31 +177,78 @@ impl Position2D {
     pub fn new(x: f64, y: f64) -> Self {
         Position2D { x, y }
     }
+
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    pub fn y(&self) -> f64 {
+        self.y
+    }
 }
 
 impl Position2D {
-    pub fn to_discrete(&self) -> DiscretePosition2D {
-        let x = self.x.round() as usize;
-        let y = self.y.round() as usize;
+    /// Returns the cell the position lies in. Positions outside of the field, e.g. a ball behind
+    /// the goal line, give cells with negative or too large coordinates.
+    pub fn to_discrete(self) -> DiscretePosition2D {
+        let x = Cells::new(self.x).round_to_cell();
+        let y = Cells::new(self.y).round_to_cell();
 
         DiscretePosition2D::new(x, y)
     }
+
+    /// Returns the position moved by the given distances along the axes.
+    pub fn shifted(self, dx: Cells, dy: Cells) -> Self {
+        Position2D::new(self.x + dx.get(), self.y + dy.get())
+    }
+
+    /// Returns the position reached after moving with the given velocity for the given time.
+    pub fn moved(self, velocity: Velocity2D, dt: Duration) -> Self {
+        self.shifted(velocity.horizontal() * dt, velocity.vertical() * dt)
+    }
 }
 
+/// A cell of the field. The coordinates are signed, so cells next to the field can be described
+/// and arithmetic at the edges of the field can not underflow.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct DiscretePosition2D {
-    x: usize,
-    y: usize,
+    x: i64,
+    y: i64,
 }
 
 impl DiscretePosition2D {
-    pub fn new(x: usize, y: usize) -> Self {
+    pub fn new(x: i64, y: i64) -> Self {
         DiscretePosition2D { x, y }
     }
 
-    pub fn to_continuous(&self) -> Position2D {
+    pub fn x(&self) -> i64 {
+        self.x
+    }
+
+    pub fn y(&self) -> i64 {
+        self.y
+    }
+
+    pub fn to_continuous(self) -> Position2D {
         Position2D::new(self.x as f64, self.y as f64)
     }
+
+    /// Returns the column and the row of the cell if it lies within a field of the given size,
+    /// where the columns range from `0` to `width` and the rows from `0` to `height`.
+    pub fn in_field(self, width: usize, height: usize) -> Option<(usize, usize)> {
+        let x = usize::try_from(self.x).ok().filter(|&x| x <= width)?;
+        let y = usize::try_from(self.y).ok().filter(|&y| y <= height)?;
+
+        Some((x, y))
+    }
+
+    /// Returns the cell of a field of the given size that is closest to this cell.
+    pub fn clamped(self, width: usize, height: usize) -> (usize, usize) {
+        (
+            self.x.clamp(0, width as i64) as usize,
+            self.y.clamp(0, height as i64) as usize,
+        )
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -58,16 +261,215 @@ impl Velocity2D {
     pub fn new(vx: f64, vy: f64) -> Self {
         Velocity2D { vx, vy }
     }
+
+    pub fn vx(&self) -> f64 {
+        self.vx
+    }
+
+    pub fn vy(&self) -> f64 {
+        self.vy
+    }
+
+    /// Returns the horizontal part of the velocity.
+    pub fn horizontal(&self) -> CellsPerSecond {
+        CellsPerSecond::new(self.vx)
+    }
+
+    /// Returns the vertical part of the velocity.
+    pub fn vertical(&self) -> CellsPerSecond {
+        CellsPerSecond::new(self.vy)
+    }
+
+    /// Returns the speed, i.e. the length of the velocity.
+    pub fn speed(&self) -> f64 {
+        self.vx.hypot(self.vy)
+    }
+
+    /// Returns the velocity with the same direction and the speed multiplied by `factor`.
+    pub fn scaled(self, factor: f64) -> Self {
+        Velocity2D::new(self.vx * factor, self.vy * factor)
+    }
+}
+
+/// The movement a player performs during one update.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PaddleCommand {
+    Up,
+    Down,
+    Stay,
+    /// Moves the paddle towards the given y coordinate at its maximal speed and stops there.
+    /// This suits inputs that point at a position, e.g. eye trackers or other assistive devices.
+    /// A target that is not a finite number is ignored, so the paddle stays.
+    MoveTo(f64),
+}
+
+/// Defines who moves a player.
+#[derive(Clone)]
+pub enum Controller {
+    /// The player is moved by a human using the keyboard.
+    Keyboard(KeyboardInput),
+    /// The player is moved by the computer with the given strategy.
+    Ai(Box<dyn AiStrategy>),
+    /// The player repeats the last command it was given from outside the game, e.g. by a learning agent.
+    External(PaddleCommand),
+}
+
+impl Controller {
+    /// Decides how the player should move in the current update.
+    ///
+    /// # Arguments
+    /// * `pressed_keys` - A reference to a `HashMap` containing `KeyCode`s of currently pressed keys.
+    /// * `state` - The `GameState` before the update.
+    /// * `side` - The `PlayerSide` of the controlled player.
+    /// * `dt` - The `Duration` since the last update.
+    pub fn next_command(
+        &mut self,
+        pressed_keys: &HashMap<KeyCode, KeyEvent>,
+        state: &GameState,
+        side: PlayerSide,
+        dt: Duration,
+    ) -> PaddleCommand {
+        match self {
+            Controller::Keyboard(input) => input.command(pressed_keys),
+            Controller::Ai(ai) => ai.decide(state, side, dt),
+            Controller::External(command) => *command,
+        }
+    }
+
+    /// Swaps the up and down keys of a keyboard controller. Other controllers are not affected.
+    pub fn mirror(&mut self) {
+        if let Controller::Keyboard(input) = self {
+            input.mirror();
+        }
+    }
+
+    /// Returns a single word for who moves the player: `human`, `computer` or `external`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Controller::Keyboard(_) => "human",
+            Controller::Ai(_) => "computer",
+            Controller::External(_) => "external",
+        }
+    }
+
+    /// Returns a short description of who moves the player.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Controller::Keyboard(_) => "the keyboard",
+            Controller::Ai(_) => "the computer",
+            Controller::External(_) => "an external program",
+        }
+    }
+}
+
+/// # Explanation
+/// The computer player of a horizontal paddle in the four-player mode. It moves the paddle to where
+/// the ball will cross the edge of the paddle, or back to the middle while the ball moves away.
+#[derive(Debug, Copy, Clone)]
+pub struct EdgeFollower {
+    edge: Edge,
+}
+
+impl EdgeFollower {
+    /// Constructs the computer player of the paddle at the given edge.
+    pub fn new(edge: Edge) -> Self {
+        EdgeFollower { edge }
+    }
+}
+
+impl AiStrategy for EdgeFollower {
+    /// The side is ignored, the follower always moves the paddle at its edge.
+    fn decide(&mut self, state: &GameState, _side: PlayerSide, _dt: Duration) -> PaddleCommand {
+        let config = state.config();
+        let ball = state.ball();
+        let (line, facing) = match self.edge {
+            Edge::Top => (config.height as f64, 1.0),
+            _ => (0.0, -1.0),
+        };
+
+        let target = if ball.velocity.vy * facing > 0.0 {
+            let time_to_line = (line - ball.position.y) / ball.velocity.vy;
+            ball.position.x + ball.velocity.vx * time_to_line
+        } else {
+            config.width as f64 / 2.0
+        };
+
+        PaddleCommand::MoveTo(target.clamp(0.0, config.width as f64))
+    }
+}
+
+/// The directions a paddle moves in.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Orientation {
+    /// The paddle moves up and down along the left or the right edge of the field.
+    #[default]
+    Vertical,
+    /// The paddle moves left and right along the top or the bottom edge of the field.
+    /// `PaddleCommand::Up` moves it to the right and `PaddleCommand::Down` to the left.
+    Horizontal,
+}
+
+/// The part of its edge a paddle may move in.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Lane {
+    /// The paddle may move along the whole edge.
+    #[default]
+    Full,
+    /// The paddle stays in the upper half of the edge, e.g. the upper paddle of a team in doubles.
+    Upper,
+    /// The paddle stays in the lower half of the edge.
+    Lower,
+}
+
+/// The edges of the field. In the four-player mode, each edge has a paddle and a goal.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Edge {
+    /// All edges in the order of the players guarding them, from player1 to player4.
+    pub const ALL: [Edge; 4] = [Edge::Left, Edge::Right, Edge::Top, Edge::Bottom];
+
+    /// Returns the name of the player guarding the edge, e.g. `player3` for the top edge.
+    pub fn player_name(self) -> &'static str {
+        match self {
+            Edge::Left => "player1",
+            Edge::Right => "player2",
+            Edge::Top => "player3",
+            Edge::Bottom => "player4",
+        }
+    }
+
+    /// Returns the side of the player guarding the edge, or `None` for the top and the bottom edge.
+    pub fn side(self) -> Option<PlayerSide> {
+        match self {
+            Edge::Left => Some(PlayerSide::Left),
+            Edge::Right => Some(PlayerSide::Right),
+            Edge::Top | Edge::Bottom => None,
+        }
+    }
 }
 
 /// This struct represents a player in the pong game.
+#[derive(Clone)]
 pub struct Player {
-    extend_up: usize,
-    extend_down: usize,
-    key_up: KeyCode,
-    key_down: KeyCode,
+    extend_up: Extent,
+    extend_down: Extent,
+    shrink: usize,
+    controller: Controller,
     position: Position2D,
     velocity: Velocity2D,
+    orientation: Orientation,
+    lane: Lane,
+    /// The speed the player moved with in the last update, positive upwards, or to the right for
+    /// horizontal paddles.
+    vertical_speed: CellsPerSecond,
+    /// The tilt of the paddle in steps of `TILT_STEP_DEGREES`, positive if it returns the ball upwards.
+    tilt: i32,
 }
 
 impl Player {
@@ -76,61 +478,264 @@ impl Player {
     /// # Arguments
     /// * `extend_up` - The distance the player extends upwards.
     /// * `extend_down` - The distance the player extends downwards.
-    /// * `key_up` - The `KeyCode` for moving the player up.
-    /// * `key_down` - The `KeyCode` for moving the player down.
+    /// * `controller` - The `Controller` that moves the player.
     /// * `position` - The starting `Position2D` of the player.
+    /// * `speed` - The maximal speed of the player in cells per second.
     ///
     /// # Returns
-    /// A new `Player` instance with a default velocity.
+    /// A new `Player` instance.
     pub fn new(
         extend_up: usize,
         extend_down: usize,
-        key_up: KeyCode,
-        key_down: KeyCode,
+        controller: Controller,
         position: Position2D,
+        speed: f64,
     ) -> Self {
-        let velocity = Velocity2D::new(0., 12.0);
+        let velocity = Velocity2D::new(0., speed);
 
         Player {
-            extend_up,
-            extend_down,
-            key_up,
-            key_down,
+            extend_up: Extent::new(extend_up),
+            extend_down: Extent::new(extend_down),
+            shrink: 0,
+            controller,
             position,
             velocity,
+            orientation: Orientation::Vertical,
+            lane: Lane::Full,
+            vertical_speed: CellsPerSecond::ZERO,
+            tilt: 0,
+        }
+    }
+
+    /// Turns the player into a paddle of the given orientation. The extents upwards and downwards
+    /// become the extents to the right and to the left for horizontal paddles.
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        let speed = self.velocity.vx.abs().max(self.velocity.vy.abs());
+        self.velocity = match orientation {
+            Orientation::Vertical => Velocity2D::new(0., speed),
+            Orientation::Horizontal => Velocity2D::new(speed, 0.),
+        };
+        self.orientation = orientation;
+
+        self
+    }
+
+    /// Keeps the paddle in the given part of its edge. Both halves include the middle cell,
+    /// so the two paddles of a team can reach every ball between them.
+    pub fn with_lane(mut self, lane: Lane) -> Self {
+        self.lane = lane;
+
+        self
+    }
+
+    /// Returns the orientation of the paddle.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Returns the position of the player.
+    pub fn position(&self) -> Position2D {
+        self.position
+    }
+
+    /// Returns the controller that moves the player.
+    pub fn controller(&self) -> &Controller {
+        &self.controller
+    }
+
+    /// Returns the coordinate of the position along the direction the paddle moves in.
+    fn along(&self, position: Position2D) -> f64 {
+        match self.orientation {
+            Orientation::Vertical => position.y,
+            Orientation::Horizontal => position.x,
+        }
+    }
+
+    /// Moves the player along the direction it moves in by the given distance.
+    fn shifted_along(&self, distance: Cells) -> Position2D {
+        match self.orientation {
+            Orientation::Vertical => self.position.shifted(Cells::ZERO, distance),
+            Orientation::Horizontal => self.position.shifted(distance, Cells::ZERO),
         }
     }
 
-    /// Updates the player's position based on the keys pressed and the elapsed time.
+    /// Updates the player's position based on the command of its controller and the elapsed time.
     ///
     /// # Arguments
-    /// * `max_height` - The maximum height of the playing field.
-    /// * `pressed_keys` - A reference to a `HashMap` containing `KeyCode`s of currently pressed keys.
+    /// * `max_height` - The maximum height of the playing field, or its width for horizontal paddles.
+    /// * `command` - The `PaddleCommand` the controller of the player chose for this update.
     /// * `dt` - The `Duration` since the last update.
     ///
     /// # Remarks
-    /// This method updates the `position` of the player based on the `velocity`, `key_up`, and `key_down` inputs.
+    /// This method updates the `position` of the player based on the `velocity` and the `PaddleCommand`.
     /// It also ensures that the player's position does not exceed the maximum height constraints.
-    pub fn update_position(
-        &mut self,
-        max_height: f64,
-        pressed_keys: &HashMap<KeyCode, KeyEvent>,
-        dt: Duration,
-    ) {
-        if pressed_keys.contains_key(&self.key_up) {
-            self.position.x += self.velocity.vx * dt.as_secs_f64();
-            self.position.y += self.velocity.vy * dt.as_secs_f64();
+    /// The target of `PaddleCommand::MoveTo` is an x-coordinate for horizontal paddles. A target
+    /// that is not a finite number would move the paddle out of the field, so it is ignored.
+    pub fn update_position(&mut self, max_height: f64, command: PaddleCommand, dt: Duration) {
+        let before = self.along(self.position);
+
+        match command {
+            PaddleCommand::Up => self.position = self.position.moved(self.velocity, dt),
+            PaddleCommand::Down => {
+                self.position = self.position.moved(self.velocity.scaled(-1.0), dt)
+            }
+            PaddleCommand::Stay => {}
+            PaddleCommand::MoveTo(target) if !target.is_finite() => {}
+            PaddleCommand::MoveTo(target) => {
+                let speed = self.velocity.vx.abs().max(self.velocity.vy.abs());
+                let max_step = CellsPerSecond::new(speed) * dt;
+                let step = Cells::new(target - before).clamp(-max_step, max_step);
+                self.position = self.shifted_along(step);
+            }
+        }
+
+        self.keep_in_field(max_height);
+        if !dt.is_zero() {
+            self.vertical_speed = Cells::new(self.along(self.position) - before) / dt;
+        }
+    }
+
+    /// Moves the player by the given number of cells, upwards if positive, without leaving the field.
+    pub fn nudge(&mut self, cells: i32, max_height: f64) {
+        self.position = self.shifted_along(Cells::new(f64::from(cells)));
+        self.keep_in_field(max_height);
+    }
+
+    fn keep_in_field(&mut self, max_height: f64) {
+        let middle = Cells::new((max_height / 2.0).round());
+        let (top, bottom) = match self.lane {
+            Lane::Full => (Cells::new(max_height), Cells::ZERO),
+            Lane::Upper => (Cells::new(max_height), middle),
+            Lane::Lower => (middle, Cells::ZERO),
+        };
+        let highest = top - self.current_extend_up().to_cells();
+        let lowest = bottom + self.current_extend_down().to_cells();
+        let along = Cells::new(self.along(self.position))
+            .min(highest)
+            .max(lowest)
+            .get();
+        match self.orientation {
+            Orientation::Vertical => self.position.y = along,
+            Orientation::Horizontal => self.position.x = along,
+        }
+    }
+
+    /// Returns the `Observation` of the game from the view of this player.
+    ///
+    /// # Arguments
+    /// * `ball` - The `Ball` the player watches.
+    /// * `config` - The `GameConfig` with the size of the field and the rules the ball follows.
+    /// * `arena` - The `Arena` the game mode added to the field.
+    pub fn observe(&self, ball: &Ball, config: &GameConfig, arena: &Arena) -> Observation {
+        let heading_towards_paddle = (ball.position.x - self.position.x) * ball.velocity.vx < 0.0;
+        let intercept_y = heading_towards_paddle
+            .then(|| {
+                ball.predict_crossing(
+                    config,
+                    arena,
+                    self.position.x,
+                    PREDICTION_STEP,
+                    LOOKAHEAD_TIME,
+                )
+            })
+            .flatten()
+            .map(|crossing| crossing.y);
+
+        Observation {
+            paddle_x: self.position.x,
+            paddle_y: self.position.y,
+            ball_x: ball.position.x,
+            ball_y: ball.position.y,
+            ball_vx: ball.velocity.vx,
+            ball_vy: ball.velocity.vy,
+            ball_ay: ball.lateral_acceleration(),
+            intercept_y,
+            field_width: config.width as f64,
+            field_height: config.height as f64,
+        }
+    }
+
+    /// Shrinks the player by one cell, alternating between the top and the bottom end.
+    /// The center cell of the player is never removed.
+    pub fn shrink(&mut self) {
+        if !self.current_extend_up().is_zero() || !self.current_extend_down().is_zero() {
+            self.shrink += 1;
+        }
+    }
+
+    /// Restores the player to its original size.
+    pub fn restore_size(&mut self) {
+        self.shrink = 0;
+    }
+
+    /// Tilts the paddle by the given number of steps, positive to return the ball more upwards.
+    /// The tilt is limited to `MAX_TILT_STEPS` steps in each direction.
+    pub fn tilt_by(&mut self, steps: i32) {
+        self.tilt = (self.tilt + steps).clamp(-MAX_TILT_STEPS, MAX_TILT_STEPS);
+    }
+
+    /// Returns the normal of the surface of the paddle as a unit vector.
+    ///
+    /// # Arguments
+    /// * `facing` - `1.0` for the paddle on the left, which faces right, `-1.0` for the paddle on the right.
+    fn surface_normal(&self, facing: f64) -> (f64, f64) {
+        let angle = (self.tilt as f64 * TILT_STEP_DEGREES).to_radians();
+        (facing * angle.cos(), angle.sin())
+    }
+
+    /// Returns the glyph of the cell of the paddle at the given row. The ends of a tilted paddle
+    /// are drawn as slashes that lean like the paddle, all other cells with the given glyph.
+    fn glyph(&self, y: i64, facing: f64, full: char) -> char {
+        if self.orientation == Orientation::Horizontal {
+            return full;
         }
-        if pressed_keys.contains_key(&self.key_down) {
-            self.position.x -= self.velocity.vx * dt.as_secs_f64();
-            self.position.y -= self.velocity.vy * dt.as_secs_f64();
+        let center = self.position.to_discrete().y;
+        let is_end = y == center + self.current_extend_up().to_offset()
+            || y == center - self.current_extend_down().to_offset();
+
+        match self.tilt as f64 * facing {
+            tilt if is_end && tilt > 0.0 => '\\',
+            tilt if is_end && tilt < 0.0 => '/',
+            _ => full,
         }
+    }
+
+    /// Returns the number of cells of the paddle, which is smaller than its full length while
+    /// the paddle is shrunk.
+    pub fn length(&self) -> usize {
+        1 + self.current_extend_up().get() + self.current_extend_down().get()
+    }
+
+    /// Returns the number of cells of the paddle at its original size.
+    pub fn full_length(&self) -> usize {
+        1 + self.extend_up.get() + self.extend_down.get()
+    }
 
-        self.position.y = self
-            .position
-            .y
-            .min(max_height - self.extend_up as f64)
-            .max(0.0 + self.extend_down as f64);
+    fn current_extend_up(&self) -> Extent {
+        self.extend_up.shrunk_by(self.shrink.div_ceil(2))
+    }
+
+    fn current_extend_down(&self) -> Extent {
+        self.extend_down.shrunk_by(self.shrink / 2)
+    }
+
+    /// Returns the bounding box of the cells of the player for the broadphase.
+    pub fn bounding_box(&self) -> Aabb {
+        let center = self.position.to_discrete().to_continuous();
+        let half_cell = Cells::new(0.5);
+        let down = self.current_extend_down().to_cells() + half_cell;
+        let up = self.current_extend_up().to_cells() + half_cell;
+
+        match self.orientation {
+            Orientation::Vertical => Aabb::new(
+                center.shifted(-half_cell, -down),
+                center.shifted(half_cell, up),
+            ),
+            Orientation::Horizontal => Aabb::new(
+                center.shifted(-down, -half_cell),
+                center.shifted(up, half_cell),
+            ),
+        }
     }
 
     /// Checks for collision between the player and a given position.
@@ -143,17 +748,197 @@ impl Player {
     pub fn collides_with(&self, position: Position2D) -> bool {
         let discrete_position = position.to_discrete();
         let own_discrete_position = self.position.to_discrete();
+        let (along, own_along, across, own_across) = match self.orientation {
+            Orientation::Vertical => (
+                discrete_position.y,
+                own_discrete_position.y,
+                discrete_position.x,
+                own_discrete_position.x,
+            ),
+            Orientation::Horizontal => (
+                discrete_position.x,
+                own_discrete_position.x,
+                discrete_position.y,
+                own_discrete_position.y,
+            ),
+        };
+
+        own_along - self.current_extend_down().to_offset() <= along
+            && along <= own_along + self.current_extend_up().to_offset()
+            && own_across == across
+    }
+}
+
+/// Returns the points of the straight path between the given positions that are checked for
+/// bricks, obstacles and portals, from `from` to `to` and `BRICK_SWEEP_STEP` apart at most.
+fn swept_path(from: Position2D, to: Position2D) -> Vec<Position2D> {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let steps = (dx.hypot(dy) / BRICK_SWEEP_STEP).ceil().max(1.0) as usize;
+
+    (0..=steps)
+        .map(|step| {
+            let share = step as f64 / steps as f64;
+            Position2D::new(from.x + dx * share, from.y + dy * share)
+        })
+        .collect()
+}
+
+/// # Explanation
+/// The arena is what a game mode adds to the plain field, e.g. further goals. The mode builds it
+/// with `GameMode::arena`, and the ball, the players and the drawing follow it, so the game does
+/// not need to know the modes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Arena {
+    /// Adds player3 and player4, who guard the top and the bottom edge with horizontal paddles.
+    /// The ball no longer bounces off these edges, and a goal on any edge counts for the player
+    /// who touched the ball last.
+    pub edge_players: bool,
+    /// Lets two players share each side. Player1 and player2 move the upper paddles of their teams,
+    /// and a teammate on each side moves a second paddle in the lower half.
+    pub doubles: bool,
+    /// Replaces player2 by a solid wall along the right goal line, which the ball bounces off.
+    pub right_wall: bool,
+    /// The static blocks the ball bounces off like off a wall.
+    pub obstacles: Vec<Obstacle>,
+    /// The pair of portals in the field, if any. A ball that enters one portal leaves the other.
+    pub portals: Option<PortalPair>,
+}
+
+impl Arena {
+    /// Returns the cells of the obstacles and the portals, as the entities they are drawn as.
+    pub fn entities(&self) -> Vec<ModeEntity> {
+        let blocks = self
+            .obstacles
+            .iter()
+            .flat_map(Obstacle::cells)
+            .map(|cell| ModeEntity {
+                position: cell.to_continuous(),
+                glyph: '\u{2588}',
+                color: EntityColor::Border,
+            });
+        // the two portals of the pair look different, so it is clear which one is which
+        let portals = self.portals.iter().flat_map(|portals| {
+            let [first, second] = portals.tiles();
+            [
+                ModeEntity {
+                    position: first.to_continuous(),
+                    glyph: '\u{25CE}',
+                    color: EntityColor::Paddle1,
+                },
+                ModeEntity {
+                    position: second.to_continuous(),
+                    glyph: '\u{25C9}',
+                    color: EntityColor::Paddle2,
+                },
+            ]
+        });
+
+        blocks.chain(portals).collect()
+    }
+}
+
+/// # Explanation
+/// A portal pair links two tiles of the field like a tunnel. A ball that enters one of the tiles
+/// leaves the other one with the same velocity.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct PortalPair {
+    tiles: [DiscretePosition2D; 2],
+}
+
+impl PortalPair {
+    /// Links the two given tiles.
+    pub fn new(first: DiscretePosition2D, second: DiscretePosition2D) -> Self {
+        PortalPair {
+            tiles: [first, second],
+        }
+    }
+
+    /// Returns the portal pair of a field of the given size: a tile in the upper left quarter and
+    /// a tile in the lower right quarter, away from the middle lane the serves fly along.
+    pub fn mid_field(width: usize, height: usize) -> Self {
+        PortalPair::new(
+            DiscretePosition2D::new((width / 4) as i64, (height * 3 / 4) as i64),
+            DiscretePosition2D::new((width * 3 / 4) as i64, (height / 4) as i64),
+        )
+    }
+
+    /// Returns both tiles of the pair.
+    pub fn tiles(&self) -> [DiscretePosition2D; 2] {
+        self.tiles
+    }
+
+    /// Returns the tile a ball that enters the given cell leaves from, or `None` if the cell is
+    /// no tile of the pair.
+    pub fn exit(&self, cell: DiscretePosition2D) -> Option<DiscretePosition2D> {
+        match self.tiles.iter().position(|&tile| tile == cell)? {
+            0 => Some(self.tiles[1]),
+            _ => Some(self.tiles[0]),
+        }
+    }
+}
+
+/// # Explanation
+/// An obstacle is a static block of cells in the field that the ball bounces off like off a wall.
+/// Obstacles never move and can not be broken, unlike the bricks of a breakout game.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Obstacle {
+    /// The lowest and leftmost cell of the block.
+    corner: DiscretePosition2D,
+    width: i64,
+    height: i64,
+}
+
+impl Obstacle {
+    /// Creates a block of the given size in cells from its lowest and leftmost cell.
+    pub fn new(corner: DiscretePosition2D, width: usize, height: usize) -> Self {
+        Obstacle {
+            corner,
+            width: width as i64,
+            height: height as i64,
+        }
+    }
+
+    /// Returns the obstacles of a field of the given size: a block above and a block below the
+    /// middle of the field, each a fifth of the height tall. The lane through the middle stays
+    /// free, so the serves are not blocked.
+    pub fn mid_field(width: usize, height: usize) -> Vec<Obstacle> {
+        let block_height = (height / 5).max(1);
+        let x = (width / 2).saturating_sub(OBSTACLE_WIDTH / 2) as i64;
 
-        own_discrete_position.y - self.extend_down <= discrete_position.y
-            && discrete_position.y <= own_discrete_position.y + self.extend_up
-            && own_discrete_position.x == discrete_position.x
+        [height / 4, height * 3 / 4]
+            .into_iter()
+            .map(|center| {
+                let bottom = center.saturating_sub(block_height / 2) as i64;
+                Obstacle::new(
+                    DiscretePosition2D::new(x, bottom),
+                    OBSTACLE_WIDTH,
+                    block_height,
+                )
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the given cell is part of the block.
+    pub fn contains(&self, cell: DiscretePosition2D) -> bool {
+        (self.corner.x..self.corner.x + self.width).contains(&cell.x)
+            && (self.corner.y..self.corner.y + self.height).contains(&cell.y)
+    }
+
+    /// Returns all cells of the block.
+    pub fn cells(&self) -> impl Iterator<Item = DiscretePosition2D> + '_ {
+        (self.corner.x..self.corner.x + self.width).flat_map(move |x| {
+            (self.corner.y..self.corner.y + self.height).map(move |y| DiscretePosition2D::new(x, y))
+        })
     }
 }
 
 /// This struct represents the ball used in the pong game.
+#[derive(Clone)]
 pub struct Ball {
     position: Position2D,
     velocity: Velocity2D,
+    /// The spin the ball got from the vertical speed of the paddle that hit it.
+    spin: CellsPerSecond,
 }
 
 impl Ball {
@@ -161,13 +946,25 @@ impl Ball {
     ///
     /// # Arguments
     /// * `position` - The starting `Position2D` of the ball.
+    /// * `speed` - The `BallSpeed` preset that scales the velocity.
+    /// * `rng` - The random number generator used for the velocity.
     ///
     /// # Returns
     /// A new `Ball` instance.
-    pub fn new(position: Position2D) -> Self {
+    pub fn new(position: Position2D, speed: BallSpeed, rng: &mut impl Rng) -> Self {
+        Ball {
+            position,
+            velocity: Self::random_ball_velocity(speed, rng),
+            spin: CellsPerSecond::ZERO,
+        }
+    }
+
+    /// Constructs a new `Ball` without spin that moves with the given velocity.
+    pub fn with_velocity(position: Position2D, velocity: Velocity2D) -> Self {
         Ball {
             position,
-            velocity: Self::random_ball_velocity(),
+            velocity,
+            spin: CellsPerSecond::ZERO,
         }
     }
 
@@ -175,238 +972,3294 @@ impl Ball {
         self.position
     }
 
+    pub fn get_velocity(&self) -> Velocity2D {
+        self.velocity
+    }
+
     /// Updates the ball's position based on its velocity, collision with walls or players, and time passed.
+    /// The field has none of the features a game mode adds, see `update_position_with_teams`.
     ///
     /// # Arguments
-    /// * `max_height` - The maximum height of the game field to handle vertical wall collisions.
+    /// * `config` - The `GameConfig` with the height of the field and the rules the ball follows.
     /// * `player1` - A reference to the first player's `Player` instance for potential collision detection.
     /// * `player2` - A reference to the second player's `Player` instance for potential collision detection.
+    /// * `rng` - The random number generator the jitter of the bounces is drawn from.
     /// * `dt` - The `Duration` since the last update.
     ///
+    /// # Returns
+    /// `true` if the ball was hit by one of the players, otherwise `false`.
+    ///
     /// # Remarks
     /// This method updates the `position` of the ball and handles collision logic with the walls and players.
     pub fn update_position(
         &mut self,
-        max_height: f64,
+        config: &GameConfig,
         player1: &Player,
         player2: &Player,
+        rng: &mut impl Rng,
         dt: Duration,
-    ) {
-        self.update_if_collision_with_wall(max_height, dt);
+    ) -> bool {
+        self.update_position_with_teams(config, &Arena::default(), &[player1], &[player2], rng, dt)
+    }
 
-        if self.velocity.vx <= 0.0 {
-            self.update_if_collision_with_player1(player1, dt);
+    /// Updates the ball like `update_position`, but every side may have several paddles, e.g. the
+    /// two paddles of a team in the doubles mode.
+    ///
+    /// # Arguments
+    /// * `config` - The `GameConfig` with the height of the field and the rules the ball follows.
+    /// * `arena` - The `Arena` the game mode added to the field.
+    /// * `left` - The paddles on the left side. The ball bounces off the first one it reaches.
+    /// * `right` - The paddles on the right side.
+    /// * `rng` - The random number generator the jitter of the bounces is drawn from.
+    /// * `dt` - The `Duration` since the last update.
+    ///
+    /// # Returns
+    /// `true` if the ball was hit by one of the paddles, otherwise `false`.
+    pub fn update_position_with_teams(
+        &mut self,
+        config: &GameConfig,
+        arena: &Arena,
+        left: &[&Player],
+        right: &[&Player],
+        rng: &mut impl Rng,
+        dt: Duration,
+    ) -> bool {
+        let (team, facing) = if self.velocity.vx <= 0.0 {
+            (left, 1.0)
         } else {
-            self.update_if_collision_with_player2(player2, dt);
+            (right, -1.0)
+        };
+        let boxes: Vec<Aabb> = std::iter::once(self.swept_box(config, dt))
+            .chain(team.iter().map(|player| player.bounding_box()))
+            .collect();
+        // a wall in place of player2 is handled like the other walls
+        let team_present = facing > 0.0 || !arena.right_wall;
+        let candidates = broadphase(&boxes);
+        let mut hitter = None;
+        if team_present {
+            for (index, &player) in team.iter().enumerate() {
+                if candidates.contains(&(0, index + 1))
+                    && self.update_if_collision_with_player(player, facing, config, arena, dt)
+                {
+                    hitter = Some(player);
+                    break;
+                }
+            }
+        }
+        let hit = hitter.is_some();
+        if let Some(hitter) = hitter {
+            self.velocity = self.velocity.scaled(config.restitution);
+            if config.jitter > 0.0 {
+                let jittered = Self::jittered(self.velocity, config.jitter, rng);
+                self.velocity = Velocity2D::new(jittered.vx.abs() * facing, jittered.vy);
+            }
+            if config.spin {
+                self.spin = hitter.vertical_speed;
+            }
         }
 
-        self.position = self.calc_next_position(dt);
-        self.velocity.vx *= VELOCITY_INCREASE;
-        self.velocity.vy *= VELOCITY_INCREASE;
-    }
+        let bounced = self.fly(config, arena, dt);
+        if bounced && config.jitter > 0.0 {
+            let jittered = Self::jittered(self.velocity, config.jitter, rng);
+            let vy = jittered.vy.abs() * self.velocity.vy.signum();
+            self.velocity = Velocity2D::new(jittered.vx, vy);
+        }
+        self.accelerate(config, dt);
+
+        hit
+    }
+
+    /// Returns the ball as it will be after the given time if nothing but the walls and the given
+    /// arena is in its way. The ball itself is not changed, so this can be used to look ahead,
+    /// e.g. by an AI.
+    ///
+    /// # Remarks
+    /// The random jitter of the bounces can not be foreseen and is left out.
+    pub fn predicted(&self, config: &GameConfig, arena: &Arena, dt: Duration) -> Ball {
+        let mut ball = self.clone();
+        ball.fly(config, arena, dt);
+        ball.accelerate(config, dt);
+
+        ball
+    }
+
+    /// Follows the predicted flight of the ball update by update until it crosses the vertical line
+    /// at `x`, e.g. the column of a paddle.
+    ///
+    /// # Arguments
+    /// * `config` - The `GameConfig` with the size of the field and the rules the ball follows.
+    /// * `arena` - The `Arena` the game mode added to the field.
+    /// * `x` - The x-coordinate of the vertical line.
+    /// * `step` - The `Duration` of a simulated update, ideally the one the game is updated with.
+    /// * `max_time` - The longest time the flight is followed.
+    ///
+    /// # Returns
+    /// The point at which the ball crosses the line, or `None` if it does not within `max_time`.
+    pub fn predict_crossing(
+        &self,
+        config: &GameConfig,
+        arena: &Arena,
+        x: f64,
+        step: Duration,
+        max_time: Duration,
+    ) -> Option<Position2D> {
+        if step.is_zero() {
+            return None;
+        }
+
+        let mut ball = self.clone();
+        let mut elapsed = Duration::ZERO;
+        while elapsed < max_time {
+            let next = ball.predicted(config, arena, step);
+            let (before, after) = (ball.position.x - x, next.position.x - x);
+            if before * after <= 0.0 && before != after {
+                // the ball crossed the line during this update, so the crossing lies in between
+                let share = before / (before - after);
+                let y = ball.position.y + (next.position.y - ball.position.y) * share;
+                return Some(Position2D::new(x, y));
+            }
+            ball = next;
+            elapsed += step;
+        }
+
+        None
+    }
+
+    /// Moves the ball along its velocity for the given time and lets it bounce off or wrap around
+    /// the walls of the field and of the arena, and pass its portals.
+    ///
+    /// # Returns
+    /// `true` if the ball bounced off the upper or the lower wall.
+    fn fly(&mut self, config: &GameConfig, arena: &Arena, dt: Duration) -> bool {
+        let max_height = config.height as f64;
+
+        let from = self.position;
+        self.position = self.calc_next_position(dt);
+        if !arena.obstacles.is_empty() {
+            let is_solid = |cell| {
+                arena
+                    .obstacles
+                    .iter()
+                    .any(|obstacle| obstacle.contains(cell))
+            };
+            // a ball that was put into an obstacle, e.g. by a teleport, flies out of it
+            if !is_solid(from.to_discrete()) {
+                self.bounce_off_cells(from, is_solid);
+            }
+        }
+        if arena.right_wall {
+            self.bounce_off_right_wall(config.width as f64);
+        }
+        let bounced = if arena.edge_players {
+            // the upper and the lower edge are goals guarded by paddles
+            false
+        } else if !config.mutators.walls() {
+            // the ball leaves the field at one wall and comes back in at the other
+            self.position.y = self.position.y.rem_euclid(max_height);
+            false
+        } else if self.bounce_off_walls(max_height) {
+            self.spin *= SPIN_BOUNCE_DECAY;
+            if config.gravity > 0.0 {
+                self.velocity.vy *= GRAVITY_BOUNCE_DAMPING;
+            }
+            true
+        } else {
+            false
+        };
+        if let Some(portals) = arena.portals {
+            self.pass_portal(portals, from);
+        }
+
+        bounced
+    }
+
+    /// Sends the ball through the first portal it entered since the given position: it leaves the
+    /// other portal of the pair with the same velocity. A ball that starts on a portal, e.g. right
+    /// after it came out of it, does not enter that portal again.
+    fn pass_portal(&mut self, portals: PortalPair, from: Position2D) {
+        let start = from.to_discrete();
+        let exit = swept_path(from, self.position)
+            .into_iter()
+            .map(Position2D::to_discrete)
+            .filter(|&cell| cell != start)
+            .find_map(|cell| portals.exit(cell));
+        if let Some(exit) = exit {
+            self.position = exit.to_continuous();
+        }
+    }
+
+    /// Bounces the ball off the first solid cell on its path since the given position. The ball is
+    /// put back to where it was right before it touched the cell.
+    ///
+    /// # Arguments
+    /// * `from` - The position of the ball before it moved.
+    /// * `is_solid` - Returns `true` for the cells the ball bounces off.
+    ///
+    /// # Returns
+    /// The cell the ball bounced off, or `None` if its path was free.
+    fn bounce_off_cells(
+        &mut self,
+        from: Position2D,
+        is_solid: impl Fn(DiscretePosition2D) -> bool,
+    ) -> Option<DiscretePosition2D> {
+        let path = swept_path(from, self.position);
+
+        for step in 1..path.len() {
+            let cell = path[step].to_discrete();
+            if !is_solid(cell) {
+                continue;
+            }
+
+            let before = path[step - 1];
+            let cell_before = before.to_discrete();
+            if cell_before.y == cell.y || cell_before.x != cell.x {
+                self.velocity.vx = -self.velocity.vx;
+            }
+            if cell_before.y != cell.y {
+                self.velocity.vy = -self.velocity.vy;
+            }
+            self.position = before;
+            return Some(cell);
+        }
+
+        None
+    }
+
+    /// Curves the flight of a spinning ball, pulls it down under gravity and speeds it up, up to
+    /// `MAX_BALL_SPEED`.
+    fn accelerate(&mut self, config: &GameConfig, dt: Duration) {
+        let acceleration = self.lateral_acceleration() - config.gravity;
+        self.velocity.vy += acceleration * dt.as_secs_f64();
+        self.velocity.vx *= VELOCITY_INCREASE;
+        self.velocity.vy *= VELOCITY_INCREASE;
+        let speed = self.velocity.speed();
+        if speed > MAX_BALL_SPEED {
+            self.velocity = self.velocity.scaled(MAX_BALL_SPEED / speed);
+        }
+    }
+
+    /// Turns the velocity by a random angle of at most `jitter` times `MAX_JITTER_DEGREES`.
+    /// The caller keeps the ball moving away from the surface it bounced off.
+    fn jittered(velocity: Velocity2D, jitter: f64, rng: &mut impl Rng) -> Velocity2D {
+        let max_angle = (jitter * MAX_JITTER_DEGREES).to_radians();
+        let (sin, cos) = rng.gen_range(-max_angle..=max_angle).sin_cos();
+
+        Velocity2D::new(
+            velocity.vx * cos - velocity.vy * sin,
+            velocity.vx * sin + velocity.vy * cos,
+        )
+    }
+
+    /// Returns the bounding box of the path of the ball during this update for the broadphase.
+    /// A ball that crosses a wall may reach any height after the bounce, so its box covers the
+    /// whole height of the field.
+    fn swept_box(&self, config: &GameConfig, dt: Duration) -> Aabb {
+        let max_height = config.height as f64;
+        let next_position = self.calc_next_position(dt);
+        // the ball touches every cell its center rounds to
+        let margin = Cells::from_count(config.mutators.ball_radius()).get() + 0.5;
+
+        if (0.0..=max_height).contains(&next_position.y) {
+            Aabb::swept(self.position, next_position, margin)
+        } else {
+            Aabb::new(
+                Position2D::new(self.position.x, -margin),
+                Position2D::new(next_position.x, max_height + margin),
+            )
+        }
+    }
+
+    /// Returns the vertical acceleration in cells per second squared that curves the flight of the ball.
+    pub fn lateral_acceleration(&self) -> f64 {
+        self.spin.get() * MAGNUS_COEFFICIENT
+    }
+
+    /// Reflects the ball at a wall once its center left the field while moving outwards.
+    /// The ball is mirrored back by the distance it went past the wall, so it never sinks into a
+    /// wall, and a ball that already moves back into the field is not bounced a second time.
+    ///
+    /// # Returns
+    /// `true` if the ball bounced off a wall.
+    fn bounce_off_walls(&mut self, max_height: f64) -> bool {
+        let y = self.position.y;
+        let bounces =
+            (y < 0.0 && self.velocity.vy < 0.0) || (y > max_height && self.velocity.vy > 0.0);
+        if bounces {
+            self.velocity.vy = -self.velocity.vy;
+        }
+        self.position.y = fold_into_field(y, max_height);
+
+        bounces
+    }
+
+    /// Reflects the ball at the wall along the right goal line, like `bounce_off_walls` does at the
+    /// upper and the lower wall.
+    fn bounce_off_right_wall(&mut self, field_width: f64) {
+        if self.position.x > field_width && self.velocity.vx > 0.0 {
+            self.velocity.vx = -self.velocity.vx;
+            self.position.x = 2.0 * field_width - self.position.x;
+        }
+    }
+
+    /// Checks whether the ball reaches the given player during this update and reflects it if so.
+    ///
+    /// # Arguments
+    /// * `player` - The player the ball moves towards.
+    /// * `facing` - `1.0` for the player on the left, `-1.0` for the player on the right.
+    /// * `config` - The `GameConfig` with the height of the field and the size of the ball.
+    /// * `arena` - The `Arena` that tells whether the ball bounces off the upper and the lower edge.
+    /// * `dt` - The `Duration` since the last update.
+    fn update_if_collision_with_player(
+        &mut self,
+        player: &Player,
+        facing: f64,
+        config: &GameConfig,
+        arena: &Arena,
+        dt: Duration,
+    ) -> bool {
+        let mut possible_collision_point =
+            crossing_point(self.position, self.velocity, player.position.x);
+        let next_position = self.calc_next_position(dt);
+        // a ball that bounces off a wall on its way to the paddle reaches it at the mirrored height
+        let max_height = config.height as f64;
+        possible_collision_point.y = match (arena.edge_players, config.mutators.walls()) {
+            (true, _) => possible_collision_point.y,
+            (false, true) => fold_into_field(possible_collision_point.y, max_height),
+            (false, false) => possible_collision_point.y.rem_euclid(max_height),
+        };
+
+        let collides = (possible_collision_point.x - next_position.x) * facing >= 0.0
+            && Self::reaches(
+                player,
+                possible_collision_point,
+                config.mutators.ball_radius(),
+            );
+        if collides {
+            self.reflect(player.surface_normal(facing));
+        }
+
+        collides
+    }
+
+    /// Checks whether the ball reaches the given horizontal player during this update and reflects it
+    /// if so. Horizontal paddles are flat and put no spin on the ball.
+    ///
+    /// # Arguments
+    /// * `player` - The player at the top or the bottom edge.
+    /// * `facing` - `1.0` for the player at the bottom, which faces up, `-1.0` for the player at the top.
+    /// * `config` - The `GameConfig` with the size of the ball and the restitution of the paddles.
+    /// * `dt` - The `Duration` since the last update.
+    fn update_if_collision_with_edge_player(
+        &mut self,
+        player: &Player,
+        facing: f64,
+        config: &GameConfig,
+        dt: Duration,
+    ) -> bool {
+        let line = player.position.y;
+        if self.velocity.vy * facing >= 0.0 || (self.position.y - line) * facing < 0.0 {
+            return false;
+        }
+        let next_position = self.calc_next_position(dt);
+        if (next_position.y - line) * facing > 0.0 {
+            return false;
+        }
+
+        let time_to_line = (line - self.position.y) / self.velocity.vy;
+        let crossing = Position2D::new(self.position.x + self.velocity.vx * time_to_line, line);
+        let collides = Self::reaches(player, crossing, config.mutators.ball_radius());
+        if collides {
+            self.velocity.vy = self.velocity.vy.abs() * facing;
+            self.velocity = self.velocity.scaled(config.restitution);
+            self.spin = CellsPerSecond::ZERO;
+        }
+
+        collides
+    }
+
+    /// Reflects the velocity of the ball at a surface with the given unit normal, which points away
+    /// from the paddle. The ball always leaves the paddle, even at a steep angle of incidence.
+    fn reflect(&mut self, (nx, ny): (f64, f64)) {
+        let along_normal = self.velocity.vx * nx + self.velocity.vy * ny;
+        let vx = self.velocity.vx - 2.0 * along_normal * nx;
+        let vy = self.velocity.vy - 2.0 * along_normal * ny;
+
+        self.velocity = Velocity2D::new(vx.abs() * nx.signum(), vy);
+    }
+
+    /// Returns `true` if a ball of the given radius at `position` touches the player.
+    fn reaches(player: &Player, position: Position2D, radius: usize) -> bool {
+        let radius = Cells::from_count(radius);
+        let mut offset = -radius;
+        while offset <= radius {
+            let touched = match player.orientation {
+                Orientation::Vertical => position.shifted(Cells::ZERO, offset),
+                Orientation::Horizontal => position.shifted(offset, Cells::ZERO),
+            };
+            if player.collides_with(touched) {
+                return true;
+            }
+            offset += Cells::new(1.0);
+        }
+
+        false
+    }
+
+    fn calc_next_position(&self, dt: Duration) -> Position2D {
+        self.position.moved(self.velocity, dt)
+    }
+
+    /// Generates a random velocity for the ball when it is initialized or reset.
+    ///
+    /// # Arguments
+    /// * `speed` - The `BallSpeed` preset that scales the velocity.
+    /// * `rng` - The random number generator used for the velocity.
+    ///
+    /// # Returns
+    /// A `Velocity2D` representing a random velocity within a specified range.
+    pub fn random_ball_velocity(speed: BallSpeed, rng: &mut impl Rng) -> Velocity2D {
+        let vx = match rng.gen::<bool>() {
+            true => rng.gen_range(10.0..20.0),
+            false => rng.gen_range(-20.0..-10.0),
+        };
+        let vy = rng.gen_range(-6.0..6.0);
+        Velocity2D::new(vx * speed.factor(), vy * speed.factor())
+    }
+}
+
+/// The speed presets of the ball.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, ValueEnum)]
+#[non_exhaustive]
+pub enum BallSpeed {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl BallSpeed {
+    /// Returns the factor the serve velocity of the ball is scaled with.
+    pub fn factor(self) -> f64 {
+        match self {
+            BallSpeed::Slow => 0.75,
+            BallSpeed::Normal => 1.0,
+            BallSpeed::Fast => 1.3,
+        }
+    }
+}
+
+/// The size of the field along one axis.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FieldSize {
+    /// The field spans the given number of cells.
+    Cells(usize),
+    /// The field spans the given percentage of the terminal.
+    Percent(usize),
+}
+
+impl FieldSize {
+    /// Returns the number of cells the field spans.
+    ///
+    /// # Arguments
+    /// * `available` - The number of cells that fit into the terminal along this axis.
+    /// * `minimum` - The number of cells a percentage resolves to at least.
+    fn resolve(self, available: usize, minimum: usize) -> usize {
+        match self {
+            FieldSize::Cells(cells) => cells,
+            FieldSize::Percent(percent) => (available * percent / 100).max(minimum),
+        }
+    }
+}
+
+impl FromStr for FieldSize {
+    type Err = String;
+
+    /// Parses a size like `60` or `80%`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.strip_suffix('%') {
+            Some(percent) => match percent.parse() {
+                Ok(percent @ 1..=100) => Ok(FieldSize::Percent(percent)),
+                _ => Err(format!("invalid percentage `{text}`, expected 1% to 100%")),
+            },
+            None => text.parse().map(FieldSize::Cells).map_err(|_| {
+                format!(
+                    "invalid size `{text}`, expected cells like `60` or a percentage like `80%`"
+                )
+            }),
+        }
+    }
+}
+
+impl fmt::Display for FieldSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldSize::Cells(cells) => write!(f, "{cells}"),
+            FieldSize::Percent(percent) => write!(f, "{percent}%"),
+        }
+    }
+}
+
+/// # Explanation
+/// A handicap evens out a game between players of different skill. Each setting that is given
+/// replaces the shared setting of the `GameConfig` for the paddles of one side only, e.g. a longer
+/// paddle for the weaker player or a head start of a few goals.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Handicap {
+    /// How much longer the paddle is upwards and downwards, or `None` for the shared extents.
+    pub extend: Option<usize>,
+    /// The maximal speed of the paddle in cells per second, or `None` for the shared speed.
+    pub paddle_speed: Option<f64>,
+    /// The goals the player starts each game with.
+    pub start_score: usize,
+}
+
+/// The `GameConfig` struct bundles all settings that customize a game.
+///
+/// # Remarks
+/// New settings are added in minor versions, so outside of this crate a config is created with
+/// [`GameConfig::builder`] or [`GameConfig::default`] instead of a struct expression.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct GameConfig {
+    /// The width of the game field.
+    pub width: usize,
+    /// The height of the game field.
+    pub height: usize,
+    /// The extension of player's reach upwards.
+    pub extend_player_height_up: usize,
+    /// The extension of player's reach downwards.
+    pub extend_player_height_down: usize,
+    /// The speed preset of the ball.
+    pub ball_speed: BallSpeed,
+    /// The maximal speed of the paddles in cells per second.
+    pub paddle_speed: f64,
+    /// The factor the speed of the ball is multiplied with when a player hits it.
+    /// Below 1 the hits slow the ball down, above 1 every return is faster.
+    pub restitution: f64,
+    /// Whether a moving paddle puts spin on the ball, which curves its flight.
+    pub spin: bool,
+    /// How much the bounces at the walls and the paddles are turned at random, from `0.0` to `1.0`.
+    pub jitter: f64,
+    /// The downward acceleration of the ball in cells per second squared, which lets it fly in
+    /// arcs. `0.0` turns the gravity off.
+    pub gravity: f64,
+    /// Whether the paddles shrink during long rallies.
+    pub shrink_paddles: bool,
+    /// Whether the live win probability meter is shown.
+    pub win_meter: bool,
+    /// The keys bound to the actions of the game.
+    pub key_bindings: KeyBindings,
+    /// How a paddle moves while both of its direction keys are held.
+    pub opposite_keys: OppositeKeys,
+    /// The colors the game is drawn with.
+    pub theme: Theme,
+    /// The look of the paddle of player1 and of the balls, drawn on top of the theme.
+    pub skin: Skin,
+    /// Whether the cells around the ball are drawn brighter.
+    pub ball_glow: bool,
+    /// Whether the glyph of the ball shows where the ball is within its cell.
+    pub smooth_ball: bool,
+    /// Whether every cell of the field is drawn two terminal columns wide.
+    pub double_width: bool,
+    /// Whether the field is drawn with the scanlines and the flicker of an old CRT screen.
+    pub crt: bool,
+    /// Whether the ball stops each time it crosses midfield until the pause key is pressed.
+    pub turn_based: bool,
+    /// Whether the rewind key for practicing is enabled.
+    pub practice: bool,
+    /// The profile of the computer controlled player1, or `None` if player1 is a human.
+    pub player1_ai: Option<AiProfile>,
+    /// The profile of the computer controlled player2, or `None` if player2 is a human.
+    pub player2_ai: Option<AiProfile>,
+    /// The handicap of player1 and its teammate.
+    pub player1_handicap: Handicap,
+    /// The handicap of player2 and its teammate.
+    pub player2_handicap: Handicap,
+    /// The seed of the random number generator, or `None` for a random seed.
+    pub seed: Option<u64>,
+    /// The mutators that change the rules of the game.
+    pub mutators: Mutators,
+    /// Plays a match of several games, or `None` to count the goals forever.
+    pub match_format: Option<MatchFormat>,
+    /// Ends the game once a player scored the given number of goals, or `None` to play forever.
+    pub score_limit: Option<usize>,
+    /// Raises the difficulty each time the given time of play has passed: the ball gets faster and
+    /// a computer controlled player2 sharper. `None` keeps the difficulty of the start.
+    pub difficulty_ramp: Option<Duration>,
+    /// The serves of the serve machine in a drill, or `None` to use the default drill.
+    pub drill: Option<Drill>,
+    /// How long the controls of a player stay mirrored by the `mirror` mutator.
+    pub mirror_interval: Duration,
+    /// The time of play between two random events of the `chaos` mutator.
+    pub chaos_interval: Duration,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            width: 60,
+            height: 18,
+            extend_player_height_up: 1,
+            extend_player_height_down: 1,
+            ball_speed: BallSpeed::Normal,
+            paddle_speed: DEFAULT_PADDLE_SPEED,
+            restitution: 1.0,
+            spin: false,
+            jitter: 0.0,
+            gravity: 0.0,
+            shrink_paddles: false,
+            win_meter: false,
+            key_bindings: KeyBindings::default(),
+            opposite_keys: OppositeKeys::Neutral,
+            theme: Theme::classic(),
+            skin: Skin::classic(),
+            ball_glow: false,
+            smooth_ball: false,
+            double_width: false,
+            crt: false,
+            turn_based: false,
+            practice: false,
+            player1_ai: None,
+            player2_ai: None,
+            player1_handicap: Handicap::default(),
+            player2_handicap: Handicap::default(),
+            seed: None,
+            mutators: Mutators::default(),
+            match_format: None,
+            score_limit: None,
+            difficulty_ramp: None,
+            drill: None,
+            mirror_interval: DEFAULT_MIRROR_INTERVAL,
+            chaos_interval: DEFAULT_CHAOS_INTERVAL,
+        }
+    }
+}
+
+impl GameConfig {
+    /// Returns a `GameConfigBuilder` that starts from the default settings.
+    pub fn builder() -> GameConfigBuilder {
+        GameConfigBuilder {
+            config: GameConfig::default(),
+        }
+    }
+
+    /// Returns the paddle speed that keeps the ratio between the paddle speed and the serve speed
+    /// of the ball the same as with the default settings, so a faster ball stays returnable.
+    pub fn balanced_paddle_speed(&self) -> f64 {
+        DEFAULT_PADDLE_SPEED * self.ball_speed.factor() * self.mutators.serve_factor()
+    }
+
+    /// Returns the handicap of the players on the given side.
+    pub fn handicap(&self, side: PlayerSide) -> Handicap {
+        match side {
+            PlayerSide::Left => self.player1_handicap,
+            PlayerSide::Right => self.player2_handicap,
+        }
+    }
+
+    /// Returns how far the paddles on the given side extend upwards and downwards.
+    pub fn paddle_extents(&self, side: PlayerSide) -> (usize, usize) {
+        match self.handicap(side).extend {
+            Some(extend) => (extend, extend),
+            None => (self.extend_player_height_up, self.extend_player_height_down),
+        }
+    }
+
+    /// Returns the maximal speed of the paddles on the given side in cells per second.
+    pub fn side_paddle_speed(&self, side: PlayerSide) -> f64 {
+        self.handicap(side)
+            .paddle_speed
+            .unwrap_or(self.paddle_speed)
+    }
+}
+
+/// # Explanation
+/// The `GameConfigBuilder` creates a `GameConfig` setting by setting. Every setting that is not
+/// given keeps its default value, so code using the builder keeps compiling when settings are added.
+#[derive(Debug, Clone)]
+pub struct GameConfigBuilder {
+    config: GameConfig,
+}
+
+impl GameConfigBuilder {
+    /// Sets [`GameConfig::width`].
+    pub fn width(mut self, width: usize) -> Self {
+        self.config.width = width;
+        self
+    }
+
+    /// Sets [`GameConfig::height`].
+    pub fn height(mut self, height: usize) -> Self {
+        self.config.height = height;
+        self
+    }
+
+    /// Sets [`GameConfig::extend_player_height_up`].
+    pub fn extend_player_height_up(mut self, extend_player_height_up: usize) -> Self {
+        self.config.extend_player_height_up = extend_player_height_up;
+        self
+    }
+
+    /// Sets [`GameConfig::extend_player_height_down`].
+    pub fn extend_player_height_down(mut self, extend_player_height_down: usize) -> Self {
+        self.config.extend_player_height_down = extend_player_height_down;
+        self
+    }
+
+    /// Sets [`GameConfig::ball_speed`].
+    pub fn ball_speed(mut self, ball_speed: BallSpeed) -> Self {
+        self.config.ball_speed = ball_speed;
+        self
+    }
+
+    /// Sets [`GameConfig::paddle_speed`].
+    pub fn paddle_speed(mut self, paddle_speed: f64) -> Self {
+        self.config.paddle_speed = paddle_speed;
+        self
+    }
+
+    /// Sets [`GameConfig::restitution`].
+    pub fn restitution(mut self, restitution: f64) -> Self {
+        self.config.restitution = restitution;
+        self
+    }
+
+    /// Sets [`GameConfig::spin`].
+    pub fn spin(mut self, spin: bool) -> Self {
+        self.config.spin = spin;
+        self
+    }
+
+    /// Sets [`GameConfig::jitter`].
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.config.jitter = jitter;
+        self
+    }
+
+    /// Sets [`GameConfig::gravity`].
+    pub fn gravity(mut self, gravity: f64) -> Self {
+        self.config.gravity = gravity;
+        self
+    }
+
+    /// Sets [`GameConfig::shrink_paddles`].
+    pub fn shrink_paddles(mut self, shrink_paddles: bool) -> Self {
+        self.config.shrink_paddles = shrink_paddles;
+        self
+    }
+
+    /// Sets [`GameConfig::win_meter`].
+    pub fn win_meter(mut self, win_meter: bool) -> Self {
+        self.config.win_meter = win_meter;
+        self
+    }
+
+    /// Sets [`GameConfig::key_bindings`].
+    pub fn key_bindings(mut self, key_bindings: KeyBindings) -> Self {
+        self.config.key_bindings = key_bindings;
+        self
+    }
+
+    /// Sets [`GameConfig::opposite_keys`].
+    pub fn opposite_keys(mut self, opposite_keys: OppositeKeys) -> Self {
+        self.config.opposite_keys = opposite_keys;
+        self
+    }
+
+    /// Sets [`GameConfig::theme`].
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.config.theme = theme;
+        self
+    }
+
+    /// Sets [`GameConfig::ball_glow`].
+    pub fn ball_glow(mut self, ball_glow: bool) -> Self {
+        self.config.ball_glow = ball_glow;
+        self
+    }
+
+    /// Sets [`GameConfig::skin`].
+    pub fn skin(mut self, skin: Skin) -> Self {
+        self.config.skin = skin;
+        self
+    }
+
+    /// Sets [`GameConfig::smooth_ball`].
+    pub fn smooth_ball(mut self, smooth_ball: bool) -> Self {
+        self.config.smooth_ball = smooth_ball;
+        self
+    }
+
+    /// Sets [`GameConfig::double_width`].
+    pub fn double_width(mut self, double_width: bool) -> Self {
+        self.config.double_width = double_width;
+        self
+    }
+
+    /// Sets [`GameConfig::crt`].
+    pub fn crt(mut self, crt: bool) -> Self {
+        self.config.crt = crt;
+        self
+    }
+
+    /// Sets [`GameConfig::turn_based`].
+    pub fn turn_based(mut self, turn_based: bool) -> Self {
+        self.config.turn_based = turn_based;
+        self
+    }
+
+    /// Sets [`GameConfig::practice`].
+    pub fn practice(mut self, practice: bool) -> Self {
+        self.config.practice = practice;
+        self
+    }
+
+    /// Sets [`GameConfig::player1_ai`].
+    pub fn player1_ai(mut self, player1_ai: Option<AiProfile>) -> Self {
+        self.config.player1_ai = player1_ai;
+        self
+    }
+
+    /// Sets [`GameConfig::player2_ai`].
+    pub fn player2_ai(mut self, player2_ai: Option<AiProfile>) -> Self {
+        self.config.player2_ai = player2_ai;
+        self
+    }
+
+    /// Sets [`GameConfig::player1_handicap`].
+    pub fn player1_handicap(mut self, player1_handicap: Handicap) -> Self {
+        self.config.player1_handicap = player1_handicap;
+        self
+    }
+
+    /// Sets [`GameConfig::player2_handicap`].
+    pub fn player2_handicap(mut self, player2_handicap: Handicap) -> Self {
+        self.config.player2_handicap = player2_handicap;
+        self
+    }
+
+    /// Sets [`GameConfig::seed`].
+    pub fn seed(mut self, seed: Option<u64>) -> Self {
+        self.config.seed = seed;
+        self
+    }
+
+    /// Sets [`GameConfig::mutators`].
+    pub fn mutators(mut self, mutators: Mutators) -> Self {
+        self.config.mutators = mutators;
+        self
+    }
+
+    /// Sets [`GameConfig::match_format`].
+    pub fn match_format(mut self, match_format: Option<MatchFormat>) -> Self {
+        self.config.match_format = match_format;
+        self
+    }
+
+    /// Sets [`GameConfig::score_limit`].
+    pub fn score_limit(mut self, score_limit: Option<usize>) -> Self {
+        self.config.score_limit = score_limit;
+        self
+    }
+
+    /// Sets [`GameConfig::difficulty_ramp`].
+    pub fn difficulty_ramp(mut self, difficulty_ramp: Option<Duration>) -> Self {
+        self.config.difficulty_ramp = difficulty_ramp;
+        self
+    }
+
+    /// Sets [`GameConfig::drill`].
+    pub fn drill(mut self, drill: Option<Drill>) -> Self {
+        self.config.drill = drill;
+        self
+    }
+
+    /// Sets [`GameConfig::mirror_interval`].
+    pub fn mirror_interval(mut self, mirror_interval: Duration) -> Self {
+        self.config.mirror_interval = mirror_interval;
+        self
+    }
+
+    /// Sets [`GameConfig::chaos_interval`].
+    pub fn chaos_interval(mut self, chaos_interval: Duration) -> Self {
+        self.config.chaos_interval = chaos_interval;
+        self
+    }
+
+    /// Returns the `GameConfig` with the given settings.
+    pub fn build(self) -> GameConfig {
+        self.config
+    }
+}
+
+/// The reasons the game can be paused for.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Pause {
+    /// The player paused the game. Nothing moves.
+    Manual,
+    /// The ball crossed midfield in a turn-based game. The players can still move.
+    Turn,
+}
+
+/// A snapshot of everything that changes during a rally, used to rewind the game. It includes the
+/// statistics, the winner, the last hitter, the clock, the random number generator, the state of the
+/// game mode and the schedules of the mutators, so the rules continue from the earlier moment.
+#[derive(Clone)]
+struct Snapshot {
+    clock: WorldClock,
+    rng: StdRng,
+    /// Whether the game waited for a turn. A manual pause is kept when rewinding.
+    turn: bool,
+    mode: Box<dyn GameMode>,
+    mirror: Option<MirrorSchedule>,
+    chaos: Option<ChaosScheduler>,
+    last_goal_crossing: Option<(Edge, Position2D)>,
+    player1_score: usize,
+    player2_score: usize,
+    match_score: Option<MatchScore>,
+    rally_hits: usize,
+    stats: RallyStats,
+    winner: Option<PlayerSide>,
+    last_hitter: Option<Edge>,
+    ramp: Option<DifficultyRamp>,
+    player1: Player,
+    player2: Player,
+    player3_score: usize,
+    player4_score: usize,
+    player3: Option<Player>,
+    player4: Option<Player>,
+    teammates: [Vec<Player>; 2],
+    balls: Vec<Ball>,
+}
+
+/// The events of a single update of the game, in the order they happened.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum GameEvent {
+    /// The player on the given side returned the ball.
+    Hit(PlayerSide),
+    /// The player on the given side scored a point.
+    Goal(PlayerSide),
+    /// The ball was served again without a point being scored.
+    PointRestarted,
+    /// A new game was started with a score of zero.
+    NewGame,
+    /// The game jumped back a few seconds.
+    Rewound,
+    /// The game was paused by a player.
+    Paused,
+    /// The game continues after a pause.
+    Resumed,
+    /// The player on the given side won a game of a match, and the next game starts.
+    GameWon(PlayerSide),
+    /// The player on the given side won the game under the rules of the game mode, the match or
+    /// by reaching the score limit.
+    Won(PlayerSide),
+    /// The player at the given edge returned the ball in a four-player game.
+    EdgeHit(Edge),
+    /// The ball left the field through the goal at the given edge in a four-player game.
+    Conceded(Edge),
+    /// The player at the top or the bottom edge scored a point in a four-player game. Goals of
+    /// player1 and player2 are reported as `Goal`.
+    EdgeGoal(Edge),
+    /// The ball broke a brick in a breakout game.
+    BrickBroken,
+    /// The difficulty rose to the given level.
+    LevelUp(u32),
+    /// The up and down keys of the player on the given side were swapped by the `mirror` mutator.
+    Mirrored(PlayerSide),
+    /// The given random event of the `chaos` mutator fired.
+    Chaos(ChaosEvent),
+    /// The player on the given side fired a taunt.
+    Taunted(PlayerSide, Taunt),
+}
+
+/// Advances a copy of the game by one update and leaves the given state untouched.
+///
+/// # Arguments
+/// * `state` - The `GameState` before the update.
+/// * `pressed_keys` - A `HashMap` representing the keys currently pressed by the players.
+/// * `dt` - The `Duration` since the last update.
+///
+/// # Returns
+/// The `GameState` after the update and the `GameEvent`s of the update.
+///
+/// # Remarks
+/// A clone of a `GameState` shares nothing with the original, including the random number generators
+/// of the game and of the computer players. Stepping the same state with the same keys therefore
+/// always leads to the same result, which rollback, rewind and replays can build on.
+pub fn step(
+    state: &GameState,
+    pressed_keys: HashMap<KeyCode, KeyEvent>,
+    dt: Duration,
+) -> (GameState, Vec<GameEvent>) {
+    let mut next = state.clone();
+    let events = next.update(pressed_keys, dt);
+
+    (next, events)
+}
+
+/// The `GameState` struct holds the entire state the pong game.
+#[derive(Clone)]
+pub struct GameState {
+    config: GameConfig,
+    player1_score: usize,
+    player2_score: usize,
+    rally_hits: usize,
+    stats: RallyStats,
+    rewind_buffer: RewindBuffer<Snapshot>,
+    rng: StdRng,
+    clock: WorldClock,
+    /// The side that scored the last goal and the timer of the flashing score.
+    last_goal: Option<(PlayerSide, Timer)>,
+    toasts: Toasts,
+    taunts: Taunts,
+    pause: Option<Pause>,
+    diagnostics: Option<String>,
+    hud: Hud,
+    mode: Box<dyn GameMode>,
+    /// What the game mode added to the field.
+    arena: Arena,
+    winner: Option<PlayerSide>,
+    match_score: Option<MatchScore>,
+    capabilities: Capabilities,
+    degradation: Option<Degradation>,
+    shown_frame: Option<Frame>,
+    standby_controllers: (Controller, Controller),
+    player1: Player,
+    player2: Player,
+    /// The goals of player3 and player4 in a four-player game.
+    player3_score: usize,
+    player4_score: usize,
+    /// The player at the top edge in a four-player game.
+    player3: Option<Player>,
+    /// The player at the bottom edge in a four-player game.
+    player4: Option<Player>,
+    /// The further paddles of the left and the right team, e.g. the lower paddles in doubles.
+    teammates: [Vec<Player>; 2],
+    /// The edge of the player who touched the ball last during the current rally.
+    last_hitter: Option<Edge>,
+    /// The edge the last goal was scored on and the point the ball crossed it at.
+    last_goal_crossing: Option<(Edge, Position2D)>,
+    /// The ramp that raises the difficulty during the game, if the config asks for one.
+    ramp: Option<DifficultyRamp>,
+    /// The schedule of the mirrored controls, if the `mirror` mutator is active.
+    mirror: Option<MirrorSchedule>,
+    /// The scheduler of the random events, if the `chaos` mutator is active.
+    chaos: Option<ChaosScheduler>,
+    /// The balls in play. The first ball is the one that is served and that the computer players
+    /// follow. Further balls are spawned by game modes or chaos events and removed at the next serve.
+    balls: Vec<Ball>,
+}
+
+impl GameState {
+    /// Constructs a new `GameState`.
+    ///
+    /// # Arguments
+    /// * `config` - The `GameConfig` describing the game field and rules.
+    ///
+    /// # Returns
+    /// A new `GameState` instance with initialized players and ball.
+    pub fn new(config: GameConfig) -> Self {
+        Self::with_arena(config, Arena::default())
+    }
+
+    /// Constructs a new `GameState` on a field with the given arena of a game mode.
+    fn with_arena(mut config: GameConfig, arena: Arena) -> Self {
+        // the chosen seed is kept, so a replay can tell which seed the game was played with
+        let seed = config.seed.unwrap_or_else(|| StdRng::from_entropy().gen());
+        config.seed = Some(seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let (controller1, standby_controller1) = Self::controllers(
+            config.player1_ai,
+            KeyboardInput::new(
+                config.key_bindings.player1_up,
+                config.key_bindings.player1_down,
+                config.opposite_keys,
+            ),
+            &mut rng,
+        );
+        let team_lane = match arena.doubles {
+            true => Lane::Upper,
+            false => Lane::Full,
+        };
+        let (extend_up1, extend_down1) = config.paddle_extents(PlayerSide::Left);
+        let mut player1 = Player::new(
+            extend_up1,
+            extend_down1,
+            controller1,
+            Self::initial_player1_position(config.width, config.height),
+            config.side_paddle_speed(PlayerSide::Left),
+        )
+        .with_lane(team_lane);
+        player1.position.y = Self::lane_y(team_lane, config.height);
+
+        let (player2_up, player2_down) = match config.mutators.invert_player2() {
+            true => (
+                config.key_bindings.player2_down,
+                config.key_bindings.player2_up,
+            ),
+            false => (
+                config.key_bindings.player2_up,
+                config.key_bindings.player2_down,
+            ),
+        };
+        let (controller2, standby_controller2) = Self::controllers(
+            config.player2_ai,
+            KeyboardInput::new(player2_up, player2_down, config.opposite_keys),
+            &mut rng,
+        );
+        let (extend_up2, extend_down2) = config.paddle_extents(PlayerSide::Right);
+        let mut player2 = Player::new(
+            extend_up2,
+            extend_down2,
+            controller2,
+            Self::initial_player2_position(config.width, config.height),
+            config.side_paddle_speed(PlayerSide::Right),
+        )
+        .with_lane(team_lane);
+        player2.position.y = Self::lane_y(team_lane, config.height);
+        let teammates = match arena.doubles {
+            true => [
+                vec![Self::new_teammate(&config, PlayerSide::Left)],
+                vec![Self::new_teammate(&config, PlayerSide::Right)],
+            ],
+            false => [Vec::new(), Vec::new()],
+        };
+
+        let (player3, player4) = match arena.edge_players {
+            true => (
+                Some(Self::new_edge_player(&config, Edge::Top)),
+                Some(Self::new_edge_player(&config, Edge::Bottom)),
+            ),
+            false => (None, None),
+        };
+
+        let mut ball = Ball::new(
+            Self::spawn_position(&config, &player1, &player2, &[]),
+            config.ball_speed,
+            &mut rng,
+        );
+        ball.velocity = ball.velocity.scaled(config.mutators.serve_factor());
+        let hud = Hud::standard(config.win_meter);
+        let match_score = config.match_format.map(MatchScore::new);
+        let ramp = config.difficulty_ramp.map(DifficultyRamp::new);
+        let mirror = config.mutators.mirror_schedule(config.mirror_interval);
+        let chaos = config.mutators.chaos_scheduler(config.chaos_interval);
+
+        GameState {
+            player1_score: config.player1_handicap.start_score,
+            player2_score: config.player2_handicap.start_score,
+            config,
+            rally_hits: 0,
+            stats: RallyStats::default(),
+            rewind_buffer: RewindBuffer::new(REWIND_CAPACITY),
+            rng,
+            clock: WorldClock::default(),
+            last_goal: None,
+            toasts: Toasts::default(),
+            taunts: Taunts::default(),
+            pause: None,
+            diagnostics: None,
+            hud,
+            mode: Box::new(Versus),
+            arena,
+            winner: None,
+            match_score,
+            capabilities: Capabilities::default(),
+            degradation: None,
+            shown_frame: None,
+            standby_controllers: (standby_controller1, standby_controller2),
+            player1,
+            player2,
+            player3_score: 0,
+            player4_score: 0,
+            player3,
+            player4,
+            teammates,
+            last_hitter: None,
+            last_goal_crossing: None,
+            ramp,
+            mirror,
+            chaos,
+            balls: vec![ball],
+        }
+    }
+
+    /// Creates the teammate who moves the lower paddle of a team in doubles with the keyboard.
+    fn new_teammate(config: &GameConfig, side: PlayerSide) -> Player {
+        let bindings = &config.key_bindings;
+        let (up, down, position) = match side {
+            PlayerSide::Left => (
+                bindings.teammate1_up,
+                bindings.teammate1_down,
+                Self::initial_player1_position(config.width, config.height),
+            ),
+            PlayerSide::Right => (
+                bindings.teammate2_up,
+                bindings.teammate2_down,
+                Self::initial_player2_position(config.width, config.height),
+            ),
+        };
+
+        let (extend_up, extend_down) = config.paddle_extents(side);
+        let mut teammate = Player::new(
+            extend_up,
+            extend_down,
+            Controller::Keyboard(KeyboardInput::new(up, down, config.opposite_keys)),
+            position,
+            config.side_paddle_speed(side),
+        )
+        .with_lane(Lane::Lower);
+        teammate.position.y = Self::lane_y(Lane::Lower, config.height);
+
+        teammate
+    }
+
+    /// Returns the height a paddle in the given lane starts at: the middle of its part of the edge.
+    fn lane_y(lane: Lane, height: usize) -> f64 {
+        let height = height as f64;
+        match lane {
+            Lane::Full => height / 2.,
+            Lane::Upper => (height * 3. / 4.).round(),
+            Lane::Lower => (height / 4.).round(),
+        }
+    }
+
+    /// Creates the horizontal player at the top or the bottom edge of a four-player game.
+    /// It is moved by the keyboard until it is swapped with the computer.
+    fn new_edge_player(config: &GameConfig, edge: Edge) -> Player {
+        Player::new(
+            config.extend_player_height_up,
+            config.extend_player_height_down,
+            Self::edge_keyboard(config, edge),
+            Self::initial_edge_position(config.width, config.height, edge),
+            config.paddle_speed,
+        )
+        .with_orientation(Orientation::Horizontal)
+    }
+
+    /// Returns the keyboard controller of the player at the top or the bottom edge.
+    fn edge_keyboard(config: &GameConfig, edge: Edge) -> Controller {
+        let bindings = &config.key_bindings;
+        let (right, left) = match edge {
+            Edge::Top => (bindings.player3_right, bindings.player3_left),
+            _ => (bindings.player4_right, bindings.player4_left),
+        };
+
+        Controller::Keyboard(KeyboardInput::new(right, left, config.opposite_keys))
+    }
+
+    fn initial_edge_position(width: usize, height: usize, edge: Edge) -> Position2D {
+        let y = match edge {
+            Edge::Top => height as f64,
+            _ => 0.0,
+        };
+
+        Position2D::new(width as f64 / 2.0, y)
+    }
+
+    /// Constructs a new `GameState` that follows the rules of the given game mode.
+    ///
+    /// # Arguments
+    /// * `config` - The `GameConfig` describing the game field and rules, before the mode changes it.
+    /// * `mode` - The `GameMode` the game is played in.
+    pub fn with_mode(mut config: GameConfig, mode: Box<dyn GameMode>) -> Self {
+        mode.mutate_config(&mut config);
+        let arena = mode.arena(&config);
+        let mut state = GameState::with_arena(config, arena);
+        mode.build_hud(&mut state.hud);
+        state.mode = mode;
+        state.run_mode_hook(|mode, state| mode.on_new_game(state));
+
+        // the first serve has to avoid the entities of the mode as well
+        let blocked = state.entity_boxes();
+        state.balls[0].position =
+            Self::spawn_position(&state.config, &state.player1, &state.player2, &blocked);
+        state.run_mode_hook(|mode, state| mode.on_serve(state));
+
+        state
+    }
+
+    /// Returns the game mode the game is played in.
+    pub fn mode(&self) -> &dyn GameMode {
+        self.mode.as_ref()
+    }
+
+    /// Returns what the game mode added to the field.
+    pub fn arena(&self) -> &Arena {
+        &self.arena
+    }
+
+    /// Returns the winner once the game mode, the match or the score limit decided the game.
+    pub fn winner(&self) -> Option<PlayerSide> {
+        self.winner
+    }
+
+    /// Returns `true` once the game is over. The game then waits until the players start a
+    /// rematch or quit.
+    pub fn is_over(&self) -> bool {
+        self.winner.is_some()
+    }
+
+    /// Returns the score of the match, or `None` if no match is played.
+    pub fn match_score(&self) -> Option<&MatchScore> {
+        self.match_score.as_ref()
+    }
+
+    /// Returns the game mode as the given type, or `None` if the game is played in another mode,
+    /// e.g. for a HUD widget that shows the state of a mode.
+    pub fn mode_as<M: GameMode + 'static>(&self) -> Option<&M> {
+        self.mode.as_any().downcast_ref()
+    }
+
+    /// Calls a hook of the game mode, which may change the whole state of the game.
+    fn run_mode_hook<R>(&mut self, hook: impl FnOnce(&mut dyn GameMode, &mut GameState) -> R) -> R {
+        // the mode is taken out while its hook runs, so the hook can borrow the state mutably
+        let mut mode = std::mem::replace(&mut self.mode, Box::new(Versus));
+        let result = hook(mode.as_mut(), self);
+        self.mode = mode;
+
+        result
+    }
+
+    /// Returns the bounding boxes of the cells of the entities of the game mode.
+    fn entity_boxes(&self) -> Vec<Aabb> {
+        self.mode
+            .extra_entities(self)
+            .iter()
+            .map(|entity| {
+                let half_cell = Cells::new(0.5);
+                Aabb::new(
+                    entity.position.shifted(-half_cell, -half_cell),
+                    entity.position.shifted(half_cell, half_cell),
+                )
+            })
+            .collect()
+    }
+
+    /// Creates the active controller of a player and the one it can be swapped with during the game.
+    ///
+    /// # Returns
+    /// The AI controller and a keyboard controller as standby if an AI profile is given,
+    /// otherwise the keyboard controller and an AI controller with the balanced style as standby.
+    fn controllers(
+        ai_profile: Option<AiProfile>,
+        keyboard: KeyboardInput,
+        rng: &mut StdRng,
+    ) -> (Controller, Controller) {
+        let keyboard = Controller::Keyboard(keyboard);
+        let ai = Controller::Ai(Box::new(AiController::new(
+            ai_profile.unwrap_or_else(|| AiStyle::Balanced.profile()),
+            StdRng::seed_from_u64(rng.gen()),
+        )));
+
+        match ai_profile {
+            Some(_) => (ai, keyboard),
+            None => (keyboard, ai),
+        }
+    }
+
+    /// Swaps the controller of the player on the given side with its standby controller,
+    /// e.g. to let the computer take over from a human player.
+    pub fn toggle_controller(&mut self, side: PlayerSide) {
+        match side {
+            PlayerSide::Left => std::mem::swap(
+                &mut self.player1.controller,
+                &mut self.standby_controllers.0,
+            ),
+            PlayerSide::Right => std::mem::swap(
+                &mut self.player2.controller,
+                &mut self.standby_controllers.1,
+            ),
+        }
+    }
+
+    /// Swaps the controller of the player at the given edge between the keyboard and the computer.
+    /// Does nothing for the top and the bottom edge unless four players play.
+    pub fn toggle_edge_controller(&mut self, edge: Edge) {
+        if let Some(side) = edge.side() {
+            self.toggle_controller(side);
+            return;
+        }
+
+        let keyboard = Self::edge_keyboard(&self.config, edge);
+        if let Some(player) = self.edge_player_mut(edge) {
+            player.controller = match player.controller {
+                Controller::Keyboard(_) => Controller::Ai(Box::new(EdgeFollower::new(edge))),
+                _ => keyboard,
+            };
+        }
+    }
+
+    /// Returns the player guarding the given edge, or `None` for the top and the bottom edge
+    /// unless four players play.
+    pub fn edge_player(&self, edge: Edge) -> Option<&Player> {
+        match edge {
+            Edge::Left => Some(&self.player1),
+            Edge::Right => Some(&self.player2),
+            Edge::Top => self.player3.as_ref(),
+            Edge::Bottom => self.player4.as_ref(),
+        }
+    }
+
+    fn edge_player_mut(&mut self, edge: Edge) -> Option<&mut Player> {
+        match edge {
+            Edge::Left => Some(&mut self.player1),
+            Edge::Right => Some(&mut self.player2),
+            Edge::Top => self.player3.as_mut(),
+            Edge::Bottom => self.player4.as_mut(),
+        }
+    }
+
+    /// Returns the ramp that raises the difficulty, or `None` if the difficulty stays the same.
+    pub fn difficulty(&self) -> Option<&DifficultyRamp> {
+        self.ramp.as_ref()
+    }
+
+    /// Returns the further paddles of the team on the given side, e.g. the lower paddle in doubles.
+    pub fn teammates(&self, side: PlayerSide) -> &[Player] {
+        &self.teammates[team_index(side)]
+    }
+
+    /// Returns the goals of player1 to player4. Player3 and player4 only score in four-player games.
+    pub fn edge_scores(&self) -> [usize; 4] {
+        [
+            self.player1_score,
+            self.player2_score,
+            self.player3_score,
+            self.player4_score,
+        ]
+    }
+
+    /// Returns the edge the last goal was scored on and the point the ball crossed the goal line at,
+    /// or `None` before the first goal of the game.
+    pub fn last_goal_crossing(&self) -> Option<(Edge, Position2D)> {
+        self.last_goal_crossing
+    }
+
+    /// Returns the point at which the ball crossed the goal line of the given edge during this update.
+    fn goal_crossing(&self, edge: Edge) -> Position2D {
+        let (width, height) = (self.config.width as f64, self.config.height as f64);
+        let (position, velocity) = (self.balls[0].position, self.balls[0].velocity);
+
+        match edge {
+            Edge::Left | Edge::Right => {
+                let x = if edge == Edge::Left { 0.0 } else { width };
+                let crossing = crossing_point(position, velocity, x);
+                Position2D::new(x, crossing.y.clamp(0.0, height))
+            }
+            Edge::Top | Edge::Bottom => {
+                let y = if edge == Edge::Bottom { 0.0 } else { height };
+                let time = (y - position.y) / velocity.vy;
+                Position2D::new((position.x + velocity.vx * time).clamp(0.0, width), y)
+            }
+        }
+    }
+
+    /// Returns `true` while the game is paused, either by the player or at the start of a turn.
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_some()
+    }
+
+    /// Shows a short message at the top of the field for a moment.
+    pub fn notify(&mut self, message: impl Into<String>) {
+        self.toasts.push(message);
+    }
+
+    /// Sets a line of diagnostic information shown above the score, or hides it with `None`.
+    pub fn set_diagnostics(&mut self, text: Option<String>) {
+        self.diagnostics = text;
+    }
+
+    /// Returns the goals of player1 and player2.
+    pub fn scores(&self) -> (usize, usize) {
+        (self.player1_score, self.player2_score)
+    }
+
+    /// Returns the line of diagnostic information, if one is set.
+    pub fn diagnostics(&self) -> Option<&str> {
+        self.diagnostics.as_deref()
+    }
+
+    /// Returns how often the ball was hit in the current rally.
+    pub fn rally_hits(&self) -> usize {
+        self.rally_hits
+    }
+
+    /// Returns the current and the full length in cells of the paddle on the given side.
+    pub fn paddle_length(&self, side: PlayerSide) -> (usize, usize) {
+        let player = match side {
+            PlayerSide::Left => &self.player1,
+            PlayerSide::Right => &self.player2,
+        };
+
+        (player.length(), player.full_length())
+    }
+
+    /// Returns the settings and rules the game is played with.
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Returns the first ball, e.g. to predict its flight.
+    pub fn ball(&self) -> &Ball {
+        &self.balls[0]
+    }
+
+    /// Returns all balls in play, starting with the first ball.
+    pub fn balls(&self) -> &[Ball] {
+        &self.balls
+    }
+
+    /// Puts another ball into play at the given position with the given velocity, e.g. for a power-up
+    /// of a game mode. The ball scores like the first one, and the next serve removes it again.
+    ///
+    /// # Returns
+    /// `false` if the ball was not spawned because four players play, whose goals only count the
+    /// first ball.
+    pub fn spawn_ball(&mut self, position: Position2D, velocity: Velocity2D) -> bool {
+        if self.arena.edge_players {
+            return false;
+        }
+
+        self.balls.push(Ball::with_velocity(position, velocity));
+        true
+    }
+
+    /// Returns the HUD, e.g. to add widgets to it.
+    pub fn hud_mut(&mut self) -> &mut Hud {
+        &mut self.hud
+    }
+
+    /// Lets the given strategy move the player on the given side. If the player was moved by the
+    /// keyboard, the keyboard becomes the standby controller, so the human can take over again.
+    pub fn set_ai_strategy(&mut self, side: PlayerSide, strategy: Box<dyn AiStrategy>) {
+        let (active, standby) = match side {
+            PlayerSide::Left => (
+                &mut self.player1.controller,
+                &mut self.standby_controllers.0,
+            ),
+            PlayerSide::Right => (
+                &mut self.player2.controller,
+                &mut self.standby_controllers.1,
+            ),
+        };
+
+        let ai = Controller::Ai(strategy);
+        match active {
+            Controller::Keyboard(_) => *standby = std::mem::replace(active, ai),
+            _ => *active = ai,
+        }
+    }
+
+    /// Asks the controller of the player on the given side for its command in this update.
+    fn next_command(
+        &mut self,
+        side: PlayerSide,
+        pressed_keys: &HashMap<KeyCode, KeyEvent>,
+        dt: Duration,
+    ) -> PaddleCommand {
+        // the controller is taken out while it decides, so it can look at the whole state
+        let placeholder = Controller::External(PaddleCommand::Stay);
+        let mut controller = std::mem::replace(&mut self.player_mut(side).controller, placeholder);
+        let command = controller.next_command(pressed_keys, self, side, dt);
+        self.player_mut(side).controller = controller;
+
+        command
+    }
+
+    /// Asks the controllers of the teammates on the given side for their commands in this update.
+    fn next_teammate_commands(
+        &mut self,
+        side: PlayerSide,
+        pressed_keys: &HashMap<KeyCode, KeyEvent>,
+        dt: Duration,
+    ) -> Vec<PaddleCommand> {
+        let team = team_index(side);
+        (0..self.teammates[team].len())
+            .map(|index| {
+                let placeholder = Controller::External(PaddleCommand::Stay);
+                let mut controller =
+                    std::mem::replace(&mut self.teammates[team][index].controller, placeholder);
+                let command = controller.next_command(pressed_keys, self, side, dt);
+                self.teammates[team][index].controller = controller;
+
+                command
+            })
+            .collect()
+    }
+
+    /// Asks the controller of the player at the top or the bottom edge for its command in this
+    /// update. Returns `PaddleCommand::Stay` unless four players play.
+    fn next_edge_command(
+        &mut self,
+        edge: Edge,
+        pressed_keys: &HashMap<KeyCode, KeyEvent>,
+        dt: Duration,
+    ) -> PaddleCommand {
+        let placeholder = Controller::External(PaddleCommand::Stay);
+        let Some(player) = self.edge_player_mut(edge) else {
+            return PaddleCommand::Stay;
+        };
+        let mut controller = std::mem::replace(&mut player.controller, placeholder);
+        // the computer players of the edges know their edge, so the side is only a placeholder
+        let command = controller.next_command(pressed_keys, self, PlayerSide::Left, dt);
+        if let Some(player) = self.edge_player_mut(edge) {
+            player.controller = controller;
+        }
+
+        command
+    }
+
+    /// Replaces the controller of the player on the given side.
+    pub fn set_controller(&mut self, side: PlayerSide, controller: Controller) {
+        self.player_mut(side).controller = controller;
+    }
+
+    /// Returns the `Observation` of the game from the view of the player on the given side.
+    pub fn observation(&self, side: PlayerSide) -> Observation {
+        let player = match side {
+            PlayerSide::Left => &self.player1,
+            PlayerSide::Right => &self.player2,
+        };
+
+        player.observe(&self.balls[0], &self.config, &self.arena)
+    }
+
+    /// Moves the paddle on the given side by the given number of cells, upwards if positive,
+    /// e.g. for the notches of a mouse wheel.
+    pub fn nudge_paddle(&mut self, side: PlayerSide, cells: i32) {
+        let max_height = self.config.height as f64;
+        self.player_mut(side).nudge(cells, max_height);
+    }
+
+    /// Returns the controller of the player on the given side.
+    pub fn controller(&self, side: PlayerSide) -> &Controller {
+        match side {
+            PlayerSide::Left => &self.player1.controller,
+            PlayerSide::Right => &self.player2.controller,
+        }
+    }
+
+    fn player_mut(&mut self, side: PlayerSide) -> &mut Player {
+        match side {
+            PlayerSide::Left => &mut self.player1,
+            PlayerSide::Right => &mut self.player2,
+        }
+    }
+
+    /// Returns the clock of the simulated time since the start of the game.
+    pub fn clock(&self) -> WorldClock {
+        self.clock
+    }
+
+    /// Returns the statistics of all rallies played so far.
+    pub fn stats(&self) -> &RallyStats {
+        &self.stats
+    }
+
+    /// Updates the state of the game including player positions, ball position, and score based on the time elapsed and the pressed keys.
+    ///
+    /// # Arguments
+    /// * `pressed_keys` - A `HashMap` representing the keys currently pressed by the players.
+    /// * `dt` - The `Duration` since the last update.
+    ///
+    /// # Returns
+    /// The `GameEvent`s of the update, in the order they happened.
+    pub fn update(
+        &mut self,
+        pressed_keys: HashMap<KeyCode, KeyEvent>,
+        dt: Duration,
+    ) -> Vec<GameEvent> {
+        self.clock.advance(dt);
+        self.toasts.update(dt);
+        self.taunts.update(dt);
+        if let Some((_, timer)) = &mut self.last_goal {
+            timer.advance(dt);
+        }
+
+        let bindings = &self.config.key_bindings;
+        if bindings.restart_game.is_pressed(&pressed_keys) {
+            self.restart_game();
+            self.notify("New game");
+            return vec![GameEvent::NewGame];
+        }
+        // a finished practice game can be rewound to before the deciding point
+        if self.config.practice && bindings.rewind.is_pressed(&pressed_keys) {
+            self.rewind();
+            self.notify("Rewind");
+            return vec![GameEvent::Rewound];
+        }
+        if self.winner.is_some() {
+            if bindings.rematch.is_pressed(&pressed_keys) {
+                self.restart_game();
+                self.notify("Rematch");
+                return vec![GameEvent::NewGame];
+            }
+            return Vec::new();
+        }
+        if bindings.restart_point.is_pressed(&pressed_keys) {
+            self.reset_ball_and_players();
+            return vec![GameEvent::PointRestarted];
+        }
+        if bindings.pause.is_pressed(&pressed_keys) {
+            self.pause = match self.pause {
+                Some(_) => None,
+                None => Some(Pause::Manual),
+            };
+            return match self.pause {
+                Some(_) => vec![GameEvent::Paused],
+                None => vec![GameEvent::Resumed],
+            };
+        }
+        if self.pause == Some(Pause::Manual) {
+            return Vec::new();
+        }
+        let toggle_player1 = bindings.toggle_player1.is_pressed(&pressed_keys);
+        let toggle_player2 = bindings.toggle_player2.is_pressed(&pressed_keys);
+        let toggle_player3 = bindings.toggle_player3.is_pressed(&pressed_keys);
+        let toggle_player4 = bindings.toggle_player4.is_pressed(&pressed_keys);
+        if toggle_player1 {
+            self.toggle_controller(PlayerSide::Left);
+            let controller = self.player1.controller.description();
+            self.notify(format!("player1 is moved by {controller}"));
+        }
+        if toggle_player2 {
+            self.toggle_controller(PlayerSide::Right);
+            let controller = self.player2.controller.description();
+            self.notify(format!("player2 is moved by {controller}"));
+        }
+        for (edge, toggle) in [(Edge::Top, toggle_player3), (Edge::Bottom, toggle_player4)] {
+            if toggle && self.edge_player(edge).is_some() {
+                self.toggle_edge_controller(edge);
+                let controller = self
+                    .edge_player(edge)
+                    .map(|player| player.controller.description());
+                self.notify(format!(
+                    "{} is moved by {}",
+                    edge.player_name(),
+                    controller.unwrap_or_default()
+                ));
+            }
+        }
+
+        let mut events = self.fire_taunts(&pressed_keys);
+
+        let bindings = &self.config.key_bindings;
+        let tilt1 = i32::from(bindings.player1_tilt_up.is_pressed(&pressed_keys))
+            - i32::from(bindings.player1_tilt_down.is_pressed(&pressed_keys));
+        let tilt2 = i32::from(bindings.player2_tilt_up.is_pressed(&pressed_keys))
+            - i32::from(bindings.player2_tilt_down.is_pressed(&pressed_keys));
+        self.player1.tilt_by(tilt1);
+        self.player2.tilt_by(tilt2);
+
+        // both controllers decide on the same state, so neither sees the other paddle move first
+        let command1 = self.next_command(PlayerSide::Left, &pressed_keys, dt);
+        let command2 = self.next_command(PlayerSide::Right, &pressed_keys, dt);
+        let max_height = self.config.height as f64;
+        let teammate_commands = [PlayerSide::Left, PlayerSide::Right]
+            .map(|side| self.next_teammate_commands(side, &pressed_keys, dt));
+        let command3 = self.next_edge_command(Edge::Top, &pressed_keys, dt);
+        let command4 = self.next_edge_command(Edge::Bottom, &pressed_keys, dt);
+        self.player1.update_position(max_height, command1, dt);
+        self.player2.update_position(max_height, command2, dt);
+        for (team, commands) in self.teammates.iter_mut().zip(teammate_commands) {
+            for (teammate, command) in team.iter_mut().zip(commands) {
+                teammate.update_position(max_height, command, dt);
+            }
+        }
+        let max_width = self.config.width as f64;
+        for (player, command) in [(&mut self.player3, command3), (&mut self.player4, command4)] {
+            if let Some(player) = player {
+                player.update_position(max_width, command, dt);
+            }
+        }
+
+        // during a turn only the players move, so they can prepare for the ball
+        if self.pause == Some(Pause::Turn) {
+            return events;
+        }
+
+        let levels = self.ramp.as_mut().map_or(0, |ramp| ramp.advance(dt));
+        if levels > 0 {
+            events.push(GameEvent::LevelUp(self.raise_difficulty(levels)));
+        }
+        if let Some(side) = self.move_mirror(dt) {
+            events.push(GameEvent::Mirrored(side));
+        }
+        if let Some(event) = self.fire_chaos(dt) {
+            events.push(GameEvent::Chaos(event));
+        }
+
+        let midfield = self.config.width as f64 / 2.0;
+        let side_before = self.balls[0].position.x < midfield;
+        if let Some(edge) = self.update_edge_hits(dt) {
+            events.push(GameEvent::EdgeHit(edge));
+        }
+        let left: Vec<&Player> = std::iter::once(&self.player1)
+            .chain(&self.teammates[0])
+            .collect();
+        let right: Vec<&Player> = std::iter::once(&self.player2)
+            .chain(&self.teammates[1])
+            .collect();
+        let balls_before: Vec<Position2D> = self.balls.iter().map(|ball| ball.position).collect();
+        let hits: Vec<bool> = self
+            .balls
+            .iter_mut()
+            .map(|ball| {
+                ball.update_position_with_teams(
+                    &self.config,
+                    &self.arena,
+                    &left,
+                    &right,
+                    &mut self.rng,
+                    dt,
+                )
+            })
+            .collect();
+        for (index, hit) in hits.into_iter().enumerate() {
+            if hit {
+                let hitter = self.register_hit(index);
+                events.push(GameEvent::Hit(hitter));
+                self.run_mode_hook(|mode, state| mode.on_hit(state, hitter));
+            }
+        }
+        for (index, before) in balls_before.into_iter().enumerate() {
+            let rule_events =
+                self.run_mode_hook(|mode, state| mode.on_ball_moved(state, index, before));
+            if rule_events.is_empty() {
+                continue;
+            }
+            events.extend(rule_events);
+            // the rules of the mode may decide the game at once, e.g. with the last brick
+            self.winner = self.mode.win_condition(self);
+            if let Some(winner) = self.winner {
+                events.push(GameEvent::Won(winner));
+                break;
+            }
+        }
+        if self.config.turn_based && side_before != (self.balls[0].position.x < midfield) {
+            self.pause = Some(Pause::Turn);
+        }
+
+        if self.arena.edge_players {
+            events.extend(self.update_edge_score());
+        } else if let Some(scorer) = self.update_score() {
+            events.push(GameEvent::Goal(scorer));
+            let progress = self.match_score.as_mut().map(|score| score.point(scorer));
+            if let Some(MatchProgress::Game(winner)) = progress {
+                events.push(GameEvent::GameWon(winner));
+                let game = self.match_score.map_or(0, |score| score.current_game() - 1);
+                self.notify(format!("{} wins game {game}", side_name(winner)));
+            }
+            self.winner = self
+                .mode
+                .win_condition(self)
+                .or(self.match_score.and_then(|score| score.winner()))
+                .or(self.score_limit_winner());
+            if let Some(winner) = self.winner {
+                events.push(GameEvent::Won(winner));
+            }
+        }
+
+        self.run_mode_hook(|mode, state| mode.on_tick(state, dt));
+        // the mode may decide the game at any time, e.g. after a return or once time ran out
+        if self.winner.is_none() {
+            self.winner = self.mode.win_condition(self);
+            if let Some(winner) = self.winner {
+                events.push(GameEvent::Won(winner));
+            }
+        }
+
+        if self.config.practice {
+            self.rewind_buffer.push(dt, self.snapshot());
+        }
+
+        events
+    }
+
+    /// Shows the taunts whose keys are pressed, at most one per player. Only players on the
+    /// keyboard can taunt.
+    fn fire_taunts(&mut self, pressed_keys: &HashMap<KeyCode, KeyEvent>) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        let keys = self.config.key_bindings.taunt_keys();
+        for (side, keys) in [PlayerSide::Left, PlayerSide::Right].into_iter().zip(keys) {
+            // a wall in place of player2 has nobody who could taunt
+            let absent = side == PlayerSide::Right && self.arena.right_wall;
+            if absent || !matches!(self.controller(side), Controller::Keyboard(_)) {
+                continue;
+            }
+            let Some(index) = keys.iter().position(|key| key.is_pressed(pressed_keys)) else {
+                continue;
+            };
+            let taunt = Taunt::ALL[index];
+            if self.taunts.fire(side, taunt) {
+                events.push(GameEvent::Taunted(side, taunt));
+            }
+        }
+
+        events
+    }
+
+    /// Bounces a ball off the first solid cell on its path since the given position, e.g. off a
+    /// brick of a game mode. The ball is put back to where it was right before it touched the cell.
+    ///
+    /// # Arguments
+    /// * `ball` - The index of the ball in `balls`.
+    /// * `from` - The position of the ball before the update.
+    /// * `is_solid` - Returns `true` for the cells the ball bounces off.
+    ///
+    /// # Returns
+    /// The cell the ball bounced off, or `None` if its path was free.
+    pub fn bounce_ball_off_cells(
+        &mut self,
+        ball: usize,
+        from: Position2D,
+        is_solid: impl Fn(DiscretePosition2D) -> bool,
+    ) -> Option<DiscretePosition2D> {
+        self.balls[ball].bounce_off_cells(from, is_solid)
+    }
+
+    /// Returns the player who reached the score limit, if any.
+    fn score_limit_winner(&self) -> Option<PlayerSide> {
+        let limit = self.config.score_limit?;
+        if self.player1_score >= limit {
+            Some(PlayerSide::Left)
+        } else if self.player2_score >= limit {
+            Some(PlayerSide::Right)
+        } else {
+            None
+        }
+    }
+
+    /// Speeds the ball up by the given number of levels of the difficulty ramp and sharpens a
+    /// computer controlled player2 to the new level.
+    ///
+    /// # Returns
+    /// The new level.
+    fn raise_difficulty(&mut self, levels: u32) -> u32 {
+        let Some(ramp) = self.ramp else {
+            return 0;
+        };
+
+        for ball in &mut self.balls {
+            ball.velocity = ball.velocity.scaled(BALL_SPEED_STEP.powi(levels as i32));
+            let speed = ball.velocity.speed();
+            if speed > MAX_BALL_SPEED {
+                ball.velocity = ball.velocity.scaled(MAX_BALL_SPEED / speed);
+            }
+        }
+        self.set_player2_ai_level(ramp);
+        self.notify(format!(
+            "Level {}: faster ball, sharper opponent",
+            ramp.level()
+        ));
+
+        ramp.level()
+    }
+
+    /// Advances the schedule of the `mirror` mutator and moves the mirror on to the next player
+    /// once its time has come.
+    ///
+    /// # Returns
+    /// The side of the player whose controls were mirrored, or `None` if the mirror stayed.
+    fn move_mirror(&mut self, dt: Duration) -> Option<PlayerSide> {
+        let mirror = self.mirror.as_mut()?;
+        let before = mirror.mirrored();
+        if !mirror.advance(dt) {
+            return None;
+        }
+        let after = mirror.mirrored();
+
+        // mirroring the controls twice restores them
+        for side in before.into_iter().chain(after) {
+            self.mirror_controls(side);
+        }
+        let side = after?;
+        self.notify(format!(
+            "Mirror! {}'s up and down keys are swapped",
+            side_name(side)
+        ));
+
+        Some(side)
+    }
+
+    /// Advances the scheduler of the `chaos` mutator and applies the event that fires.
+    ///
+    /// # Returns
+    /// The event that fired, or `None` if none did.
+    fn fire_chaos(&mut self, dt: Duration) -> Option<ChaosEvent> {
+        // only one second ball flies at a time, and only into the goals of player1 and player2
+        let second_ball_allowed = self.balls.len() == 1 && !self.arena.edge_players;
+        let allowed: Vec<ChaosEvent> = ChaosEvent::ALL
+            .into_iter()
+            .filter(|&event| event != ChaosEvent::SecondBall || second_ball_allowed)
+            .collect();
+        let event = self.chaos.as_mut()?.advance(dt, &allowed, &mut self.rng)?;
+
+        match event {
+            ChaosEvent::Teleport => {
+                let (width, height) = (self.config.width as f64, self.config.height as f64);
+                self.balls[0].position = Position2D::new(
+                    self.rng.gen_range(width / 3.0..=2.0 * width / 3.0),
+                    self.rng.gen_range(1.0..=(height - 1.0).max(1.0)),
+                );
+            }
+            ChaosEvent::SpeedBurst => {
+                let factor =
+                    SPEED_BURST_FACTOR.min(MAX_BALL_SPEED / self.balls[0].velocity.speed());
+                self.balls[0].velocity = self.balls[0].velocity.scaled(factor);
+            }
+            ChaosEvent::PaddleShrink => {
+                let player = match self.rng.gen::<bool>() {
+                    true => &mut self.player1,
+                    false => &mut self.player2,
+                };
+                for _ in 0..CHAOS_SHRINK_CELLS {
+                    player.shrink();
+                }
+            }
+            ChaosEvent::SecondBall => {
+                let blocked = self.entity_boxes();
+                let position =
+                    Self::spawn_position(&self.config, &self.player1, &self.player2, &blocked);
+                let velocity = Ball::random_ball_velocity(self.config.ball_speed, &mut self.rng)
+                    .scaled(self.config.mutators.serve_factor());
+                self.spawn_ball(position, velocity);
+            }
+        }
+        self.notify(event.notice());
+
+        Some(event)
+    }
+
+    /// Returns the scheduler of the `chaos` mutator, or `None` if no chaos events fire.
+    pub fn chaos(&self) -> Option<&ChaosScheduler> {
+        self.chaos.as_ref()
+    }
+
+    /// Swaps the up and down keys of all keyboard controllers of the given side, including the
+    /// standby controller and the teammates. Computer players are not affected.
+    fn mirror_controls(&mut self, side: PlayerSide) {
+        let (player, standby, team) = match side {
+            PlayerSide::Left => (
+                &mut self.player1,
+                &mut self.standby_controllers.0,
+                &mut self.teammates[0],
+            ),
+            PlayerSide::Right => (
+                &mut self.player2,
+                &mut self.standby_controllers.1,
+                &mut self.teammates[1],
+            ),
+        };
+        let controllers = [&mut player.controller, standby]
+            .into_iter()
+            .chain(team.iter_mut().map(|teammate| &mut teammate.controller));
+        for controller in controllers {
+            controller.mirror();
+        }
+    }
+
+    /// Returns the schedule of the `mirror` mutator, or `None` if the controls are never mirrored.
+    pub fn mirror(&self) -> Option<&MirrorSchedule> {
+        self.mirror.as_ref()
+    }
+
+    /// Replaces the AI of player2 by the AI of the chosen profile sharpened to the level of the
+    /// given ramp. A human player2 keeps the keyboard and only its standby AI is replaced.
+    fn set_player2_ai_level(&mut self, ramp: DifficultyRamp) {
+        let base = self
+            .config
+            .player2_ai
+            .unwrap_or_else(|| AiStyle::Balanced.profile());
+        let ai = Controller::Ai(Box::new(AiController::new(
+            ramp.ai_profile(base),
+            StdRng::seed_from_u64(self.rng.gen()),
+        )));
+        match self.player2.controller {
+            Controller::Ai(_) => self.player2.controller = ai,
+            _ => self.standby_controllers.1 = ai,
+        }
+    }
+
+    /// Starts the game again with a score of zero. The controllers and the settings are kept.
+    fn restart_game(&mut self) {
+        self.player1_score = self.config.player1_handicap.start_score;
+        self.player2_score = self.config.player2_handicap.start_score;
+        self.player3_score = 0;
+        self.player4_score = 0;
+        self.last_goal_crossing = None;
+        self.rally_hits = 0;
+        self.stats = RallyStats::default();
+        self.last_goal = None;
+        self.winner = None;
+        self.match_score = self.config.match_format.map(MatchScore::new);
+        self.rewind_buffer = RewindBuffer::new(REWIND_CAPACITY);
+        self.run_mode_hook(|mode, state| mode.on_new_game(state));
+        let raised = self.ramp.is_some_and(|ramp| ramp.level() > 0);
+        self.ramp = self.config.difficulty_ramp.map(DifficultyRamp::new);
+        if let (Some(ramp), true) = (self.ramp, raised) {
+            self.set_player2_ai_level(ramp);
+        }
+        if let Some(side) = self.mirror.and_then(|mirror| mirror.mirrored()) {
+            self.mirror_controls(side);
+        }
+        self.mirror = self
+            .config
+            .mutators
+            .mirror_schedule(self.config.mirror_interval);
+        self.chaos = self
+            .config
+            .mutators
+            .chaos_scheduler(self.config.chaos_interval);
+        self.reset_ball_and_players();
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            clock: self.clock,
+            rng: self.rng.clone(),
+            turn: self.pause == Some(Pause::Turn),
+            mode: self.mode.clone(),
+            mirror: self.mirror,
+            chaos: self.chaos,
+            last_goal_crossing: self.last_goal_crossing,
+            player1_score: self.player1_score,
+            player2_score: self.player2_score,
+            match_score: self.match_score,
+            rally_hits: self.rally_hits,
+            stats: self.stats.clone(),
+            winner: self.winner,
+            last_hitter: self.last_hitter,
+            ramp: self.ramp,
+            player1: self.player1.clone(),
+            player2: self.player2.clone(),
+            player3_score: self.player3_score,
+            player4_score: self.player4_score,
+            player3: self.player3.clone(),
+            player4: self.player4.clone(),
+            teammates: self.teammates.clone(),
+            balls: self.balls.clone(),
+        }
+    }
+
+    fn rewind(&mut self) {
+        if let Some(snapshot) = self.rewind_buffer.rewind(REWIND_TIME) {
+            self.clock = snapshot.clock;
+            self.rng = snapshot.rng;
+            if self.pause != Some(Pause::Manual) {
+                self.pause = snapshot.turn.then_some(Pause::Turn);
+            }
+            self.mode = snapshot.mode;
+            self.chaos = snapshot.chaos;
+            self.last_goal_crossing = snapshot.last_goal_crossing;
+            self.player1_score = snapshot.player1_score;
+            self.player2_score = snapshot.player2_score;
+            self.match_score = snapshot.match_score;
+            self.rally_hits = snapshot.rally_hits;
+            self.stats = snapshot.stats;
+            self.winner = snapshot.winner;
+            self.last_hitter = snapshot.last_hitter;
+            self.balls = snapshot.balls;
+
+            // the players keep their current controllers, which may have been swapped in the meantime
+            let controller1 = std::mem::replace(&mut self.player1, snapshot.player1).controller;
+            let controller2 = std::mem::replace(&mut self.player2, snapshot.player2).controller;
+            self.player1.controller = controller1;
+            self.player2.controller = controller2;
+
+            self.player3_score = snapshot.player3_score;
+            self.player4_score = snapshot.player4_score;
+            for (current, earlier) in [
+                (&mut self.player3, snapshot.player3),
+                (&mut self.player4, snapshot.player4),
+            ] {
+                if let (Some(current), Some(earlier)) = (current, earlier) {
+                    let controller = std::mem::replace(current, earlier).controller;
+                    current.controller = controller;
+                }
+            }
+            for (team, earlier_team) in self.teammates.iter_mut().zip(snapshot.teammates) {
+                for (current, earlier) in team.iter_mut().zip(earlier_team) {
+                    let controller = std::mem::replace(current, earlier).controller;
+                    current.controller = controller;
+                }
+            }
+
+            // the controls are mirrored for the player of the earlier moment again
+            let mirrored = self.mirror.and_then(|mirror| mirror.mirrored());
+            let earlier_mirrored = snapshot.mirror.and_then(|mirror| mirror.mirrored());
+            if mirrored != earlier_mirrored {
+                for side in mirrored.into_iter().chain(earlier_mirrored) {
+                    self.mirror_controls(side);
+                }
+            }
+            self.mirror = snapshot.mirror;
+
+            // a computer controlled player2 plays at the level of the earlier moment again
+            let level = self.ramp.map(|ramp| ramp.level());
+            self.ramp = snapshot.ramp;
+            if let Some(ramp) = self.ramp.filter(|ramp| Some(ramp.level()) != level) {
+                self.set_player2_ai_level(ramp);
+            }
+        }
+    }
+
+    /// Counts a return of the ball with the given index and returns the side of the player that
+    /// hit it.
+    fn register_hit(&mut self, ball_index: usize) -> PlayerSide {
+        self.rally_hits += 1;
+        // after the hit the ball moves away from the player that returned it
+        let hitter = if self.balls[ball_index].velocity.vx > 0.0 {
+            PlayerSide::Left
+        } else {
+            PlayerSide::Right
+        };
+        self.stats.register_return(hitter);
+        self.last_hitter = Some(match hitter {
+            PlayerSide::Left => Edge::Left,
+            PlayerSide::Right => Edge::Right,
+        });
+        self.shrink_after_hit();
+
+        hitter
+    }
+
+    /// Counts a return of the ball by the player at the top or the bottom edge.
+    fn register_edge_hit(&mut self, edge: Edge) {
+        self.rally_hits += 1;
+        self.last_hitter = Some(edge);
+        self.shrink_after_hit();
+    }
 
-    fn update_if_collision_with_wall(&mut self, max_height: f64, dt: Duration) {
-        let next_position = self.calc_next_position(dt);
-        if next_position.y <= 0.0 || next_position.y >= max_height {
-            self.velocity.vy = -self.velocity.vy;
+    fn shrink_after_hit(&mut self) {
+        if self.config.shrink_paddles && self.rally_hits.is_multiple_of(SHRINK_HIT_INTERVAL) {
+            self.player1.shrink();
+            self.player2.shrink();
+            for player in [&mut self.player3, &mut self.player4].into_iter().flatten() {
+                player.shrink();
+            }
+            for teammate in self.teammates.iter_mut().flatten() {
+                teammate.shrink();
+            }
         }
     }
 
-    fn update_if_collision_with_player1(&mut self, player1: &Player, dt: Duration) {
-        let possible_collision_point = self.calculate_collision_point_with_player(player1);
-        let next_position = self.calc_next_position(dt);
+    /// Lets the ball bounce off the players at the top and the bottom edge in a four-player game.
+    ///
+    /// # Returns
+    /// The edge of the player who hit the ball, if any.
+    fn update_edge_hits(&mut self, dt: Duration) -> Option<Edge> {
+        let hitters = [
+            (Edge::Top, &self.player3, -1.0),
+            (Edge::Bottom, &self.player4, 1.0),
+        ];
+        let hitter = hitters.into_iter().find_map(|(edge, player, facing)| {
+            let player = player.as_ref()?;
+            self.balls[0]
+                .update_if_collision_with_edge_player(player, facing, &self.config, dt)
+                .then_some(edge)
+        })?;
+        self.register_edge_hit(hitter);
 
-        if possible_collision_point.x >= next_position.x
-            && player1.collides_with(possible_collision_point)
-        {
-            self.velocity.vx = -self.velocity.vx;
+        Some(hitter)
+    }
+
+    /// Counts a goal in a four-player game if the ball left the field through any edge.
+    /// The goal counts for the player who touched the ball last, unless the ball went through
+    /// the goal of that player or nobody touched it.
+    ///
+    /// # Returns
+    /// The `GameEvent`s of the goal.
+    fn update_edge_score(&mut self) -> Vec<GameEvent> {
+        let width = self.config.width as f64;
+        let height = self.config.height as f64;
+        let Some(conceded) = edge_conceded(self.balls[0].position, width, height) else {
+            return Vec::new();
+        };
+
+        self.last_goal_crossing = Some((conceded, self.goal_crossing(conceded)));
+        let mut events = vec![GameEvent::Conceded(conceded)];
+        if let Some(side) = conceded.side() {
+            self.stats.register_miss(side);
+        }
+        self.stats.register_rally(self.rally_hits);
+
+        match self.last_hitter.filter(|&hitter| hitter != conceded) {
+            Some(scorer) => {
+                match scorer {
+                    Edge::Left => self.player1_score += 1,
+                    Edge::Right => self.player2_score += 1,
+                    Edge::Top => self.player3_score += 1,
+                    Edge::Bottom => self.player4_score += 1,
+                }
+                match scorer.side() {
+                    Some(side) => {
+                        self.last_goal = Some((side, Timer::once(SCORE_FLASH_TIME)));
+                        self.run_mode_hook(|mode, state| mode.on_goal(state, side));
+                        events.push(GameEvent::Goal(side));
+                    }
+                    None => events.push(GameEvent::EdgeGoal(scorer)),
+                }
+            }
+            None => self.notify(format!("Nobody scores on {}", conceded.player_name())),
         }
+        self.reset_ball_and_players();
+
+        events
     }
 
-    fn update_if_collision_with_player2(&mut self, player2: &Player, dt: Duration) {
-        let possible_collision_point = self.calculate_collision_point_with_player(player2);
-        let next_position = self.calc_next_position(dt);
+    /// Counts a goal if the ball crossed a goal line and returns the side that scored.
+    fn update_score(&mut self) -> Option<PlayerSide> {
+        let width = self.config.width as f64;
+        // any ball scores, and the serve afterwards removes the others
+        let (index, scorer) = self
+            .balls
+            .iter()
+            .enumerate()
+            .find_map(|(index, ball)| Some((index, goal_scored(ball.position, width)?)))?;
+        self.balls.swap(0, index);
+        let conceded = match scorer {
+            PlayerSide::Left => Edge::Right,
+            PlayerSide::Right => Edge::Left,
+        };
+        self.last_goal_crossing = Some((conceded, self.goal_crossing(conceded)));
 
-        if possible_collision_point.x <= next_position.x
-            && player2.collides_with(possible_collision_point)
-        {
-            self.velocity.vx = -self.velocity.vx;
+        match scorer {
+            PlayerSide::Left => {
+                self.player1_score += 1;
+                self.stats.register_miss(PlayerSide::Right);
+            }
+            PlayerSide::Right => {
+                self.player2_score += 1;
+                self.stats.register_miss(PlayerSide::Left);
+            }
         }
+        self.last_goal = Some((scorer, Timer::once(SCORE_FLASH_TIME)));
+        self.stats.register_rally(self.rally_hits);
+        self.run_mode_hook(|mode, state| mode.on_goal(state, scorer));
+        self.reset_ball_and_players();
+
+        Some(scorer)
     }
 
-    fn calculate_collision_point_with_player(&self, player: &Player) -> Position2D {
-        let collision_r = (player.position.x - self.position.x) / self.velocity.vx;
-        let possible_collision_x = self.position.x + self.velocity.vx * collision_r;
-        let possible_collision_y = self.position.y + self.velocity.vy * collision_r;
-        Position2D::new(possible_collision_x, possible_collision_y)
+    /// Returns the width and the height of a field of the given size in a terminal of the given size.
+    /// A percentage refers to the largest field whose frame fits into the terminal.
+    pub fn field_size(
+        width: FieldSize,
+        height: FieldSize,
+        double_width: bool,
+        (columns, rows): (usize, usize),
+    ) -> (usize, usize) {
+        let cell_width = if double_width { 2 } else { 1 };
+        // the frame spans the borders of the field, the rows above it and a free last row
+        let available_width = (columns / cell_width).saturating_sub(1);
+        let available_height = rows.saturating_sub(FIELD_TOP + 3);
+
+        (
+            width.resolve(available_width, MIN_FIELD_WIDTH),
+            height.resolve(available_height, MIN_FIELD_HEIGHT),
+        )
     }
 
-    fn calc_next_position(&self, dt: Duration) -> Position2D {
-        let next_position_x = self.position.x + self.velocity.vx * dt.as_secs_f64();
-        let next_position_y = self.position.y + self.velocity.vy * dt.as_secs_f64();
-        Position2D::new(next_position_x, next_position_y)
+    /// Changes the size of the field during the game, e.g. after the terminal was resized.
+    /// The paddles and the ball are moved back into the field and the rewind buffer is cleared.
+    pub fn resize_field(&mut self, width: usize, height: usize) {
+        if (width, height) == (self.config.width, self.config.height) {
+            return;
+        }
+
+        let previous_size = (self.config.width, self.config.height);
+        self.config.width = width;
+        self.config.height = height;
+        self.arena = self.mode.arena(&self.config);
+
+        self.player2.position.x = width as f64;
+        self.player1.keep_in_field(height as f64);
+        self.player2.keep_in_field(height as f64);
+        for teammate in &mut self.teammates[1] {
+            teammate.position.x = width as f64;
+        }
+        for teammate in self.teammates.iter_mut().flatten() {
+            teammate.keep_in_field(height as f64);
+        }
+        if let Some(player3) = &mut self.player3 {
+            player3.position.y = height as f64;
+        }
+        for player in [&mut self.player3, &mut self.player4].into_iter().flatten() {
+            player.keep_in_field(width as f64);
+        }
+        for ball in &mut self.balls {
+            ball.position.x = ball.position.x.clamp(0.0, width as f64);
+            ball.position.y = ball.position.y.clamp(0.0, height as f64);
+        }
+        self.run_mode_hook(|mode, state| mode.on_resize(state, previous_size));
+
+        // the snapshots refer to the old field
+        self.rewind_buffer = RewindBuffer::new(REWIND_CAPACITY);
     }
 
-    /// Generates a random velocity for the ball when it is initialized or reset.
-    ///
-    /// # Returns
-    /// A `Velocity2D` representing a random velocity within a specified range.
-    pub fn random_ball_velocity() -> Velocity2D {
-        let vx = match random::<bool>() {
-            true => rand::thread_rng().gen_range(10.0..20.0),
-            false => rand::thread_rng().gen_range(-20.0..-10.0),
+    /// Puts the ball at the given position with the given velocity and without spin, e.g. for a
+    /// serve of a game mode.
+    pub fn launch_ball(&mut self, position: Position2D, velocity: Velocity2D) {
+        self.balls[0].position = position;
+        self.balls[0].velocity = velocity;
+        self.balls[0].spin = CellsPerSecond::ZERO;
+    }
+
+    /// Resets the ball and the players and serves the ball towards the given side.
+    pub fn serve(&mut self, towards: PlayerSide) {
+        self.reset_ball_and_players();
+
+        let vx = self.balls[0].velocity.vx.abs();
+        self.balls[0].velocity.vx = match towards {
+            PlayerSide::Left => -vx,
+            PlayerSide::Right => vx,
         };
-        let vy = rand::thread_rng().gen_range(-6.0..6.0);
-        Velocity2D::new(vx, vy)
     }
-}
 
-/// The `GameState` struct holds the entire state the pong game.
-pub struct GameState {
-    width: usize,
-    height: usize,
-    player1_score: usize,
-    player2_score: usize,
-    player1: Player,
-    player2: Player,
-    ball: Ball,
-}
+    fn reset_ball_and_players(&mut self) {
+        self.balls.truncate(1);
+        self.player1.position =
+            Self::initial_player1_position(self.config.width, self.config.height);
+        self.player2.position =
+            Self::initial_player2_position(self.config.width, self.config.height);
+        let height = self.config.height;
+        for player in [&mut self.player1, &mut self.player2]
+            .into_iter()
+            .chain(self.teammates.iter_mut().flatten())
+        {
+            player.position.y = Self::lane_y(player.lane, height);
+        }
+        let blocked = self.entity_boxes();
+        self.balls[0].position =
+            Self::spawn_position(&self.config, &self.player1, &self.player2, &blocked);
 
-impl GameState {
-    /// Constructs a new `GameState`.
+        self.balls[0].velocity = Ball::random_ball_velocity(self.config.ball_speed, &mut self.rng)
+            .scaled(self.config.mutators.serve_factor());
+        if let Some(ramp) = self.ramp {
+            self.balls[0].velocity = self.balls[0].velocity.scaled(
+                ramp.ball_speed_factor()
+                    .min(MAX_BALL_SPEED / self.balls[0].velocity.speed()),
+            );
+        }
+        self.balls[0].spin = CellsPerSecond::ZERO;
+        if self.arena.edge_players && self.rng.gen::<bool>() {
+            // half of the serves go towards the top or the bottom edge
+            let velocity = self.balls[0].velocity;
+            self.balls[0].velocity = Velocity2D::new(velocity.vy, velocity.vx);
+        }
+
+        self.rally_hits = 0;
+        self.last_hitter = None;
+        self.player1.restore_size();
+        self.player2.restore_size();
+        self.player1.tilt = 0;
+        self.player2.tilt = 0;
+        for teammate in self.teammates.iter_mut().flatten() {
+            teammate.restore_size();
+        }
+        for edge in [Edge::Top, Edge::Bottom] {
+            let position = Self::initial_edge_position(self.config.width, self.config.height, edge);
+            if let Some(player) = self.edge_player_mut(edge) {
+                player.position = position;
+                player.restore_size();
+            }
+        }
+
+        self.run_mode_hook(|mode, state| mode.on_serve(state));
+    }
+
+    /// Renders the current game state to the terminal.
+    ///
+    /// # Returns
+    /// An `io::Result` indicating the outcome of the render operation.
+    pub fn display(&mut self) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        let (columns, rows) = terminal::size()?;
+        // the last row stays free, so the line break after the frame does not scroll the terminal
+        let frame = self.compose_view(columns as usize, (rows as usize).saturating_sub(1));
+
+        let mut capabilities = self.capabilities;
+        if self.is_degraded(Degradation::Colors) {
+            capabilities.color_depth = ColorDepth::Monochrome;
+        }
+        match &self.shown_frame {
+            Some(shown) if self.is_degraded(Degradation::PartialRedraw) => {
+                frame.flush_changes(&mut stdout, shown, &capabilities)?
+            }
+            _ => frame.flush_adapted(&mut stdout, &capabilities)?,
+        }
+        if self.is_degraded(Degradation::PartialRedraw) {
+            self.shown_frame = Some(frame);
+        }
+        stdout.flush()
+    }
+
+    /// Renders the current game state as terminal commands into the given writer.
     ///
     /// # Arguments
-    /// * `width` - The width of the game field.
-    /// * `height` - The height of the game field.
-    /// * `extend_player_height_up` - The extension of player's reach upwards.
-    /// * `extend_player_height_down` - The extension of player's reach downwards.
+    /// * `stdout` - The writer the commands are queued in, e.g. the terminal or a buffer for spectators.
     ///
     /// # Returns
-    /// A new `GameState` instance with initialized players and ball.
-    pub fn new(
-        width: usize,
-        height: usize,
-        extend_player_height_up: usize,
-        extend_player_height_down: usize,
-    ) -> Self {
-        let player1 = Player::new(
-            extend_player_height_up,
-            extend_player_height_down,
-            KeyCode::Char('w'),
-            KeyCode::Char('s'),
-            Self::initial_player1_position(width, height),
-        );
+    /// An `io::Result` indicating the outcome of the render operation.
+    pub fn render(&self, stdout: &mut impl Write) -> io::Result<()> {
+        self.compose_frame().flush(stdout)
+    }
 
-        let player2 = Player::new(
-            extend_player_height_up,
-            extend_player_height_down,
-            KeyCode::Up,
-            KeyCode::Down,
-            Self::initial_player2_position(width, height),
-        );
+    /// Draws the current game state into the layers of a new `Frame`.
+    pub fn compose_frame(&self) -> Frame {
+        let width = self.field_columns().max(HUD_WIDTH);
+        let mut frame = Frame::new(width, FIELD_TOP + self.config.height + 2);
 
-        let ball = Ball::new(Self::initial_ball_position(width, height));
+        self.draw_court(&mut frame);
+        self.draw_entities(&mut frame);
+        self.draw_effects(&mut frame);
+        self.hud.draw(self, &mut frame, self.config.theme.text);
+        self.draw_taunts(&mut frame);
+        self.draw_toast(&mut frame);
+        self.draw_pause(&mut frame);
 
-        GameState {
-            width,
-            height,
-            player1_score: 0,
-            player2_score: 0,
-            player1,
-            player2,
-            ball,
+        frame
+    }
+
+    /// Draws the court of the current game state into a new `Frame` that is as wide as the field,
+    /// without the HUD above it, e.g. for views that show the score in their own way.
+    pub fn compose_court(&self) -> Frame {
+        let frame = self.compose_frame();
+        let court_top = FIELD_TOP - 1;
+        let mut court = Frame::new(self.field_columns(), frame.height() - court_top);
+        court.copy_from(&frame, 0, court_top, 0, 0, court.width(), court.height());
+
+        court
+    }
+
+    /// Returns the characters of the current game state without colors, one row per entry, e.g. for
+    /// tests or frontends that draw the game themselves. The grid shows the same as `compose_frame`.
+    pub fn render_to_grid(&self) -> Vec<Vec<char>> {
+        self.compose_frame().chars()
+    }
+
+    /// Draws the current game state into a `Frame` that fits into a terminal of the given size.
+    ///
+    /// # Remarks
+    /// If the field is larger than the terminal, only a viewport following the ball is shown
+    /// and a minimap of the whole field is drawn into its top right corner.
+    pub fn compose_view(&self, columns: usize, rows: usize) -> Frame {
+        let frame = self.compose_frame();
+        if self.field_columns() <= columns && frame.height() <= rows {
+            return frame;
         }
+
+        let mut view = Frame::new(columns, rows);
+        view.copy_from(&frame, 0, 0, 0, 0, columns, FIELD_TOP - 1);
+
+        // the viewport covers the field including its borders
+        let court_top = FIELD_TOP - 1;
+        let court_rows = frame.height() - court_top;
+        let view_rows = rows.saturating_sub(court_top);
+        let (ball_x, ball_y) = self.balls[0]
+            .get_position()
+            .to_discrete()
+            .clamped(self.config.width, self.config.height);
+        let ball_column = ball_x * self.cell_width();
+        let ball_row = self.frame_row(ball_y) - court_top;
+
+        let left = Self::viewport_start(ball_column, columns, self.field_columns());
+        let top = Self::viewport_start(ball_row, view_rows, court_rows);
+        view.copy_from(
+            &frame,
+            left,
+            court_top + top,
+            0,
+            court_top,
+            columns,
+            view_rows,
+        );
+
+        self.draw_minimap(&mut view, (left, top), (columns, view_rows));
+
+        view
     }
 
-    /// Updates the state of the game including player positions, ball position, and score based on the time elapsed and the pressed keys.
+    /// Returns the first position of a viewport of the given length that centers `target`
+    /// without leaving the range `0..total`.
+    fn viewport_start(target: usize, length: usize, total: usize) -> usize {
+        target
+            .saturating_sub(length / 2)
+            .min(total.saturating_sub(length))
+    }
+
+    /// Draws a scaled-down map of the whole field into the top right corner of the viewport.
     ///
     /// # Arguments
-    /// * `pressed_keys` - A `HashMap` representing the keys currently pressed by the players.
-    /// * `dt` - The `Duration` since the last update.
-    pub fn update(&mut self, pressed_keys: HashMap<KeyCode, KeyEvent>, dt: Duration) {
-        if pressed_keys.contains_key(&KeyCode::Char('r')) {
-            self.reset_ball_and_players();
+    /// * `view` - The `Frame` the viewport is drawn in.
+    /// * `viewport_start` - The column and the row of the court the viewport starts at.
+    /// * `viewport_size` - The number of columns and rows of the viewport.
+    fn draw_minimap(
+        &self,
+        view: &mut Frame,
+        viewport_start: (usize, usize),
+        viewport_size: (usize, usize),
+    ) {
+        let theme = &self.config.theme;
+        let skin = &self.config.skin;
+        let map_width = MINIMAP_WIDTH.min(viewport_size.0 / 3);
+        let map_height = MINIMAP_HEIGHT.min(viewport_size.1 / 3);
+        if map_width == 0 || map_height == 0 {
             return;
         }
 
-        self.player1
-            .update_position(self.height as f64, &pressed_keys, dt);
-        self.player2
-            .update_position(self.height as f64, &pressed_keys, dt);
+        let (left, top) = viewport_start;
+        let (columns, rows) = viewport_size;
+        let map_left = columns - map_width - 1;
+        let entities = self.mode.extra_entities(self);
 
-        self.ball
-            .update_position(self.height as f64, &self.player1, &self.player2, dt);
+        for map_y in 0..map_height {
+            // the rows of the map count from the top, the rows of the field from the bottom
+            let rows_from_top = Self::scaled_range(map_y, map_height, self.config.height + 1);
+            for map_x in 0..map_width {
+                let xs = Self::scaled_range(map_x, map_width, self.config.width + 1);
+                let contains = |position: Position2D| {
+                    let cell = position.to_discrete();
+                    cell.in_field(self.config.width, self.config.height)
+                        .is_some_and(|(x, y)| {
+                            xs.contains(&x) && rows_from_top.contains(&(self.config.height - y))
+                        })
+                };
+
+                // the court starts with the top border, so the first field row is court row 1
+                let visible = (left..left + columns).contains(&(xs.start * self.cell_width()))
+                    && (top..top + rows).contains(&(rows_from_top.start + 1));
+
+                let cell = if self.balls.iter().map(Ball::get_position).any(contains) {
+                    Cell::new(skin.ball, skin.ball_color.or(theme.ball))
+                } else if contains(self.player1.position) {
+                    Cell::new(skin.paddle, skin.paddle_color.or(theme.paddle1))
+                } else if !self.arena.right_wall && contains(self.player2.position) {
+                    Cell::new('\u{2588}', theme.paddle2)
+                } else if let Some((_, _, color)) = self
+                    .teammates
+                    .iter()
+                    .flatten()
+                    .find(|teammate| contains(teammate.position))
+                    .and_then(|teammate| self.teammate_at(teammate.position))
+                {
+                    Cell::new('\u{2588}', color)
+                } else if self.player3.as_ref().is_some_and(|p| contains(p.position)) {
+                    Cell::new('\u{2588}', theme.paddle1)
+                } else if self.player4.as_ref().is_some_and(|p| contains(p.position)) {
+                    Cell::new('\u{2588}', theme.paddle2)
+                } else if let Some(entity) =
+                    entities.iter().find(|entity| contains(entity.position))
+                {
+                    Cell::new(entity.glyph, entity.color.of(theme))
+                } else if visible {
+                    Cell::new('\u{00B7}', theme.text)
+                } else {
+                    Cell::new(' ', None)
+                };
+
+                let cell = Cell {
+                    background: Some(MINIMAP_BACKGROUND),
+                    ..cell
+                };
+                view.set(Layer::Overlay, map_left + map_x, FIELD_TOP + map_y, cell);
+            }
+        }
+    }
 
-        self.update_score();
+    /// Splits `0..total` into `parts` ranges of about equal length and returns the range with the given index.
+    fn scaled_range(index: usize, parts: usize, total: usize) -> std::ops::Range<usize> {
+        let start = index * total / parts;
+        let end = ((index + 1) * total / parts).max(start + 1);
+
+        start..end
     }
 
-    fn update_score(&mut self) {
-        if self.ball.velocity.vx <= 0.0 && self.ball.position.x < self.player1.position.x {
-            self.player2_score += 1;
-            self.reset_ball_and_players();
-        } else if self.ball.velocity.vx > 0.0 && self.ball.position.x > self.player2.position.x {
-            self.player1_score += 1;
-            self.reset_ball_and_players();
+    /// Returns how many columns of the terminal a single cell of the field takes.
+    fn cell_width(&self) -> usize {
+        if self.config.double_width {
+            2
+        } else {
+            1
         }
     }
 
-    fn reset_ball_and_players(&mut self) {
-        self.player1.position = Self::initial_player1_position(self.width, self.height);
-        self.player2.position = Self::initial_player2_position(self.width, self.height);
-        self.ball.position = Self::initial_ball_position(self.width, self.height);
+    /// Returns how many columns of the terminal the field takes.
+    fn field_columns(&self) -> usize {
+        (self.config.width + 1) * self.cell_width()
+    }
 
-        self.ball.velocity = Ball::random_ball_velocity();
+    /// Returns the row of the frame that shows the given row of the field.
+    fn frame_row(&self, y: usize) -> usize {
+        FIELD_TOP + self.config.height - y
     }
 
-    /// Renders the current game state to the terminal.
+    /// Sets all columns of the frame that show the given cell of the field.
+    fn set_field_cell(&self, frame: &mut Frame, layer: Layer, x: usize, y: usize, cell: Cell) {
+        for column in x * self.cell_width()..(x + 1) * self.cell_width() {
+            frame.set(layer, column, self.frame_row(y), cell);
+        }
+    }
+
+    fn draw_court(&self, frame: &mut Frame) {
+        let theme = &self.config.theme;
+
+        let pattern = match self.is_degraded(Degradation::Pattern) {
+            true => Pattern::None,
+            false => theme.pattern,
+        };
+
+        // the pattern is drawn per column, so it keeps its density with double-width cells
+        let last_column = self.field_columns() - 1;
+        for column in 0..=last_column {
+            // in a four-player game the upper and the lower edge are open goals
+            if !self.arena.edge_players {
+                let border = Cell::new('\u{2588}', theme.border);
+                frame.set(Layer::Background, column, FIELD_TOP - 1, border);
+                frame.set(Layer::Background, column, self.frame_row(0) + 1, border);
+            }
+
+            for y in 0..=self.config.height {
+                let cell = Cell {
+                    background: theme.background,
+                    ..Cell::new(pattern.cell(column, y, last_column), theme.pattern_color)
+                };
+                frame.set(Layer::Background, column, self.frame_row(y), cell);
+            }
+        }
+
+        if self.arena.right_wall {
+            let wall = Cell::new('\u{2588}', theme.border);
+            for y in 0..=self.config.height {
+                self.set_field_cell(frame, Layer::Background, self.config.width, y, wall);
+            }
+        }
+    }
+
+    fn draw_entities(&self, frame: &mut Frame) {
+        let theme = &self.config.theme;
+        let skin = &self.config.skin;
+
+        for entity in self.mode.extra_entities(self) {
+            let cell = entity.position.to_discrete();
+            if let Some((x, y)) = cell.in_field(self.config.width, self.config.height) {
+                let glyph = Cell::new(entity.glyph, entity.color.of(theme));
+                self.set_field_cell(frame, Layer::Entities, x, y, glyph);
+            }
+        }
+
+        for y in 0..=self.config.height {
+            for x in 0..=self.config.width {
+                let current_cell = DiscretePosition2D::new(x as i64, y as i64).to_continuous();
+                if self.player1.collides_with(current_cell) {
+                    let glyph = self.player1.glyph(y as i64, 1.0, skin.paddle);
+                    let paddle = Cell::new(glyph, skin.paddle_color.or(theme.paddle1));
+                    self.set_field_cell(frame, Layer::Entities, x, y, paddle);
+                } else if !self.arena.right_wall && self.player2.collides_with(current_cell) {
+                    let paddle = Cell::new(
+                        self.player2.glyph(y as i64, -1.0, '\u{2588}'),
+                        theme.paddle2,
+                    );
+                    self.set_field_cell(frame, Layer::Entities, x, y, paddle);
+                } else if let Some((teammate, facing, color)) = self.teammate_at(current_cell) {
+                    let paddle = Cell::new(teammate.glyph(y as i64, facing, '\u{2588}'), color);
+                    self.set_field_cell(frame, Layer::Entities, x, y, paddle);
+                } else if let Some(color) = self.edge_paddle_color(current_cell) {
+                    let paddle = Cell::new('\u{2588}', color);
+                    self.set_field_cell(frame, Layer::Entities, x, y, paddle);
+                }
+            }
+        }
+
+        let radius = self.config.mutators.ball_radius() as i64;
+        for ball in &self.balls {
+            let glyph = self.ball_glyph(ball);
+            let center = ball.get_position().to_discrete();
+            for y in center.y - radius..=center.y + radius {
+                let cell = DiscretePosition2D::new(center.x, y);
+                if let Some((x, y)) = cell.in_field(self.config.width, self.config.height) {
+                    self.draw_ball_cell(frame, x, y, glyph);
+                }
+            }
+        }
+    }
+
+    /// Returns the teammate whose paddle covers the given cell, the direction it faces and the color
+    /// of its team.
+    fn teammate_at(&self, cell: Position2D) -> Option<(&Player, f64, Option<Color>)> {
+        let theme = &self.config.theme;
+        let teams = [(1.0, theme.paddle1), (-1.0, theme.paddle2)];
+
+        self.teammates
+            .iter()
+            .zip(teams)
+            .find_map(|(team, (facing, color))| {
+                team.iter()
+                    .find(|teammate| teammate.collides_with(cell))
+                    .map(|teammate| (teammate, facing, color))
+            })
+    }
+
+    /// Returns the color of the paddle of player3 or player4 if it covers the given cell.
+    /// Player3 is drawn in the color of player1 and player4 in the color of player2.
+    fn edge_paddle_color(&self, cell: Position2D) -> Option<Option<Color>> {
+        let theme = &self.config.theme;
+        let covers =
+            |player: &Option<Player>| player.as_ref().is_some_and(|p| p.collides_with(cell));
+
+        if covers(&self.player3) {
+            Some(theme.paddle1)
+        } else if covers(&self.player4) {
+            Some(theme.paddle2)
+        } else {
+            None
+        }
+    }
+
+    fn draw_ball_cell(&self, frame: &mut Frame, x: usize, y: usize, glyph: char) {
+        let color = self.config.skin.ball_color.or(self.config.theme.ball);
+        if self.config.double_width {
+            // two half circles form a ball that fills both columns
+            let column = x * 2;
+            let row = self.frame_row(y);
+            frame.set(Layer::Entities, column, row, Cell::new('\u{25D6}', color));
+            frame.set(
+                Layer::Entities,
+                column + 1,
+                row,
+                Cell::new('\u{25D7}', color),
+            );
+        } else {
+            let cell = Cell::new(glyph, color);
+            self.set_field_cell(frame, Layer::Entities, x, y, cell);
+        }
+    }
+
+    /// Returns the glyph of the given ball. With `smooth_ball` a half filled circle leans towards
+    /// the neighboring cell the ball is closest to, which smooths the motion between the cells.
+    fn ball_glyph(&self, ball: &Ball) -> char {
+        const CENTER_MARGIN: f64 = 0.25;
+
+        let position = ball.get_position();
+        let dx = position.x - position.x.round();
+        let dy = position.y - position.y.round();
+
+        if !self.config.smooth_ball || dx.abs().max(dy.abs()) < CENTER_MARGIN {
+            self.config.skin.ball
+        } else if dx.abs() >= dy.abs() {
+            if dx > 0.0 {
+                '\u{25D1}'
+            } else {
+                '\u{25D0}'
+            }
+        } else if dy > 0.0 {
+            '\u{25D3}'
+        } else {
+            '\u{25D2}'
+        }
+    }
+
+    fn draw_effects(&self, frame: &mut Frame) {
+        if self.is_degraded(Degradation::Effects) {
+            return;
+        }
+        let scanline_phase = usize::from(self.crt_flickers());
+
+        for y in 0..=self.config.height {
+            for x in 0..=self.config.width {
+                if let Some(glow) = self.glow(DiscretePosition2D::new(x as i64, y as i64)) {
+                    self.set_field_cell(frame, Layer::Effects, x, y, Cell::background(glow));
+                }
+            }
+
+            if self.config.crt && y % 2 == scanline_phase {
+                for column in 0..self.field_columns() {
+                    if let Some(cell) = frame.cell_mut(Layer::Effects, column, self.frame_row(y)) {
+                        cell.dim = true;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draws the shown taunts of the players next to their paddles: above the paddle, or below it
+    /// if the paddle is at the top of the field.
+    fn draw_taunts(&self, frame: &mut Frame) {
+        let theme = &self.config.theme;
+        let players = [
+            (
+                PlayerSide::Left,
+                &self.player1,
+                self.config.skin.paddle_color.or(theme.paddle1),
+            ),
+            (PlayerSide::Right, &self.player2, theme.paddle2),
+        ];
+        for (side, player, color) in players {
+            let Some(taunt) = self.taunts.current(side) else {
+                continue;
+            };
+            let center = player.position.to_discrete();
+            let above = center.y + player.current_extend_up().to_offset() + 1;
+            let below = center.y - player.current_extend_down().to_offset() - 1;
+            let y = if above <= self.config.height as i64 {
+                above
+            } else {
+                below
+            };
+            let (x, y) =
+                DiscretePosition2D::new(center.x, y).clamped(self.config.width, self.config.height);
+
+            let message = taunt.message();
+            let column = match side {
+                PlayerSide::Left => x * self.cell_width(),
+                PlayerSide::Right => {
+                    ((x + 1) * self.cell_width()).saturating_sub(message.chars().count())
+                }
+            };
+            frame.print(Layer::Effects, column, self.frame_row(y), message, color);
+        }
+    }
+
+    /// Draws the current toast centered into the top row of the field.
+    fn draw_toast(&self, frame: &mut Frame) {
+        if let Some(message) = self.toasts.current() {
+            self.draw_banner(frame, FIELD_TOP, message);
+        }
+    }
+
+    /// Draws a hint how to continue the paused game into the middle of the field.
+    fn draw_pause(&self, frame: &mut Frame) {
+        if let Some(winner) = self.winner {
+            let name = side_name(winner);
+            let bindings = &self.config.key_bindings;
+            let message = format!(
+                "{name} wins {}:{} - press {} for a rematch or {} to quit",
+                self.player1_score, self.player2_score, bindings.rematch, bindings.leave
+            );
+            self.draw_banner(frame, self.frame_row(self.config.height / 2), &message);
+            return;
+        }
+
+        let key = self.config.key_bindings.pause;
+        let message = match self.pause {
+            Some(Pause::Manual) => format!("Paused - press {key} to continue"),
+            Some(Pause::Turn) => format!("Move your paddle - press {key} to continue"),
+            None => return,
+        };
+
+        self.draw_banner(frame, self.frame_row(self.config.height / 2), &message);
+    }
+
+    /// Draws a message highlighted and centered above the field into the given row.
+    fn draw_banner(&self, frame: &mut Frame, row: usize, message: &str) {
+        let text = format!(" {message} ");
+        let x = self.field_columns().saturating_sub(text.chars().count()) / 2;
+        let end = frame.print(Layer::Overlay, x, row, &text, self.config.theme.text);
+        for column in x..end {
+            if let Some(cell) = frame.cell_mut(Layer::Overlay, column, row) {
+                cell.reverse = true;
+            }
+        }
+    }
+
+    /// Returns the background color of a cell lit by the ball, or `None` if the glow is disabled
+    /// or the cell is out of its reach.
     ///
-    /// # Returns
-    /// An `io::Result` indicating the outcome of the render operation.
-    pub fn display(&mut self) -> io::Result<()> {
-        let mut stdout = io::stdout();
+    /// # Remarks
+    /// Terminal cells are about twice as high as wide, so vertical distances count double.
+    /// The brightness decreases linearly with the distance and is mapped to the grayscale ramp
+    /// of the 256 color palette.
+    fn glow(&self, cell: DiscretePosition2D) -> Option<Color> {
+        if !self.config.ball_glow {
+            return None;
+        }
 
-        stdout.queue(terminal::Clear(ClearType::All))?;
-        stdout.queue(cursor::Hide)?;
-        stdout.queue(cursor::MoveTo(0, 0))?;
+        let cell = cell.to_continuous();
+        let dx = self.balls[0].position.x - cell.x;
+        let dy = 2.0 * (self.balls[0].position.y - cell.y);
+        let distance = (dx * dx + dy * dy).sqrt();
+        if distance > GLOW_RADIUS {
+            return None;
+        }
 
-        stdout.queue(Print(format!(
-            "\r\nGoals of player1: {},  Goals of player2: {}\r\n\r\n",
-            self.player1_score, self.player2_score
-        )))?;
+        let level = ((1.0 - distance / GLOW_RADIUS) * GLOW_LEVELS).round() as u8;
+        (level > 0).then_some(Color::AnsiValue(232 + level))
+    }
 
-        for _ in 0..=self.width {
-            stdout.queue(Print('\u{2588}'))?;
+    /// Decides whether the score of the given side is highlighted in the current frame.
+    /// After a goal the score of the scoring player flashes for a moment.
+    pub fn score_flashes(&self, side: PlayerSide) -> bool {
+        match self.last_goal {
+            Some((scorer, timer)) if scorer == side => {
+                !timer.is_finished()
+                    && (timer.elapsed().as_millis() / SCORE_FLASH_INTERVAL.as_millis())
+                        .is_multiple_of(2)
+            }
+            _ => false,
         }
-        stdout.queue(Print("\r\n"))?;
+    }
 
-        for y in (0..=self.height).rev() {
-            for x in 0..=self.width {
-                let current_cell = DiscretePosition2D::new(x, y);
+    /// Decides whether the current frame flickers. The scanlines then swap rows for a frame.
+    ///
+    /// # Remarks
+    /// Two sine waves of different frequencies rarely peak together, which makes the flicker
+    /// appear irregular while the frames stay deterministic, e.g. for spectators and replays.
+    fn crt_flickers(&self) -> bool {
+        let time = self.clock.elapsed().as_secs_f64();
+        (time * 13.0).sin() + (time * 7.3).sin() > 1.9
+    }
 
-                let character = if self.ball.get_position().to_discrete() == current_cell {
-                    '\u{25CF}'
-                } else if self.player1.collides_with(current_cell.to_continuous()) {
-                    '\u{2588}'
-                } else if self.player2.collides_with(current_cell.to_continuous()) {
-                    '\u{2588}'
-                } else {
-                    ' '
-                };
+    /// Replaces the theme the game is drawn with. It is used from the next rendered frame on.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.config.theme = theme;
+    }
+
+    /// Turns off the visual features up to the given `Degradation`, e.g. because the terminal is
+    /// too slow for them. The simulation is not affected.
+    pub fn degrade(&mut self, degradation: Degradation) {
+        self.degradation = self.degradation.max(Some(degradation));
+    }
+
+    /// Returns `true` if the given visual feature was turned off with `degrade`.
+    pub fn is_degraded(&self, degradation: Degradation) -> bool {
+        self.degradation >= Some(degradation)
+    }
+
+    /// Sets what the terminal `display` draws into can show, e.g. to replace the colors of the
+    /// theme by the closest ones the terminal knows.
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+    }
 
-                stdout.queue(Print(character))?;
+    /// Returns what the terminal the game is shown in can show, see `set_capabilities`.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// Returns the options that shape the game as pairs of the command-line option and its value,
+    /// so a replay describes the game it was recorded from.
+    pub fn replay_options(&self) -> Vec<(String, String)> {
+        let config = &self.config;
+        let mutators: Vec<String> = config
+            .mutators
+            .active()
+            .iter()
+            .filter_map(|mutator| mutator.to_possible_value())
+            .map(|value| value.get_name().to_string())
+            .collect();
+        let ball_speed = config
+            .ball_speed
+            .to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default();
+
+        let mut options = vec![
+            ("ball-speed", ball_speed),
+            ("paddle-speed", config.paddle_speed.to_string()),
+            ("restitution", config.restitution.to_string()),
+            ("spin", config.spin.to_string()),
+            ("jitter", config.jitter.to_string()),
+            (
+                "up-extend-player-height",
+                config.extend_player_height_up.to_string(),
+            ),
+            (
+                "down-extend-player-height",
+                config.extend_player_height_down.to_string(),
+            ),
+            ("shrink-paddles", config.shrink_paddles.to_string()),
+            ("turn-based", config.turn_based.to_string()),
+            ("mode", self.mode.name().to_string()),
+            ("mutator", mutators.join(",")),
+            ("player1", self.player1.controller.kind().to_string()),
+            ("player2", self.player2.controller.kind().to_string()),
+        ];
+        if config.gravity > 0.0 {
+            options.push(("gravity", config.gravity.to_string()));
+        }
+        if config.mutators.is_active(Mutator::Mirror) {
+            options.push((
+                "mirror-interval",
+                config.mirror_interval.as_secs_f64().to_string(),
+            ));
+        }
+        if config.mutators.is_active(Mutator::Chaos) {
+            options.push((
+                "chaos-interval",
+                config.chaos_interval.as_secs_f64().to_string(),
+            ));
+        }
+        if let Some(seed) = config.seed {
+            options.push(("seed", seed.to_string()));
+        }
+        if let Some(limit) = config.score_limit {
+            options.push(("score-limit", limit.to_string()));
+        }
+        if let Some(format) = config.match_format {
+            options.push(("points-to-win", format.points_to_win.to_string()));
+            options.push(("best-of", format.best_of.to_string()));
+        }
+        for (handicap, [extend, speed, start_score]) in [
+            (
+                config.player1_handicap,
+                ["p1-extend", "p1-speed", "p1-start-score"],
+            ),
+            (
+                config.player2_handicap,
+                ["p2-extend", "p2-speed", "p2-start-score"],
+            ),
+        ] {
+            if let Some(cells) = handicap.extend {
+                options.push((extend, cells.to_string()));
+            }
+            if let Some(cells_per_second) = handicap.paddle_speed {
+                options.push((speed, cells_per_second.to_string()));
+            }
+            if handicap.start_score > 0 {
+                options.push((start_score, handicap.start_score.to_string()));
             }
-            stdout.queue(Print("\r\n"))?;
         }
 
-        for _ in 0..=self.width {
-            stdout.queue(Print('\u{2588}'))?;
+        options
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    /// Captures the current state of the game as a frame of a replay.
+    ///
+    /// # Arguments
+    /// * `dt` - The `Duration` of game time since the previous frame.
+    pub fn replay_frame(&self, dt: Duration) -> ReplayFrame {
+        ReplayFrame {
+            dt,
+            player1_y: self.player1.position.y,
+            player2_y: self.player2.position.y,
+            ball_x: self.balls[0].position.x,
+            ball_y: self.balls[0].position.y,
+            ball_vx: self.balls[0].velocity.vx,
+            ball_vy: self.balls[0].velocity.vy,
+            player1_score: self.player1_score,
+            player2_score: self.player2_score,
         }
-        stdout.queue(Print("\r\n"))?;
+    }
 
-        stdout.queue(cursor::Show)?;
-        stdout.flush()?;
+    /// Shows a recorded frame of a replay: the paddles and the ball move to their recorded positions
+    /// and the score changes to the recorded score. The game itself is not simulated.
+    pub fn show_replay_frame(&mut self, frame: &ReplayFrame) {
+        self.clock.advance(frame.dt);
+        self.toasts.update(frame.dt);
+        self.player1.position.y = frame.player1_y;
+        self.player2.position.y = frame.player2_y;
+        self.balls[0].position = Position2D::new(frame.ball_x, frame.ball_y);
+        self.balls[0].velocity = Velocity2D::new(frame.ball_vx, frame.ball_vy);
+        self.player1_score = frame.player1_score;
+        self.player2_score = frame.player2_score;
+    }
+
+    /// Returns a fingerprint of the simulation: the paddles, the ball, the scores, the match and the
+    /// winner. Games with the same fingerprint are in the same state, e.g. to check that a game plays
+    /// out the same way for the same seed.
+    ///
+    /// # Remarks
+    /// The fingerprint is an FNV-1a hash, so it is the same on every platform and with every version
+    /// of the compiler. It changes whenever the physics change.
+    pub fn state_hash(&self) -> u64 {
+        let (games, points) = self
+            .match_score
+            .map_or(((0, 0), (0, 0)), |score| (score.games(), score.points()));
+        let winner = match self.winner {
+            None => 0,
+            Some(PlayerSide::Left) => 1,
+            Some(PlayerSide::Right) => 2,
+        };
+        let values = [
+            self.player1.position.x.to_bits(),
+            self.player1.position.y.to_bits(),
+            self.player2.position.x.to_bits(),
+            self.player2.position.y.to_bits(),
+            self.balls[0].position.x.to_bits(),
+            self.balls[0].position.y.to_bits(),
+            self.balls[0].velocity.vx.to_bits(),
+            self.balls[0].velocity.vy.to_bits(),
+            self.player1_score as u64,
+            self.player2_score as u64,
+            self.rally_hits as u64,
+            games.0 as u64,
+            games.1 as u64,
+            points.0 as u64,
+            points.1 as u64,
+            winner,
+        ];
+        // the players of a four-player game only count where they exist, so the fingerprints
+        // of two-player games stay the same
+        let edge_values = [
+            (&self.player3, self.player3_score),
+            (&self.player4, self.player4_score),
+        ]
+        .into_iter()
+        .filter_map(|(player, score)| player.as_ref().map(|player| (player, score)))
+        .flat_map(|(player, score)| [player.position.x.to_bits(), score as u64]);
+        let teammate_values = self
+            .teammates
+            .iter()
+            .flatten()
+            .map(|teammate| teammate.position.y.to_bits());
+        let entity_values = self.mode.extra_entities(self).into_iter().map(|entity| {
+            let cell = entity.position.to_discrete();
+            ((cell.x as u64) << 32) | cell.y as u64
+        });
+        let ramp_values = self.ramp.map(|ramp| ramp.level() as u64);
+        let extra_ball_values = self.balls[1..].iter().flat_map(|ball| {
+            [
+                ball.position.x.to_bits(),
+                ball.position.y.to_bits(),
+                ball.velocity.vx.to_bits(),
+                ball.velocity.vy.to_bits(),
+            ]
+        });
+        let mirror_values = self
+            .mirror
+            .map(|mirror| mirror.mirrored().map_or(0, |side| side as u64 + 1));
+
+        values
+            .into_iter()
+            .chain(edge_values)
+            .chain(teammate_values)
+            .chain(entity_values)
+            .chain(ramp_values)
+            .chain(mirror_values)
+            .chain(extra_ball_values)
+            .flat_map(|value| value.to_le_bytes())
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    /// Estimates the probability that player1 wins based on the score, the ball and the rally statistics.
+    pub fn player1_win_probability(&self) -> f64 {
+        let heading_to = if self.balls[0].velocity.vx <= 0.0 {
+            PlayerSide::Left
+        } else {
+            PlayerSide::Right
+        };
+        let distance_to_defender = match heading_to {
+            PlayerSide::Left => self.balls[0].position.x - self.player1.position.x,
+            PlayerSide::Right => self.player2.position.x - self.balls[0].position.x,
+        };
+        let score_difference = self.player1_score as f64 - self.player2_score as f64;
 
-        Ok(())
+        stats::win_probability(
+            score_difference,
+            heading_to,
+            (distance_to_defender / self.config.width as f64).clamp(0.0, 1.0),
+            &self.stats,
+        )
     }
 
     fn initial_player1_position(_: usize, height: usize) -> Position2D {
@@ -429,4 +4282,23 @@ impl GameState {
 
         Position2D::new(x, y)
     }
+
+    /// Returns the position the ball is served from, which is moved away from the center if
+    /// the ball would overlap a paddle or one of the `blocked` boxes there, e.g. in a very small field.
+    fn spawn_position(
+        config: &GameConfig,
+        player1: &Player,
+        player2: &Player,
+        blocked: &[Aabb],
+    ) -> Position2D {
+        let mut blocked = blocked.to_vec();
+        blocked.extend([player1.bounding_box(), player2.bounding_box()]);
+
+        safe_spawn_point(
+            Self::initial_ball_position(config.width, config.height),
+            (config.width as f64, config.height as f64),
+            config.mutators.ball_radius() as f64,
+            &blocked,
+        )
+    }
 }
@@ -0,0 +1,114 @@
+use clap::ValueEnum;
+
+/// Defines how strongly a single goal of difference shifts the win probability.
+const GOAL_WEIGHT: f64 = 0.6;
+
+/// Identifies one of the two sides of the game field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum PlayerSide {
+    Left,
+    Right,
+}
+
+impl PlayerSide {
+    fn index(self) -> usize {
+        match self {
+            PlayerSide::Left => 0,
+            PlayerSide::Right => 1,
+        }
+    }
+}
+
+/// This struct keeps track of how often each player returned or missed the ball.
+#[derive(Debug, Clone, Default)]
+pub struct RallyStats {
+    returns: [usize; 2],
+    misses: [usize; 2],
+    rallies: usize,
+    rally_hits: usize,
+    longest_rally: usize,
+}
+
+impl RallyStats {
+    /// Registers that the player on the given side returned the ball.
+    pub fn register_return(&mut self, side: PlayerSide) {
+        self.returns[side.index()] += 1;
+    }
+
+    /// Registers that the player on the given side missed the ball.
+    pub fn register_miss(&mut self, side: PlayerSide) {
+        self.misses[side.index()] += 1;
+    }
+
+    /// Registers a finished rally with the given number of hits.
+    pub fn register_rally(&mut self, hits: usize) {
+        self.rallies += 1;
+        self.rally_hits += hits;
+        self.longest_rally = self.longest_rally.max(hits);
+    }
+
+    /// Returns how often the player on the given side returned the ball.
+    pub fn returns(&self, side: PlayerSide) -> usize {
+        self.returns[side.index()]
+    }
+
+    /// Returns how often the player on the given side missed the ball.
+    pub fn misses(&self, side: PlayerSide) -> usize {
+        self.misses[side.index()]
+    }
+
+    /// Returns the number of finished rallies.
+    pub fn rallies(&self) -> usize {
+        self.rallies
+    }
+
+    /// Returns the total number of hits in all finished rallies.
+    pub fn rally_hits(&self) -> usize {
+        self.rally_hits
+    }
+
+    /// Returns the number of hits of the longest finished rally.
+    pub fn longest_rally(&self) -> usize {
+        self.longest_rally
+    }
+
+    /// Returns the share of balls the player on the given side returned so far.
+    /// Without any data the rate starts at 50% and approaches the observed rate over time.
+    pub fn return_rate(&self, side: PlayerSide) -> f64 {
+        let returns = self.returns[side.index()] as f64;
+        let misses = self.misses[side.index()] as f64;
+
+        (returns + 1.0) / (returns + misses + 2.0)
+    }
+}
+
+/// Estimates the probability that the left player wins.
+///
+/// # Arguments
+/// * `score_difference` - The goals of the left player minus the goals of the right player.
+/// * `heading_to` - The side the ball is currently moving towards.
+/// * `distance_to_defender` - The distance of the ball to the defending player relative to the field width (0 to 1).
+/// * `stats` - The `RallyStats` collected so far.
+///
+/// # Remarks
+/// The current point is decided by the return rate of the defending player. The further away the ball is,
+/// the less certain this guess becomes. The expected goal difference is then mapped to a probability
+/// with a logistic function.
+pub fn win_probability(
+    score_difference: f64,
+    heading_to: PlayerSide,
+    distance_to_defender: f64,
+    stats: &RallyStats,
+) -> f64 {
+    let certainty = 1.0 - distance_to_defender;
+    let concede_probability =
+        (1.0 - stats.return_rate(heading_to)) * certainty + 0.5 * (1.0 - certainty);
+
+    let left_point_probability = match heading_to {
+        PlayerSide::Left => 1.0 - concede_probability,
+        PlayerSide::Right => concede_probability,
+    };
+    let expected_difference = score_difference + (2.0 * left_point_probability - 1.0);
+
+    1.0 / (1.0 + (-GOAL_WEIGHT * expected_difference).exp())
+}
@@ -0,0 +1,213 @@
+use crate::utils::data_dir;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// The kinds of data the game keeps between two runs. Each collection is stored in its own file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Collection {
+    /// The achievements unlocked so far.
+    Achievements,
+    /// The anonymous gameplay metrics of a player who turned the telemetry on.
+    Telemetry,
+    /// The season of the club that is played and the finished ones.
+    Seasons,
+}
+
+impl Collection {
+    /// Returns the name of the file the collection is stored in.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Collection::Achievements => "achievements.json",
+            Collection::Telemetry => "telemetry.json",
+            Collection::Seasons => "seasons.json",
+        }
+    }
+}
+
+/// How a collection was recovered whose file could not be read, because it was corrupted, e.g.
+/// by a crash while it was written by an older version. The corrupted file is kept next to the
+/// collection with the extension `.corrupt`, so it can be inspected or repaired by hand.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Recovery {
+    /// The version from before the last save was restored.
+    FromBackup,
+    /// There was no usable backup, so the collection starts out empty.
+    Reset,
+}
+
+/// A collection read by `Storage::load`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loaded<T> {
+    pub value: T,
+    /// How the collection was recovered, or `None` if its file was fine or did not exist yet.
+    pub recovery: Option<Recovery>,
+}
+
+/// # Explanation
+/// The storage decides where the data lives that the game keeps between two runs, so features
+/// like achievements, telemetry or seasons only say what they store. Every collection is a JSON
+/// file in one directory, by default the data directory of the platform. Files the player names
+/// or edits, e.g. AI profiles, presets or themes, are no collections, but they are written with
+/// `write_atomically` as well.
+///
+/// # Remarks
+/// A collection is written to a temporary file first, which then replaces the old file, so a
+/// crash while saving never leaves a half written file behind. The previous version is kept as
+/// a `.bak` file, which `load` falls back to if the file is corrupted anyway.
+#[derive(Debug, Clone)]
+pub struct Storage {
+    dir: PathBuf,
+}
+
+impl Storage {
+    /// Creates a storage that keeps its files in the given directory. The directory is created
+    /// with the first save.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Storage { dir: dir.into() }
+    }
+
+    /// Returns the storage in the data directory of the platform, or `None` if there is no such
+    /// directory, e.g. because `HOME` is not set.
+    pub fn open_default() -> Option<Self> {
+        data_dir().map(Storage::new)
+    }
+
+    /// Returns the directory the files are kept in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path of the file the given collection is stored in.
+    pub fn path(&self, collection: Collection) -> PathBuf {
+        self.dir.join(collection.file_name())
+    }
+
+    /// Reads a collection. A collection that was never saved is the default value of its type.
+    ///
+    /// # Arguments
+    /// * `collection` - The `Collection` to read.
+    ///
+    /// # Returns
+    /// The collection and whether it had to be recovered, see `Recovery`. An error is only
+    /// returned if a file exists but can not be read at all, e.g. because of missing permissions.
+    pub fn load<T: DeserializeOwned + Default>(
+        &self,
+        collection: Collection,
+    ) -> io::Result<Loaded<T>> {
+        let path = self.path(collection);
+        let Some(content) = read_if_exists(&path)? else {
+            return Ok(Loaded {
+                value: T::default(),
+                recovery: None,
+            });
+        };
+        if let Ok(value) = serde_json::from_str(&content) {
+            return Ok(Loaded {
+                value,
+                recovery: None,
+            });
+        }
+
+        fs::rename(&path, with_suffix(&path, "corrupt"))?;
+        let backup = with_suffix(&path, "bak");
+        let restored = read_if_exists(&backup)?.and_then(|content| {
+            serde_json::from_str(&content)
+                .ok()
+                .map(|value| (content, value))
+        });
+
+        match restored {
+            Some((content, value)) => {
+                write_atomically(&path, &content)?;
+                Ok(Loaded {
+                    value,
+                    recovery: Some(Recovery::FromBackup),
+                })
+            }
+            None => Ok(Loaded {
+                value: T::default(),
+                recovery: Some(Recovery::Reset),
+            }),
+        }
+    }
+
+    /// Writes a collection, replacing the stored version. The stored version is kept as backup.
+    ///
+    /// # Arguments
+    /// * `collection` - The `Collection` to write.
+    /// * `value` - The new content of the collection.
+    pub fn save<T: Serialize>(&self, collection: Collection, value: &T) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(value).map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} could not be serialized: {error}",
+                    collection.file_name()
+                ),
+            )
+        })?;
+
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(collection);
+        if path.exists() {
+            fs::copy(&path, with_suffix(&path, "bak"))?;
+        }
+
+        write_atomically(&path, &content)
+    }
+
+    /// Reads a collection, changes it and writes it back.
+    ///
+    /// # Arguments
+    /// * `collection` - The `Collection` to change.
+    /// * `change` - Changes the collection in place.
+    ///
+    /// # Returns
+    /// The changed collection.
+    pub fn update<T: Serialize + DeserializeOwned + Default>(
+        &self,
+        collection: Collection,
+        change: impl FnOnce(&mut T),
+    ) -> io::Result<T> {
+        let mut value = self.load(collection)?.value;
+        change(&mut value);
+        self.save(collection, &value)?;
+
+        Ok(value)
+    }
+}
+
+/// Reads a file, or returns `None` if it does not exist.
+fn read_if_exists(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        // a file that is no valid UTF-8 can only be corrupted
+        Err(error) if error.kind() == io::ErrorKind::InvalidData => Ok(Some(String::new())),
+        Err(error) => Err(error),
+    }
+}
+
+/// Writes the content to a temporary file next to the path and moves it over the path once it
+/// is completely on the disk. Renaming a file within a directory replaces the old file at once,
+/// so a crash while writing never leaves a half written file behind.
+pub fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    let temporary = with_suffix(path, "tmp");
+    let mut file = File::create(&temporary)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+
+    fs::rename(&temporary, path)
+}
+
+/// Appends a suffix to the file name of a path, e.g. `stats.json.bak`.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+
+    path.with_file_name(name)
+}
@@ -0,0 +1,96 @@
+use crate::stats::PlayerSide;
+use crate::timer::Timer;
+use std::time::Duration;
+
+/// Defines how long a taunt is shown next to the paddle.
+pub const TAUNT_TIME: Duration = Duration::from_secs(1);
+
+/// Defines how long a player has to wait after a taunt before the next one is shown.
+pub const TAUNT_COOLDOWN: Duration = Duration::from_secs(3);
+
+/// The short messages the players can fire at each other, one per taunt key.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Taunt {
+    GoodGame,
+    TooEasy,
+    Close,
+    Wow,
+}
+
+impl Taunt {
+    /// All taunts in the order of their keys.
+    pub const ALL: [Taunt; 4] = [Taunt::GoodGame, Taunt::TooEasy, Taunt::Close, Taunt::Wow];
+
+    /// Returns the message shown next to the paddle.
+    pub fn message(&self) -> &'static str {
+        match self {
+            Taunt::GoodGame => "GG!",
+            Taunt::TooEasy => "Too easy!",
+            Taunt::Close => "So close!",
+            Taunt::Wow => "Wow!",
+        }
+    }
+}
+
+/// # Explanation
+/// The taunts keep the taunt each player fired last while it is shown and limit how often a
+/// player can taunt, so the messages can not flood the field. The time runs with the simulated
+/// time of the game.
+#[derive(Debug, Clone)]
+pub struct Taunts {
+    shown: [Option<(Taunt, Timer)>; 2],
+    cooldowns: [Timer; 2],
+}
+
+impl Default for Taunts {
+    fn default() -> Self {
+        Taunts {
+            shown: [None, None],
+            cooldowns: [Timer::once(Duration::ZERO); 2],
+        }
+    }
+}
+
+impl Taunts {
+    /// Shows the given taunt of the player on the given side unless the player taunted too
+    /// recently.
+    ///
+    /// # Returns
+    /// `true` if the taunt is shown, `false` if it was rate-limited.
+    pub fn fire(&mut self, side: PlayerSide, taunt: Taunt) -> bool {
+        let index = Self::index(side);
+        if !self.cooldowns[index].is_finished() {
+            return false;
+        }
+
+        self.shown[index] = Some((taunt, Timer::once(TAUNT_TIME)));
+        self.cooldowns[index] = Timer::once(TAUNT_COOLDOWN);
+        true
+    }
+
+    /// Advances the time of the shown taunts and of the rate limits.
+    pub fn update(&mut self, dt: Duration) {
+        for shown in &mut self.shown {
+            if let Some((_, timer)) = shown {
+                if timer.advance(dt) > 0 {
+                    *shown = None;
+                }
+            }
+        }
+        for cooldown in &mut self.cooldowns {
+            cooldown.advance(dt);
+        }
+    }
+
+    /// Returns the taunt of the player on the given side that is currently shown.
+    pub fn current(&self, side: PlayerSide) -> Option<Taunt> {
+        self.shown[Self::index(side)].map(|(taunt, _)| taunt)
+    }
+
+    fn index(side: PlayerSide) -> usize {
+        match side {
+            PlayerSide::Left => 0,
+            PlayerSide::Right => 1,
+        }
+    }
+}
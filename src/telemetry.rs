@@ -0,0 +1,189 @@
+use crate::observer::GameObserver;
+use crate::state::{GameEvent, GameState};
+use crate::storage::{Collection, Storage};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The anonymous gameplay metrics summed up over all sessions. They hold no names, paths, seeds or
+/// times, only counts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GameplayMetrics {
+    /// The number of games started.
+    pub sessions: u64,
+    /// The number of games that ended without a crash.
+    pub crash_free_sessions: u64,
+    /// The number of games started in each mode.
+    pub modes: BTreeMap<String, u64>,
+    /// The number of rallies that ended with a goal.
+    pub rallies: u64,
+    /// The number of returns in these rallies.
+    pub rally_hits: u64,
+}
+
+impl GameplayMetrics {
+    /// Returns the average number of returns of a rally, or `0.0` if no rally was recorded yet.
+    pub fn average_rally_length(&self) -> f64 {
+        match self.rallies {
+            0 => 0.0,
+            rallies => self.rally_hits as f64 / rallies as f64,
+        }
+    }
+
+    /// Returns the share of the games that ended without a crash, from `0.0` to `1.0`, or `1.0`
+    /// if no game was started yet.
+    pub fn crash_free_rate(&self) -> f64 {
+        match self.sessions {
+            0 => 1.0,
+            sessions => self.crash_free_sessions as f64 / sessions as f64,
+        }
+    }
+
+    /// Describes the metrics as lines of text, the modes sorted by the number of games.
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Games: {}", self.sessions),
+            format!(
+                "Crash-free games: {} ({:.1}%)",
+                self.crash_free_sessions,
+                100.0 * self.crash_free_rate()
+            ),
+            format!(
+                "Average rally: {:.1} returns over {} rallies",
+                self.average_rally_length(),
+                self.rallies
+            ),
+        ];
+
+        let mut modes: Vec<(&String, &u64)> = self.modes.iter().collect();
+        modes.sort_by(|(_, a), (_, b)| b.cmp(a));
+        lines.extend(
+            modes
+                .into_iter()
+                .map(|(mode, games)| format!("Mode {mode}: {games} games")),
+        );
+
+        lines
+    }
+}
+
+/// The telemetry collection as it is stored: whether the player opted in and the metrics so far.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Telemetry {
+    /// `true` once the player turned the telemetry on. Nothing is recorded before.
+    pub enabled: bool,
+    pub metrics: GameplayMetrics,
+}
+
+impl Telemetry {
+    /// Reads the telemetry from the given storage. It is off until the player turns it on.
+    pub fn load(storage: &Storage) -> io::Result<Self> {
+        storage
+            .load(Collection::Telemetry)
+            .map(|loaded| loaded.value)
+    }
+
+    /// Turns the recording on or off. The metrics collected so far are kept.
+    pub fn set_enabled(storage: &Storage, enabled: bool) -> io::Result<Self> {
+        storage.update(Collection::Telemetry, |telemetry: &mut Telemetry| {
+            telemetry.enabled = enabled;
+        })
+    }
+
+    /// Deletes the metrics collected so far. Whether the recording is on stays as it is.
+    pub fn reset(storage: &Storage) -> io::Result<Self> {
+        storage.update(Collection::Telemetry, |telemetry: &mut Telemetry| {
+            telemetry.metrics = GameplayMetrics::default();
+        })
+    }
+
+    /// Writes the metrics as a JSON report to the given file, e.g. to share them with the
+    /// developers. This is the only way the metrics leave the data directory.
+    pub fn export(&self, path: &Path) -> io::Result<()> {
+        let report = serde_json::to_string_pretty(&self.metrics)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+
+        fs::write(path, report + "\n")
+    }
+}
+
+/// # Explanation
+/// The telemetry recorder is the observer that collects the gameplay metrics of a game for a
+/// player who opted in. The game is counted when the recorder starts, and it only counts as
+/// crash-free once the recorder is finished, so a game that crashed stays a game without a
+/// crash-free end.
+///
+/// # Remarks
+/// The telemetry must never disturb the game: the recorder keeps the rallies in memory and only
+/// writes them when the game ends, and a failed write is ignored.
+pub struct TelemetryRecorder {
+    storage: Storage,
+    rallies: u64,
+    rally_hits: u64,
+    /// The returns of the current rally, which are only counted once it ends with a goal.
+    current_hits: u64,
+}
+
+impl TelemetryRecorder {
+    /// Counts a new game in the given mode and starts to record it.
+    ///
+    /// # Arguments
+    /// * `storage` - The `Storage` the telemetry is kept in.
+    /// * `mode` - The name of the game mode.
+    ///
+    /// # Returns
+    /// The recorder, or `None` if the player did not turn the telemetry on.
+    pub fn start(storage: Storage, mode: &str) -> io::Result<Option<Self>> {
+        if !Telemetry::load(&storage)?.enabled {
+            return Ok(None);
+        }
+
+        storage.update(Collection::Telemetry, |telemetry: &mut Telemetry| {
+            telemetry.metrics.sessions += 1;
+            *telemetry.metrics.modes.entry(mode.to_string()).or_default() += 1;
+        })?;
+
+        Ok(Some(TelemetryRecorder {
+            storage,
+            rallies: 0,
+            rally_hits: 0,
+            current_hits: 0,
+        }))
+    }
+}
+
+impl GameObserver for TelemetryRecorder {
+    fn on_event(&mut self, _state: &GameState, event: GameEvent) -> io::Result<()> {
+        match event {
+            GameEvent::Hit(_) | GameEvent::EdgeHit(_) => self.current_hits += 1,
+            GameEvent::Goal(_) | GameEvent::EdgeGoal(_) => {
+                self.rallies += 1;
+                self.rally_hits += std::mem::take(&mut self.current_hits);
+            }
+            GameEvent::PointRestarted | GameEvent::Rewound | GameEvent::NewGame => {
+                self.current_hits = 0;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn on_finish(&mut self) -> io::Result<()> {
+        let (rallies, rally_hits) = (self.rallies, self.rally_hits);
+        // the player may have turned the telemetry off while the game was running
+        let _ = self
+            .storage
+            .update(Collection::Telemetry, |telemetry: &mut Telemetry| {
+                if telemetry.enabled {
+                    telemetry.metrics.crash_free_sessions += 1;
+                    telemetry.metrics.rallies += rallies;
+                    telemetry.metrics.rally_hits += rally_hits;
+                }
+            });
+
+        Ok(())
+    }
+}
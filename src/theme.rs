@@ -0,0 +1,293 @@
+use crate::storage::write_atomically;
+use crate::utils::config_dir;
+use crossterm::style::Color;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// The colors the game is drawn with. `None` keeps the default color of the terminal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub text: Option<Color>,
+    pub border: Option<Color>,
+    pub background: Option<Color>,
+    pub paddle1: Option<Color>,
+    pub paddle2: Option<Color>,
+    pub ball: Option<Color>,
+    pub pattern: Pattern,
+    pub pattern_color: Option<Color>,
+}
+
+/// The fill drawn on the empty cells of the field, beneath the paddles and the ball.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Pattern {
+    /// Leaves the field empty.
+    #[default]
+    None,
+    /// A subtle grid of dots.
+    Dots,
+    /// Shading with dim block characters that fades from the goal lines towards the center.
+    Gradient,
+}
+
+impl Pattern {
+    /// Returns the character of the pattern at the given cell of a field with the given width.
+    pub fn cell(self, x: usize, y: usize, width: usize) -> char {
+        match self {
+            Pattern::None => ' ',
+            Pattern::Dots if x % 4 == 2 && y % 2 == 1 => '\u{00B7}',
+            Pattern::Dots => ' ',
+            Pattern::Gradient => {
+                let distance_to_goal_line = x.min(width.saturating_sub(x));
+                match distance_to_goal_line * 16 / (width + 1) {
+                    0 => '\u{2592}',
+                    1 => '\u{2591}',
+                    _ => ' ',
+                }
+            }
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::classic()
+    }
+}
+
+impl Theme {
+    /// The original look of the game using the default colors of the terminal.
+    pub fn classic() -> Self {
+        Theme {
+            name: "classic".to_string(),
+            text: None,
+            border: None,
+            background: None,
+            paddle1: None,
+            paddle2: None,
+            ball: None,
+            pattern: Pattern::None,
+            pattern_color: None,
+        }
+    }
+
+    /// Returns all themes that ship with the game.
+    pub fn built_in() -> Vec<Theme> {
+        vec![
+            Theme::classic(),
+            Theme {
+                name: "neon".to_string(),
+                text: Some(Color::Magenta),
+                border: Some(Color::Magenta),
+                background: Some(Color::Black),
+                paddle1: Some(Color::Cyan),
+                paddle2: Some(Color::Yellow),
+                ball: Some(Color::White),
+                pattern: Pattern::Dots,
+                pattern_color: Some(Color::DarkMagenta),
+            },
+            Theme {
+                name: "forest".to_string(),
+                text: Some(Color::Green),
+                border: Some(Color::DarkGreen),
+                background: None,
+                paddle1: Some(Color::Green),
+                paddle2: Some(Color::Green),
+                ball: Some(Color::Yellow),
+                pattern: Pattern::Gradient,
+                pattern_color: Some(Color::DarkGreen),
+            },
+            Theme {
+                name: "amber".to_string(),
+                text: Some(Color::DarkYellow),
+                border: Some(Color::DarkYellow),
+                background: Some(Color::Black),
+                paddle1: Some(Color::DarkYellow),
+                paddle2: Some(Color::DarkYellow),
+                ball: Some(Color::Yellow),
+                pattern: Pattern::None,
+                pattern_color: None,
+            },
+            Theme {
+                name: "ice".to_string(),
+                text: Some(Color::Cyan),
+                border: Some(Color::Blue),
+                background: None,
+                paddle1: Some(Color::White),
+                paddle2: Some(Color::White),
+                ball: Some(Color::Cyan),
+                pattern: Pattern::Dots,
+                pattern_color: Some(Color::DarkBlue),
+            },
+        ]
+    }
+
+    /// Reads a theme from a TOML file. Without a `name` entry the theme is named after the file.
+    ///
+    /// # Remarks
+    /// Colors are given by name (e.g. `"dark_green"`) or as hex code (e.g. `"#ff8800"`).
+    /// The background `pattern` is one of `"none"`, `"dots"` or `"gradient"`.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let file: ThemeFile =
+            toml::from_str(&content).map_err(|error| invalid_data(path, error))?;
+        let color = |color: Option<String>| -> io::Result<Option<Color>> {
+            color
+                .map(|color| {
+                    parse_color(&color)
+                        .ok_or_else(|| invalid_data(path, format!("unknown color `{color}`")))
+                })
+                .transpose()
+        };
+
+        Ok(Theme {
+            name: file.name.unwrap_or_else(|| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            }),
+            text: color(file.text)?,
+            border: color(file.border)?,
+            background: color(file.background)?,
+            paddle1: color(file.paddle1)?,
+            paddle2: color(file.paddle2)?,
+            ball: color(file.ball)?,
+            pattern: file.pattern.unwrap_or_default(),
+            pattern_color: color(file.pattern_color)?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    name: Option<String>,
+    text: Option<String>,
+    border: Option<String>,
+    background: Option<String>,
+    paddle1: Option<String>,
+    paddle2: Option<String>,
+    ball: Option<String>,
+    pattern: Option<Pattern>,
+    pattern_color: Option<String>,
+}
+
+fn parse_color(color: &str) -> Option<Color> {
+    match color.strip_prefix('#') {
+        Some(hex) if hex.len() == 6 => {
+            let channel = |index: usize| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok();
+            Some(Color::Rgb {
+                r: channel(0)?,
+                g: channel(2)?,
+                b: channel(4)?,
+            })
+        }
+        Some(_) => None,
+        None => Color::try_from(color).ok(),
+    }
+}
+
+fn invalid_data(path: &Path, error: impl ToString) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {}", path.display(), error.to_string()),
+    )
+}
+
+/// # Explanation
+/// The installed themes consist of the built-in themes and the TOML files in the `themes` directory
+/// of the configuration directory. The library can cycle through them and remembers the chosen theme
+/// across games.
+pub struct Themes {
+    themes: Vec<Theme>,
+    current: usize,
+}
+
+impl Themes {
+    /// Loads all installed themes and selects the theme chosen in the last game.
+    ///
+    /// # Returns
+    /// The themes and the errors of theme files that could not be loaded.
+    pub fn load_installed() -> (Self, Vec<io::Error>) {
+        let mut themes = Theme::built_in();
+        let mut errors = Vec::new();
+
+        if let Some(Ok(entries)) = themes_dir().map(fs::read_dir) {
+            let mut paths: Vec<_> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension == "toml")
+                })
+                .collect();
+            paths.sort();
+
+            for path in paths {
+                match Theme::load(&path) {
+                    Ok(theme) => themes.push(theme),
+                    Err(error) => errors.push(error),
+                }
+            }
+        }
+
+        let mut installed = Themes { themes, current: 0 };
+        if let Some(name) = choice_file().and_then(|path| fs::read_to_string(path).ok()) {
+            installed.select(name.trim());
+        }
+
+        (installed, errors)
+    }
+
+    /// Selects the theme with the given name.
+    ///
+    /// # Returns
+    /// `true` if a theme with that name is installed, otherwise `false`.
+    pub fn select(&mut self, name: &str) -> bool {
+        match self.themes.iter().position(|theme| theme.name == name) {
+            Some(index) => {
+                self.current = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the names of all installed themes.
+    pub fn names(&self) -> Vec<&str> {
+        self.themes
+            .iter()
+            .map(|theme| theme.name.as_str())
+            .collect()
+    }
+
+    /// Returns the selected theme.
+    pub fn current(&self) -> &Theme {
+        &self.themes[self.current]
+    }
+
+    /// Selects the next installed theme and returns it.
+    pub fn cycle(&mut self) -> &Theme {
+        self.current = (self.current + 1) % self.themes.len();
+        self.current()
+    }
+
+    /// Remembers the selected theme for the next games.
+    pub fn save_choice(&self) -> io::Result<()> {
+        let path = choice_file()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no configuration directory"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_atomically(&path, &self.current().name)
+    }
+}
+
+fn themes_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("themes"))
+}
+
+fn choice_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("theme"))
+}
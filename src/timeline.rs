@@ -0,0 +1,278 @@
+use crate::frame::{Frame, Layer};
+use crate::observer::GameObserver;
+use crate::state::{Edge, GameEvent, GameState};
+use crate::stats::PlayerSide;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::io;
+use std::ops::Range;
+use std::time::Duration;
+
+/// The rows above the list: the title, a free row and the column headers.
+const LIST_TOP: usize = 3;
+
+/// The number of characters of the bar of the longest point.
+const BAR_WIDTH: usize = 20;
+
+/// What happened in a single point of a match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointRecord {
+    /// The frames of the replay of the match that show the point, counted from the first update.
+    pub frames: Range<usize>,
+    /// The time the ball was in play, without the pauses and the waits before the serve.
+    pub duration: Duration,
+    /// The number of times the ball was returned.
+    pub hits: usize,
+    /// The speed of the ball in cells per second when it left the field.
+    pub final_speed: f64,
+    /// The edge of the player who scored the point.
+    pub scorer: Edge,
+    /// The scores of player1 and player2 after the point.
+    pub score: (usize, usize),
+}
+
+/// # Explanation
+/// The point timeline collects what happened in each point of a match. It is built from the
+/// updates of the game, one update after the other, and it counts the updates, so every point
+/// knows the frames of the replay of the match it was played in.
+///
+/// # Remarks
+/// A new game or a rematch starts an empty timeline, but the frames keep counting, as the replay
+/// continues. Points that were served again or rewound and points nobody scored in a four-player
+/// game are left out.
+#[derive(Debug, Clone, Default)]
+pub struct PointTimeline {
+    points: Vec<PointRecord>,
+    /// The number of recorded updates, which is the frame of the next update.
+    frame: usize,
+    /// The first frame of the current point.
+    start: usize,
+    elapsed: Duration,
+    hits: usize,
+    /// The speed of the ball after the previous update.
+    speed: f64,
+    /// The events of the running update, which are recorded once the update ends.
+    pending: Vec<GameEvent>,
+}
+
+impl PointTimeline {
+    /// Adds an update to the timeline.
+    ///
+    /// # Arguments
+    /// * `state` - The `GameState` after the update.
+    /// * `events` - The `GameEvent`s of the update.
+    /// * `dt` - The `Duration` the update advanced the game by.
+    pub fn record(&mut self, state: &GameState, events: &[GameEvent], dt: Duration) {
+        if !state.is_paused() {
+            self.elapsed += dt;
+        }
+
+        let mut point_over = false;
+        for &event in events {
+            match event {
+                GameEvent::NewGame => {
+                    self.points.clear();
+                    point_over = true;
+                }
+                GameEvent::Hit(_) | GameEvent::EdgeHit(_) => self.hits += 1,
+                GameEvent::Goal(side) => {
+                    self.finish_point(state, edge_of(side));
+                    point_over = true;
+                }
+                GameEvent::EdgeGoal(edge) => {
+                    self.finish_point(state, edge);
+                    point_over = true;
+                }
+                GameEvent::PointRestarted | GameEvent::Rewound | GameEvent::Conceded(_) => {
+                    point_over = true;
+                }
+                _ => {}
+            }
+        }
+
+        self.frame += 1;
+        if point_over {
+            self.start = self.frame;
+            self.elapsed = Duration::ZERO;
+            self.hits = 0;
+        }
+        self.speed = state.ball().get_velocity().speed();
+    }
+
+    fn finish_point(&mut self, state: &GameState, scorer: Edge) {
+        self.points.push(PointRecord {
+            frames: self.start..self.frame + 1,
+            duration: self.elapsed,
+            hits: self.hits,
+            // the ball is served again right after the goal, so it is the speed of the update before
+            final_speed: self.speed,
+            scorer,
+            score: state.scores(),
+        });
+    }
+
+    /// Returns the recorded points in the order they were played.
+    pub fn points(&self) -> &[PointRecord] {
+        &self.points
+    }
+
+    /// Returns `true` if no point was recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+impl GameObserver for PointTimeline {
+    fn on_event(&mut self, _state: &GameState, event: GameEvent) -> io::Result<()> {
+        self.pending.push(event);
+        Ok(())
+    }
+
+    fn on_tick(&mut self, state: &GameState, dt: Duration) -> io::Result<()> {
+        let events = std::mem::take(&mut self.pending);
+        self.record(state, &events, dt);
+        Ok(())
+    }
+}
+
+/// What the caller of the timeline view has to do after a key was handled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimelineAction {
+    /// Keep showing the timeline.
+    Stay,
+    /// Play the given frames of the replay of the match, then come back to the timeline.
+    Play(Range<usize>),
+    /// Close the timeline.
+    Quit,
+}
+
+/// # Explanation
+/// The timeline view is shown after a match. It lists the points of a `PointTimeline` with a bar
+/// of their duration, and it plays the part of the replay of the selected point. Like the replay
+/// browser, the view only handles keys and draws itself, the caller reads the terminal and plays
+/// the replay.
+pub struct TimelineView {
+    timeline: PointTimeline,
+    selected: usize,
+}
+
+impl TimelineView {
+    /// Constructs a view of the given timeline with the first point selected.
+    pub fn new(timeline: PointTimeline) -> Self {
+        TimelineView {
+            timeline,
+            selected: 0,
+        }
+    }
+
+    /// Returns the selected point, or `None` if the timeline is empty.
+    pub fn selected(&self) -> Option<&PointRecord> {
+        self.timeline.points.get(self.selected)
+    }
+
+    /// Handles a key press.
+    ///
+    /// # Returns
+    /// The `TimelineAction` the caller has to take.
+    ///
+    /// # Remarks
+    /// The arrow keys move to the previous or the next point, `home` and `end` to the first or the
+    /// last one, and `enter` plays the selected point. `q` and `esc` close the view.
+    pub fn handle_key(&mut self, key: KeyEvent) -> TimelineAction {
+        if key.kind == KeyEventKind::Release {
+            return TimelineAction::Stay;
+        }
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return TimelineAction::Quit;
+        }
+
+        let last = self.timeline.points.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return TimelineAction::Quit,
+            KeyCode::Up | KeyCode::Left | KeyCode::Char('k') | KeyCode::Char('h') => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Right | KeyCode::Char('j') | KeyCode::Char('l') => {
+                self.selected = (self.selected + 1).min(last);
+            }
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = last,
+            KeyCode::Enter | KeyCode::Char('p') => {
+                if let Some(point) = self.selected() {
+                    return TimelineAction::Play(point.frames.clone());
+                }
+            }
+            _ => {}
+        }
+
+        TimelineAction::Stay
+    }
+
+    /// Draws the view into a `Frame` of the given size: the title, the list of points with their
+    /// details and a last row with the keys.
+    pub fn compose_frame(&self, columns: usize, rows: usize) -> Frame {
+        let mut frame = Frame::new(columns, rows);
+        let points = &self.timeline.points;
+        let title = format!("Timeline of the match: {} points", points.len());
+        frame.print(Layer::Hud, 0, 0, &title, None);
+        frame.print(
+            Layer::Hud,
+            0,
+            LIST_TOP - 1,
+            &format!(
+                "{:>3}  {:<8} {:>7} {:>8} {:>5} {:>7}  {}",
+                "#", "Scorer", "Score", "Duration", "Hits", "Speed", "Length"
+            ),
+            None,
+        );
+
+        let longest = points
+            .iter()
+            .map(|point| point.duration)
+            .max()
+            .unwrap_or_default()
+            .max(Duration::from_millis(1));
+        let visible = rows.saturating_sub(LIST_TOP + 1).max(1);
+        let first = (self.selected + 1).saturating_sub(visible);
+        for (index, point) in points.iter().enumerate().skip(first).take(visible) {
+            let y = LIST_TOP + index - first;
+            let bar = (point.duration.as_secs_f64() / longest.as_secs_f64() * BAR_WIDTH as f64)
+                .ceil() as usize;
+            let text = format!(
+                "{:>3}  {:<8} {:>7} {:>7.1}s {:>5} {:>7.1}  {}",
+                index + 1,
+                point.scorer.player_name(),
+                format!("{}:{}", point.score.0, point.score.1),
+                point.duration.as_secs_f64(),
+                point.hits,
+                point.final_speed,
+                "#".repeat(bar)
+            );
+            frame.print(Layer::Hud, 0, y, &text, None);
+            if index == self.selected {
+                for x in 0..columns {
+                    if let Some(cell) = frame.cell_mut(Layer::Overlay, x, y) {
+                        cell.reverse = true;
+                    }
+                }
+            }
+        }
+
+        let last_row = rows.saturating_sub(1);
+        frame.print(
+            Layer::Hud,
+            0,
+            last_row,
+            "enter: play point  up/down: previous/next point  q: quit",
+            None,
+        );
+
+        frame
+    }
+}
+
+fn edge_of(side: PlayerSide) -> Edge {
+    match side {
+        PlayerSide::Left => Edge::Left,
+        PlayerSide::Right => Edge::Right,
+    }
+}
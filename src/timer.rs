@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+/// # Explanation
+/// The world clock counts the updates of the game and the simulated time they added up to.
+/// Everything that happens after some time, e.g. an effect that runs out, measures the time with
+/// the world clock or a `Timer` instead of the wall clock, so a replay or a rewind plays it back
+/// exactly like it happened, no matter how fast the frames were rendered.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct WorldClock {
+    tick: u64,
+    elapsed: Duration,
+}
+
+impl WorldClock {
+    /// Advances the clock by one update of the given duration.
+    pub fn advance(&mut self, dt: Duration) {
+        self.tick += 1;
+        self.elapsed += dt;
+    }
+
+    /// Returns the number of updates since the start of the game.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Returns the simulated time since the start of the game.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// # Explanation
+/// A timer runs down with the simulated time it is advanced by. A one-shot timer fires once and is
+/// finished afterwards, a repeating timer fires every period and never finishes. Time that is left
+/// over when a repeating timer fires counts towards its next period, so it does not drift.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Timer {
+    period: Duration,
+    remaining: Duration,
+    repeating: bool,
+}
+
+impl Timer {
+    /// Creates a timer that fires once after the given duration. A timer of zero duration is
+    /// finished right away.
+    pub fn once(duration: Duration) -> Self {
+        Timer {
+            period: duration,
+            remaining: duration,
+            repeating: false,
+        }
+    }
+
+    /// Creates a timer that fires every `period`. A period of zero is treated as one microsecond,
+    /// so the timer can not fire infinitely often.
+    pub fn repeating(period: Duration) -> Self {
+        let period = period.max(Duration::from_micros(1));
+
+        Timer {
+            period,
+            remaining: period,
+            repeating: true,
+        }
+    }
+
+    /// Advances the timer by the given simulated time.
+    ///
+    /// # Returns
+    /// How often the timer fired during this time, which is at most once for a one-shot timer.
+    pub fn advance(&mut self, dt: Duration) -> u32 {
+        if self.is_finished() {
+            return 0;
+        }
+
+        if dt < self.remaining {
+            self.remaining -= dt;
+            return 0;
+        }
+
+        if !self.repeating {
+            self.remaining = Duration::ZERO;
+            return 1;
+        }
+
+        let overshoot = (dt - self.remaining).as_nanos();
+        let period = self.period.as_nanos();
+        let fired = 1 + overshoot / period;
+        self.remaining = Duration::from_nanos((period - overshoot % period) as u64);
+
+        fired.min(u32::MAX as u128) as u32
+    }
+
+    /// Returns `true` if a one-shot timer already fired.
+    pub fn is_finished(&self) -> bool {
+        !self.repeating && self.remaining.is_zero()
+    }
+
+    /// Returns the time until the timer fires next.
+    pub fn remaining(&self) -> Duration {
+        self.remaining
+    }
+
+    /// Returns the time since the timer was started or fired last.
+    pub fn elapsed(&self) -> Duration {
+        self.period - self.remaining
+    }
+
+    /// Starts the current period again from the beginning.
+    pub fn reset(&mut self) {
+        self.remaining = self.period;
+    }
+}
@@ -0,0 +1,53 @@
+use crate::timer::Timer;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Defines how long a single message is shown.
+const TOAST_TIME: Duration = Duration::from_secs(2);
+
+/// Defines how many messages may wait to be shown. Older messages are dropped.
+const TOAST_QUEUE_LIMIT: usize = 5;
+
+/// # Explanation
+/// The toasts are short messages shown one after another for a moment, e.g. to confirm a key
+/// press. Messages that arrive while another message is shown wait in a queue.
+#[derive(Debug, Clone, Default)]
+pub struct Toasts {
+    queue: VecDeque<String>,
+    current: Option<(String, Timer)>,
+}
+
+impl Toasts {
+    /// Queues a message. It is shown right away if no other message is shown.
+    pub fn push(&mut self, message: impl Into<String>) {
+        if self.queue.len() == TOAST_QUEUE_LIMIT {
+            self.queue.pop_front();
+        }
+        self.queue.push_back(message.into());
+
+        if self.current.is_none() {
+            self.next();
+        }
+    }
+
+    /// Advances the time of the shown message and moves on to the next message once it expired.
+    pub fn update(&mut self, dt: Duration) {
+        if let Some((_, timer)) = &mut self.current {
+            if timer.advance(dt) > 0 {
+                self.next();
+            }
+        }
+    }
+
+    /// Returns the message that is currently shown.
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_ref().map(|(message, _)| message.as_str())
+    }
+
+    fn next(&mut self) {
+        self.current = self
+            .queue
+            .pop_front()
+            .map(|message| (message, Timer::once(TOAST_TIME)));
+    }
+}
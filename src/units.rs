@@ -0,0 +1,190 @@
+//! Typed units for the quantities of the simulation. Positions and distances are measured in
+//! `Cells`, speeds in `CellsPerSecond` and the sizes of paddles in whole cells with `Extent`.
+//! Converting between them takes an explicit method or operator, e.g. a speed multiplied by a
+//! `Duration` gives a distance, so mixing up units or rounding by accident does not compile.
+
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::time::Duration;
+
+/// A distance or coordinate on the field, measured in cells.
+#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd)]
+pub struct Cells(f64);
+
+impl Cells {
+    pub const ZERO: Cells = Cells(0.0);
+
+    pub fn new(cells: f64) -> Self {
+        Cells(cells)
+    }
+
+    /// Returns the exact length of a whole number of cells, e.g. of the field height.
+    pub fn from_count(count: usize) -> Self {
+        Cells(count as f64)
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+
+    /// Returns the index of the cell this coordinate lies in, rounding halves away from zero.
+    /// Coordinates outside of the range of `i64` saturate.
+    pub fn round_to_cell(self) -> i64 {
+        self.0.round() as i64
+    }
+
+    pub fn abs(self) -> Self {
+        Cells(self.0.abs())
+    }
+
+    pub fn min(self, other: Cells) -> Self {
+        Cells(self.0.min(other.0))
+    }
+
+    pub fn max(self, other: Cells) -> Self {
+        Cells(self.0.max(other.0))
+    }
+
+    /// Restricts the distance to the range from `min` to `max`.
+    pub fn clamp(self, min: Cells, max: Cells) -> Self {
+        Cells(self.0.clamp(min.0, max.0))
+    }
+}
+
+impl Add for Cells {
+    type Output = Cells;
+
+    fn add(self, other: Cells) -> Cells {
+        Cells(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Cells {
+    fn add_assign(&mut self, other: Cells) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Cells {
+    type Output = Cells;
+
+    fn sub(self, other: Cells) -> Cells {
+        Cells(self.0 - other.0)
+    }
+}
+
+impl SubAssign for Cells {
+    fn sub_assign(&mut self, other: Cells) {
+        self.0 -= other.0;
+    }
+}
+
+impl Neg for Cells {
+    type Output = Cells;
+
+    fn neg(self) -> Cells {
+        Cells(-self.0)
+    }
+}
+
+impl Mul<f64> for Cells {
+    type Output = Cells;
+
+    fn mul(self, factor: f64) -> Cells {
+        Cells(self.0 * factor)
+    }
+}
+
+impl Div<Duration> for Cells {
+    type Output = CellsPerSecond;
+
+    /// Returns the speed that covers the distance in the given time, or zero for no time at all.
+    fn div(self, time: Duration) -> CellsPerSecond {
+        match time.is_zero() {
+            true => CellsPerSecond::ZERO,
+            false => CellsPerSecond(self.0 / time.as_secs_f64()),
+        }
+    }
+}
+
+/// A speed along one axis of the field, measured in cells per second.
+#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd)]
+pub struct CellsPerSecond(f64);
+
+impl CellsPerSecond {
+    pub const ZERO: CellsPerSecond = CellsPerSecond(0.0);
+
+    pub fn new(cells_per_second: f64) -> Self {
+        CellsPerSecond(cells_per_second)
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+
+    pub fn abs(self) -> Self {
+        CellsPerSecond(self.0.abs())
+    }
+}
+
+impl Mul<Duration> for CellsPerSecond {
+    type Output = Cells;
+
+    fn mul(self, time: Duration) -> Cells {
+        Cells(self.0 * time.as_secs_f64())
+    }
+}
+
+impl Mul<f64> for CellsPerSecond {
+    type Output = CellsPerSecond;
+
+    fn mul(self, factor: f64) -> CellsPerSecond {
+        CellsPerSecond(self.0 * factor)
+    }
+}
+
+impl MulAssign<f64> for CellsPerSecond {
+    fn mul_assign(&mut self, factor: f64) {
+        self.0 *= factor;
+    }
+}
+
+impl Neg for CellsPerSecond {
+    type Output = CellsPerSecond;
+
+    fn neg(self) -> CellsPerSecond {
+        CellsPerSecond(-self.0)
+    }
+}
+
+/// How many whole cells something reaches beyond its center cell, e.g. a paddle upwards.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Extent(usize);
+
+impl Extent {
+    pub fn new(cells: usize) -> Self {
+        Extent(cells)
+    }
+
+    pub fn get(self) -> usize {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the extent reduced by the given number of cells, but not below zero.
+    pub fn shrunk_by(self, cells: usize) -> Self {
+        Extent(self.0.saturating_sub(cells))
+    }
+
+    /// Returns the extent as a distance on the field.
+    pub fn to_cells(self) -> Cells {
+        Cells::from_count(self.0)
+    }
+
+    /// Returns the extent as an offset between cell indices, saturating at `i64::MAX`.
+    pub fn to_offset(self) -> i64 {
+        i64::try_from(self.0).unwrap_or(i64::MAX)
+    }
+}
@@ -1,4 +1,5 @@
-use std::time::{Duration, Instant};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// # Explanation
 /// The game loop is an iterator that waits when the next function is called if the execution is faster
@@ -45,4 +46,56 @@ impl Iterator for GameLoop {
         Some(frame_number)
     }
 }
+/// Returns the directory the user configuration of the game is stored in.
+/// This is `$XDG_CONFIG_HOME/cli_pong` or `~/.config/cli_pong` as a fallback.
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("cli_pong"))
+}
+
+/// Returns the directory the data the game collects while it is played is stored in, e.g. the
+/// statistics. This is `$XDG_DATA_HOME/cli_pong` or `~/.local/share/cli_pong` as a fallback.
+pub fn data_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })
+        .map(|dir| dir.join("cli_pong"))
+}
 
+/// Formats a point in time as a date and a time of day in UTC, e.g. `2024-03-09 17:05`.
+/// Times before 1970 are shown as `1970-01-01 00:00`.
+pub fn format_utc(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_secs());
+    let days = (seconds / 86_400) as i64;
+    let minutes_of_day = seconds % 86_400 / 60;
+
+    // converts the days since 1970-01-01 into a date of the proleptic Gregorian calendar
+    let shifted = days + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        minutes_of_day / 60,
+        minutes_of_day % 60
+    )
+}
@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Defines over how many of the latest frames the render times are judged.
+const WATCHED_FRAMES: usize = 30;
+
+/// Defines which share of the watched frames has to exceed the budget before the next visual
+/// feature is turned off. A few slow frames, e.g. while the terminal is resized, are tolerated.
+const SLOW_FRAME_SHARE: f64 = 0.8;
+
+/// The visual features that are turned off one after the other while rendering is too slow, from
+/// the first to the last.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Degradation {
+    /// The glow around the ball and the scanlines of the CRT effect.
+    Effects,
+    /// The background pattern of the theme.
+    Pattern,
+    /// All colors of the theme.
+    Colors,
+    /// Only the cells that changed since the previous frame are written to the terminal.
+    PartialRedraw,
+}
+
+impl Degradation {
+    /// All degradations in the order they are applied.
+    pub const ORDER: [Degradation; 4] = [
+        Degradation::Effects,
+        Degradation::Pattern,
+        Degradation::Colors,
+        Degradation::PartialRedraw,
+    ];
+
+    /// Returns a short message that tells the players what was turned off.
+    pub fn message(self) -> &'static str {
+        match self {
+            Degradation::Effects => "Slow terminal: effects turned off",
+            Degradation::Pattern => "Slow terminal: background pattern turned off",
+            Degradation::Colors => "Slow terminal: colors turned off",
+            Degradation::PartialRedraw => "Slow terminal: only changes are redrawn",
+        }
+    }
+
+    /// Returns the degradation that follows this one, or `None` for the last one.
+    fn next(self) -> Option<Degradation> {
+        let index = Self::ORDER.iter().position(|&other| other == self)?;
+        Self::ORDER.get(index + 1).copied()
+    }
+}
+
+/// # Explanation
+/// The frame watchdog measures how long each frame takes to render. Once most of the latest frames
+/// took longer than the budget, it turns off the next visual feature, see `Degradation`. The
+/// simulation is not affected, so the game keeps its speed while it looks simpler.
+#[derive(Debug, Clone)]
+pub struct FrameWatchdog {
+    budget: Duration,
+    slow_frames: VecDeque<bool>,
+    level: Option<Degradation>,
+}
+
+impl FrameWatchdog {
+    /// Constructs a new `FrameWatchdog`.
+    ///
+    /// # Arguments
+    /// * `budget` - The time a frame may take to render.
+    pub fn new(budget: Duration) -> Self {
+        FrameWatchdog {
+            budget,
+            slow_frames: VecDeque::with_capacity(WATCHED_FRAMES),
+            level: None,
+        }
+    }
+
+    /// Registers the time the latest frame took to render.
+    ///
+    /// # Returns
+    /// The `Degradation` that has to be applied from now on, or `None` if nothing changes.
+    pub fn frame_rendered(&mut self, render_time: Duration) -> Option<Degradation> {
+        if self.slow_frames.len() == WATCHED_FRAMES {
+            self.slow_frames.pop_front();
+        }
+        self.slow_frames.push_back(render_time > self.budget);
+
+        let slow = self.slow_frames.iter().filter(|&&slow| slow).count();
+        let consistently_slow = self.slow_frames.len() == WATCHED_FRAMES
+            && slow as f64 >= SLOW_FRAME_SHARE * WATCHED_FRAMES as f64;
+        if !consistently_slow {
+            return None;
+        }
+
+        let next = match self.level {
+            Some(level) => level.next()?,
+            None => Degradation::ORDER[0],
+        };
+        self.level = Some(next);
+        // the next feature is only turned off if the frames stay slow without this one
+        self.slow_frames.clear();
+
+        Some(next)
+    }
+
+    /// Returns the last degradation that was applied, or `None` if every feature is still on.
+    pub fn level(&self) -> Option<Degradation> {
+        self.level
+    }
+}
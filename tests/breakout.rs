@@ -0,0 +1,120 @@
+mod common;
+
+use cli_pong::ai::AiStyle;
+use cli_pong::mode::{Breakout, BREAKOUT_LIVES};
+use cli_pong::state::{DiscretePosition2D, GameConfig, GameEvent, GameState};
+use cli_pong::stats::PlayerSide;
+use common::{place_ball, play_until_over, press};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+const BRICK: char = '\u{2593}';
+
+fn game(config: GameConfig) -> GameState {
+    GameState::with_mode(config, Box::new(Breakout::default()))
+}
+
+fn bricks(state: &GameState) -> &[DiscretePosition2D] {
+    state.mode_as::<Breakout>().unwrap().bricks()
+}
+
+#[test]
+fn bricks_fill_the_columns_in_front_of_the_right_wall() {
+    let state = game(GameConfig::builder().seed(Some(1)).build());
+    let config = state.config();
+    let bricks = bricks(&state);
+
+    assert_eq!(bricks.len(), 3 * (config.height + 1));
+    let columns = config.width as i64 - 4..=config.width as i64 - 2;
+    assert!(bricks.iter().all(|brick| columns.contains(&brick.x())));
+    assert!(GameState::new(GameConfig::default()).mode_as::<Breakout>().is_none());
+
+    let grid = state.render_to_grid();
+    let drawn: usize = grid
+        .iter()
+        .map(|row| row.iter().filter(|&&cell| cell == BRICK).count())
+        .sum();
+    assert_eq!(drawn, bricks.len());
+}
+
+#[test]
+fn ball_breaks_a_brick_and_bounces_back() {
+    let mut state = game(GameConfig::builder().seed(Some(2)).build());
+    let count = bricks(&state).len();
+    place_ball(&mut state, (50.0, 9.0), (15.0, 0.0));
+
+    let mut events = Vec::new();
+    for _ in 0..10 {
+        events.extend(state.update(HashMap::new(), DT));
+    }
+
+    assert_eq!(
+        events
+            .iter()
+            .filter(|&&event| event == GameEvent::BrickBroken)
+            .count(),
+        1
+    );
+    assert_eq!(bricks(&state).len(), count - 1);
+    assert!(!bricks(&state).contains(&DiscretePosition2D::new(56, 9)));
+    assert!(state.ball().get_velocity().vx() < 0.0);
+}
+
+#[test]
+fn fast_balls_break_the_first_brick_in_their_way() {
+    let mut state = game(GameConfig::builder().seed(Some(3)).build());
+    place_ball(&mut state, (54.0, 4.0), (50.0, 0.0));
+
+    let events = state.update(HashMap::new(), Duration::from_millis(100));
+
+    assert!(events.contains(&GameEvent::BrickBroken));
+    assert!(!bricks(&state).contains(&DiscretePosition2D::new(56, 4)));
+    assert!(bricks(&state).contains(&DiscretePosition2D::new(57, 4)));
+    assert!(state.ball().get_position().x() < 56.0);
+}
+
+#[test]
+fn breaking_all_bricks_wins_the_game() {
+    let config = GameConfig::builder()
+        .seed(Some(4))
+        .width(16)
+        .height(4)
+        .player1_ai(Some(AiStyle::Balanced.profile()))
+        .build();
+    let mut state = game(config);
+
+    let events = play_until_over(&mut state);
+
+    assert!(bricks(&state).is_empty());
+    assert_eq!(state.winner(), Some(PlayerSide::Left));
+    assert!(events.contains(&GameEvent::Won(PlayerSide::Left)));
+    assert!(state.scores().1 < BREAKOUT_LIVES);
+}
+
+#[test]
+fn each_lost_ball_costs_a_life() {
+    let mut state = game(GameConfig::builder().seed(Some(5)).build());
+    let hud = |state: &GameState| -> String { state.render_to_grid()[1].iter().collect() };
+    assert!(hud(&state).starts_with("Bricks: 57,  Lives: 3"), "{}", hud(&state));
+
+    // player1 does not move, so the balls get past its paddle sooner or later
+    let events = play_until_over(&mut state);
+
+    assert_eq!(state.scores().1, BREAKOUT_LIVES);
+    assert_eq!(state.winner(), Some(PlayerSide::Right));
+    assert!(events.contains(&GameEvent::Won(PlayerSide::Right)));
+    assert!(hud(&state).contains("Lives: 0"), "{}", hud(&state));
+}
+
+#[test]
+fn new_game_after_a_resize_fills_the_resized_field() {
+    let mut state = game(GameConfig::builder().seed(Some(1)).build());
+    state.resize_field(40, 12);
+    state.update(press(KeyCode::Char('r'), KeyModifiers::CONTROL), DT);
+
+    let bricks = bricks(&state);
+    assert_eq!(bricks.len(), 3 * 13);
+    assert!(bricks.iter().all(|brick| (36..=38).contains(&brick.x())));
+}
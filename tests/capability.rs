@@ -0,0 +1,90 @@
+use cli_pong::capability::{Capabilities, ColorDepth};
+use cli_pong::state::{GameConfig, GameState};
+use crossterm::style::Color;
+use std::collections::HashMap;
+
+fn from_env(vars: &[(&str, &str)]) -> Capabilities {
+    let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+    Capabilities::from_env(|name| vars.get(name).map(|value| value.to_string()))
+}
+
+fn capabilities(color_depth: ColorDepth, unicode: bool) -> Capabilities {
+    Capabilities {
+        color_depth,
+        unicode,
+        ..Capabilities::default()
+    }
+}
+
+#[test]
+fn color_depth_is_read_from_the_environment() {
+    let truecolor = from_env(&[("TERM", "xterm-256color"), ("COLORTERM", "truecolor")]);
+    assert_eq!(truecolor.color_depth, ColorDepth::TrueColor);
+
+    let xterm = from_env(&[("TERM", "xterm-256color")]);
+    assert_eq!(xterm.color_depth, ColorDepth::Ansi256);
+
+    let console = from_env(&[("TERM", "linux")]);
+    assert_eq!(console.color_depth, ColorDepth::Ansi16);
+
+    let no_color = from_env(&[("TERM", "xterm-256color"), ("NO_COLOR", "1")]);
+    assert_eq!(no_color.color_depth, ColorDepth::Monochrome);
+}
+
+#[test]
+fn unicode_follows_the_locale() {
+    assert!(from_env(&[("LANG", "en_US.UTF-8")]).unicode);
+    assert!(from_env(&[("LC_ALL", "de_DE.utf8"), ("LANG", "C")]).unicode);
+    assert!(!from_env(&[("LC_ALL", "C"), ("LANG", "en_US.UTF-8")]).unicode);
+    assert!(!from_env(&[]).unicode);
+}
+
+#[test]
+fn colors_are_mapped_to_the_closest_available_color() {
+    let orange = Some(Color::Rgb {
+        r: 255,
+        g: 135,
+        b: 0,
+    });
+
+    assert_eq!(
+        capabilities(ColorDepth::TrueColor, true).color(orange),
+        orange
+    );
+    assert_eq!(
+        capabilities(ColorDepth::Ansi256, true).color(orange),
+        Some(Color::AnsiValue(208))
+    );
+    assert_eq!(
+        capabilities(ColorDepth::Ansi16, true).color(Some(Color::Rgb { r: 250, g: 5, b: 0 })),
+        Some(Color::Red)
+    );
+    assert_eq!(
+        capabilities(ColorDepth::Ansi16, true).color(Some(Color::AnsiValue(244))),
+        Some(Color::DarkGrey)
+    );
+    assert_eq!(
+        capabilities(ColorDepth::Monochrome, true).color(orange),
+        None
+    );
+    assert_eq!(capabilities(ColorDepth::Ansi16, true).color(None), None);
+}
+
+#[test]
+fn frames_without_unicode_are_plain_ascii() {
+    let state = GameState::new(GameConfig::builder().seed(Some(1)).build());
+    let mut unicode = Vec::new();
+    let mut ascii = Vec::new();
+
+    let frame = state.compose_frame();
+    frame
+        .flush_adapted(&mut unicode, &capabilities(ColorDepth::Monochrome, true))
+        .unwrap();
+    frame
+        .flush_adapted(&mut ascii, &capabilities(ColorDepth::Monochrome, false))
+        .unwrap();
+
+    assert!(!unicode.is_ascii());
+    assert!(ascii.is_ascii());
+    assert!(String::from_utf8(ascii).unwrap().contains('#'));
+}
@@ -0,0 +1,109 @@
+use cli_pong::ai::AiStyle;
+use cli_pong::caster::{Caster, GOAL_REPLAY_TIME, SLOW_MOTION};
+use cli_pong::state::{step, GameConfig, GameEvent, GameState};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+/// The number of updates after which a game without a goal counts as stuck.
+const MAX_UPDATES: usize = 10_000;
+
+fn caster() -> Caster {
+    Caster::new(["Ada".to_string(), "Grace".to_string()])
+}
+
+fn ai_game() -> GameState {
+    GameState::new(
+        GameConfig::builder()
+            .seed(Some(3))
+            .player1_ai(Some(AiStyle::Balanced.profile()))
+            .player2_ai(Some(AiStyle::Aggressive.profile()))
+            .build(),
+    )
+}
+
+/// Advances the game by one update and lets the caster follow it.
+fn advance(state: &mut GameState, caster: &mut Caster) -> Vec<GameEvent> {
+    let (next, events) = step(state, HashMap::new(), DT);
+    *state = next;
+    caster.update(state, &events, DT);
+    events
+}
+
+/// Plays until the first goal.
+fn play_until_goal(state: &mut GameState, caster: &mut Caster) {
+    for _ in 0..MAX_UPDATES {
+        let events = advance(state, caster);
+        if events
+            .iter()
+            .any(|event| matches!(event, GameEvent::Goal(_)))
+        {
+            return;
+        }
+        assert!(!caster.is_replaying());
+    }
+
+    panic!("no goal after {MAX_UPDATES} updates");
+}
+
+fn text(caster: &Caster, state: &GameState) -> Vec<String> {
+    caster
+        .compose(state)
+        .chars()
+        .into_iter()
+        .map(|row| row.into_iter().collect())
+        .collect()
+}
+
+#[test]
+fn a_goal_is_replayed_in_slow_motion() {
+    let mut state = ai_game();
+    let mut caster = caster();
+
+    play_until_goal(&mut state, &mut caster);
+    assert!(caster.is_replaying());
+
+    // the replay shows the seconds before the goal at half the speed of the game
+    let replay_updates = (GOAL_REPLAY_TIME.as_secs_f64() / SLOW_MOTION / DT.as_secs_f64()) as usize;
+    for _ in 0..replay_updates - 2 {
+        advance(&mut state, &mut caster);
+        assert!(caster.is_replaying());
+    }
+    for _ in 0..4 {
+        advance(&mut state, &mut caster);
+    }
+    assert!(!caster.is_replaying());
+}
+
+#[test]
+fn the_header_shows_the_score_and_the_names() {
+    let mut state = ai_game();
+    let mut caster = caster();
+
+    let lines = text(&caster, &state);
+    assert!(lines[3].contains("Ada") && lines[3].contains("Grace"));
+    // the big zeros of both players
+    assert_eq!(lines[1].matches("███").count(), 2);
+    assert!(lines.iter().any(|line| line.contains("Rally: 0")));
+
+    play_until_goal(&mut state, &mut caster);
+    let (player1, _) = state.scores();
+    let scorer = if player1 == 1 { "Ada" } else { "Grace" };
+    let lines = text(&caster, &state);
+    assert!(lines
+        .iter()
+        .any(|line| line.contains(&format!("REPLAY  goal for {scorer}"))));
+}
+
+#[test]
+fn the_court_is_shown_below_the_header() {
+    let state = ai_game();
+    let caster = caster();
+
+    let court = state.compose_court().chars();
+    let view = caster.compose(&state).chars();
+
+    assert_eq!(view.len(), court.len() + 8);
+    assert_eq!(&view[8..], &court[..]);
+}
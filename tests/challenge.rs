@@ -0,0 +1,240 @@
+mod common;
+
+use cli_pong::ai::AiStyle;
+use cli_pong::challenge::{BallStart, Challenge, ChallengeGoal, ChallengeOutcome, Scenario};
+use cli_pong::state::{GameConfig, GameEvent, GameState};
+use cli_pong::stats::PlayerSide;
+use common::{game_with_mode, play_until_over, press, temp_dir, DT};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+
+fn scenario(goal: ChallengeGoal) -> Scenario {
+    Scenario {
+        name: "test".to_string(),
+        description: String::new(),
+        opponent: AiStyle::Rookie,
+        player1_score: 0,
+        player2_score: 0,
+        player1_extend: None,
+        player2_extend: None,
+        ball: None,
+        time_limit: None,
+        goal,
+    }
+}
+
+fn game(scenario: Scenario, seed: u64) -> GameState {
+    game_with_mode(seed, Challenge::new(scenario))
+}
+
+#[test]
+fn scenarios_are_read_from_toml() {
+    let path = temp_dir("scenarios_are_read_from_toml").join("challenge.toml");
+    fs::write(
+        &path,
+        "name = \"Comeback\"\nplayer2_score = 9\ntime_limit = 300\n\n\
+         [goal]\ntype = \"win\"\ngoals = 10\n",
+    )
+    .unwrap();
+
+    let comeback = Scenario::load(&path).unwrap();
+
+    assert_eq!(comeback.name, "Comeback");
+    assert_eq!(comeback.opponent, AiStyle::Balanced);
+    assert_eq!((comeback.player1_score, comeback.player2_score), (0, 9));
+    assert_eq!(comeback.goal, ChallengeGoal::Win { goals: 10 });
+    assert_eq!(
+        comeback.goal_description(),
+        "reach 10 goals first within 300s"
+    );
+    fs::write(
+        &path,
+        "name = \"Smashes\"\n[goal]\ntype = \"returns\"\ncount = 10\nmin_speed = 30.0\n",
+    )
+    .unwrap();
+    assert_eq!(
+        Scenario::load(&path).unwrap().goal,
+        ChallengeGoal::Returns {
+            count: 10,
+            min_speed: 30.0,
+            allowed_misses: 0
+        }
+    );
+    // the opponent would already have won
+    fs::write(
+        &path,
+        "name = \"Lost\"\nplayer2_score = 9\n[goal]\ntype = \"win\"\ngoals = 9\n",
+    )
+    .unwrap();
+    assert!(Scenario::load(&path).is_err());
+    fs::write(&path, "name = \"Vague\"\n[goal]\ntype = \"enjoy\"\n").unwrap();
+    assert!(Scenario::load(&path).is_err());
+}
+
+#[test]
+fn games_start_as_the_scenario_describes() {
+    let state = game(
+        Scenario {
+            player2_score: 9,
+            player1_extend: Some(3),
+            ball: Some(BallStart {
+                x: 40.0,
+                y: 5.0,
+                vx: -20.0,
+                vy: 4.0,
+            }),
+            ..scenario(ChallengeGoal::Win { goals: 10 })
+        },
+        1,
+    );
+    let ball = state.ball();
+
+    assert_eq!(state.scores(), (0, 9));
+    assert_eq!(state.config().paddle_extents(PlayerSide::Left), (3, 3));
+    assert_eq!(state.config().score_limit, None);
+    assert_eq!(
+        (ball.get_position().x(), ball.get_position().y()),
+        (40.0, 5.0)
+    );
+    assert_eq!(
+        (ball.get_velocity().vx(), ball.get_velocity().vy()),
+        (-20.0, 4.0)
+    );
+    assert!(state.render_to_grid()[2]
+        .iter()
+        .collect::<String>()
+        .starts_with("First to 10"));
+}
+
+#[test]
+fn goals_against_player1_fail_the_challenge() {
+    let challenge = scenario(ChallengeGoal::Survive { seconds: 600 });
+    let mut state = game(challenge.clone(), 2);
+
+    // player1 does not move, so a ball gets past its paddle sooner or later
+    let events = play_until_over(&mut state);
+
+    assert_eq!(state.winner(), Some(PlayerSide::Right));
+    assert!(events.contains(&GameEvent::Won(PlayerSide::Right)));
+    assert_eq!(
+        Challenge::new(challenge).outcome(&state),
+        Some(ChallengeOutcome::Failure("a ball got past you".to_string()))
+    );
+    assert_eq!(
+        state.mode().summary(&state),
+        vec![
+            "Challenge `test`: survive 600s",
+            "Failed: a ball got past you"
+        ]
+    );
+    assert_eq!(
+        state.mode().status(&state).as_deref(),
+        Some("Failed: a ball got past you")
+    );
+}
+
+#[test]
+fn surviving_long_enough_completes_the_challenge() {
+    let mut state = GameState::with_mode(
+        GameConfig::builder()
+            .seed(Some(3))
+            .player1_ai(Some(AiStyle::Balanced.profile()))
+            .build(),
+        Box::new(Challenge::new(Scenario {
+            opponent: AiStyle::Balanced,
+            ..scenario(ChallengeGoal::Survive { seconds: 2 })
+        })),
+    );
+
+    play_until_over(&mut state);
+
+    assert_eq!(state.winner(), Some(PlayerSide::Left));
+    assert_eq!(
+        state.mode().status(&state).as_deref(),
+        Some("Challenge complete!")
+    );
+    assert!(state.mode().summary(&state)[1].starts_with("Complete after 2."));
+}
+
+#[test]
+fn time_limits_end_open_challenges() {
+    let mut state = GameState::with_mode(
+        GameConfig::builder()
+            .seed(Some(4))
+            .player1_ai(Some(AiStyle::Balanced.profile()))
+            .build(),
+        Box::new(Challenge::new(Scenario {
+            time_limit: Some(1),
+            ..scenario(ChallengeGoal::Win { goals: 10 })
+        })),
+    );
+    assert_eq!(
+        state.mode().status(&state).as_deref(),
+        Some("First to 10,  time left: 1s")
+    );
+
+    play_until_over(&mut state);
+
+    assert_eq!(state.winner(), Some(PlayerSide::Right));
+    assert_eq!(
+        state.mode().status(&state).as_deref(),
+        Some("Failed: time is up")
+    );
+}
+
+#[test]
+fn only_fast_enough_returns_count() {
+    let config = GameConfig::builder()
+        .seed(Some(5))
+        .player1_ai(Some(AiStyle::Balanced.profile()))
+        .build();
+    let returns = |min_speed| ChallengeGoal::Returns {
+        count: 3,
+        min_speed,
+        allowed_misses: 0,
+    };
+    let mut slow = GameState::with_mode(
+        config.clone(),
+        Box::new(Challenge::new(scenario(returns(0.0)))),
+    );
+    let mut fast = GameState::with_mode(
+        config,
+        Box::new(Challenge::new(Scenario {
+            time_limit: Some(20),
+            ..scenario(returns(1000.0))
+        })),
+    );
+
+    play_until_over(&mut slow);
+    play_until_over(&mut fast);
+
+    assert_eq!(slow.winner(), Some(PlayerSide::Left));
+    assert_eq!(slow.stats().returns(PlayerSide::Left), 3);
+    assert_eq!(fast.winner(), Some(PlayerSide::Right));
+    assert!(fast.stats().returns(PlayerSide::Left) > 0);
+    assert!(fast.mode().status(&fast).unwrap().starts_with("Failed: "));
+}
+
+#[test]
+fn rematches_restart_the_challenge() {
+    let mut state = game(
+        Scenario {
+            player2_score: 2,
+            ..scenario(ChallengeGoal::Returns {
+                count: 5,
+                min_speed: 0.0,
+                allowed_misses: 0,
+            })
+        },
+        6,
+    );
+    play_until_over(&mut state);
+    assert_eq!(state.scores().1, 3);
+
+    state.update(press(KeyCode::Char('r'), KeyModifiers::CONTROL), DT);
+
+    assert!(!state.is_over());
+    assert_eq!(state.scores(), (0, 2));
+    assert_eq!(state.mode().status(&state).as_deref(), Some("Returns: 0/5"));
+    assert_eq!(state.mode().summary(&state)[1], "Not finished");
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use cli_pong::chaos::{ChaosEvent, ChaosScheduler, SPEED_BURST_FACTOR};
+use cli_pong::mutator::{Mutator, Mutators};
+use cli_pong::state::{step, GameConfig, GameEvent, GameState};
+use common::{ball_speed, press};
+use crossterm::event::{KeyCode, KeyModifiers};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+const BALL: char = '\u{25CF}';
+const PADDLE: char = '\u{2588}';
+
+fn chaos_game(seed: u64) -> GameState {
+    GameState::new(
+        GameConfig::builder()
+            .seed(Some(seed))
+            .mutators(Mutators::new([Mutator::Chaos]))
+            .chaos_interval(Duration::from_secs(1))
+            .build(),
+    )
+}
+
+/// Plays games with different seeds until the given chaos event fires, and returns the game right
+/// before and right after the update it fired in.
+fn before_and_after(event: ChaosEvent) -> (GameState, GameState) {
+    for seed in 0..100 {
+        let mut state = chaos_game(seed);
+        for _ in 0..100 {
+            let (next, events) = step(&state, HashMap::new(), DT);
+            if events.contains(&GameEvent::Chaos(event)) {
+                // hits and goals of the same update would hide the effect of the event
+                if events.len() == 1 {
+                    return (state, next);
+                }
+                break;
+            }
+            state = next;
+        }
+    }
+
+    panic!("{event:?} never fired on its own");
+}
+
+/// Counts the cells of the field that show the given glyph, without the rows of the walls.
+fn count_cells(state: &GameState, glyph: char) -> usize {
+    state
+        .render_to_grid()
+        .iter()
+        .map(|row| row.iter().filter(|&&cell| cell == glyph).count())
+        .filter(|&count| count <= 2)
+        .sum()
+}
+
+#[test]
+fn scheduler_fires_one_allowed_event_per_interval() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut scheduler = ChaosScheduler::new(Duration::from_secs(8));
+    let only_teleports = [ChaosEvent::Teleport];
+
+    assert_eq!(
+        scheduler.advance(Duration::from_secs(7), &ChaosEvent::ALL, &mut rng),
+        None
+    );
+    assert_eq!(scheduler.time_to_next_event(), Duration::from_secs(1));
+    assert_eq!(
+        scheduler.advance(Duration::from_secs(1), &only_teleports, &mut rng),
+        Some(ChaosEvent::Teleport)
+    );
+    assert_eq!(
+        scheduler.advance(Duration::from_secs(8), &[], &mut rng),
+        None
+    );
+    assert!(scheduler
+        .advance(Duration::from_secs(8), &ChaosEvent::ALL, &mut rng)
+        .is_some());
+    assert!(Mutators::default()
+        .chaos_scheduler(Duration::from_secs(8))
+        .is_none());
+}
+
+#[test]
+fn events_are_announced_on_the_screen() {
+    let mut state = chaos_game(1);
+    let mut events = Vec::new();
+    for _ in 0..20 {
+        events.extend(state.update(HashMap::new(), DT));
+    }
+
+    let fired: Vec<ChaosEvent> = events
+        .iter()
+        .filter_map(|event| match event {
+            GameEvent::Chaos(chaos) => Some(*chaos),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(fired.len(), 1);
+    let screen: String = state.render_to_grid().into_iter().flatten().collect();
+    assert!(screen.contains(fired[0].notice()), "{screen}");
+    assert!(GameState::new(GameConfig::default()).chaos().is_none());
+}
+
+#[test]
+fn speed_bursts_speed_the_ball_up() {
+    let (before, after) = before_and_after(ChaosEvent::SpeedBurst);
+
+    // the ball also speeds up a little during the update itself
+    let burst = ball_speed(&after) / ball_speed(&before);
+    assert!((burst - SPEED_BURST_FACTOR).abs() < 0.01, "{burst}");
+}
+
+#[test]
+fn teleports_move_the_ball_into_the_middle() {
+    let (_, after) = before_and_after(ChaosEvent::Teleport);
+    let width = after.config().width as f64;
+    let x = after.ball().get_position().x();
+
+    assert!((width / 3.0..=2.0 * width / 3.0).contains(&x), "{x}");
+}
+
+#[test]
+fn shrunk_paddles_grow_back_with_the_next_serve() {
+    let (before, mut after) = before_and_after(ChaosEvent::PaddleShrink);
+    let paddles = count_cells(&before, PADDLE);
+
+    assert_eq!(count_cells(&after, PADDLE), paddles - 2);
+
+    after.update(press(KeyCode::Char('r'), KeyModifiers::NONE), DT);
+    assert_eq!(count_cells(&after, PADDLE), paddles);
+}
+
+#[test]
+fn second_balls_score_until_the_next_serve() {
+    let (before, mut state) = before_and_after(ChaosEvent::SecondBall);
+    assert_eq!(before.balls().len(), 1);
+    assert_eq!(state.balls().len(), 2);
+    assert_eq!(count_cells(&state, BALL), 2);
+
+    let scores = state.scores();
+    while state.scores() == scores {
+        state.update(HashMap::new(), DT);
+    }
+
+    let (player1, player2) = state.scores();
+    assert_eq!(player1 + player2, scores.0 + scores.1 + 1);
+    assert_eq!(state.balls().len(), 1);
+    assert_eq!(count_cells(&state, BALL), 1);
+}
+
+#[test]
+fn new_games_start_without_chaos() {
+    let (_, mut state) = before_and_after(ChaosEvent::SecondBall);
+
+    state.update(press(KeyCode::Char('r'), KeyModifiers::CONTROL), DT);
+
+    assert_eq!(state.balls().len(), 1);
+    assert_eq!(
+        state.chaos().unwrap().time_to_next_event(),
+        Duration::from_secs(1)
+    );
+    assert!(state
+        .replay_options()
+        .contains(&("chaos-interval".to_string(), "1".to_string())));
+}
@@ -0,0 +1,378 @@
+mod common;
+
+use cli_pong::collision::{broadphase, crossing_point, safe_spawn_point, Aabb};
+use cli_pong::state::{
+    goal_scored, Arena, Ball, Controller, DiscretePosition2D, GameConfig, PaddleCommand, Player,
+    Position2D, Velocity2D,
+};
+use cli_pong::stats::PlayerSide;
+use common::still_player;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(100);
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(0)
+}
+
+fn players(player1_y: f64) -> (Player, Player) {
+    (still_player(0.0, player1_y), still_player(60.0, 9.0))
+}
+
+fn ball(x: f64, y: f64, vx: f64, vy: f64) -> Ball {
+    Ball::with_velocity(Position2D::new(x, y), Velocity2D::new(vx, vy))
+}
+
+#[test]
+fn ball_is_mirrored_at_the_bottom_wall() {
+    let config = GameConfig::default();
+    let (player1, player2) = players(9.0);
+    let mut ball = ball(30.0, 0.2, 10.0, -5.0);
+
+    ball.update_position(&config, &player1, &player2, &mut rng(), DT);
+
+    assert!((ball.get_position().y() - 0.3).abs() < 1e-9);
+    assert!(ball.get_velocity().vy() > 0.0);
+}
+
+#[test]
+fn ball_is_mirrored_at_the_top_wall() {
+    let config = GameConfig::default();
+    let (player1, player2) = players(9.0);
+    let mut ball = ball(30.0, 17.8, 10.0, 5.0);
+
+    ball.update_position(&config, &player1, &player2, &mut rng(), DT);
+
+    assert!((ball.get_position().y() - 17.7).abs() < 1e-9);
+    assert!(ball.get_velocity().vy() < 0.0);
+}
+
+#[test]
+fn ball_moving_back_into_the_field_is_not_bounced_again() {
+    let config = GameConfig::default();
+    let (player1, player2) = players(9.0);
+    let mut ball = ball(30.0, -0.2, 10.0, 5.0);
+
+    ball.update_position(&config, &player1, &player2, &mut rng(), DT);
+
+    assert!(ball.get_position().y() >= 0.0);
+    assert!(ball.get_velocity().vy() > 0.0);
+}
+
+#[test]
+fn ball_stays_within_the_edge_rows() {
+    let config = GameConfig::default();
+    let (player1, player2) = players(9.0);
+    let mut ball = ball(30.0, 17.9, 1.0, 30.0);
+
+    for _ in 0..20 {
+        ball.update_position(&config, &player1, &player2, &mut rng(), DT);
+        let y = ball.get_position().y();
+        assert!(
+            (0.0..=config.height as f64).contains(&y),
+            "ball left the field at y = {y}"
+        );
+    }
+}
+
+#[test]
+fn ball_hits_the_tip_of_the_paddle() {
+    let config = GameConfig::default();
+    let (player1, player2) = players(5.0);
+    let mut ball = ball(1.0, 6.4, -10.0, 0.0);
+
+    assert!(ball.update_position(&config, &player1, &player2, &mut rng(), DT));
+    assert!(ball.get_velocity().vx() > 0.0);
+}
+
+#[test]
+fn ball_misses_just_beyond_the_tip_of_the_paddle() {
+    let config = GameConfig::default();
+    let (player1, player2) = players(5.0);
+    let mut ball = ball(1.0, 6.6, -10.0, 0.0);
+
+    assert!(!ball.update_position(&config, &player1, &player2, &mut rng(), DT));
+    assert!(ball.get_velocity().vx() < 0.0);
+}
+
+#[test]
+fn ball_bouncing_off_the_bottom_wall_hits_a_paddle_in_the_corner() {
+    let config = GameConfig::default();
+    let (player1, player2) = players(1.0);
+    let mut ball = ball(1.0, 0.5, -10.0, -10.0);
+
+    assert!(ball.update_position(&config, &player1, &player2, &mut rng(), DT));
+    assert!(ball.get_velocity().vx() > 0.0);
+    assert!(ball.get_position().y() >= 0.0);
+}
+
+#[test]
+fn ball_bouncing_off_the_top_wall_hits_a_paddle_in_the_corner() {
+    let config = GameConfig::default();
+    let (player1, player2) = players(17.0);
+    let mut ball = ball(1.0, 17.5, -10.0, 10.0);
+
+    assert!(ball.update_position(&config, &player1, &player2, &mut rng(), DT));
+    assert!(ball.get_velocity().vx() > 0.0);
+    assert!(ball.get_position().y() <= config.height as f64);
+}
+
+#[test]
+fn jittered_bounces_keep_the_ball_moving_away() {
+    let config = GameConfig::builder().jitter(1.0).build();
+    let (player1, player2) = players(5.0);
+    let mut rng = rng();
+
+    for _ in 0..100 {
+        let mut hit_ball = ball(1.0, 5.0, -10.0, 0.0);
+        assert!(hit_ball.update_position(&config, &player1, &player2, &mut rng, DT));
+        assert!(hit_ball.get_velocity().vx() > 0.0);
+
+        let mut bounced_ball = ball(30.0, 0.2, 10.0, -5.0);
+        bounced_ball.update_position(&config, &player1, &player2, &mut rng, DT);
+        assert!(bounced_ball.get_velocity().vy() > 0.0);
+    }
+}
+
+fn aabb(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Aabb {
+    Aabb::new(Position2D::new(min_x, min_y), Position2D::new(max_x, max_y))
+}
+
+#[test]
+fn broadphase_finds_only_overlapping_boxes() {
+    let boxes = [
+        aabb(0.0, 0.0, 2.0, 2.0),
+        aabb(1.0, 1.0, 3.0, 3.0),
+        aabb(10.0, 0.0, 12.0, 2.0),
+        aabb(1.5, 5.0, 2.5, 6.0),
+        aabb(11.0, 2.0, 11.5, 4.0),
+    ];
+
+    assert_eq!(broadphase(&boxes), vec![(0, 1), (2, 4)]);
+}
+
+#[test]
+fn broadphase_counts_touching_borders_as_overlap() {
+    let boxes = [aabb(0.0, 0.0, 1.0, 1.0), aabb(1.0, 1.0, 2.0, 2.0)];
+
+    assert_eq!(broadphase(&boxes), vec![(0, 1)]);
+}
+
+#[test]
+fn crossing_point_follows_the_line_of_flight() {
+    let point = crossing_point(Position2D::new(10.0, 4.0), Velocity2D::new(-5.0, 1.0), 0.0);
+
+    assert_eq!(point.x(), 0.0);
+    assert!((point.y() - 6.0).abs() < 1e-9);
+}
+
+#[test]
+fn last_moment_save_is_no_goal() {
+    let config = GameConfig::default();
+    let (player1, player2) = players(5.0);
+    // the ball reaches the goal line within this update, but the tip of the paddle is in the way
+    let mut ball = ball(0.4, 6.3, -10.0, 1.0);
+
+    assert!(ball.update_position(&config, &player1, &player2, &mut rng(), DT));
+    assert_eq!(goal_scored(ball.get_position(), config.width as f64), None);
+}
+
+#[test]
+fn last_moment_save_of_player2_is_no_goal() {
+    let config = GameConfig::default();
+    let (player1, player2) = players(5.0);
+    let mut ball = ball(59.7, 10.4, 10.0, 0.0);
+
+    assert!(ball.update_position(&config, &player1, &player2, &mut rng(), DT));
+    assert_eq!(goal_scored(ball.get_position(), config.width as f64), None);
+}
+
+#[test]
+fn ball_passing_the_paddle_at_the_last_moment_is_a_goal() {
+    let config = GameConfig::default();
+    let (player1, player2) = players(5.0);
+    let mut ball = ball(0.4, 6.6, -10.0, 0.0);
+
+    assert!(!ball.update_position(&config, &player1, &player2, &mut rng(), DT));
+    assert_eq!(
+        goal_scored(ball.get_position(), config.width as f64),
+        Some(PlayerSide::Right)
+    );
+}
+
+#[test]
+fn goal_lines_do_not_depend_on_the_direction_of_the_ball() {
+    assert_eq!(
+        goal_scored(Position2D::new(-0.1, 5.0), 60.0),
+        Some(PlayerSide::Right)
+    );
+    assert_eq!(
+        goal_scored(Position2D::new(60.1, 5.0), 60.0),
+        Some(PlayerSide::Left)
+    );
+    assert_eq!(goal_scored(Position2D::new(0.0, 5.0), 60.0), None);
+    assert_eq!(goal_scored(Position2D::new(60.0, 5.0), 60.0), None);
+}
+
+#[test]
+fn ball_spawns_at_the_preferred_point_if_it_is_free() {
+    let spawn = safe_spawn_point(Position2D::new(30.0, 9.0), (60.0, 18.0), 0.0, &[]);
+
+    assert_eq!((spawn.x(), spawn.y()), (30.0, 9.0));
+}
+
+#[test]
+fn ball_spawns_next_to_an_obstacle_in_the_center() {
+    let blocked = [aabb(29.5, 8.5, 30.5, 9.5)];
+    let spawn = safe_spawn_point(Position2D::new(30.0, 9.0), (60.0, 18.0), 0.0, &blocked);
+
+    assert!(!blocked[0].overlaps(&aabb(
+        spawn.x() - 0.5,
+        spawn.y() - 0.5,
+        spawn.x() + 0.5,
+        spawn.y() + 0.5
+    )));
+    assert!((spawn.x() - 30.0).abs() + (spawn.y() - 9.0).abs() <= 2.0);
+}
+
+#[test]
+fn ball_spawns_past_a_bar_across_the_field() {
+    // a bar over the whole width with only the top rows left free
+    let blocked = [aabb(-1.0, -1.0, 61.0, 14.5)];
+    let spawn = safe_spawn_point(Position2D::new(30.0, 9.0), (60.0, 18.0), 0.0, &blocked);
+
+    assert!(spawn.y() >= 15.0 && spawn.y() <= 18.0);
+}
+
+#[test]
+fn big_ball_does_not_spawn_in_a_wall() {
+    // only the bottom rows are free, and a big ball must not reach into the wall below them
+    let blocked = [aabb(-1.0, 3.5, 61.0, 19.0)];
+    let spawn = safe_spawn_point(Position2D::new(30.0, 9.0), (60.0, 18.0), 1.0, &blocked);
+
+    assert_eq!((spawn.x(), spawn.y()), (30.0, 1.0));
+}
+
+#[test]
+fn ball_does_not_spawn_on_a_goal_line() {
+    // everything but the goal columns is blocked
+    let blocked = [aabb(0.5, -1.0, 59.5, 19.0)];
+    let spawn = safe_spawn_point(Position2D::new(30.0, 9.0), (60.0, 18.0), 0.0, &blocked);
+
+    assert_eq!((spawn.x(), spawn.y()), (30.0, 9.0));
+}
+
+#[test]
+fn ball_spawns_between_the_paddles_of_a_tiny_field() {
+    let (player1, player2) = (still_player(0.0, 2.0), still_player(2.0, 2.0));
+    let blocked = [player1.bounding_box(), player2.bounding_box()];
+    let spawn = safe_spawn_point(Position2D::new(1.0, 2.0), (2.0, 4.0), 0.0, &blocked);
+
+    // the single free column touches both paddles, so no point is safe
+    assert_eq!((spawn.x(), spawn.y()), (1.0, 2.0));
+
+    let (player1, player2) = (still_player(0.0, 2.0), still_player(4.0, 2.0));
+    let blocked = [player1.bounding_box(), player2.bounding_box()];
+    let spawn = safe_spawn_point(Position2D::new(2.0, 2.0), (4.0, 4.0), 0.0, &blocked);
+
+    assert_eq!((spawn.x(), spawn.y()), (2.0, 2.0));
+}
+
+fn tall_player(x: f64, y: f64) -> Player {
+    Player::new(
+        8,
+        8,
+        Controller::External(PaddleCommand::Stay),
+        Position2D::new(x, y),
+        12.0,
+    )
+}
+
+#[test]
+fn paddle_at_the_bottom_edge_reaches_below_the_field() {
+    let player = tall_player(0.0, 0.0);
+
+    assert!(player.collides_with(Position2D::new(0.0, 0.0)));
+    assert!(player.collides_with(Position2D::new(0.0, 8.0)));
+    assert!(player.collides_with(Position2D::new(0.0, -8.0)));
+    assert!(!player.collides_with(Position2D::new(0.0, 9.0)));
+    assert!(!player.collides_with(Position2D::new(0.0, -9.0)));
+}
+
+#[test]
+fn paddle_at_the_top_edge_reaches_above_the_field() {
+    let config = GameConfig::default();
+    let height = config.height as f64;
+    let player = tall_player(60.0, height);
+
+    assert!(player.collides_with(Position2D::new(60.0, height + 8.0)));
+    assert!(!player.collides_with(Position2D::new(60.0, height + 9.0)));
+}
+
+#[test]
+fn ball_behind_the_goal_line_does_not_touch_the_paddle() {
+    let player = tall_player(0.0, 0.0);
+
+    assert!(!player.collides_with(Position2D::new(-0.7, 0.0)));
+    assert!(!player.collides_with(Position2D::new(-1e12, -1e12)));
+}
+
+#[test]
+fn discrete_positions_outside_of_the_field_are_signed() {
+    let cell = Position2D::new(-0.7, -3.2).to_discrete();
+
+    assert_eq!((cell.x(), cell.y()), (-1, -3));
+    assert_eq!(cell.in_field(60, 18), None);
+    assert_eq!(cell.clamped(60, 18), (0, 0));
+    assert_eq!(DiscretePosition2D::new(61, 19).in_field(60, 18), None);
+    assert_eq!(DiscretePosition2D::new(61, 19).clamped(60, 18), (60, 18));
+    assert_eq!(
+        DiscretePosition2D::new(60, 0).in_field(60, 18),
+        Some((60, 0))
+    );
+}
+
+#[test]
+fn prediction_leaves_the_ball_untouched_and_matches_the_update() {
+    let config = GameConfig::default();
+    let (player1, player2) = players(9.0);
+    let mut ball = ball(30.0, 0.2, 10.0, -5.0);
+
+    let predicted = ball.predicted(&config, &Arena::default(), DT);
+    assert_eq!(ball.get_position().y(), 0.2);
+
+    ball.update_position(&config, &player1, &player2, &mut rng(), DT);
+    assert_eq!(predicted.get_position().x(), ball.get_position().x());
+    assert_eq!(predicted.get_position().y(), ball.get_position().y());
+    assert_eq!(predicted.get_velocity().vy(), ball.get_velocity().vy());
+}
+
+#[test]
+fn predicted_crossing_follows_the_bounces_at_the_walls() {
+    let config = GameConfig::default();
+    // flies 3 cells down while it crosses 30 columns, so it bounces off the bottom wall once
+    let ball = ball(30.0, 1.0, -10.0, -1.0);
+
+    let crossing = ball
+        .predict_crossing(&config, &Arena::default(), 0.0, DT, Duration::from_secs(10))
+        .unwrap();
+
+    assert_eq!(crossing.x(), 0.0);
+    assert!(
+        (crossing.y() - 2.0).abs() < 0.1,
+        "crossed at {}",
+        crossing.y()
+    );
+}
+
+#[test]
+fn ball_moving_away_has_no_predicted_crossing() {
+    let config = GameConfig::default();
+    let ball = ball(30.0, 9.0, 10.0, 0.0);
+
+    assert!(ball
+        .predict_crossing(&config, &Arena::default(), 0.0, DT, Duration::from_secs(2))
+        .is_none());
+}
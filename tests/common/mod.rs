@@ -0,0 +1,140 @@
+//! The fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+use cli_pong::ai::AiStyle;
+use cli_pong::mode::GameMode;
+use cli_pong::replay::ReplayFrame;
+use cli_pong::state::{
+    Controller, GameConfig, GameEvent, GameState, PaddleCommand, Player, Position2D,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The time step with which the fixtures update a game.
+pub const DT: Duration = Duration::from_millis(50);
+
+/// Returns an empty temporary directory that is unique for each test.
+pub fn temp_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cli_pong-{}-{test}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+/// Returns a game with the default configuration and the given seed.
+pub fn game(seed: u64) -> GameState {
+    GameState::new(GameConfig::builder().seed(Some(seed)).build())
+}
+
+/// Returns a match of the computer against itself that ends after the given goals. Two balanced
+/// players may rally forever, so player2 plays the aggressive style.
+pub fn ai_match(seed: u64, score_limit: usize) -> GameConfig {
+    GameConfig::builder()
+        .seed(Some(seed))
+        .player1_ai(Some(AiStyle::Balanced.profile()))
+        .player2_ai(Some(AiStyle::Aggressive.profile()))
+        .score_limit(Some(score_limit))
+        .build()
+}
+
+/// Returns a game of the given mode with the default configuration and the given seed.
+pub fn game_with_mode(seed: u64, mode: impl GameMode + 'static) -> GameState {
+    GameState::with_mode(
+        GameConfig::builder().seed(Some(seed)).build(),
+        Box::new(mode),
+    )
+}
+
+/// Moves the ball to the given position and velocity with both paddles in the middle of their
+/// edges. The scores stay as they are.
+pub fn place_ball(state: &mut GameState, (x, y): (f64, f64), (vx, vy): (f64, f64)) {
+    let (player1_score, player2_score) = state.scores();
+    state.show_replay_frame(&ReplayFrame {
+        dt: Duration::ZERO,
+        player1_y: 9.0,
+        player2_y: 9.0,
+        ball_x: x,
+        ball_y: y,
+        ball_vx: vx,
+        ball_vy: vy,
+        player1_score,
+        player2_score,
+    });
+}
+
+/// Returns the speed of the ball of the game.
+pub fn ball_speed(state: &GameState) -> f64 {
+    let velocity = state.ball().get_velocity();
+    velocity.vx().hypot(velocity.vy())
+}
+
+/// Updates the game without keys until it is over, and returns all events.
+pub fn play_until_over(state: &mut GameState) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+    for _ in 0..100_000 {
+        if state.is_over() {
+            return events;
+        }
+        events.extend(state.update(HashMap::new(), DT));
+    }
+
+    panic!("the game was not over");
+}
+
+/// Counts the cells of the rendered game that show the given glyph.
+pub fn count_glyphs(state: &GameState, glyph: char) -> usize {
+    state
+        .render_to_grid()
+        .iter()
+        .flatten()
+        .filter(|&&cell| cell == glyph)
+        .count()
+}
+
+/// Returns a paddle at the given position that stays where it is.
+pub fn still_player(x: f64, y: f64) -> Player {
+    Player::new(
+        1,
+        1,
+        Controller::External(PaddleCommand::Stay),
+        Position2D::new(x, y),
+        12.0,
+    )
+}
+
+/// Returns the players of a field on which the ball never reaches a paddle.
+pub fn out_of_reach_players(y: f64) -> (Player, Player) {
+    (still_player(-100.0, y), still_player(1000.0, y))
+}
+
+/// Returns the event of the given key without modifiers.
+pub fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+/// Returns the given key pressed with the given modifiers.
+pub fn press(code: KeyCode, modifiers: KeyModifiers) -> HashMap<KeyCode, KeyEvent> {
+    HashMap::from([(code, KeyEvent::new(code, modifiers))])
+}
+
+/// Returns the given keys pressed at once, each with its modifiers.
+pub fn press_all(keys: &[(KeyCode, KeyModifiers)]) -> HashMap<KeyCode, KeyEvent> {
+    keys.iter()
+        .map(|&(code, modifiers)| (code, KeyEvent::new(code, modifiers)))
+        .collect()
+}
+
+/// Returns the keys of the given characters pressed at once without modifiers.
+pub fn press_chars(characters: &[char]) -> HashMap<KeyCode, KeyEvent> {
+    characters
+        .iter()
+        .map(|&character| {
+            let code = KeyCode::Char(character);
+            (code, KeyEvent::new(code, KeyModifiers::NONE))
+        })
+        .collect()
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use cli_pong::config::ConfigFile;
+use common::temp_dir;
+use std::fs;
+
+/// A configuration file of format version 1, which spelled the options with underscores.
+const VERSION_1: &str = "win_meter = true\n";
+
+#[test]
+fn migrated_file_replaces_the_old_one_with_a_backup() {
+    let dir = temp_dir("migrated");
+    let path = dir.join("config.toml");
+    fs::write(&path, VERSION_1).unwrap();
+
+    let file = ConfigFile::load(&path).unwrap();
+
+    assert_eq!(file.global_args().unwrap(), ["--win-meter"]);
+    assert_eq!(
+        fs::read_to_string(dir.join("config.toml.v1.bak")).unwrap(),
+        VERSION_1
+    );
+    let migrated = fs::read_to_string(&path).unwrap();
+    assert!(migrated.contains("win-meter = true"), "{migrated}");
+    assert!(!dir.join("config.toml.tmp").exists());
+}
+
+#[test]
+fn file_is_kept_if_the_backup_can_not_be_written() {
+    let dir = temp_dir("no_backup");
+    let path = dir.join("config.toml");
+    fs::write(&path, VERSION_1).unwrap();
+    // a directory in place of the backup can not be replaced by a file
+    fs::create_dir_all(dir.join("config.toml.v1.bak").join("blocked")).unwrap();
+
+    let file = ConfigFile::load(&path).unwrap();
+
+    assert_eq!(file.global_args().unwrap(), ["--win-meter"]);
+    assert_eq!(fs::read_to_string(&path).unwrap(), VERSION_1);
+}
+
+#[test]
+fn migration_keeps_the_comments_and_the_order() {
+    let dir = temp_dir("comments");
+    let path = dir.join("config.toml");
+    fs::write(
+        &path,
+        "# my settings\nwidth = 80\n# glow\nball_glow = true\nheight = 20\n",
+    )
+    .unwrap();
+
+    ConfigFile::load(&path).unwrap();
+
+    let migrated = fs::read_to_string(&path).unwrap();
+    assert!(
+        migrated.starts_with("# my settings\nwidth = 80\n# glow\nball-glow = true\nheight = 20\n"),
+        "{migrated}"
+    );
+}
+
+#[test]
+fn file_without_old_option_names_is_left_untouched() {
+    let dir = temp_dir("unchanged");
+    let path = dir.join("config.toml");
+    let content = "# no version, but nothing to migrate\nwin-meter = true\n";
+    fs::write(&path, content).unwrap();
+
+    let file = ConfigFile::load(&path).unwrap();
+
+    assert_eq!(file.global_args().unwrap(), ["--win-meter"]);
+    assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    assert!(!dir.join("config.toml.v1.bak").exists());
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use cli_pong::crash::CrashReport;
+use cli_pong::debug::DebugHistory;
+use cli_pong::state::{GameConfig, GameState};
+use common::temp_dir;
+use std::collections::HashMap;
+use std::fs;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(100);
+
+fn report() -> CrashReport {
+    CrashReport::capture(
+        "panic: panicked at src/state.rs:1:1:\nattempt to divide by zero",
+        vec![
+            ("config file".to_string(), "--width 80".to_string()),
+            ("command line".to_string(), "--mode single".to_string()),
+        ],
+    )
+}
+
+/// Returns a debug history of the given number of updates.
+fn history(updates: usize) -> DebugHistory {
+    let mut state = GameState::new(GameConfig::builder().seed(Some(5)).build());
+    let mut history = DebugHistory::new(10, &state);
+    for _ in 0..updates {
+        let events = state.update(HashMap::new(), DT);
+        history.record(&state, &HashMap::new(), &events, DT);
+    }
+
+    history
+}
+
+fn written(report: &CrashReport, history: Option<&DebugHistory>) -> String {
+    let mut buffer = Vec::new();
+    report.write(&mut buffer, history).unwrap();
+
+    String::from_utf8(buffer).unwrap()
+}
+
+#[test]
+fn report_bundles_every_section() {
+    let text = written(&report(), Some(&history(4)));
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert_eq!(lines[0], "cli_pong crash report");
+    assert_eq!(
+        lines[2],
+        "reason panic: panicked at src/state.rs:1:1: attempt to divide by zero"
+    );
+    assert!(lines.contains(&format!("os {}", std::env::consts::OS).as_str()));
+    assert!(lines.contains(&"config file: --width 80"));
+    assert!(lines.contains(&"command line: --mode single"));
+
+    let sections: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|line| line.starts_with("== "))
+        .collect();
+    assert_eq!(
+        sections,
+        vec![
+            "== platform ==",
+            "== configuration ==",
+            "== backtrace ==",
+            "== recent updates =="
+        ]
+    );
+    let backtrace = text.split("== backtrace ==").nth(1).unwrap();
+    assert!(!backtrace
+        .split("== recent updates ==")
+        .next()
+        .unwrap()
+        .trim()
+        .is_empty());
+    assert!(text.contains("cli_pong debug dump"));
+    assert!(text.contains("updates 4"));
+}
+
+#[test]
+fn report_says_why_the_updates_are_missing() {
+    let report = report();
+
+    assert!(written(&report, Some(&history(0))).ends_with("the debug history is turned off\n"));
+    assert!(written(&report, None).ends_with("the debug history was not available\n"));
+}
+
+#[test]
+fn reports_never_overwrite_each_other() {
+    let dir = temp_dir("reports_never_overwrite_each_other").join("crashes");
+    let report = report();
+
+    let first = report.save(&dir, None).unwrap();
+    let second = report.save(&dir, None).unwrap();
+
+    assert_ne!(first, second);
+    let name = first.file_name().unwrap().to_string_lossy().into_owned();
+    assert!(
+        name.starts_with("crash-") && name.ends_with(".txt"),
+        "{name}"
+    );
+    assert!(fs::read_to_string(&second)
+        .unwrap()
+        .starts_with("cli_pong crash report"));
+    assert_eq!(report.reason().lines().count(), 2);
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use cli_pong::debug::DebugHistory;
+use cli_pong::observer::Observers;
+use cli_pong::state::GameState;
+use common::{game, press_all, temp_dir};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(100);
+
+/// Plays the given number of updates and keeps each of them in the history.
+fn play(state: &mut GameState, history: &mut DebugHistory, updates: usize) {
+    for _ in 0..updates {
+        let pressed = press_all(&[(KeyCode::Char('w'), KeyModifiers::NONE)]);
+        let events = state.update(pressed.clone(), DT);
+        history.record(state, &pressed, &events, DT);
+    }
+}
+
+#[test]
+fn history_keeps_only_the_most_recent_updates() {
+    let mut state = game(3);
+    let mut history = DebugHistory::new(5, &state);
+
+    play(&mut state, &mut history, 12);
+
+    assert_eq!(history.len(), 5);
+    let newest = history.entries().last().unwrap();
+    assert_eq!(newest.frame, state.replay_frame(DT));
+    assert_eq!(newest.keys, vec!["w"]);
+}
+
+#[test]
+fn empty_history_keeps_nothing() {
+    let mut state = game(3);
+    let mut history = DebugHistory::new(0, &state);
+
+    play(&mut state, &mut history, 3);
+
+    assert!(history.is_empty());
+}
+
+#[test]
+fn pressed_keys_are_named_like_bindings() {
+    let mut state = game(3);
+    let mut history = DebugHistory::new(1, &state);
+    let pressed = press_all(&[
+        (KeyCode::Up, KeyModifiers::NONE),
+        (KeyCode::Char('r'), KeyModifiers::CONTROL),
+    ]);
+
+    let events = state.update(pressed.clone(), DT);
+    history.record(&state, &pressed, &events, DT);
+
+    assert_eq!(history.entries().next().unwrap().keys, vec!["ctrl+r", "up"]);
+}
+
+#[test]
+fn dump_describes_the_game_and_every_kept_update() {
+    let mut state = game(3);
+    let mut history = DebugHistory::new(4, &state);
+    play(&mut state, &mut history, 4);
+
+    let mut dump = Vec::new();
+    history
+        .write(&mut dump, "panic: ball left\nthe field")
+        .unwrap();
+    let dump = String::from_utf8(dump).unwrap();
+    let lines: Vec<&str> = dump.lines().collect();
+
+    assert_eq!(lines[0], "cli_pong debug dump");
+    assert!(lines.contains(&"reason panic: ball left the field"));
+    assert!(lines.contains(&"option seed 3"));
+    assert!(lines.contains(&"updates 4"));
+    let updates: Vec<&&str> = lines
+        .iter()
+        .filter(|line| line.contains(" dt=100000us "))
+        .collect();
+    assert_eq!(updates.len(), 4);
+    assert!(updates[3].starts_with("3 "));
+    assert!(updates[3].contains("keys=[w]"));
+}
+
+#[test]
+fn dumps_never_overwrite_each_other() {
+    let dir = temp_dir("dumps_never_overwrite_each_other").join("dumps");
+    let mut state = game(3);
+    let mut history = DebugHistory::new(3, &state);
+    play(&mut state, &mut history, 3);
+
+    let first = history.dump(&dir, "first").unwrap();
+    let second = history.dump(&dir, "second").unwrap();
+
+    assert_ne!(first, second);
+    assert!(fs::read_to_string(&first).unwrap().contains("reason first"));
+    assert!(fs::read_to_string(&second)
+        .unwrap()
+        .contains("reason second"));
+}
+
+#[test]
+fn observed_history_keeps_the_keys_of_each_update() {
+    let mut state = game(3);
+    let history = Arc::new(Mutex::new(DebugHistory::new(5, &state)));
+    let mut observers = Observers::default();
+    observers.register(Arc::clone(&history));
+    let pressed = press_all(&[(KeyCode::Up, KeyModifiers::NONE)]);
+
+    observers.input(&state, &pressed).unwrap();
+    let events = state.update(pressed, DT);
+    observers.notify(&state, &events, DT).unwrap();
+    observers.notify(&state, &[], DT).unwrap();
+
+    let history = history.lock().unwrap();
+    let keys: Vec<_> = history.entries().map(|entry| entry.keys.clone()).collect();
+    assert_eq!(keys, vec![vec!["up".to_string()], Vec::new()]);
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use cli_pong::mode::Doubles;
+use cli_pong::replay::ReplayFrame;
+use cli_pong::state::{
+    Arena, Ball, Controller, GameConfig, GameEvent, GameState, Lane, PaddleCommand, Player,
+    Position2D, Velocity2D,
+};
+use cli_pong::stats::PlayerSide;
+use common::{game_with_mode, press_chars};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+const PADDLE: char = '\u{2588}';
+
+fn paddle(x: f64, y: f64) -> Player {
+    Player::new(
+        1,
+        1,
+        Controller::External(PaddleCommand::Stay),
+        Position2D::new(x, y),
+        10.0,
+    )
+}
+
+#[test]
+fn each_team_has_an_upper_and_a_lower_paddle() {
+    let state = game_with_mode(6, Doubles);
+
+    for side in [PlayerSide::Left, PlayerSide::Right] {
+        let teammates = state.teammates(side);
+        assert_eq!(teammates.len(), 1);
+        assert!(teammates[0].position().y() < state.config().height as f64 / 2.0);
+    }
+    assert!(GameState::new(GameConfig::default())
+        .teammates(PlayerSide::Left)
+        .is_empty());
+}
+
+#[test]
+fn paddles_stay_in_their_half_of_the_side() {
+    let mut state = game_with_mode(6, Doubles);
+    let middle = state.config().height as f64 / 2.0;
+
+    for _ in 0..40 {
+        state.update(press_chars(&['f', 's']), DT);
+    }
+
+    let teammate = state.teammates(PlayerSide::Left)[0].position().y();
+    let observation = state.observation(PlayerSide::Left);
+    assert!(teammate < middle, "{teammate}");
+    assert!(teammate > middle - 2.0, "{teammate}");
+    assert!(observation.paddle_y > middle, "{}", observation.paddle_y);
+}
+
+#[test]
+fn teammates_move_with_their_own_keys() {
+    let mut state = game_with_mode(6, Doubles);
+    let y = |state: &GameState| state.teammates(PlayerSide::Right)[0].position().y();
+    let start = y(&state);
+
+    state.update(press_chars(&['i']), DT);
+    assert!(y(&state) > start);
+    state.update(press_chars(&['k']), DT);
+    state.update(press_chars(&['k']), DT);
+    assert!(y(&state) < start);
+}
+
+#[test]
+fn lower_paddle_returns_the_ball() {
+    let mut state = game_with_mode(6, Doubles);
+    let player1_y = state.observation(PlayerSide::Left).paddle_y;
+    let teammate_y = state.teammates(PlayerSide::Left)[0].position().y();
+    state.show_replay_frame(&ReplayFrame {
+        dt: Duration::ZERO,
+        player1_y,
+        player2_y: 14.0,
+        ball_x: 4.0,
+        ball_y: teammate_y,
+        ball_vx: -15.0,
+        ball_vy: 0.0,
+        player1_score: 0,
+        player2_score: 0,
+    });
+
+    let mut events = Vec::new();
+    for _ in 0..8 {
+        events.extend(state.update(HashMap::new(), DT));
+    }
+
+    assert!(events.contains(&GameEvent::Hit(PlayerSide::Left)));
+    assert!(state.ball().get_velocity().vx() > 0.0);
+}
+
+#[test]
+fn ball_checks_every_paddle_of_a_team() {
+    let config = GameConfig::default();
+    let upper = paddle(0.0, 14.0).with_lane(Lane::Upper);
+    let lower = paddle(0.0, 4.0).with_lane(Lane::Lower);
+    let right = paddle(60.0, 9.0);
+    let mut ball = Ball::with_velocity(Position2D::new(0.5, 4.0), Velocity2D::new(-10.0, 0.0));
+
+    assert!(ball.update_position_with_teams(
+        &config,
+        &Arena::default(),
+        &[&upper, &lower],
+        &[&right],
+        &mut StdRng::seed_from_u64(1),
+        DT
+    ));
+    assert!(ball.get_velocity().vx() > 0.0);
+
+    let mut missed = Ball::with_velocity(Position2D::new(0.5, 9.0), Velocity2D::new(-10.0, 0.0));
+    assert!(!missed.update_position_with_teams(
+        &config,
+        &Arena::default(),
+        &[&upper, &lower],
+        &[&right],
+        &mut StdRng::seed_from_u64(1),
+        DT
+    ));
+}
+
+#[test]
+fn all_four_paddles_are_drawn() {
+    let config = GameConfig::default();
+    let paddle_cells = 1 + config.extend_player_height_up + config.extend_player_height_down;
+    let grid = game_with_mode(6, Doubles).render_to_grid();
+
+    // the walls are the rows full of blocks, the paddles take at most two cells of a row
+    let drawn: usize = grid
+        .iter()
+        .map(|row| row.iter().filter(|&&cell| cell == PADDLE).count())
+        .filter(|&cells| cells <= 2)
+        .sum();
+    assert_eq!(drawn, 4 * paddle_cells);
+}
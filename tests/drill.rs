@@ -0,0 +1,143 @@
+mod common;
+
+use cli_pong::ai::AiStyle;
+use cli_pong::drill::{Drill, ServePattern};
+use cli_pong::mode::{GameMode, ServeDrill};
+use cli_pong::state::{GameConfig, GameEvent, GameState};
+use cli_pong::stats::PlayerSide;
+use common::{count_glyphs, play_until_over, temp_dir};
+use std::fs;
+
+const MACHINE: char = '\u{25C4}';
+
+fn drill(pattern: ServePattern, serves: usize) -> Drill {
+    Drill {
+        serves,
+        ..Drill::new(pattern)
+    }
+}
+
+fn game(config: GameConfig) -> GameState {
+    GameState::with_mode(config, Box::new(ServeDrill))
+}
+
+#[test]
+fn patterns_vary_the_serves() {
+    let angles = |pattern, seed| -> Vec<f64> {
+        (0..13)
+            .map(|index| Drill::new(pattern).serve(index, seed).0)
+            .collect()
+    };
+
+    assert!(angles(ServePattern::Fixed, 1)
+        .iter()
+        .all(|&angle| angle == 15.0));
+    let sweep = angles(ServePattern::Sweep, 1);
+    assert_eq!((sweep[0], sweep[6], sweep[12]), (-35.0, 35.0, -35.0));
+    assert!(sweep[..7].windows(2).all(|pair| pair[0] < pair[1]));
+    let random = angles(ServePattern::Random, 1);
+    assert!(random.iter().all(|angle| (-35.0..=35.0).contains(angle)));
+    assert_eq!(random, angles(ServePattern::Random, 1));
+    assert_ne!(random, angles(ServePattern::Random, 2));
+
+    let accelerating = Drill::new(ServePattern::Accelerating);
+    assert_eq!(accelerating.serve(0, 1).1, 15.0);
+    assert_eq!(accelerating.serve(2, 1).1, 18.0);
+    assert_eq!(accelerating.serve(1000, 1).1, 60.0);
+}
+
+#[test]
+fn drills_are_read_from_toml() {
+    let path = temp_dir("drills_are_read_from_toml").join("drill.toml");
+    fs::write(
+        &path,
+        "pattern = \"random\"\nmin_angle = -10.0\nserves = 5\n",
+    )
+    .unwrap();
+
+    let drill = Drill::load(&path).unwrap();
+
+    assert_eq!(drill.pattern, ServePattern::Random);
+    assert_eq!((drill.min_angle, drill.max_angle), (-10.0, 35.0));
+    assert_eq!(drill.serves, 5);
+    assert_eq!(drill.speed, Drill::default().speed);
+    fs::write(&path, "pattern = \"sideways\"\n").unwrap();
+    assert!(Drill::load(&path).is_err());
+}
+
+#[test]
+fn machine_serves_at_player1_from_the_right() {
+    let state = game(
+        GameConfig::builder()
+            .seed(Some(1))
+            .drill(Some(drill(ServePattern::Fixed, 5)))
+            .build(),
+    );
+    let config = state.config();
+    let ball = state.ball();
+
+    assert!(state.arena().right_wall);
+    assert!(ball.get_position().x() > config.width as f64 - 4.0);
+    assert!(ball.get_velocity().vx() < 0.0);
+    assert!(ball.get_velocity().vy() > 0.0);
+    assert_eq!(count_glyphs(&state, MACHINE), 1);
+    let grid = state.render_to_grid();
+    assert!(grid[1]
+        .iter()
+        .collect::<String>()
+        .starts_with("Serve 1 of 5,  returned: 0"));
+}
+
+#[test]
+fn returns_are_counted_and_served_again() {
+    let mut state = game(
+        GameConfig::builder()
+            .seed(Some(2))
+            .drill(Some(drill(ServePattern::Sweep, 6)))
+            .player1_ai(Some(AiStyle::Balanced.profile()))
+            .build(),
+    );
+
+    let events = play_until_over(&mut state);
+
+    let returns = state.stats().returns(PlayerSide::Left);
+    let misses = state.stats().misses(PlayerSide::Left);
+    assert_eq!(returns + misses, 6);
+    assert!(returns > 0);
+    assert_eq!(
+        events
+            .iter()
+            .filter(|&&event| event == GameEvent::Hit(PlayerSide::Left))
+            .count(),
+        returns
+    );
+    assert!(events.contains(&GameEvent::Won(PlayerSide::Left)));
+    // every return is followed by a serve instead of a rally against the wall
+    assert_eq!(state.stats().returns(PlayerSide::Right), 0);
+    assert!(ServeDrill.summary(&state)[0].ends_with(&format!(
+        "{returns} of 6 serves ({:.0}%)",
+        100.0 * returns as f64 / 6.0
+    )));
+}
+
+#[test]
+fn missed_serves_end_the_drill_as_well() {
+    let mut state = game(
+        GameConfig::builder()
+            .seed(Some(3))
+            .drill(Some(drill(ServePattern::Accelerating, 3)))
+            .build(),
+    );
+
+    // player1 does not move, so the fixed angle misses the paddle every time
+    play_until_over(&mut state);
+
+    assert_eq!(state.stats().misses(PlayerSide::Left), 3);
+    assert_eq!(
+        ServeDrill.summary(&state),
+        vec![
+            "Returned 0 of 3 serves (0%)",
+            "Drill: accelerating serves at 15.0 cells/s"
+        ]
+    );
+}
@@ -0,0 +1,67 @@
+use cli_pong::ai::AiStyle;
+use cli_pong::env::PongEnv;
+use cli_pong::state::{Controller, GameConfig, PaddleCommand, Player, Position2D};
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(100);
+
+fn player(y: f64) -> Player {
+    Player::new(
+        1,
+        1,
+        Controller::External(PaddleCommand::Stay),
+        Position2D::new(0.0, y),
+        10.0,
+    )
+}
+
+#[test]
+fn move_to_is_limited_by_the_speed_of_the_paddle() {
+    let mut paddle = player(5.0);
+
+    paddle.update_position(24.0, PaddleCommand::MoveTo(20.0), DT);
+    assert_eq!(paddle.position().y(), 6.0);
+
+    paddle.update_position(24.0, PaddleCommand::MoveTo(0.0), DT);
+    assert_eq!(paddle.position().y(), 5.0);
+}
+
+#[test]
+fn move_to_stops_at_the_target() {
+    let mut paddle = player(5.0);
+
+    paddle.update_position(24.0, PaddleCommand::MoveTo(5.5), DT);
+    assert_eq!(paddle.position().y(), 5.5);
+
+    paddle.update_position(24.0, PaddleCommand::MoveTo(5.5), DT);
+    assert_eq!(paddle.position().y(), 5.5);
+}
+
+#[test]
+fn non_finite_targets_are_ignored() {
+    let mut paddle = player(5.0);
+
+    for target in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        paddle.update_position(24.0, PaddleCommand::MoveTo(target), DT);
+        assert_eq!(paddle.position().y(), 5.0);
+    }
+}
+
+#[test]
+fn agents_can_not_break_the_game_with_non_finite_targets() {
+    let config = GameConfig::builder().seed(Some(3)).build();
+    let mut env = PongEnv::new(config, AiStyle::Balanced.profile(), 3, 200);
+    let start = env.reset();
+
+    let (observation, _, _) = env.step(PaddleCommand::MoveTo(f64::NAN));
+    assert_eq!(observation.paddle_y, start.paddle_y);
+
+    for _ in 0..100 {
+        let (observation, _, done) = env.step(PaddleCommand::MoveTo(f64::INFINITY));
+        assert!(observation.paddle_y.is_finite());
+        assert!(observation.ball_x.is_finite() && observation.ball_y.is_finite());
+        if done {
+            break;
+        }
+    }
+}
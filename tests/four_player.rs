@@ -0,0 +1,219 @@
+mod common;
+
+use cli_pong::mode::FourPlayer;
+use cli_pong::replay::ReplayFrame;
+use cli_pong::state::{
+    Controller, Edge, GameConfig, GameEvent, GameState, Orientation, PaddleCommand, Player,
+    Position2D,
+};
+use cli_pong::stats::PlayerSide;
+use common::{game_with_mode, press};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+const PADDLE: char = '\u{2588}';
+
+/// Moves the ball to the given position and velocity. Player1 waits in the middle of its edge and
+/// player2 at the bottom of its edge.
+fn place_ball(state: &mut GameState, (x, y): (f64, f64), (vx, vy): (f64, f64)) {
+    let (player1_score, player2_score) = state.scores();
+    state.show_replay_frame(&ReplayFrame {
+        dt: Duration::ZERO,
+        player1_y: 9.0,
+        player2_y: 2.0,
+        ball_x: x,
+        ball_y: y,
+        ball_vx: vx,
+        ball_vy: vy,
+        player1_score,
+        player2_score,
+    });
+}
+
+/// Updates the game without keys until the ball left the field, and returns all events.
+fn play_until_conceded(state: &mut GameState) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+    for _ in 0..200 {
+        events.extend(state.update(HashMap::new(), DT));
+        if events
+            .iter()
+            .any(|event| matches!(event, GameEvent::Conceded(_)))
+        {
+            return events;
+        }
+    }
+
+    panic!("the ball never left the field: {events:?}");
+}
+
+fn horizontal_player(x: f64) -> Player {
+    Player::new(
+        1,
+        1,
+        Controller::External(PaddleCommand::Stay),
+        Position2D::new(x, 0.0),
+        10.0,
+    )
+    .with_orientation(Orientation::Horizontal)
+}
+
+#[test]
+fn horizontal_paddles_move_along_their_edge() {
+    let mut player = horizontal_player(30.0);
+
+    player.update_position(60.0, PaddleCommand::Up, Duration::from_millis(500));
+    assert_eq!(player.position().x(), 35.0);
+    assert_eq!(player.position().y(), 0.0);
+
+    player.update_position(60.0, PaddleCommand::MoveTo(100.0), Duration::from_secs(10));
+    assert_eq!(player.position().x(), 59.0);
+
+    player.update_position(60.0, PaddleCommand::Down, Duration::from_secs(10));
+    assert_eq!(player.position().x(), 1.0);
+}
+
+#[test]
+fn horizontal_paddles_cover_the_cells_of_their_row() {
+    let player = horizontal_player(30.0);
+
+    assert!(player.collides_with(Position2D::new(29.0, 0.0)));
+    assert!(player.collides_with(Position2D::new(31.0, 0.0)));
+    assert!(!player.collides_with(Position2D::new(32.0, 0.0)));
+    assert!(!player.collides_with(Position2D::new(30.0, 1.0)));
+}
+
+#[test]
+fn four_player_games_have_paddles_at_every_edge() {
+    let state = game_with_mode(4, FourPlayer);
+
+    for edge in Edge::ALL {
+        assert!(state.edge_player(edge).is_some(), "{edge:?}");
+    }
+    let top = state.edge_player(Edge::Top).unwrap();
+    assert_eq!(top.orientation(), Orientation::Horizontal);
+    assert_eq!(top.position().y(), state.config().height as f64);
+    assert!(GameState::new(GameConfig::default())
+        .edge_player(Edge::Top)
+        .is_none());
+}
+
+#[test]
+fn ball_bounces_off_the_bottom_paddle() {
+    let mut state = game_with_mode(4, FourPlayer);
+    place_ball(&mut state, (30.0, 2.0), (0.0, -10.0));
+
+    let mut events = Vec::new();
+    for _ in 0..6 {
+        events.extend(state.update(HashMap::new(), DT));
+    }
+
+    assert!(events.contains(&GameEvent::EdgeHit(Edge::Bottom)));
+    assert!(state.ball().get_velocity().vy() > 0.0);
+    assert_eq!(state.edge_scores(), [0, 0, 0, 0]);
+}
+
+#[test]
+fn goals_count_for_the_player_who_touched_the_ball_last() {
+    let mut state = game_with_mode(4, FourPlayer);
+    // the ball is returned by player4 and flies past player3 at the top
+    place_ball(&mut state, (30.0, 2.0), (10.0, -15.0));
+
+    let events = play_until_conceded(&mut state);
+
+    assert!(events.contains(&GameEvent::EdgeHit(Edge::Bottom)));
+    assert!(events.contains(&GameEvent::Conceded(Edge::Top)));
+    assert!(events.contains(&GameEvent::EdgeGoal(Edge::Bottom)));
+    assert_eq!(state.edge_scores(), [0, 0, 0, 1]);
+}
+
+#[test]
+fn goals_of_player1_are_reported_as_goals_of_the_left_side() {
+    let mut state = game_with_mode(4, FourPlayer);
+    place_ball(&mut state, (3.0, 9.0), (-15.0, 0.0));
+
+    let events = play_until_conceded(&mut state);
+
+    assert!(events.contains(&GameEvent::Hit(PlayerSide::Left)));
+    assert!(events.contains(&GameEvent::Conceded(Edge::Right)));
+    assert!(events.contains(&GameEvent::Goal(PlayerSide::Left)));
+    assert_eq!(state.edge_scores(), [1, 0, 0, 0]);
+}
+
+#[test]
+fn balls_nobody_touched_score_no_goal() {
+    let mut state = game_with_mode(4, FourPlayer);
+    place_ball(&mut state, (10.0, 9.0), (0.0, -15.0));
+
+    let events = play_until_conceded(&mut state);
+
+    assert!(events.contains(&GameEvent::Conceded(Edge::Bottom)));
+    assert!(!events
+        .iter()
+        .any(|event| matches!(event, GameEvent::Goal(_) | GameEvent::EdgeGoal(_))));
+    assert_eq!(state.edge_scores(), [0, 0, 0, 0]);
+}
+
+#[test]
+fn top_paddle_moves_with_its_keys() {
+    let mut state = game_with_mode(4, FourPlayer);
+    let x = |state: &GameState| state.edge_player(Edge::Top).unwrap().position().x();
+    let start = x(&state);
+
+    state.update(press(KeyCode::Char('x'), KeyModifiers::NONE), DT);
+    assert!(x(&state) > start);
+    state.update(press(KeyCode::Char('z'), KeyModifiers::NONE), DT);
+    state.update(press(KeyCode::Char('z'), KeyModifiers::NONE), DT);
+    assert!(x(&state) < start);
+}
+
+#[test]
+fn computer_moves_the_bottom_paddle_towards_the_ball() {
+    let mut state = game_with_mode(4, FourPlayer);
+    state.update(press(KeyCode::F(4), KeyModifiers::NONE), DT);
+    assert!(matches!(
+        state.edge_player(Edge::Bottom).unwrap().controller(),
+        Controller::Ai(_)
+    ));
+
+    place_ball(&mut state, (45.0, 15.0), (0.0, -4.0));
+    for _ in 0..30 {
+        state.update(HashMap::new(), DT);
+    }
+
+    assert!(state.edge_player(Edge::Bottom).unwrap().position().x() > 40.0);
+}
+
+#[test]
+fn top_and_bottom_edges_are_drawn_open_with_the_paddles() {
+    let grid = game_with_mode(4, FourPlayer).render_to_grid();
+    let paddle_cells = |row: &Vec<char>| row.iter().filter(|&&cell| cell == PADDLE).count();
+
+    // the upper and the lower edge hold the three cells of the horizontal paddles, not a wall
+    let rows: Vec<usize> = grid.iter().map(paddle_cells).collect();
+    assert!(rows.iter().all(|&cells| cells <= 3), "{rows:?}");
+    assert_eq!(rows.iter().filter(|&&cells| cells == 3).count(), 2);
+}
+
+#[test]
+fn hud_shows_the_goals_of_all_four_players() {
+    let header: String = game_with_mode(4, FourPlayer).render_to_grid()[1]
+        .iter()
+        .collect();
+
+    assert!(header.contains("player1 0"), "{header}");
+    assert!(header.contains("player4 0"), "{header}");
+}
+
+#[test]
+fn edge_players_come_with_the_arena_of_the_mode() {
+    let plain = GameState::new(GameConfig::builder().seed(Some(4)).build());
+    let state = game_with_mode(4, FourPlayer);
+
+    assert!(!plain.arena().edge_players);
+    assert!(plain.edge_player(Edge::Top).is_none());
+    assert!(state.arena().edge_players);
+    assert!(state.edge_player(Edge::Top).is_some());
+    assert!(state.edge_player(Edge::Bottom).is_some());
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use cli_pong::ai::AiStyle;
+use cli_pong::state::{
+    Arena, Ball, GameConfig, GameState, Position2D, Velocity2D, GRAVITY_BOUNCE_DAMPING,
+};
+use cli_pong::stats::PlayerSide;
+use common::{out_of_reach_players, still_player};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+const GRAVITY: f64 = 20.0;
+
+fn config() -> GameConfig {
+    GameConfig::builder().gravity(GRAVITY).build()
+}
+
+#[test]
+fn gravity_bends_the_flight_into_an_arc() {
+    let (player1, player2) = out_of_reach_players(9.0);
+    let mut ball = Ball::with_velocity(Position2D::new(30.0, 9.0), Velocity2D::new(10.0, 0.0));
+    let mut straight = ball.clone();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    ball.update_position(&config(), &player1, &player2, &mut rng, DT);
+    straight.update_position(&GameConfig::default(), &player1, &player2, &mut rng, DT);
+
+    let vy = ball.get_velocity().vy();
+    assert!(
+        (vy + GRAVITY * DT.as_secs_f64() * 1.003).abs() < 1e-9,
+        "{vy}"
+    );
+    assert_eq!(straight.get_velocity().vy(), 0.0);
+    // the ball keeps its horizontal speed
+    assert_eq!(ball.get_velocity().vx(), straight.get_velocity().vx());
+}
+
+#[test]
+fn bounces_under_gravity_are_damped() {
+    let (player1, player2) = out_of_reach_players(9.0);
+    let mut rng = StdRng::seed_from_u64(0);
+    let bounce = |config: &GameConfig, rng: &mut StdRng| {
+        let mut ball = Ball::with_velocity(Position2D::new(30.0, 0.2), Velocity2D::new(0.0, -10.0));
+        ball.update_position(config, &player1, &player2, rng, DT);
+        ball.get_velocity().vy()
+    };
+
+    let free = bounce(&GameConfig::default(), &mut rng);
+    let damped = bounce(&config(), &mut rng);
+
+    assert!(free > 0.0 && damped > 0.0);
+    // gravity pulls the ball down before the bounce and slows it down after it
+    assert!(damped < free * GRAVITY_BOUNCE_DAMPING);
+}
+
+#[test]
+fn bouncing_ball_loses_energy() {
+    let (player1, player2) = out_of_reach_players(9.0);
+    let config = config();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut ball = Ball::with_velocity(Position2D::new(30.0, 9.0), Velocity2D::new(0.0, 0.0));
+    let energy =
+        |ball: &Ball| ball.get_velocity().vy().powi(2) / 2.0 + GRAVITY * ball.get_position().y();
+    let start = energy(&ball);
+
+    for _ in 0..1200 {
+        ball.update_position(&config, &player1, &player2, &mut rng, DT);
+        assert!(ball.get_position().y() <= 9.0 + 1e-9);
+    }
+
+    assert!(energy(&ball) < start / 2.0, "{} {start}", energy(&ball));
+}
+
+#[test]
+fn computer_players_follow_the_arcs() {
+    let mut state = GameState::new(
+        GameConfig::builder()
+            .seed(Some(4))
+            .gravity(GRAVITY)
+            .player1_ai(Some(AiStyle::Balanced.profile()))
+            .player2_ai(Some(AiStyle::Balanced.profile()))
+            .build(),
+    );
+
+    for _ in 0..600 {
+        state.update(HashMap::new(), DT);
+    }
+
+    let returns =
+        state.stats().returns(PlayerSide::Left) + state.stats().returns(PlayerSide::Right);
+    assert!(returns >= 5, "{returns} returns");
+    assert!(state
+        .replay_options()
+        .contains(&("gravity".to_string(), "20".to_string())));
+}
+
+#[test]
+fn predicted_intercept_follows_the_arcs() {
+    let config = config();
+    let paddle = still_player(50.0, 12.0);
+    // the ball falls onto the lower wall before it reaches the paddle
+    let ball = Ball::with_velocity(Position2D::new(30.0, 4.0), Velocity2D::new(20.0, 0.0));
+
+    let predicted = paddle
+        .observe(&ball, &config, &Arena::default())
+        .intercept_y
+        .unwrap();
+    let crossing = ball
+        .predict_crossing(&config, &Arena::default(), 50.0, DT, Duration::from_secs(5))
+        .unwrap();
+
+    assert!(
+        (predicted - crossing.y()).abs() < 0.5,
+        "{predicted} {}",
+        crossing.y()
+    );
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use cli_pong::state::{GameConfig, GameState, Handicap};
+use cli_pong::stats::PlayerSide;
+use common::press_all;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+const PADDLE: char = '\u{2588}';
+
+/// Counts the paddle cells in the left and in the right half of the field. The rows of the walls
+/// are skipped, as the walls are drawn with the same glyph as the paddles.
+fn paddle_cells(state: &GameState) -> (usize, usize) {
+    let grid = state.render_to_grid();
+    let middle = grid[0].len() / 2;
+    let mut cells = (0, 0);
+    for row in grid {
+        let columns: Vec<usize> = (0..row.len()).filter(|&x| row[x] == PADDLE).collect();
+        if columns.len() > 2 {
+            continue;
+        }
+        cells.0 += columns.iter().filter(|&&x| x < middle).count();
+        cells.1 += columns.iter().filter(|&&x| x >= middle).count();
+    }
+
+    cells
+}
+
+#[test]
+fn paddles_take_the_length_of_their_handicap() {
+    let state = GameState::new(
+        GameConfig::builder()
+            .seed(Some(1))
+            .player1_handicap(Handicap {
+                extend: Some(3),
+                ..Handicap::default()
+            })
+            .build(),
+    );
+
+    assert_eq!(state.config().paddle_extents(PlayerSide::Left), (3, 3));
+    assert_eq!(state.config().paddle_extents(PlayerSide::Right), (1, 1));
+    assert_eq!(paddle_cells(&state), (7, 3));
+}
+
+#[test]
+fn faster_paddles_move_further() {
+    let mut state = GameState::new(
+        GameConfig::builder()
+            .seed(Some(2))
+            .paddle_speed(10.0)
+            .player2_handicap(Handicap {
+                paddle_speed: Some(20.0),
+                ..Handicap::default()
+            })
+            .build(),
+    );
+    let start = |side| state.observation(side).paddle_y;
+    let (start1, start2) = (start(PlayerSide::Left), start(PlayerSide::Right));
+
+    let keys = press_all(&[
+        (KeyCode::Char('w'), KeyModifiers::NONE),
+        (KeyCode::Up, KeyModifiers::NONE),
+    ]);
+    for _ in 0..4 {
+        state.update(keys.clone(), DT);
+    }
+
+    let moved1 = (state.observation(PlayerSide::Left).paddle_y - start1).abs();
+    let moved2 = (state.observation(PlayerSide::Right).paddle_y - start2).abs();
+    assert!(moved1 > 0.0);
+    assert!(moved2 > 1.5 * moved1, "{moved1} {moved2}");
+}
+
+#[test]
+fn games_start_with_the_handicap_scores() {
+    let handicap = Handicap {
+        start_score: 3,
+        ..Handicap::default()
+    };
+    let mut state = GameState::new(
+        GameConfig::builder()
+            .seed(Some(3))
+            .score_limit(Some(4))
+            .player1_handicap(handicap)
+            .player2_handicap(handicap)
+            .build(),
+    );
+    assert_eq!(state.scores(), (3, 3));
+
+    // the first goal of either player reaches the score limit
+    while !state.is_over() {
+        state.update(HashMap::new(), DT);
+    }
+    let (player1, player2) = state.scores();
+    assert_eq!(player1.max(player2), 4);
+    assert_eq!(player1.min(player2), 3);
+
+    state.update(
+        press_all(&[(KeyCode::Char('r'), KeyModifiers::CONTROL)]),
+        DT,
+    );
+    assert_eq!(state.scores(), (3, 3));
+}
+
+#[test]
+fn replays_describe_the_handicaps() {
+    let option = |state: &GameState, name: &str| {
+        state
+            .replay_options()
+            .into_iter()
+            .find(|(option, _)| option == name)
+            .map(|(_, value)| value)
+    };
+    let even = GameState::new(GameConfig::default());
+    let handicapped = GameState::new(
+        GameConfig::builder()
+            .player1_handicap(Handicap {
+                extend: Some(2),
+                paddle_speed: Some(12.5),
+                start_score: 0,
+            })
+            .player2_handicap(Handicap {
+                start_score: 5,
+                ..Handicap::default()
+            })
+            .build(),
+    );
+
+    assert!(even
+        .replay_options()
+        .iter()
+        .all(|(name, _)| !name.starts_with("p1-") && !name.starts_with("p2-")));
+    assert_eq!(option(&handicapped, "p1-extend").as_deref(), Some("2"));
+    assert_eq!(option(&handicapped, "p1-speed").as_deref(), Some("12.5"));
+    assert_eq!(option(&handicapped, "p1-start-score"), None);
+    assert_eq!(option(&handicapped, "p2-start-score").as_deref(), Some("5"));
+    assert_eq!(option(&handicapped, "p2-extend"), None);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use cli_pong::hud::{HudAlign, HudSlot, HudSpan, HudWidget, RallyCounter, HUD_ROWS};
+use cli_pong::state::GameState;
+use common::game;
+
+fn row(state: &GameState, row: usize) -> String {
+    state.render_to_grid()[row].iter().collect()
+}
+
+/// A widget that always shows the same text.
+struct Label(&'static str);
+
+impl HudWidget for Label {
+    fn spans(&self, _state: &GameState) -> Vec<HudSpan> {
+        match self.0.is_empty() {
+            true => Vec::new(),
+            false => vec![HudSpan::plain(self.0)],
+        }
+    }
+}
+
+#[test]
+fn standard_hud_shows_the_score() {
+    let state = game(3);
+
+    assert!(row(&state, 1).starts_with("Goals of player1: 0,  Goals of player2: 0"));
+}
+
+#[test]
+fn widgets_are_aligned_within_their_row() {
+    let mut state = game(3);
+    state
+        .hud_mut()
+        .add(HudSlot::new(0, HudAlign::Right), Label("right"));
+    state
+        .hud_mut()
+        .add(HudSlot::new(2, HudAlign::Center), Label("center"));
+
+    let top = row(&state, 0);
+    assert!(top.trim_end().ends_with("right"));
+    assert_eq!(top.trim_end().len(), top.chars().count());
+
+    let bottom = row(&state, 2);
+    let start = bottom.find("center").unwrap();
+    let end = bottom.chars().count() - start - "center".len();
+    assert!(start.abs_diff(end) <= 1);
+}
+
+#[test]
+fn widgets_of_a_slot_are_drawn_next_to_each_other() {
+    let mut state = game(3);
+    let slot = HudSlot::new(2, HudAlign::Left);
+    state.hud_mut().add(slot, Label("first"));
+    state.hud_mut().add(slot, Label(""));
+    state.hud_mut().add(slot, RallyCounter);
+
+    assert!(row(&state, 2).starts_with("first  Rally: 0"));
+}
+
+#[test]
+fn slots_below_the_hud_rows_are_not_drawn() {
+    let mut state = game(3);
+    let before = state.render_to_grid();
+    state
+        .hud_mut()
+        .add(HudSlot::new(HUD_ROWS, HudAlign::Left), Label("hidden"));
+
+    assert_eq!(state.render_to_grid(), before);
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use cli_pong::ai::{AiController, AiProfile, AiStyle, AimErrorDistribution, Observation};
+use cli_pong::state::PaddleCommand;
+use common::temp_dir;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fs;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+/// A ball in the middle of the field that flies straight towards the paddle on the right.
+fn observation(paddle_y: f64) -> Observation {
+    Observation {
+        paddle_x: 79.0,
+        paddle_y,
+        ball_x: 60.0,
+        ball_y: 12.0,
+        ball_vx: 10.0,
+        ball_vy: 0.0,
+        ball_ay: 0.0,
+        intercept_y: Some(12.0),
+        field_width: 80.0,
+        field_height: 24.0,
+    }
+}
+
+fn profile(reaction_ms: u64, error_sigma: f64) -> AiProfile {
+    let mut profile = AiStyle::Balanced
+        .profile()
+        .humanized(Some(Duration::from_millis(reaction_ms)), Some(error_sigma));
+    profile.reaction_distance = 1.0;
+    profile.target_offsets = Default::default();
+
+    profile
+}
+
+/// Counts how often an AI on a paddle `distance` cells above the ball wants to move further up.
+fn aims_above(profile: &AiProfile, distance: f64) -> usize {
+    (0..500)
+        .filter(|&seed| {
+            let mut ai = AiController::new(*profile, StdRng::seed_from_u64(seed));
+            ai.command(&observation(12.0 + distance), DT) == PaddleCommand::Up
+        })
+        .count()
+}
+
+#[test]
+fn humanized_profile_uses_gaussian_errors() {
+    let profile = AiStyle::Aggressive
+        .profile()
+        .humanized(Some(Duration::from_millis(250)), Some(1.5));
+
+    assert_eq!(profile.reaction_time, 0.25);
+    assert_eq!(profile.aim_error, 1.5);
+    assert_eq!(
+        profile.aim_error_distribution,
+        AimErrorDistribution::Gaussian
+    );
+}
+
+#[test]
+fn humanized_profile_keeps_what_is_not_given() {
+    let balanced = AiStyle::Balanced.profile();
+    let profile = balanced.humanized(None, None);
+
+    assert_eq!(profile.reaction_time, balanced.reaction_time);
+    assert_eq!(profile.aim_error, balanced.aim_error);
+    assert_eq!(
+        profile.aim_error_distribution,
+        AimErrorDistribution::Uniform
+    );
+}
+
+#[test]
+fn ai_only_rereads_the_ball_after_its_reaction_time() {
+    let mut ai = AiController::new(profile(375, 0.0), StdRng::seed_from_u64(1));
+
+    assert_eq!(ai.command(&observation(6.0), DT), PaddleCommand::Up);
+
+    // the paddle passed the ball, but the AI still aims for where it saw the ball last
+    for _ in 0..7 {
+        assert_eq!(ai.command(&observation(18.0), DT), PaddleCommand::Down);
+    }
+    let mut moved = observation(18.0);
+    moved.ball_y = 20.0;
+    moved.intercept_y = Some(20.0);
+    assert_eq!(ai.command(&moved, DT), PaddleCommand::Up);
+}
+
+#[test]
+fn gaussian_errors_sometimes_miss_by_more_than_sigma() {
+    let gaussian = profile(100, 2.0);
+    let mut uniform = gaussian;
+    uniform.aim_error_distribution = AimErrorDistribution::Uniform;
+    // the AI only moves up if it misses the ball by more than 2 cells
+    let distance = 2.0 - uniform.tolerance;
+
+    assert_eq!(aims_above(&uniform, distance), 0);
+    let misses = aims_above(&gaussian, distance);
+    assert!((40..=120).contains(&misses), "{misses} of 500 aims missed");
+}
+
+#[test]
+fn profiles_without_a_distribution_use_uniform_errors() {
+    let content = toml::to_string(&AiStyle::Balanced.profile())
+        .unwrap()
+        .replace("aim_error_distribution = \"uniform\"\n", "");
+    assert!(!content.contains("aim_error_distribution"));
+
+    let profile: AiProfile = toml::from_str(&content).unwrap();
+    assert_eq!(
+        profile.aim_error_distribution,
+        AimErrorDistribution::Uniform
+    );
+}
+
+#[test]
+fn hand_written_profiles_load_without_target_offsets() {
+    let path = temp_dir("hand_written_profiles_load_without_target_offsets").join("ai.toml");
+    fs::write(
+        &path,
+        "reaction_time = 0.2\nreaction_distance = 0.6\naim_error = 1.5\n\
+         predicts_bounces = true\nrecovers_to_center = false\ntolerance = 0.5\n",
+    )
+    .unwrap();
+
+    let profile = AiProfile::load(&path).unwrap();
+
+    assert_eq!(profile.reaction_time, 0.2);
+    assert!(profile.predicts_bounces);
+    assert!(profile
+        .target_offsets
+        .iter()
+        .flatten()
+        .all(|&offset| offset == 0.0));
+}
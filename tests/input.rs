@@ -0,0 +1,98 @@
+use cli_pong::input::{KeyTracker, PolledInput, MAX_EVENTS_PER_FRAME};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind,
+};
+
+fn key(character: char) -> Event {
+    Event::Key(KeyEvent::new(KeyCode::Char(character), KeyModifiers::NONE))
+}
+
+fn release(character: char) -> Event {
+    Event::Key(KeyEvent::new_with_kind(
+        KeyCode::Char(character),
+        KeyModifiers::NONE,
+        KeyEventKind::Release,
+    ))
+}
+
+fn scroll(kind: MouseEventKind) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        column: 0,
+        row: 0,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+#[test]
+fn pasted_text_is_dropped() {
+    let input = PolledInput::collect([key('w'), Event::Paste("s".repeat(10_000)), key('s')]);
+
+    assert_eq!(input.pressed_keys.len(), 2);
+    assert_eq!(input.dropped_events, 1);
+}
+
+#[test]
+fn unused_events_are_dropped() {
+    let input = PolledInput::collect([
+        Event::FocusLost,
+        Event::Resize(80, 24),
+        key('w'),
+        Event::FocusGained,
+    ]);
+
+    assert!(input.pressed_keys.contains_key(&KeyCode::Char('w')));
+    assert_eq!(input.dropped_events, 3);
+    assert!(input.first_key_received.is_some());
+}
+
+#[test]
+fn a_flood_of_events_is_cut_off() {
+    let flood = "pasted text without bracketed paste"
+        .chars()
+        .cycle()
+        .take(100_000)
+        .map(key);
+    let input = PolledInput::collect(std::iter::once(key('w')).chain(flood));
+
+    assert!(input.pressed_keys.contains_key(&KeyCode::Char('w')));
+    assert_eq!(input.dropped_events, 100_001 - MAX_EVENTS_PER_FRAME);
+}
+
+#[test]
+fn wheel_notches_add_up() {
+    let input = PolledInput::collect([
+        scroll(MouseEventKind::ScrollUp),
+        scroll(MouseEventKind::ScrollUp),
+        scroll(MouseEventKind::Moved),
+        scroll(MouseEventKind::ScrollDown),
+        scroll(MouseEventKind::ScrollUp),
+    ]);
+
+    assert_eq!(input.wheel_notches, 2);
+    assert_eq!(input.dropped_events, 1);
+    assert!(input.pressed_keys.is_empty());
+}
+
+#[test]
+fn key_tapped_within_a_frame_counts_as_pressed() {
+    let input = PolledInput::collect([key('r'), release('r')]);
+
+    let event = input.pressed_keys[&KeyCode::Char('r')];
+    assert_eq!(event.kind, KeyEventKind::Press);
+}
+
+#[test]
+fn released_keys_end_the_hold_and_trigger_nothing() {
+    let mut tracker = KeyTracker::new(5, [KeyCode::Char('w')]);
+    tracker.update(PolledInput::collect([key('w')]).pressed_keys);
+    assert!(tracker
+        .update(PolledInput::collect([]).pressed_keys)
+        .contains_key(&KeyCode::Char('w')));
+
+    let pressed = tracker.update(PolledInput::collect([release('w'), release('r')]).pressed_keys);
+    assert!(pressed.is_empty());
+    assert!(tracker
+        .update(PolledInput::collect([]).pressed_keys)
+        .is_empty());
+}
@@ -0,0 +1,190 @@
+mod common;
+
+use cli_pong::browser::{BrowserAction, ReplayBrowser};
+use cli_pong::library::{ReplayEntry, ReplayLibrary};
+use cli_pong::replay::{ReplayFrame, ReplayRecorder};
+use cli_pong::utils::format_utc;
+use common::{key, temp_dir};
+use crossterm::event::KeyCode;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Records a replay of the given number of frames that ends with the given score.
+fn record(path: &Path, frames: usize, score: (usize, usize)) {
+    let options = [
+        ("player1".to_string(), "human".to_string()),
+        ("player2".to_string(), "computer".to_string()),
+    ];
+    let mut recorder = ReplayRecorder::create(path, 60, 18, &options).unwrap();
+    for _ in 0..frames {
+        recorder
+            .record(ReplayFrame {
+                dt: Duration::from_millis(100),
+                player1_y: 9.0,
+                player2_y: 9.0,
+                ball_x: 30.0,
+                ball_y: 9.0,
+                ball_vx: 10.0,
+                ball_vy: 0.0,
+                player1_score: score.0,
+                player2_score: score.1,
+            })
+            .unwrap();
+    }
+    recorder.finish().unwrap();
+}
+
+fn type_text(browser: &mut ReplayBrowser, text: &str) {
+    for character in text.chars() {
+        browser.handle_key(key(KeyCode::Char(character)));
+    }
+}
+
+#[test]
+fn entries_describe_the_replays() {
+    let dir = temp_dir("entries");
+    record(&dir.join("final.replay"), 650, (11, 7));
+    fs::write(dir.join("notes.txt"), "not a replay").unwrap();
+
+    let entries = ReplayLibrary::new(&dir).entries().unwrap();
+
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.name, "final");
+    assert_eq!(
+        entry.players,
+        Some(("human".to_string(), "computer".to_string()))
+    );
+    assert_eq!(entry.score, (11, 7));
+    assert_eq!(entry.duration, Duration::from_secs(65));
+}
+
+#[test]
+fn missing_library_is_empty() {
+    let library = ReplayLibrary::new(temp_dir("missing").join("replays"));
+
+    assert!(library.entries().unwrap().is_empty());
+}
+
+#[test]
+fn new_replay_paths_do_not_collide() {
+    let library = ReplayLibrary::new(temp_dir("new").join("replays"));
+
+    let first = library.new_replay_path().unwrap();
+    record(&first, 1, (0, 0));
+    let second = library.new_replay_path().unwrap();
+
+    assert_ne!(first, second);
+    assert_eq!(first.extension().unwrap(), "replay");
+}
+
+#[test]
+fn rename_keeps_the_extension_and_refuses_other_files() {
+    let dir = temp_dir("rename");
+    record(&dir.join("a.replay"), 1, (0, 0));
+    record(&dir.join("b.replay"), 1, (0, 0));
+    let library = ReplayLibrary::new(&dir);
+    let entry = ReplayEntry::read(&dir.join("a.replay")).unwrap();
+
+    assert!(library.rename(&entry, "b").is_err());
+    assert!(library.rename(&entry, "../outside").is_err());
+    let renamed = library.rename(&entry, "final v2.0").unwrap();
+
+    assert_eq!(renamed, dir.join("final v2.0.replay"));
+    assert!(renamed.exists());
+    assert!(!dir.join("a.replay").exists());
+}
+
+#[test]
+fn export_copies_into_a_directory_without_overwriting() {
+    let dir = temp_dir("export");
+    let target = temp_dir("export-target");
+    record(&dir.join("a.replay"), 3, (1, 0));
+    let library = ReplayLibrary::new(&dir);
+    let entry = ReplayEntry::read(&dir.join("a.replay")).unwrap();
+
+    let copy = library.export(&entry, &target).unwrap();
+
+    assert_eq!(copy, target.join("a.replay"));
+    assert_eq!(fs::read(&copy).unwrap(), fs::read(&entry.path).unwrap());
+    assert!(library.export(&entry, &target).is_err());
+}
+
+#[test]
+fn browser_plays_the_selected_replay() {
+    let dir = temp_dir("browser-play");
+    record(&dir.join("a.replay"), 1, (0, 0));
+    record(&dir.join("b.replay"), 1, (0, 0));
+    let mut browser = ReplayBrowser::open(ReplayLibrary::new(&dir)).unwrap();
+
+    browser.handle_key(key(KeyCode::Down));
+    browser.handle_key(key(KeyCode::Down));
+    let selected = browser.selected().unwrap().path.clone();
+
+    assert_eq!(browser.entries()[1].path, selected);
+    assert_eq!(
+        browser.handle_key(key(KeyCode::Enter)),
+        BrowserAction::Play(selected)
+    );
+    assert_eq!(browser.handle_key(key(KeyCode::Esc)), BrowserAction::Quit);
+}
+
+#[test]
+fn browser_renames_and_deletes_replays() {
+    let dir = temp_dir("browser-manage");
+    record(&dir.join("old.replay"), 1, (0, 0));
+    let mut browser = ReplayBrowser::open(ReplayLibrary::new(&dir)).unwrap();
+
+    browser.handle_key(key(KeyCode::Char('n')));
+    for _ in 0.."old".len() {
+        browser.handle_key(key(KeyCode::Backspace));
+    }
+    type_text(&mut browser, "new");
+    browser.handle_key(key(KeyCode::Enter));
+
+    assert_eq!(browser.message(), Some("Renamed to new.replay"));
+    assert_eq!(browser.entries()[0].name, "new");
+
+    browser.handle_key(key(KeyCode::Char('d')));
+    browser.handle_key(key(KeyCode::Char('n')));
+    assert_eq!(browser.entries().len(), 1);
+
+    browser.handle_key(key(KeyCode::Char('d')));
+    browser.handle_key(key(KeyCode::Char('y')));
+    assert!(browser.entries().is_empty());
+    assert!(!dir.join("new.replay").exists());
+}
+
+#[test]
+fn browser_lists_the_details_of_every_replay() {
+    let dir = temp_dir("browser-draw");
+    record(&dir.join("final.replay"), 1250, (11, 9));
+    let browser = ReplayBrowser::open(ReplayLibrary::new(&dir)).unwrap();
+
+    let rows: Vec<String> = browser
+        .compose_frame(100, 10)
+        .chars()
+        .iter()
+        .map(|row| row.iter().collect())
+        .collect();
+
+    let line = rows.iter().find(|row| row.starts_with("final")).unwrap();
+    assert!(line.contains("human vs computer"));
+    assert!(line.contains("11:9"));
+    assert!(line.contains("2:05"));
+    assert!(rows[9].starts_with("enter: play"));
+}
+
+#[test]
+fn dates_are_formatted_in_utc() {
+    assert_eq!(format_utc(UNIX_EPOCH), "1970-01-01 00:00");
+    assert_eq!(
+        format_utc(UNIX_EPOCH + Duration::from_secs(951_782_400 + 3_661)),
+        "2000-02-29 01:01"
+    );
+    assert_eq!(
+        format_utc(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        "2023-11-14 22:13"
+    );
+}
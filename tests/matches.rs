@@ -0,0 +1,228 @@
+//! Complete headless matches played through the public simulation API. They pin down how whole
+//! games play out, so a refactoring that changes the behavior of the game shows up here.
+
+mod common;
+
+use cli_pong::ai::AiStyle;
+use cli_pong::scoring::MatchFormat;
+use cli_pong::state::{step, GameConfig, GameEvent, GameState};
+use cli_pong::stats::PlayerSide;
+use common::{ai_match, press_chars};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+/// The number of updates after which a match counts as stuck.
+const MAX_UPDATES: usize = 50_000;
+
+/// The goals that end the matches of these tests.
+const SCORE_LIMIT: usize = 5;
+
+/// A complete match: the state once it was over and all events in the order they happened.
+struct PlayedMatch {
+    state: GameState,
+    events: Vec<GameEvent>,
+    updates: usize,
+}
+
+/// Plays the game until it is over. The script returns the keys pressed in the given update.
+fn play(config: GameConfig, script: impl Fn(usize) -> Vec<char>) -> PlayedMatch {
+    let mut state = GameState::new(config);
+    let mut events = Vec::new();
+
+    for update in 0..MAX_UPDATES {
+        if state.is_over() {
+            return PlayedMatch {
+                state,
+                events,
+                updates: update,
+            };
+        }
+        let (next, new_events) = step(&state, press_chars(&script(update)), DT);
+        state = next;
+        events.extend(new_events);
+    }
+
+    panic!("the match was not over after {MAX_UPDATES} updates");
+}
+
+fn goals(events: &[GameEvent], side: PlayerSide) -> usize {
+    events
+        .iter()
+        .filter(|&&event| event == GameEvent::Goal(side))
+        .count()
+}
+
+#[test]
+fn ai_matches_end_at_the_score_limit() {
+    for seed in 1..=8 {
+        let played = play(ai_match(seed, SCORE_LIMIT), |_| Vec::new());
+        let winner = played.state.winner().unwrap();
+        let (player1_score, player2_score) = played.state.scores();
+
+        let (winner_score, loser_score) = match winner {
+            PlayerSide::Left => (player1_score, player2_score),
+            PlayerSide::Right => (player2_score, player1_score),
+        };
+        assert_eq!(winner_score, SCORE_LIMIT, "seed {seed}");
+        assert!(loser_score < SCORE_LIMIT, "seed {seed}");
+        assert_eq!(goals(&played.events, PlayerSide::Left), player1_score);
+        assert_eq!(goals(&played.events, PlayerSide::Right), player2_score);
+        assert_eq!(played.events.last(), Some(&GameEvent::Won(winner)));
+        assert_eq!(
+            played
+                .events
+                .iter()
+                .filter(|event| matches!(event, GameEvent::Won(_)))
+                .count(),
+            1
+        );
+    }
+}
+
+#[test]
+fn paddles_take_turns_in_returning_the_ball() {
+    for seed in 1..=8 {
+        let played = play(ai_match(seed, SCORE_LIMIT), |_| Vec::new());
+        let mut last_hitter = None;
+
+        for event in played.events {
+            match event {
+                GameEvent::Hit(side) => {
+                    assert_ne!(last_hitter, Some(side), "seed {seed}");
+                    last_hitter = Some(side);
+                }
+                GameEvent::Goal(_) => last_hitter = None,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[test]
+fn matches_with_the_same_seed_play_out_the_same() {
+    for seed in [3, 17, 2024] {
+        let first = play(ai_match(seed, SCORE_LIMIT), |_| Vec::new());
+        let second = play(ai_match(seed, SCORE_LIMIT), |_| Vec::new());
+
+        assert_eq!(first.events, second.events);
+        assert_eq!(first.updates, second.updates);
+        assert_eq!(first.state.state_hash(), second.state.state_hash());
+    }
+
+    let hashes: Vec<u64> = (1..=4)
+        .map(|seed| {
+            play(ai_match(seed, SCORE_LIMIT), |_| Vec::new())
+                .state
+                .state_hash()
+        })
+        .collect();
+    for (index, hash) in hashes.iter().enumerate() {
+        assert!(!hashes[index + 1..].contains(hash));
+    }
+}
+
+#[test]
+fn state_hash_follows_every_update() {
+    let mut state = GameState::new(ai_match(9, SCORE_LIMIT));
+    let mut hashes = vec![state.state_hash()];
+
+    for _ in 0..20 {
+        state.update(HashMap::new(), DT);
+        hashes.push(state.state_hash());
+    }
+
+    assert!(hashes.windows(2).all(|pair| pair[0] != pair[1]));
+    assert_eq!(state.clone().state_hash(), state.state_hash());
+}
+
+#[test]
+fn a_paddle_held_at_the_top_loses_the_match() {
+    let config = GameConfig::builder()
+        .seed(Some(11))
+        .player2_ai(Some(AiStyle::Balanced.profile()))
+        .score_limit(Some(SCORE_LIMIT))
+        .build();
+
+    let played = play(config, |_| vec!['w']);
+
+    assert_eq!(played.state.winner(), Some(PlayerSide::Right));
+    assert_eq!(played.state.scores().1, SCORE_LIMIT);
+}
+
+#[test]
+fn scripted_restarts_serve_the_point_again() {
+    let restarts = [10, 40, 90];
+    let played = play(ai_match(5, SCORE_LIMIT), |update| {
+        if restarts.contains(&update) {
+            vec!['r']
+        } else {
+            Vec::new()
+        }
+    });
+
+    let restarted = played
+        .events
+        .iter()
+        .filter(|&&event| event == GameEvent::PointRestarted)
+        .count();
+    assert_eq!(restarted, restarts.len());
+    assert!(played.state.is_over());
+}
+
+#[test]
+fn ai_best_of_three_is_won_with_two_games() {
+    let format = MatchFormat {
+        points_to_win: 3,
+        best_of: 3,
+    };
+    let config = GameConfig::builder()
+        .seed(Some(21))
+        .player1_ai(Some(AiStyle::Balanced.profile()))
+        .player2_ai(Some(AiStyle::Aggressive.profile()))
+        .match_format(Some(format))
+        .build();
+
+    let played = play(config, |_| Vec::new());
+    let winner = played.state.winner().unwrap();
+    let (games1, games2) = played.state.match_score().unwrap().games();
+
+    let games_won = |side| {
+        played
+            .events
+            .iter()
+            .filter(|&&event| event == GameEvent::GameWon(side))
+            .count()
+    };
+    let winner_games = match winner {
+        PlayerSide::Left => games1,
+        PlayerSide::Right => games2,
+    };
+    assert_eq!(winner_games, format.games_to_win());
+    // the game that decides the match is reported as the won match instead
+    assert_eq!(
+        games_won(PlayerSide::Left) + games_won(PlayerSide::Right) + 1,
+        games1 + games2
+    );
+}
+
+/// The final scores, the length and the fingerprint of the first AI matches. A change of the
+/// physics, the AI or the random numbers changes them, so they have to be updated along with such
+/// a change on purpose.
+#[test]
+fn ai_matches_end_as_recorded() {
+    let recorded = [
+        (1, (5, 0), 2457, 0xb163_e984_24fc_5bab),
+        (2, (5, 2), 5922, 0x5946_6a59_6c3f_87f1),
+        (3, (5, 1), 5497, 0x5d8d_b3d9_b015_97b7),
+    ];
+
+    for (seed, scores, updates, hash) in recorded {
+        let played = play(ai_match(seed, SCORE_LIMIT), |_| Vec::new());
+
+        assert_eq!(played.state.scores(), scores, "seed {seed}");
+        assert_eq!(played.updates, updates, "seed {seed}");
+        assert_eq!(played.state.state_hash(), hash, "seed {seed}");
+    }
+}
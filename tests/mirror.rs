@@ -0,0 +1,110 @@
+mod common;
+
+use cli_pong::mutator::{MirrorSchedule, Mutator, Mutators};
+use cli_pong::state::{GameConfig, GameEvent, GameState};
+use cli_pong::stats::PlayerSide;
+use common::press;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+fn mirrored_game(seed: u64) -> GameState {
+    GameState::new(
+        GameConfig::builder()
+            .seed(Some(seed))
+            .mutators(Mutators::new([Mutator::Mirror]))
+            .mirror_interval(Duration::from_secs(1))
+            .build(),
+    )
+}
+
+/// Holds the up key of player1 for one update and returns how far its paddle moved.
+fn move_player1_up(state: &mut GameState) -> f64 {
+    let before = state.observation(PlayerSide::Left).paddle_y;
+    state.update(press(KeyCode::Char('w'), KeyModifiers::NONE), DT);
+    state.observation(PlayerSide::Left).paddle_y - before
+}
+
+#[test]
+fn mirror_moves_from_player_to_player() {
+    let mut schedule = MirrorSchedule::new(Duration::from_secs(10));
+
+    assert!(!schedule.advance(Duration::from_secs(9)));
+    assert_eq!(schedule.mirrored(), None);
+    assert_eq!(schedule.time_to_switch(), Duration::from_secs(1));
+    assert!(schedule.advance(Duration::from_secs(1)));
+    assert_eq!(schedule.mirrored(), Some(PlayerSide::Left));
+    assert!(schedule.advance(Duration::from_secs(10)));
+    assert_eq!(schedule.mirrored(), Some(PlayerSide::Right));
+    // two switches at once end where they started
+    assert!(!schedule.advance(Duration::from_secs(20)));
+    assert_eq!(schedule.mirrored(), Some(PlayerSide::Right));
+    assert!(Mutators::default()
+        .mirror_schedule(Duration::from_secs(10))
+        .is_none());
+}
+
+#[test]
+fn mirrored_keys_move_the_paddle_the_other_way() {
+    let mut state = mirrored_game(1);
+    let usual = move_player1_up(&mut state);
+    assert_ne!(usual, 0.0);
+
+    let mut events = Vec::new();
+    while state.mirror().unwrap().mirrored().is_none() {
+        events.extend(state.update(HashMap::new(), DT));
+    }
+
+    assert_eq!(events, vec![GameEvent::Mirrored(PlayerSide::Left)]);
+    assert!(state
+        .render_to_grid()
+        .iter()
+        .any(|row| row.iter().collect::<String>().contains("Mirror! player1")));
+    assert!(move_player1_up(&mut state) * usual < 0.0);
+    assert!(GameState::new(GameConfig::default()).mirror().is_none());
+}
+
+#[test]
+fn player1_gets_its_keys_back_when_the_mirror_moves_on() {
+    let mut state = mirrored_game(2);
+    let usual = move_player1_up(&mut state);
+
+    let mut mirrored = Vec::new();
+    for _ in 0..40 {
+        mirrored.extend(
+            state
+                .update(HashMap::new(), DT)
+                .into_iter()
+                .filter(|event| matches!(event, GameEvent::Mirrored(_))),
+        );
+    }
+
+    assert_eq!(
+        mirrored,
+        vec![
+            GameEvent::Mirrored(PlayerSide::Left),
+            GameEvent::Mirrored(PlayerSide::Right)
+        ]
+    );
+    assert!(move_player1_up(&mut state) * usual > 0.0);
+}
+
+#[test]
+fn new_games_start_with_the_usual_keys() {
+    let mut state = mirrored_game(3);
+    let usual = move_player1_up(&mut state);
+    for _ in 0..20 {
+        state.update(HashMap::new(), DT);
+    }
+    assert_eq!(state.mirror().unwrap().mirrored(), Some(PlayerSide::Left));
+
+    state.update(press(KeyCode::Char('r'), KeyModifiers::CONTROL), DT);
+
+    assert_eq!(state.mirror().unwrap().mirrored(), None);
+    assert!(move_player1_up(&mut state) * usual > 0.0);
+    assert!(state
+        .replay_options()
+        .contains(&("mirror-interval".to_string(), "1".to_string())));
+}
@@ -0,0 +1,265 @@
+use cli_pong::ai::AiStyle;
+use cli_pong::mode::{EntityColor, GameMode, ModeEntity, ModeRegistry};
+use cli_pong::state::{
+    step, Controller, DiscretePosition2D, GameConfig, GameEvent, GameState, Position2D, Velocity2D,
+};
+use cli_pong::stats::PlayerSide;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+fn config() -> GameConfig {
+    GameConfig::builder().seed(Some(7)).build()
+}
+
+/// A mode in which the first player with two goals wins. It counts the goals itself.
+#[derive(Clone, Default)]
+struct FirstToTwo {
+    goals: (usize, usize),
+}
+
+impl GameMode for FirstToTwo {
+    fn name(&self) -> &str {
+        "first-to-two"
+    }
+
+    fn on_goal(&mut self, _state: &mut GameState, scorer: PlayerSide) {
+        match scorer {
+            PlayerSide::Left => self.goals.0 += 1,
+            PlayerSide::Right => self.goals.1 += 1,
+        }
+    }
+
+    fn win_condition(&self, _state: &GameState) -> Option<PlayerSide> {
+        match self.goals {
+            (2, _) => Some(PlayerSide::Left),
+            (_, 2) => Some(PlayerSide::Right),
+            _ => None,
+        }
+    }
+}
+
+/// A mode with a single entity in the middle of the field.
+#[derive(Clone)]
+struct Pillar;
+
+impl GameMode for Pillar {
+    fn name(&self) -> &str {
+        "pillar"
+    }
+
+    fn extra_entities(&self, state: &GameState) -> Vec<ModeEntity> {
+        let config = state.config();
+        let center = Position2D::new(config.width as f64 / 2.0, config.height as f64 / 2.0);
+
+        vec![ModeEntity {
+            position: center,
+            glyph: '#',
+            color: EntityColor::Text,
+        }]
+    }
+}
+
+/// A mode with a target in the middle of the field. The ball bounces off the target, and the
+/// first hit wins the game for player1.
+#[derive(Clone, Default)]
+struct Target {
+    hit: bool,
+}
+
+impl Target {
+    fn cell(state: &GameState) -> DiscretePosition2D {
+        let config = state.config();
+        DiscretePosition2D::new(config.width as i64 / 2, config.height as i64 / 2)
+    }
+}
+
+impl GameMode for Target {
+    fn name(&self) -> &str {
+        "target"
+    }
+
+    fn on_ball_moved(
+        &mut self,
+        state: &mut GameState,
+        ball: usize,
+        from: Position2D,
+    ) -> Vec<GameEvent> {
+        let target = Self::cell(state);
+        if state
+            .bounce_ball_off_cells(ball, from, |cell| cell == target)
+            .is_none()
+        {
+            return Vec::new();
+        }
+
+        self.hit = true;
+        vec![GameEvent::BrickBroken]
+    }
+
+    fn win_condition(&self, _state: &GameState) -> Option<PlayerSide> {
+        self.hit.then_some(PlayerSide::Left)
+    }
+
+    fn extra_entities(&self, state: &GameState) -> Vec<ModeEntity> {
+        vec![ModeEntity {
+            position: Self::cell(state).to_continuous(),
+            glyph: 'o',
+            color: EntityColor::Paddle1,
+        }]
+    }
+}
+
+#[test]
+fn registry_contains_the_built_in_modes() {
+    let modes = ModeRegistry::default();
+
+    assert_eq!(
+        modes.names(),
+        vec![
+            "versus",
+            "single",
+            "demo",
+            "squash",
+            "four-player",
+            "doubles",
+            "breakout",
+            "survival",
+            "drill",
+            "obstacles",
+            "portals"
+        ]
+    );
+    assert_eq!(modes.create("single").unwrap().name(), "single");
+    assert!(modes.create("unknown").is_none());
+}
+
+#[test]
+fn single_mode_lets_the_computer_play_player2() {
+    let mode = ModeRegistry::default().create("single").unwrap();
+    let state = GameState::with_mode(config(), mode);
+
+    assert!(matches!(
+        state.controller(PlayerSide::Right),
+        Controller::Ai(_)
+    ));
+    assert!(matches!(
+        state.controller(PlayerSide::Left),
+        Controller::Keyboard(_)
+    ));
+}
+
+#[test]
+fn demo_mode_lets_the_computer_play_both_sides() {
+    let mode = ModeRegistry::default().create("demo").unwrap();
+    assert!(!mode.accepts_input());
+    let mut state = GameState::with_mode(config(), mode);
+
+    for side in [PlayerSide::Left, PlayerSide::Right] {
+        assert!(matches!(state.controller(side), Controller::Ai(_)));
+    }
+
+    let mut hitters = Vec::new();
+    for _ in 0..1000 {
+        let (next, events) = step(&state, HashMap::new(), DT);
+        state = next;
+        hitters.extend(events.into_iter().filter_map(|event| match event {
+            GameEvent::Hit(side) => Some(side),
+            _ => None,
+        }));
+    }
+    assert!(hitters.contains(&PlayerSide::Left));
+    assert!(hitters.contains(&PlayerSide::Right));
+}
+
+#[test]
+fn squash_ball_bounces_off_the_wall_in_place_of_player2() {
+    let mode = ModeRegistry::default().create("squash").unwrap();
+    let config = GameConfig::builder()
+        .seed(Some(7))
+        .player1_ai(Some(AiStyle::Balanced.profile()))
+        .build();
+    let mut state = GameState::with_mode(config, mode);
+    let mut events = Vec::new();
+
+    for _ in 0..2000 {
+        let (next, new_events) = step(&state, HashMap::new(), DT);
+        state = next;
+        events.extend(new_events);
+        assert!(state.ball().get_position().x() <= state.config().width as f64);
+    }
+
+    assert!(events.contains(&GameEvent::Hit(PlayerSide::Left)));
+    assert!(!events.contains(&GameEvent::Hit(PlayerSide::Right)));
+    assert!(!events.contains(&GameEvent::Goal(PlayerSide::Left)));
+}
+
+#[test]
+fn squash_draws_the_wall_and_counts_returns() {
+    let mode = ModeRegistry::default().create("squash").unwrap();
+    let state = GameState::with_mode(config(), mode);
+    let grid = state.render_to_grid();
+    let wall_column = state.config().width;
+
+    let field_rows = &grid[4..grid.len() - 1];
+    assert!(field_rows.iter().all(|row| row[wall_column] == '\u{2588}'));
+    let hud: String = grid[1].iter().collect();
+    assert!(hud.starts_with("Returns in a row: 0,  Best: 0,  Misses: 0"));
+}
+
+#[test]
+fn game_stops_once_the_mode_declares_a_winner() {
+    let mut state = GameState::with_mode(config(), Box::new(FirstToTwo::default()));
+    let mut won = Vec::new();
+
+    for _ in 0..5000 {
+        let (next, events) = step(&state, HashMap::new(), DT);
+        state = next;
+        won.extend(events.into_iter().filter_map(|event| match event {
+            GameEvent::Won(winner) => Some(winner),
+            _ => None,
+        }));
+    }
+
+    let (player1_score, player2_score) = state.scores();
+    assert_eq!(player1_score.max(player2_score), 2);
+    assert_eq!(won.len(), 1);
+    assert_eq!(state.winner(), Some(won[0]));
+}
+
+#[test]
+fn mode_entities_are_drawn_and_kept_free_at_the_serve() {
+    let state = GameState::with_mode(config(), Box::new(Pillar));
+    let ball = state.ball().get_position();
+    let config = state.config();
+
+    assert!(state
+        .render_to_grid()
+        .iter()
+        .flatten()
+        .any(|&cell| cell == '#'));
+    assert!(
+        ball.x() != config.width as f64 / 2.0 || ball.y() != config.height as f64 / 2.0,
+        "the ball was served into the pillar"
+    );
+}
+
+#[test]
+fn mode_rules_bounce_the_ball_and_decide_the_game() {
+    let mut state = GameState::with_mode(config(), Box::new(Target::default()));
+    let target = Target::cell(&state).to_continuous();
+    state.launch_ball(
+        Position2D::new(target.x() - 2.0, target.y()),
+        Velocity2D::new(60.0, 0.0),
+    );
+
+    let events = state.update(HashMap::new(), DT);
+
+    assert_eq!(
+        events,
+        vec![GameEvent::BrickBroken, GameEvent::Won(PlayerSide::Left)]
+    );
+    assert!(state.ball().get_velocity().vx() < 0.0);
+    assert!(state.ball().get_position().x() < target.x());
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use cli_pong::mode::FourPlayer;
+use cli_pong::state::{GameConfig, GameEvent, GameState, Position2D, Velocity2D};
+use cli_pong::stats::PlayerSide;
+use common::{count_glyphs, game, press};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+const BALL: char = '\u{25CF}';
+
+#[test]
+fn spawned_balls_are_drawn_and_fly() {
+    let mut state = game(1);
+    assert_eq!(state.balls().len(), 1);
+
+    assert!(state.spawn_ball(Position2D::new(20.0, 4.0), Velocity2D::new(5.0, 0.0)));
+    assert!(state.spawn_ball(Position2D::new(40.0, 14.0), Velocity2D::new(-5.0, 0.0)));
+    assert_eq!(state.balls().len(), 3);
+    assert_eq!(count_glyphs(&state, BALL), 3);
+
+    state.update(HashMap::new(), DT);
+
+    let positions: Vec<f64> = state.balls()[1..]
+        .iter()
+        .map(|ball| ball.get_position().x())
+        .collect();
+    assert_eq!(positions, vec![20.25, 39.75]);
+}
+
+#[test]
+fn any_ball_scores_and_the_serve_removes_the_others() {
+    let mut state = game(2);
+    // the extra ball is about to cross the goal line of player2
+    state.spawn_ball(Position2D::new(59.9, 1.0), Velocity2D::new(20.0, 0.0));
+
+    let events = state.update(HashMap::new(), DT);
+
+    assert_eq!(events, vec![GameEvent::Goal(PlayerSide::Left)]);
+    assert_eq!(state.scores(), (1, 0));
+    assert_eq!(state.balls().len(), 1);
+    assert_eq!(count_glyphs(&state, BALL), 1);
+}
+
+#[test]
+fn returns_of_every_ball_count() {
+    let mut state = game(3);
+    let paddle_y = state.observation(PlayerSide::Left).paddle_y;
+    // the extra ball flies straight into the paddle of player1
+    state.spawn_ball(Position2D::new(3.0, paddle_y), Velocity2D::new(-20.0, 0.0));
+
+    let mut events = Vec::new();
+    for _ in 0..4 {
+        events.extend(state.update(HashMap::new(), DT));
+    }
+
+    assert!(events.contains(&GameEvent::Hit(PlayerSide::Left)));
+    assert_eq!(state.stats().returns(PlayerSide::Left), 1);
+    assert!(state.balls()[1].get_velocity().vx() > 0.0);
+}
+
+#[test]
+fn new_points_start_with_one_ball() {
+    let mut state = game(4);
+    state.spawn_ball(Position2D::new(30.0, 5.0), Velocity2D::new(5.0, 5.0));
+
+    state.update(press(KeyCode::Char('r'), KeyModifiers::NONE), DT);
+
+    assert_eq!(state.balls().len(), 1);
+    let mut four_players = GameState::with_mode(GameConfig::default(), Box::new(FourPlayer));
+    assert!(!four_players.spawn_ball(Position2D::new(30.0, 5.0), Velocity2D::new(5.0, 5.0)));
+    assert_eq!(four_players.balls().len(), 1);
+}
@@ -0,0 +1,96 @@
+use cli_pong::observer::{GameObserver, Observers};
+use cli_pong::state::{GameConfig, GameEvent, GameState};
+use cli_pong::timeline::PointTimeline;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+use std::rc::Rc;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
+struct Log {
+    goals: usize,
+    ticks: usize,
+    finished: bool,
+}
+
+struct Recorder(Rc<RefCell<Log>>);
+
+impl GameObserver for Recorder {
+    fn on_event(&mut self, _state: &GameState, event: GameEvent) -> io::Result<()> {
+        if let GameEvent::Goal(_) = event {
+            self.0.borrow_mut().goals += 1;
+        }
+        Ok(())
+    }
+
+    fn on_tick(&mut self, _state: &GameState, _dt: Duration) -> io::Result<()> {
+        self.0.borrow_mut().ticks += 1;
+        Ok(())
+    }
+
+    fn on_finish(&mut self) -> io::Result<()> {
+        self.0.borrow_mut().finished = true;
+        Ok(())
+    }
+}
+
+struct Failing;
+
+impl GameObserver for Failing {
+    fn on_tick(&mut self, _state: &GameState, _dt: Duration) -> io::Result<()> {
+        Err(io::Error::other("disk full"))
+    }
+}
+
+#[test]
+fn observers_receive_every_update_and_goal() {
+    let log = Rc::new(RefCell::new(Log::default()));
+    let mut observers = Observers::default();
+    observers.register(Recorder(Rc::clone(&log)));
+    let mut state = GameState::new(GameConfig::builder().seed(Some(11)).build());
+
+    for _ in 0..1000 {
+        let events = state.update(HashMap::new(), DT);
+        observers.notify(&state, &events, DT).unwrap();
+    }
+    observers.finish().unwrap();
+
+    let (left, right) = state.scores();
+    let log = log.borrow();
+    assert_eq!(log.ticks, 1000);
+    assert_eq!(log.goals, left + right);
+    assert!(log.finished);
+}
+
+#[test]
+fn failing_observer_does_not_stop_the_others() {
+    let log = Rc::new(RefCell::new(Log::default()));
+    let mut observers = Observers::default();
+    observers.register(Failing);
+    observers.register(Recorder(Rc::clone(&log)));
+    let state = GameState::new(GameConfig::default());
+
+    assert!(observers.notify(&state, &[], DT).is_err());
+    assert_eq!(log.borrow().ticks, 1);
+}
+
+#[test]
+fn shared_timeline_records_like_the_game_loop() {
+    let timeline = Rc::new(RefCell::new(PointTimeline::default()));
+    let mut observers = Observers::default();
+    observers.register(Rc::clone(&timeline));
+    let mut direct = PointTimeline::default();
+    let mut state = GameState::new(GameConfig::builder().seed(Some(11)).build());
+
+    for _ in 0..1000 {
+        let events = state.update(HashMap::new(), DT);
+        observers.notify(&state, &events, DT).unwrap();
+        direct.record(&state, &events, DT);
+    }
+
+    assert!(!direct.is_empty());
+    assert_eq!(timeline.borrow().points(), direct.points());
+}
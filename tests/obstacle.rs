@@ -0,0 +1,128 @@
+mod common;
+
+use cli_pong::mode::ModeRegistry;
+use cli_pong::state::{
+    Arena, Ball, DiscretePosition2D, GameConfig, GameState, Obstacle, Position2D, Velocity2D,
+    OBSTACLE_WIDTH,
+};
+use common::out_of_reach_players;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+const WIDTH: usize = 60;
+const HEIGHT: usize = 20;
+
+fn config() -> GameConfig {
+    GameConfig::builder().width(WIDTH).height(HEIGHT).build()
+}
+
+fn arena() -> Arena {
+    Arena {
+        obstacles: Obstacle::mid_field(WIDTH, HEIGHT),
+        ..Arena::default()
+    }
+}
+
+fn is_blocked(obstacles: &[Obstacle], position: Position2D) -> bool {
+    obstacles
+        .iter()
+        .any(|obstacle| obstacle.contains(position.to_discrete()))
+}
+
+#[test]
+fn the_blocks_leave_the_middle_lane_free() {
+    let obstacles = Obstacle::mid_field(WIDTH, HEIGHT);
+
+    assert_eq!(obstacles.len(), 2);
+    let middle = WIDTH as i64 / 2;
+    assert!(!is_blocked(
+        &obstacles,
+        Position2D::new(middle as f64, HEIGHT as f64 / 2.0)
+    ));
+    // a block above and a block below the middle, each as wide as an obstacle
+    for y in [HEIGHT as i64 / 4, HEIGHT as i64 * 3 / 4] {
+        let columns = (0..=WIDTH as i64)
+            .filter(|&x| {
+                obstacles
+                    .iter()
+                    .any(|obstacle| obstacle.contains(DiscretePosition2D::new(x, y)))
+            })
+            .count();
+        assert_eq!(columns, OBSTACLE_WIDTH);
+    }
+}
+
+#[test]
+fn the_ball_bounces_off_a_block() {
+    let (player1, player2) = out_of_reach_players(10.0);
+    let obstacles = Obstacle::mid_field(WIDTH, HEIGHT);
+    let y = HEIGHT as f64 / 4.0;
+    let mut ball = Ball::with_velocity(Position2D::new(28.0, y), Velocity2D::new(20.0, 0.0));
+    let mut rng = StdRng::seed_from_u64(0);
+
+    ball.update_position_with_teams(&config(), &arena(), &[&player1], &[&player2], &mut rng, DT);
+
+    assert!(ball.get_velocity().vx() < 0.0);
+    assert!(!is_blocked(&obstacles, ball.get_position()));
+}
+
+#[test]
+fn a_fast_ball_can_not_pass_through_a_block() {
+    let (player1, player2) = out_of_reach_players(10.0);
+    let y = HEIGHT as f64 * 3.0 / 4.0;
+    // the ball would cross the whole block within a single update
+    let mut ball = Ball::with_velocity(Position2D::new(26.0, y), Velocity2D::new(120.0, 0.0));
+    let mut rng = StdRng::seed_from_u64(0);
+
+    ball.update_position_with_teams(&config(), &arena(), &[&player1], &[&player2], &mut rng, DT);
+
+    assert!(ball.get_position().x() < (WIDTH / 2) as f64);
+}
+
+#[test]
+fn the_ball_flies_straight_without_obstacles() {
+    let (player1, player2) = out_of_reach_players(10.0);
+    let y = HEIGHT as f64 / 4.0;
+    let mut ball = Ball::with_velocity(Position2D::new(28.0, y), Velocity2D::new(20.0, 0.0));
+    let mut rng = StdRng::seed_from_u64(0);
+    let config = GameConfig::builder().width(WIDTH).height(HEIGHT).build();
+
+    ball.update_position(&config, &player1, &player2, &mut rng, DT);
+
+    assert!(ball.get_velocity().vx() > 0.0);
+}
+
+#[test]
+fn the_obstacles_mode_draws_the_blocks() {
+    let mode = ModeRegistry::default().create("obstacles").unwrap();
+    let state = GameState::with_mode(GameConfig::builder().seed(Some(1)).build(), mode);
+    let plain = GameState::new(GameConfig::builder().seed(Some(1)).build());
+
+    assert_eq!(state.arena().obstacles.len(), 2);
+    assert!(plain.arena().obstacles.is_empty());
+    let blocks = |state: &GameState| {
+        state
+            .compose_court()
+            .chars()
+            .iter()
+            .flatten()
+            .filter(|&&character| character == '\u{2588}')
+            .count()
+    };
+    assert!(blocks(&state) > blocks(&plain));
+}
+
+#[test]
+fn the_blocks_move_with_a_resized_field() {
+    let mode = ModeRegistry::default().create("obstacles").unwrap();
+    let mut state = GameState::with_mode(config(), mode);
+
+    state.resize_field(40, 12);
+
+    assert_eq!(
+        state.arena().obstacles,
+        Obstacle::mid_field(40, 12).as_slice()
+    );
+}
@@ -0,0 +1,164 @@
+use cli_pong::ai::AiStyle;
+use cli_pong::mode::GameMode;
+use cli_pong::mutator::{Mutator, Mutators};
+use cli_pong::state::{
+    step, Arena, BallSpeed, GameConfig, GameEvent, GameState, Obstacle, PortalPair, MAX_BALL_SPEED,
+};
+use cli_pong::stats::PlayerSide;
+use proptest::prelude::*;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// A small margin for the rounding errors of the floating point arithmetic.
+const EPSILON: f64 = 1e-9;
+
+/// A mode that places any combination of the features of the field modes.
+#[derive(Debug, Copy, Clone)]
+struct Field {
+    obstacles: bool,
+    portals: bool,
+}
+
+impl GameMode for Field {
+    fn name(&self) -> &str {
+        "field"
+    }
+
+    fn arena(&self, config: &GameConfig) -> Arena {
+        let mut arena = Arena::default();
+        if self.obstacles {
+            arena.obstacles = Obstacle::mid_field(config.width, config.height);
+        }
+        if self.portals {
+            arena.portals = Some(PortalPair::mid_field(config.width, config.height));
+        }
+        arena
+    }
+}
+
+fn ball_speed() -> impl Strategy<Value = BallSpeed> {
+    prop_oneof![
+        Just(BallSpeed::Slow),
+        Just(BallSpeed::Normal),
+        Just(BallSpeed::Fast)
+    ]
+}
+
+fn ai_style() -> impl Strategy<Value = Option<AiStyle>> {
+    prop_oneof![
+        Just(None),
+        Just(Some(AiStyle::Balanced)),
+        Just(Some(AiStyle::Aggressive)),
+        Just(Some(AiStyle::Defensive)),
+        Just(Some(AiStyle::Rookie)),
+    ]
+}
+
+fn mutators() -> impl Strategy<Value = Mutators> {
+    proptest::sample::subsequence(
+        vec![
+            Mutator::NoWalls,
+            Mutator::BigBall,
+            Mutator::FastServe,
+            Mutator::InvertP2,
+            Mutator::Mirror,
+            Mutator::Chaos,
+        ],
+        0..=6,
+    )
+    .prop_map(Mutators::new)
+}
+
+prop_compose! {
+    fn game_config()(
+        seed in any::<u64>(),
+        width in 10usize..120,
+        height in 4usize..40,
+        extend_up in 0usize..3,
+        extend_down in 0usize..3,
+        ball_speed in ball_speed(),
+        paddle_speed in 1.0f64..40.0,
+        restitution in 0.5f64..1.5,
+        spin in any::<bool>(),
+        jitter in 0.0f64..=1.0,
+        gravity in prop_oneof![Just(0.0), 0.0f64..40.0],
+        shrink_paddles in any::<bool>(),
+        player1_ai in ai_style(),
+        player2_ai in ai_style(),
+        mutators in mutators(),
+    ) -> GameConfig {
+        GameConfig::builder()
+            .width(width)
+            .height(height)
+            .extend_player_height_up(extend_up)
+            .extend_player_height_down(extend_down)
+            .ball_speed(ball_speed)
+            .paddle_speed(paddle_speed)
+            .restitution(restitution)
+            .spin(spin)
+            .jitter(jitter)
+            .gravity(gravity)
+            .shrink_paddles(shrink_paddles)
+            .player1_ai(player1_ai.map(AiStyle::profile))
+            .player2_ai(player2_ai.map(AiStyle::profile))
+            .seed(Some(seed))
+            .mutators(mutators)
+            .build()
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn physics_invariants_hold(
+        config in game_config(),
+        obstacles in any::<bool>(),
+        portals in any::<bool>(),
+        frame_millis in 10u64..120,
+        updates in 100usize..600,
+    ) {
+        let (width, height) = (config.width as f64, config.height as f64);
+        // chaos events shrink paddles as well
+        let shrink_paddles = config.shrink_paddles || config.mutators.is_active(Mutator::Chaos);
+        let extends = (
+            config.extend_player_height_up as f64,
+            config.extend_player_height_down as f64,
+        );
+        let dt = Duration::from_millis(frame_millis);
+        let mut state = GameState::with_mode(config, Box::new(Field { obstacles, portals }));
+
+        for _ in 0..updates {
+            let scores_before = state.scores();
+            let (next, events) = step(&state, HashMap::new(), dt);
+            state = next;
+
+            // a ball that crosses a goal line is served again, so it is always inside the field
+            let observation = state.observation(PlayerSide::Left);
+            prop_assert!((-EPSILON..=width + EPSILON).contains(&observation.ball_x));
+            prop_assert!((-EPSILON..=height + EPSILON).contains(&observation.ball_y));
+
+            let speed = observation.ball_vx.hypot(observation.ball_vy);
+            prop_assert!(speed <= MAX_BALL_SPEED + EPSILON, "speed {} above the cap", speed);
+
+            for side in [PlayerSide::Left, PlayerSide::Right] {
+                let paddle_y = state.observation(side).paddle_y;
+                prop_assert!((-EPSILON..=height + EPSILON).contains(&paddle_y));
+                if !shrink_paddles {
+                    prop_assert!(paddle_y - extends.1 >= -EPSILON);
+                    prop_assert!(paddle_y + extends.0 <= height + EPSILON);
+                }
+            }
+
+            let goals = |scorer| {
+                events
+                    .iter()
+                    .filter(|&&event| event == GameEvent::Goal(scorer))
+                    .count()
+            };
+            let scores = state.scores();
+            prop_assert_eq!(scores.0, scores_before.0 + goals(PlayerSide::Left));
+            prop_assert_eq!(scores.1, scores_before.1 + goals(PlayerSide::Right));
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use cli_pong::mode::ModeRegistry;
+use cli_pong::state::{
+    step, Ball, DiscretePosition2D, GameConfig, GameState, PortalPair, Position2D, Velocity2D,
+};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+const WIDTH: usize = 60;
+const HEIGHT: usize = 20;
+
+fn portal_game() -> GameState {
+    let mode = ModeRegistry::default().create("portals").unwrap();
+    GameState::with_mode(
+        GameConfig::builder()
+            .width(WIDTH)
+            .height(HEIGHT)
+            .seed(Some(1))
+            .build(),
+        mode,
+    )
+}
+
+#[test]
+fn each_portal_leads_to_the_other_one() {
+    let portals = PortalPair::mid_field(WIDTH, HEIGHT);
+    let [first, second] = portals.tiles();
+
+    assert_ne!(first, second);
+    assert_eq!(portals.exit(first), Some(second));
+    assert_eq!(portals.exit(second), Some(first));
+    assert_eq!(portals.exit(DiscretePosition2D::new(0, 0)), None);
+}
+
+#[test]
+fn a_ball_that_enters_a_portal_leaves_the_other_one() {
+    let mut state = portal_game();
+    let [entrance, exit] = state.arena().portals.unwrap().tiles();
+    let start = Position2D::new(entrance.x() as f64 - 1.0, entrance.y() as f64);
+    state.launch_ball(start, Velocity2D::new(20.0, 0.0));
+
+    let (state, _) = step(&state, HashMap::new(), DT);
+
+    assert_eq!(state.ball().get_position().to_discrete(), exit);
+    // the ball keeps flying in the same direction
+    assert!(state.ball().get_velocity().vx() > 0.0);
+    assert_eq!(state.ball().get_velocity().vy(), 0.0);
+
+    // and leaves the portal it came out of instead of going back
+    let (state, _) = step(&state, HashMap::new(), DT);
+    assert_eq!(state.ball().get_position().y(), exit.y() as f64);
+    assert!(state.ball().get_position().x() > exit.x() as f64);
+}
+
+#[test]
+fn a_fast_ball_does_not_skip_a_portal() {
+    let mut state = portal_game();
+    let [entrance, exit] = state.arena().portals.unwrap().tiles();
+    // the ball would cross the portal between two updates
+    let start = Position2D::new(entrance.x() as f64 - 3.0, entrance.y() as f64);
+    state.launch_ball(start, Velocity2D::new(120.0, 0.0));
+
+    let (state, _) = step(&state, HashMap::new(), DT);
+
+    assert_eq!(state.ball().get_position().to_discrete(), exit);
+}
+
+#[test]
+fn the_portals_mode_draws_both_portals() {
+    let mode = ModeRegistry::default().create("portals").unwrap();
+    let state = GameState::with_mode(GameConfig::builder().seed(Some(1)).build(), mode);
+    let plain = GameState::new(GameConfig::builder().seed(Some(1)).build());
+
+    assert!(plain.arena().portals.is_none());
+    let court: String = state
+        .compose_court()
+        .chars()
+        .into_iter()
+        .flatten()
+        .collect();
+    assert!(court.contains('\u{25CE}') && court.contains('\u{25C9}'));
+}
+
+#[test]
+fn the_portals_move_with_a_resized_field() {
+    let mut state = portal_game();
+
+    state.resize_field(40, 12);
+
+    assert_eq!(state.arena().portals, Some(PortalPair::mid_field(40, 12)));
+}
+
+#[test]
+fn the_predicted_ball_passes_the_portals() {
+    let state = portal_game();
+    let [entrance, exit] = state.arena().portals.unwrap().tiles();
+    let start = Position2D::new(entrance.x() as f64 - 1.0, entrance.y() as f64);
+    let ball = Ball::with_velocity(start, Velocity2D::new(20.0, 0.0));
+
+    let predicted = ball.predicted(state.config(), state.arena(), DT);
+
+    assert_eq!(predicted.get_position().to_discrete(), exit);
+}
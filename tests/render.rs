@@ -0,0 +1,68 @@
+mod common;
+
+use cli_pong::state::GameConfig;
+use common::game;
+
+const BALL: char = '\u{25CF}';
+const PADDLE: char = '\u{2588}';
+
+/// Returns the rows of the grid between the top and the bottom wall, which are drawn with the
+/// same glyph as the paddles.
+fn field_rows(grid: &[Vec<char>]) -> Vec<Vec<char>> {
+    let is_wall = |row: &Vec<char>| row.iter().filter(|&&cell| cell == PADDLE).count() > 2;
+    let top = grid.iter().position(is_wall).unwrap();
+    let bottom = grid.iter().rposition(is_wall).unwrap();
+
+    grid[top + 1..bottom].to_vec()
+}
+
+fn count(grid: &[Vec<char>], character: char) -> usize {
+    field_rows(grid)
+        .iter()
+        .flatten()
+        .filter(|&&cell| cell == character)
+        .count()
+}
+
+#[test]
+fn grid_rows_are_as_wide_as_the_frame() {
+    let state = game(3);
+    let frame = state.compose_frame();
+    let grid = state.render_to_grid();
+
+    assert_eq!(grid.len(), frame.height());
+    assert!(grid.iter().all(|row| row.len() == frame.width()));
+}
+
+#[test]
+fn grid_shows_the_ball_and_both_paddles() {
+    let config = GameConfig::default();
+    let paddle_cells = 1 + config.extend_player_height_up + config.extend_player_height_down;
+    let grid = game(3).render_to_grid();
+
+    assert_eq!(count(&grid, BALL), 1);
+    assert_eq!(count(&grid, PADDLE), 2 * paddle_cells);
+}
+
+#[test]
+fn grid_shows_the_paddles_in_the_outer_columns_of_the_field() {
+    let grid = game(3).render_to_grid();
+    let paddle_columns: Vec<usize> = field_rows(&grid)
+        .iter()
+        .flat_map(|row| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &cell)| cell == PADDLE)
+                .map(|(column, _)| column)
+        })
+        .collect();
+    let (first, last) = (
+        paddle_columns.iter().min().unwrap(),
+        paddle_columns.iter().max().unwrap(),
+    );
+
+    assert!(paddle_columns
+        .iter()
+        .all(|column| column == first || column == last));
+    assert_eq!(last - first, GameConfig::default().width);
+}
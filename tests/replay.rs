@@ -0,0 +1,86 @@
+use cli_pong::mutator::Mutator;
+use cli_pong::replay::Replay;
+use cli_pong::state::{GameConfig, GameState};
+use std::path::Path;
+
+fn read(content: &str) -> Replay {
+    Replay::read(content.as_bytes(), Path::new("test.replay")).unwrap()
+}
+
+#[test]
+fn replays_of_other_versions_are_detected() {
+    let current = read(&format!(
+        "cli_pong replay v2 80 24\nengine {}\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+    let other = read("cli_pong replay v2 80 24\nengine 0.0.1\n");
+    let unknown = read("cli_pong replay v1 80 24\n");
+
+    assert!(!current.is_from_other_engine());
+    assert!(other.is_from_other_engine());
+    assert!(!unknown.is_from_other_engine());
+}
+
+#[test]
+fn recorded_options_shape_the_played_back_game() {
+    let replay = read(
+        "cli_pong replay v2 60 20\n\
+         engine 0.0.1\n\
+         option gravity 20\n\
+         option up-extend-player-height 3\n\
+         option mutator no-walls,big-ball\n\
+         option p2-extend 5\n\
+         option mode obstacles\n",
+    );
+
+    let state = replay.game_state();
+    let config = state.config();
+
+    assert_eq!((config.width, config.height), (60, 20));
+    assert_eq!(config.gravity, 20.0);
+    assert_eq!(config.extend_player_height_up, 3);
+    assert!(config.mutators.is_active(Mutator::NoWalls));
+    assert!(config.mutators.is_active(Mutator::BigBall));
+    assert_eq!(config.player2_handicap.extend, Some(5));
+    assert_eq!(state.mode().name(), "obstacles");
+    assert!(!state.arena().obstacles.is_empty());
+}
+
+#[test]
+fn unreadable_and_unknown_options_keep_the_defaults() {
+    let replay = read(
+        "cli_pong replay v2 80 24\n\
+         option gravity lots\n\
+         option future-option 1\n\
+         option mode future-mode\n",
+    );
+
+    let state = replay.game_state();
+
+    assert_eq!(state.config().gravity, GameConfig::default().gravity);
+    assert_eq!(state.mode().name(), "versus");
+}
+
+#[test]
+fn options_of_a_game_survive_the_replay() {
+    let recorded = GameState::new(
+        GameConfig::builder()
+            .seed(Some(7))
+            .spin(true)
+            .jitter(0.5)
+            .score_limit(Some(3))
+            .build(),
+    );
+    let options: String = recorded
+        .replay_options()
+        .iter()
+        .map(|(name, value)| format!("option {name} {value}\n"))
+        .collect();
+
+    let config = read(&format!("cli_pong replay v2 80 24\n{options}")).config();
+
+    assert_eq!(config.seed, Some(7));
+    assert!(config.spin);
+    assert_eq!(config.jitter, 0.5);
+    assert_eq!(config.score_limit, Some(3));
+}
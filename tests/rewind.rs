@@ -0,0 +1,123 @@
+mod common;
+
+use cli_pong::mutator::{Mutator, Mutators};
+use cli_pong::state::{GameConfig, GameEvent, GameState};
+use cli_pong::stats::PlayerSide;
+use common::press_chars;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+fn level(state: &GameState) -> u32 {
+    state.difficulty().map_or(0, |ramp| ramp.level())
+}
+
+#[test]
+fn rewinding_across_a_point_takes_the_point_back() {
+    let mut state = GameState::new(
+        GameConfig::builder()
+            .seed(Some(3))
+            .practice(true)
+            .difficulty_ramp(Some(Duration::from_millis(500)))
+            .build(),
+    );
+    let before = state.clone();
+    let mut scored = false;
+    for _ in 0..2000 {
+        if state
+            .update(HashMap::new(), DT)
+            .iter()
+            .any(|event| matches!(event, GameEvent::Goal(_)))
+        {
+            scored = true;
+            break;
+        }
+    }
+    assert!(scored);
+    let raised = level(&state);
+    assert!(raised > level(&before));
+
+    let events = state.update(press_chars(&['b']), DT);
+
+    assert_eq!(events, vec![GameEvent::Rewound]);
+    assert_eq!(state.scores(), before.scores());
+    for side in [PlayerSide::Left, PlayerSide::Right] {
+        assert_eq!(state.stats().misses(side), 0);
+    }
+    assert_eq!(state.stats().rallies(), 0);
+    assert!(level(&state) < raised);
+    assert_eq!(state.winner(), None);
+}
+
+#[test]
+fn finished_practice_game_can_be_rewound() {
+    let mut state = GameState::new(
+        GameConfig::builder()
+            .seed(Some(3))
+            .practice(true)
+            .score_limit(Some(1))
+            .build(),
+    );
+    for _ in 0..2000 {
+        state.update(HashMap::new(), DT);
+        if state.winner().is_some() {
+            break;
+        }
+    }
+    assert!(state.winner().is_some());
+
+    let events = state.update(press_chars(&['b']), DT);
+
+    assert_eq!(events, vec![GameEvent::Rewound]);
+    assert_eq!(state.winner(), None);
+    assert_eq!(state.scores(), (0, 0));
+    // the game goes on from the earlier moment
+    let ball_x = state.observation(PlayerSide::Left).ball_x;
+    state.update(HashMap::new(), DT);
+    assert_ne!(state.observation(PlayerSide::Left).ball_x, ball_x);
+}
+
+#[test]
+fn rewinding_restores_the_clock_and_the_mirror() {
+    let mut state = GameState::new(
+        GameConfig::builder()
+            .seed(Some(3))
+            .practice(true)
+            .mutators(Mutators::new([Mutator::Mirror]))
+            .mirror_interval(Duration::from_secs(1))
+            .build(),
+    );
+    let mut earlier = Vec::new();
+    for _ in 0..100 {
+        state.update(HashMap::new(), DT);
+        earlier.push(state.clone());
+    }
+
+    state.update(press_chars(&['b']), DT);
+
+    let earlier = earlier
+        .iter()
+        .find(|earlier| earlier.clock() == state.clock())
+        .expect("the clock is one of an earlier update");
+    assert!(state.clock().elapsed() <= Duration::from_secs(2) + DT);
+    assert_eq!(
+        state.mirror().and_then(|mirror| mirror.mirrored()),
+        earlier.mirror().and_then(|mirror| mirror.mirrored())
+    );
+    let mut continued = state.clone();
+    let mut expected = earlier.clone();
+    let keys = press_chars(&['w']);
+    assert_eq!(
+        continued.update(keys.clone(), DT),
+        expected.update(keys, DT)
+    );
+    for side in [PlayerSide::Left, PlayerSide::Right] {
+        let (continued, expected) = (continued.observation(side), expected.observation(side));
+        assert_eq!(continued.paddle_y, expected.paddle_y);
+        assert_eq!(
+            (continued.ball_x, continued.ball_y),
+            (expected.ball_x, expected.ball_y)
+        );
+    }
+}
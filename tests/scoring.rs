@@ -0,0 +1,178 @@
+mod common;
+
+use cli_pong::scoring::{MatchFormat, MatchProgress, MatchScore};
+use cli_pong::state::{step, GameConfig, GameEvent, GameState};
+use cli_pong::stats::PlayerSide;
+use common::press_chars;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const LEFT: PlayerSide = PlayerSide::Left;
+const RIGHT: PlayerSide = PlayerSide::Right;
+
+fn format(points_to_win: usize, best_of: usize) -> MatchFormat {
+    MatchFormat {
+        points_to_win,
+        best_of,
+    }
+}
+
+#[test]
+fn a_game_is_won_at_the_point_target() {
+    let mut score = MatchScore::new(format(3, 3));
+
+    assert_eq!(score.point(LEFT), MatchProgress::Point);
+    assert_eq!(score.point(RIGHT), MatchProgress::Point);
+    assert_eq!(score.point(LEFT), MatchProgress::Point);
+    assert_eq!(score.points(), (2, 1));
+
+    assert_eq!(score.point(LEFT), MatchProgress::Game(LEFT));
+    assert_eq!(score.points(), (0, 0));
+    assert_eq!(score.games(), (1, 0));
+    assert_eq!(score.current_game(), 2);
+}
+
+#[test]
+fn the_match_is_won_with_the_majority_of_games() {
+    let mut score = MatchScore::new(format(1, 5));
+
+    assert_eq!(score.point(LEFT), MatchProgress::Game(LEFT));
+    assert_eq!(score.point(RIGHT), MatchProgress::Game(RIGHT));
+    assert_eq!(score.point(RIGHT), MatchProgress::Game(RIGHT));
+    assert_eq!(score.point(LEFT), MatchProgress::Game(LEFT));
+    assert_eq!(score.point(RIGHT), MatchProgress::Match(RIGHT));
+
+    assert_eq!(score.winner(), Some(RIGHT));
+    assert_eq!(score.games(), (2, 3));
+    assert_eq!(score.current_game(), 5);
+
+    // goals after the match change nothing
+    assert_eq!(score.point(LEFT), MatchProgress::Point);
+    assert_eq!(score.games(), (2, 3));
+}
+
+#[test]
+fn game_ends_with_the_match() {
+    let config = GameConfig::builder()
+        .seed(Some(5))
+        .match_format(Some(format(1, 3)))
+        .build();
+    let mut state = GameState::new(config);
+    let mut games_won = 0;
+    let mut won = Vec::new();
+
+    for _ in 0..5000 {
+        let (next, events) = step(&state, HashMap::new(), Duration::from_millis(50));
+        state = next;
+        for event in events {
+            match event {
+                GameEvent::GameWon(_) => games_won += 1,
+                GameEvent::Won(winner) => won.push(winner),
+                _ => {}
+            }
+        }
+    }
+
+    let score = state.match_score().unwrap();
+    assert_eq!(won.len(), 1);
+    assert_eq!(state.winner(), Some(won[0]));
+    assert_eq!(score.winner(), Some(won[0]));
+    assert_eq!(games_won + 1, score.games().0 + score.games().1);
+}
+
+#[test]
+fn header_shows_the_state_of_the_match() {
+    let config = GameConfig::builder()
+        .seed(Some(5))
+        .match_format(Some(format(11, 5)))
+        .build();
+    let state = GameState::new(config);
+    let header: String = state.render_to_grid()[1].iter().collect();
+
+    assert!(header.starts_with("Game 1 of best of 5  Games: 0-0  Points: 0-0 (to 11)"));
+}
+
+/// Plays the game until it is over and returns the winners of all `Won` events.
+fn play_until_over(state: &mut GameState) -> Vec<PlayerSide> {
+    let mut won = Vec::new();
+    for _ in 0..5000 {
+        if state.is_over() {
+            break;
+        }
+        let (next, events) = step(state, HashMap::new(), Duration::from_millis(50));
+        *state = next;
+        won.extend(events.into_iter().filter_map(|event| match event {
+            GameEvent::Won(winner) => Some(winner),
+            _ => None,
+        }));
+    }
+
+    won
+}
+
+#[test]
+fn game_is_over_once_a_player_reaches_the_score_limit() {
+    let config = GameConfig::builder()
+        .seed(Some(5))
+        .score_limit(Some(2))
+        .build();
+    let mut state = GameState::new(config);
+
+    let won = play_until_over(&mut state);
+
+    assert!(state.is_over());
+    assert_eq!(won.len(), 1);
+    assert_eq!(state.winner(), Some(won[0]));
+    let (player1_score, player2_score) = state.scores();
+    assert_eq!(player1_score.max(player2_score), 2);
+
+    // the game stands still until the rematch
+    let (after, events) = step(&state, HashMap::new(), Duration::from_millis(500));
+    assert!(events.is_empty());
+    assert_eq!(after.scores(), state.scores());
+    assert_eq!(
+        after.replay_frame(Duration::ZERO),
+        state.replay_frame(Duration::ZERO)
+    );
+}
+
+#[test]
+fn rematch_starts_the_game_again() {
+    let config = GameConfig::builder()
+        .seed(Some(5))
+        .score_limit(Some(1))
+        .build();
+    let mut state = GameState::new(config);
+    play_until_over(&mut state);
+
+    let (state, events) = step(&state, press_chars(&['r']), Duration::from_millis(50));
+
+    assert_eq!(events, vec![GameEvent::NewGame]);
+    assert!(!state.is_over());
+    assert_eq!(state.scores(), (0, 0));
+}
+
+#[test]
+fn game_over_banner_offers_a_rematch() {
+    let config = GameConfig::builder()
+        .seed(Some(5))
+        .score_limit(Some(1))
+        .build();
+    let mut state = GameState::new(config);
+    let header: String = state.render_to_grid()[1].iter().collect();
+    assert!(header.contains("First to 1"));
+
+    play_until_over(&mut state);
+    let name = match state.winner().unwrap() {
+        PlayerSide::Left => "player1",
+        PlayerSide::Right => "player2",
+    };
+    let (player1_score, player2_score) = state.scores();
+    let banner =
+        format!("{name} wins {player1_score}:{player2_score} - press r for a rematch or q to quit");
+
+    assert!(state
+        .render_to_grid()
+        .iter()
+        .any(|row| row.iter().collect::<String>().contains(&banner)));
+}
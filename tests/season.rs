@@ -0,0 +1,166 @@
+mod common;
+
+use cli_pong::ai::AiStyle;
+use cli_pong::observer::GameObserver;
+use cli_pong::season::{Season, SeasonGame, SeasonRecorder, Seasons};
+use cli_pong::state::{GameConfig, GameEvent, GameState};
+use cli_pong::storage::Storage;
+use common::temp_dir;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+fn players(names: &str) -> Vec<String> {
+    names.split(' ').map(String::from).collect()
+}
+
+/// Records the result of the fixture between the two players, whoever plays at home.
+fn record(season: &mut Season, player1: &str, player2: &str, goals: (usize, usize)) {
+    let index = season
+        .fixtures
+        .iter()
+        .position(|fixture| {
+            (fixture.home == player1 && fixture.away == player2)
+                || (fixture.home == player2 && fixture.away == player1)
+        })
+        .unwrap();
+    let score = match season.fixtures[index].home == player1 {
+        true => goals,
+        false => (goals.1, goals.0),
+    };
+    season.record(index, score).unwrap();
+}
+
+#[test]
+fn every_player_meets_every_other_player_once() {
+    for (names, fixtures, rounds) in [("a b c d", 6, 3), ("a b c d e", 10, 5)] {
+        let season = Season::new("club", players(names), 5).unwrap();
+
+        assert_eq!(season.fixtures.len(), fixtures);
+        let pairs: HashSet<(String, String)> = season
+            .fixtures
+            .iter()
+            .map(|fixture| {
+                let mut pair = [fixture.home.clone(), fixture.away.clone()];
+                pair.sort();
+                (pair[0].clone(), pair[1].clone())
+            })
+            .collect();
+        assert_eq!(pairs.len(), fixtures);
+        for round in 1..=rounds {
+            let playing: Vec<&String> = season
+                .fixtures
+                .iter()
+                .filter(|fixture| fixture.round == round)
+                .flat_map(|fixture| [&fixture.home, &fixture.away])
+                .collect();
+            let unique: HashSet<&&String> = playing.iter().collect();
+            assert_eq!(unique.len(), playing.len());
+            assert_eq!(playing.len(), 4);
+        }
+    }
+
+    assert!(Season::new("club", players("a"), 5).is_err());
+    assert!(Season::new("club", players("a b a"), 5).is_err());
+    assert!(Season::new("club", players("a b"), 0).is_err());
+}
+
+#[test]
+fn the_table_breaks_ties_by_goals_and_head_to_head() {
+    let mut season = Season::new("club", players("a b c d"), 5).unwrap();
+    record(&mut season, "a", "b", (5, 0));
+    record(&mut season, "a", "c", (5, 2));
+    record(&mut season, "b", "d", (5, 4));
+    record(&mut season, "c", "b", (5, 4));
+    record(&mut season, "c", "d", (2, 5));
+    assert_eq!(season.champion(), None);
+    let played = season
+        .fixtures
+        .iter()
+        .position(|fixture| fixture.score.is_some())
+        .unwrap();
+    assert!(season.record(played, (5, 1)).is_err());
+    record(&mut season, "a", "d", (5, 0));
+
+    let table: Vec<(String, usize, i64)> = season
+        .standings()
+        .into_iter()
+        .map(|standing| {
+            let difference = standing.goal_difference();
+            (standing.player, standing.wins, difference)
+        })
+        .collect();
+    // b and c are even on wins and goals, but c won their game
+    assert_eq!(
+        table,
+        vec![
+            ("a".to_string(), 3, 13),
+            ("d".to_string(), 1, -3),
+            ("c".to_string(), 1, -5),
+            ("b".to_string(), 1, -5),
+        ]
+    );
+    assert_eq!(season.champion().as_deref(), Some("a"));
+    assert!(season.table()[1].contains("a"));
+}
+
+#[test]
+fn seasons_are_kept_until_they_are_finished() {
+    let storage = Storage::new(temp_dir("kept"));
+    let season = Season::new("spring", players("a b"), 3).unwrap();
+
+    Seasons::start(&storage, season.clone(), false).unwrap();
+    assert!(Seasons::start(&storage, season.clone(), false).is_err());
+    let recorded = Seasons::record(&storage, 0, (3, 1)).unwrap();
+    assert!(recorded.is_complete());
+    assert!(Seasons::record(&storage, 0, (3, 2)).is_err());
+
+    let autumn = Season::new("autumn", players("a b c"), 3).unwrap();
+    Seasons::start(&storage, autumn.clone(), false).unwrap();
+    let seasons = Seasons::load(&storage).unwrap();
+    assert_eq!(seasons.current, Some(autumn));
+    assert_eq!(seasons.finished, vec![recorded]);
+
+    let winter = Season::new("winter", players("b c"), 3).unwrap();
+    assert!(Seasons::start(&storage, winter.clone(), false).is_err());
+    Seasons::start(&storage, winter.clone(), true).unwrap();
+    assert_eq!(Seasons::load(&storage).unwrap().current, Some(winter));
+}
+
+#[test]
+fn the_first_game_of_a_fixture_is_recorded() {
+    let storage = Storage::new(temp_dir("recorded"));
+    let season = Season::new("club", players("alice bob carol"), 2).unwrap();
+    Seasons::start(&storage, season.clone(), false).unwrap();
+    let index = season.next_fixture().unwrap();
+    let game = SeasonGame::new(&season, index).unwrap();
+    let mut state = GameState::with_mode(
+        GameConfig::builder()
+            .seed(Some(5))
+            .player1_ai(Some(AiStyle::Balanced.profile()))
+            .player2_ai(Some(AiStyle::Rookie.profile()))
+            .build(),
+        Box::new(game),
+    );
+    let mut recorder = SeasonRecorder::new(storage.clone(), index);
+
+    assert_eq!(state.config().score_limit, Some(2));
+    let mut events = Vec::new();
+    while !state.is_over() {
+        events = state.update(HashMap::new(), Duration::from_millis(50));
+        for &event in &events {
+            recorder.on_event(&state, event).unwrap();
+        }
+    }
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, GameEvent::Won(_))));
+    // a second win, e.g. of a rematch, does not change the result
+    let (home, away) = state.scores();
+    recorder.on_event(&state, events[events.len() - 1]).unwrap();
+    recorder.on_finish().unwrap();
+
+    let current = Seasons::load(&storage).unwrap().current.unwrap();
+    assert_eq!(current.fixtures[index].score, Some((home, away)));
+    assert_eq!(home.max(away), 2);
+    assert_ne!(current.next_fixture(), Some(index));
+}
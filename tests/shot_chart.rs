@@ -0,0 +1,96 @@
+mod common;
+
+use cli_pong::ai::AiStyle;
+use cli_pong::shot_chart::{ShotChart, SHOT_CHART_BANDS};
+use cli_pong::state::{Edge, GameConfig, GameEvent, GameState};
+use cli_pong::stats::PlayerSide;
+use common::{ai_match, press_chars};
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+/// Plays the game until it is over and records every update in a shot chart.
+fn play(config: GameConfig, keys: &[char]) -> (GameState, ShotChart) {
+    let mut state = GameState::new(config);
+    let mut chart = ShotChart::default();
+    let pressed = press_chars(keys);
+
+    for _ in 0..50_000 {
+        if state.is_over() {
+            return (state, chart);
+        }
+        let events = state.update(pressed.clone(), DT);
+        chart.record(&state, &events);
+    }
+
+    panic!("the game was not over");
+}
+
+#[test]
+fn chart_counts_every_goal_for_the_player_who_conceded_it() {
+    for seed in 1..=4 {
+        let (state, chart) = play(ai_match(seed, 5), &[]);
+        let (player1_score, player2_score) = state.scores();
+
+        assert_eq!(chart.conceded(PlayerSide::Left), player2_score);
+        assert_eq!(chart.conceded(PlayerSide::Right), player1_score);
+        assert_eq!(
+            chart.bands(PlayerSide::Left).iter().sum::<usize>(),
+            player2_score
+        );
+    }
+}
+
+#[test]
+fn goals_cross_the_goal_line_inside_the_field() {
+    let (state, _) = play(ai_match(2, 5), &[]);
+    let (edge, crossing) = state.last_goal_crossing().unwrap();
+    let config = state.config();
+
+    let x = match edge {
+        Edge::Left => 0.0,
+        _ => config.width as f64,
+    };
+    assert_eq!(crossing.x(), x);
+    assert!((0.0..=config.height as f64).contains(&crossing.y()));
+}
+
+#[test]
+fn new_games_start_an_empty_chart() {
+    let (state, mut chart) = play(ai_match(3, 5), &[]);
+    assert!(!chart.is_empty());
+
+    chart.record(&state, &[GameEvent::NewGame]);
+
+    assert!(chart.is_empty());
+}
+
+#[test]
+fn chart_has_a_row_for_every_band() {
+    let (_, chart) = play(ai_match(1, 5), &[]);
+    let lines = chart.render();
+
+    assert_eq!(lines[0], "Where the goals crossed the goal lines");
+    assert!(lines[1].contains("player1 conceded"));
+    assert!(lines[1].contains("player2 conceded"));
+    assert!(lines[2].starts_with("15-18"));
+    assert!(lines[1 + SHOT_CHART_BANDS].starts_with("0-2"));
+}
+
+#[test]
+fn chart_points_out_goals_conceded_in_one_half() {
+    // player1 waits at the top, and the balls that beat it pass just below its paddle
+    let config = GameConfig::builder()
+        .seed(Some(11))
+        .player2_ai(Some(AiStyle::Balanced.profile()))
+        .score_limit(Some(5))
+        .build();
+    let (_, chart) = play(config, &['w']);
+
+    assert_eq!(chart.conceded(PlayerSide::Left), 5);
+    let bands = chart.bands(PlayerSide::Left);
+    assert_eq!(bands[SHOT_CHART_BANDS - 1], 0);
+    assert!(chart
+        .render()
+        .contains(&"player1 lets in most goals high: 5 of 5".to_string()));
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use cli_pong::achievement::{Achievement, AchievementTracker, Achievements, LONG_RALLY_HITS};
+use cli_pong::ai::AiStyle;
+use cli_pong::observer::GameObserver;
+use cli_pong::skin::Skin;
+use cli_pong::state::{GameConfig, GameEvent, GameState, Handicap};
+use cli_pong::stats::PlayerSide;
+use cli_pong::storage::Storage;
+use common::{count_glyphs, temp_dir};
+
+fn against_computer(player2_start_score: usize) -> GameState {
+    GameState::new(
+        GameConfig::builder()
+            .seed(Some(1))
+            .player2_ai(Some(AiStyle::Balanced.profile()))
+            .player2_handicap(Handicap {
+                start_score: player2_start_score,
+                ..Handicap::default()
+            })
+            .build(),
+    )
+}
+
+#[test]
+fn skins_change_the_paddle_of_player1_and_the_ball() {
+    let classic = GameState::new(GameConfig::builder().seed(Some(1)).build());
+    let ghost = GameState::new(
+        GameConfig::builder()
+            .seed(Some(1))
+            .skin(Skin::find("ghost").unwrap())
+            .build(),
+    );
+
+    assert_eq!(count_glyphs(&classic, '\u{25CF}'), 1);
+    assert_eq!(count_glyphs(&classic, '\u{2592}'), 0);
+    assert_eq!(count_glyphs(&ghost, '\u{25CF}'), 0);
+    assert_eq!(count_glyphs(&ghost, '\u{25CB}'), 1);
+    // only the paddle of player1 changes, player2 and the walls keep their glyph
+    assert_eq!(count_glyphs(&ghost, '\u{2592}'), 3);
+    assert_eq!(
+        count_glyphs(&ghost, '\u{2588}'),
+        count_glyphs(&classic, '\u{2588}') - 3
+    );
+    assert_eq!(ghost.state_hash(), classic.state_hash());
+}
+
+#[test]
+fn skins_are_locked_until_their_achievement_is_unlocked() {
+    let mut achievements = Achievements::default();
+    let ember = Skin::find("ember").unwrap();
+
+    assert!(Skin::classic().is_unlocked(&achievements));
+    assert!(!ember.is_unlocked(&achievements));
+    achievements.unlocked.insert(Achievement::FirstWin);
+    assert!(ember.is_unlocked(&achievements));
+    assert!(Skin::find("gold").is_none());
+    for achievement in Achievement::ALL {
+        assert!(Skin::unlocked_by(achievement).is_some());
+    }
+}
+
+#[test]
+fn wins_against_the_computer_are_saved_once() {
+    let storage = Storage::new(temp_dir("wins"));
+    let mut tracker = AchievementTracker::new(storage.clone());
+
+    tracker
+        .on_event(&against_computer(2), GameEvent::Won(PlayerSide::Left))
+        .unwrap();
+    assert_eq!(
+        tracker.earned().iter().copied().collect::<Vec<_>>(),
+        vec![Achievement::FirstWin]
+    );
+    tracker
+        .on_event(&against_computer(0), GameEvent::Won(PlayerSide::Left))
+        .unwrap();
+
+    assert_eq!(
+        tracker.save().unwrap(),
+        vec![Achievement::FirstWin, Achievement::Shutout]
+    );
+    assert!(tracker.save().unwrap().is_empty());
+    let achievements = Achievements::load(&storage).unwrap();
+    assert!(achievements.is_unlocked(Achievement::Shutout));
+    assert!(!achievements.is_unlocked(Achievement::LongRally));
+}
+
+#[test]
+fn only_player1_on_the_keyboard_earns_achievements() {
+    let storage = Storage::new(temp_dir("keyboard"));
+    let versus = GameState::new(GameConfig::default());
+    let computers = GameState::new(
+        GameConfig::builder()
+            .player1_ai(Some(AiStyle::Balanced.profile()))
+            .player2_ai(Some(AiStyle::Balanced.profile()))
+            .build(),
+    );
+    let mut tracker = AchievementTracker::new(storage);
+
+    // a win against another human does not count, a long rally does
+    tracker
+        .on_event(&versus, GameEvent::Won(PlayerSide::Left))
+        .unwrap();
+    for _ in 0..LONG_RALLY_HITS - 1 {
+        tracker
+            .on_event(&versus, GameEvent::Hit(PlayerSide::Right))
+            .unwrap();
+    }
+    tracker
+        .on_event(&versus, GameEvent::PointRestarted)
+        .unwrap();
+    tracker
+        .on_event(&versus, GameEvent::Hit(PlayerSide::Left))
+        .unwrap();
+    assert!(tracker.earned().is_empty());
+    for _ in 0..LONG_RALLY_HITS {
+        tracker
+            .on_event(&computers, GameEvent::Hit(PlayerSide::Left))
+            .unwrap();
+    }
+    tracker
+        .on_event(&computers, GameEvent::Won(PlayerSide::Left))
+        .unwrap();
+    assert!(tracker.earned().is_empty());
+
+    for _ in 1..LONG_RALLY_HITS {
+        tracker
+            .on_event(&versus, GameEvent::Hit(PlayerSide::Left))
+            .unwrap();
+    }
+    assert_eq!(
+        tracker.earned().iter().copied().collect::<Vec<_>>(),
+        vec![Achievement::LongRally]
+    );
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use cli_pong::capability::Capabilities;
+use cli_pong::observer::GameObserver;
+use cli_pong::spectate::SpectatorServer;
+use common::{game, temp_dir};
+use std::fs;
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::time::Duration;
+
+fn names() -> [String; 2] {
+    ["Player 1".to_string(), "Player 2".to_string()]
+}
+
+#[test]
+fn leftover_sockets_are_replaced() {
+    let path = temp_dir("leftover_sockets_are_replaced").join("game.sock");
+    drop(UnixListener::bind(&path).unwrap());
+
+    assert!(SpectatorServer::bind(&path, names()).is_ok());
+}
+
+#[test]
+fn sockets_of_running_games_are_kept() {
+    let path = temp_dir("sockets_of_running_games_are_kept").join("game.sock");
+    let _running = UnixListener::bind(&path).unwrap();
+
+    let error = SpectatorServer::bind(&path, names()).err().unwrap();
+
+    assert_eq!(error.kind(), ErrorKind::AddrInUse);
+}
+
+#[test]
+fn other_files_are_never_replaced() {
+    let path = temp_dir("other_files_are_never_replaced").join("notes.txt");
+    fs::write(&path, "my notes").unwrap();
+
+    let error = SpectatorServer::bind(&path, names()).err().unwrap();
+
+    assert_eq!(error.kind(), ErrorKind::AlreadyExists);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "my notes");
+}
+
+#[test]
+fn caster_view_falls_back_to_ascii() {
+    let path = temp_dir("caster_view_falls_back_to_ascii").join("game.sock");
+    let mut server = SpectatorServer::bind(&path, names()).unwrap();
+    let mut state = game(1);
+    state.set_capabilities(Capabilities {
+        unicode: false,
+        ..Capabilities::default()
+    });
+    let mut spectator = UnixStream::connect(&path).unwrap();
+    spectator.write_all(b"c").unwrap();
+
+    server.on_tick(&state, Duration::from_millis(50)).unwrap();
+    drop(server);
+    let mut received = String::new();
+    spectator.read_to_string(&mut received).unwrap();
+
+    // the digits of the big score are blocks like the walls
+    assert!(received.contains("###"), "{received}");
+    assert!(received.is_ascii(), "{received}");
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use cli_pong::state::{step, GameEvent, GameState};
+use cli_pong::stats::PlayerSide;
+use common::game;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+fn ball_position(state: &GameState) -> (f64, f64) {
+    let observation = state.observation(PlayerSide::Left);
+    (observation.ball_x, observation.ball_y)
+}
+
+#[test]
+fn step_leaves_the_given_state_untouched() {
+    let state = game(7);
+    let before = ball_position(&state);
+
+    let (next, _) = step(&state, HashMap::new(), DT);
+
+    assert_eq!(ball_position(&state), before);
+    assert_ne!(ball_position(&next), before);
+}
+
+#[test]
+fn stepping_the_same_state_gives_the_same_result() {
+    let mut first = game(7);
+    let mut second = first.clone();
+
+    for _ in 0..2000 {
+        let (next, first_events) = step(&first, HashMap::new(), DT);
+        first = next;
+        let (next, second_events) = step(&second, HashMap::new(), DT);
+        second = next;
+
+        assert_eq!(first_events, second_events);
+        assert_eq!(ball_position(&first), ball_position(&second));
+        assert_eq!(first.scores(), second.scores());
+    }
+}
+
+#[test]
+fn goals_are_reported_as_events() {
+    let mut state = game(7);
+    let mut goals = Vec::new();
+
+    for _ in 0..2000 {
+        let (next, events) = step(&state, HashMap::new(), DT);
+        state = next;
+        goals.extend(events.into_iter().filter_map(|event| match event {
+            GameEvent::Goal(side) => Some(side),
+            _ => None,
+        }));
+    }
+
+    let left = goals
+        .iter()
+        .filter(|&&side| side == PlayerSide::Left)
+        .count();
+    let right = goals.len() - left;
+    assert!(!goals.is_empty());
+    assert_eq!(state.scores(), (left, right));
+}
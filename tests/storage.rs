@@ -0,0 +1,111 @@
+mod common;
+
+use cli_pong::storage::{write_atomically, Collection, Loaded, Recovery, Storage};
+use common::temp_dir;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Record {
+    longest_rally: usize,
+    names: Vec<String>,
+}
+
+/// Returns an empty storage in a temporary directory that is unique for each test.
+fn storage(test: &str) -> Storage {
+    Storage::new(temp_dir(test))
+}
+
+fn scores(longest_rally: usize) -> Record {
+    Record {
+        longest_rally,
+        names: vec!["player1".to_string()],
+    }
+}
+
+#[test]
+fn missing_collection_is_empty() {
+    let storage = storage("missing");
+
+    let loaded: Loaded<Record> = storage.load(Collection::Achievements).unwrap();
+    assert_eq!(loaded.value, Record::default());
+    assert_eq!(loaded.recovery, None);
+}
+
+#[test]
+fn saved_collection_is_loaded_again() {
+    let storage = storage("roundtrip");
+    storage.save(Collection::Achievements, &scores(12)).unwrap();
+
+    let loaded: Loaded<Record> = storage.load(Collection::Achievements).unwrap();
+    assert_eq!(loaded.value, scores(12));
+    assert_eq!(loaded.recovery, None);
+    assert!(!storage.dir().join("achievements.json.tmp").exists());
+}
+
+#[test]
+fn collections_are_stored_in_separate_files() {
+    let storage = storage("separate");
+    storage.save(Collection::Achievements, &scores(3)).unwrap();
+
+    let telemetry: Loaded<Record> = storage.load(Collection::Telemetry).unwrap();
+    assert_eq!(telemetry.value, Record::default());
+}
+
+#[test]
+fn corrupted_collection_is_restored_from_the_backup() {
+    let storage = storage("backup");
+    storage.save(Collection::Achievements, &scores(5)).unwrap();
+    storage.save(Collection::Achievements, &scores(8)).unwrap();
+    let path = storage.path(Collection::Achievements);
+    fs::write(&path, "{\"longest_rally\": 8, \"na").unwrap();
+
+    let loaded: Loaded<Record> = storage.load(Collection::Achievements).unwrap();
+    assert_eq!(loaded.value, scores(5));
+    assert_eq!(loaded.recovery, Some(Recovery::FromBackup));
+    assert!(storage.dir().join("achievements.json.corrupt").exists());
+
+    // the restored version is the stored one from now on
+    let loaded: Loaded<Record> = storage.load(Collection::Achievements).unwrap();
+    assert_eq!(loaded.recovery, None);
+}
+
+#[test]
+fn corrupted_collection_without_backup_is_reset() {
+    let storage = storage("reset");
+    storage.save(Collection::Achievements, &scores(1)).unwrap();
+    fs::write(storage.path(Collection::Achievements), [0xff, 0xfe]).unwrap();
+
+    let loaded: Loaded<Record> = storage.load(Collection::Achievements).unwrap();
+    assert_eq!(loaded.value, Record::default());
+    assert_eq!(loaded.recovery, Some(Recovery::Reset));
+}
+
+#[test]
+fn update_changes_the_stored_collection() {
+    let storage = storage("update");
+    storage.save(Collection::Seasons, &scores(4)).unwrap();
+
+    let updated = storage
+        .update(Collection::Seasons, |scores: &mut Record| {
+            scores.longest_rally += 1
+        })
+        .unwrap();
+
+    assert_eq!(updated, scores(5));
+    let loaded: Loaded<Record> = storage.load(Collection::Seasons).unwrap();
+    assert_eq!(loaded.value, scores(5));
+}
+
+#[test]
+fn atomic_write_replaces_the_file() {
+    let storage = storage("atomic");
+    fs::create_dir_all(storage.dir()).unwrap();
+    let path = storage.dir().join("preset.toml");
+    fs::write(&path, "old").unwrap();
+
+    write_atomically(&path, "new").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    assert!(!storage.dir().join("preset.toml.tmp").exists());
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use cli_pong::ai::{AiStrategy, StrategyRegistry};
+use cli_pong::state::{step, Controller, GameEvent, GameState, PaddleCommand};
+use cli_pong::stats::PlayerSide;
+use common::game;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+fn paddle_y(state: &GameState, side: PlayerSide) -> f64 {
+    state.observation(side).paddle_y
+}
+
+/// A custom bot that always moves its paddle to the top of the field.
+#[derive(Clone)]
+struct Climber;
+
+impl AiStrategy for Climber {
+    fn decide(&mut self, _state: &GameState, _side: PlayerSide, _dt: Duration) -> PaddleCommand {
+        PaddleCommand::Up
+    }
+}
+
+#[test]
+fn registry_contains_the_built_in_strategies() {
+    let strategies = StrategyRegistry::default();
+
+    assert_eq!(strategies.names(), vec!["follow-ball", "predictive"]);
+    assert!(strategies.create("predictive").is_some());
+    assert!(strategies.create("unknown").is_none());
+}
+
+#[test]
+fn custom_strategy_moves_the_paddle() {
+    let mut strategies = StrategyRegistry::default();
+    strategies.register("climber", || Box::new(Climber));
+    let mut state = game(7);
+    state.set_ai_strategy(PlayerSide::Right, strategies.create("climber").unwrap());
+
+    for _ in 0..40 {
+        state = step(&state, HashMap::new(), DT).0;
+    }
+
+    let height = state.observation(PlayerSide::Right).field_height;
+    assert!(matches!(
+        state.controller(PlayerSide::Right),
+        Controller::Ai(_)
+    ));
+    assert!(paddle_y(&state, PlayerSide::Right) > height - 2.0);
+}
+
+#[test]
+fn keyboard_takes_over_from_a_strategy_again() {
+    let mut state = game(7);
+    state.set_ai_strategy(PlayerSide::Left, Box::new(Climber));
+
+    state.toggle_controller(PlayerSide::Left);
+
+    assert!(matches!(
+        state.controller(PlayerSide::Left),
+        Controller::Keyboard(_)
+    ));
+}
+
+#[test]
+fn built_in_strategies_return_balls() {
+    for name in StrategyRegistry::default().names() {
+        let strategies = StrategyRegistry::default();
+        let mut state = game(7);
+        state.set_ai_strategy(PlayerSide::Left, strategies.create(name).unwrap());
+        state.set_ai_strategy(PlayerSide::Right, strategies.create(name).unwrap());
+
+        let mut hits = 0;
+        for _ in 0..400 {
+            let (next, events) = step(&state, HashMap::new(), DT);
+            state = next;
+            hits += events
+                .iter()
+                .filter(|event| matches!(event, GameEvent::Hit(_)))
+                .count();
+        }
+
+        assert!(hits >= 5, "{name} returned only {hits} balls");
+    }
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use cli_pong::ai::AiStyle;
+use cli_pong::mode::{GameMode, Survival};
+use cli_pong::ramp::{DifficultyRamp, BALL_SPEED_STEP, SURVIVAL_RAMP_INTERVAL};
+use cli_pong::state::{GameConfig, GameEvent, GameState};
+use cli_pong::stats::PlayerSide;
+use common::{ball_speed, place_ball, press};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+#[test]
+fn survival_ramps_up_against_the_rookie() {
+    let state = GameState::with_mode(
+        GameConfig::builder()
+            .seed(Some(1))
+            .score_limit(Some(5))
+            .build(),
+        Box::new(Survival),
+    );
+    let config = state.config();
+
+    assert_eq!(config.difficulty_ramp, Some(SURVIVAL_RAMP_INTERVAL));
+    assert_eq!(config.score_limit, None);
+    assert_eq!(
+        config.player2_ai.unwrap().reaction_time,
+        AiStyle::Rookie.profile().reaction_time
+    );
+    assert_eq!(state.difficulty().unwrap().level(), 0);
+    assert!(GameState::new(GameConfig::default()).difficulty().is_none());
+}
+
+#[test]
+fn each_level_speeds_the_ball_up() {
+    let config = GameConfig::builder().seed(Some(2));
+    let mut ramped = GameState::new(
+        config
+            .clone()
+            .difficulty_ramp(Some(Duration::from_secs(1)))
+            .build(),
+    );
+    let mut steady = GameState::new(config.build());
+    place_ball(&mut ramped, (30.0, 9.0), (5.0, 0.0));
+    place_ball(&mut steady, (30.0, 9.0), (5.0, 0.0));
+
+    for _ in 0..19 {
+        assert!(ramped.update(HashMap::new(), DT).is_empty());
+        steady.update(HashMap::new(), DT);
+    }
+    assert_eq!(ball_speed(&ramped), ball_speed(&steady));
+
+    let events = ramped.update(HashMap::new(), DT);
+    steady.update(HashMap::new(), DT);
+
+    assert_eq!(events, vec![GameEvent::LevelUp(1)]);
+    assert_eq!(ramped.difficulty().unwrap().level(), 1);
+    assert!((ball_speed(&ramped) / ball_speed(&steady) - BALL_SPEED_STEP).abs() < 1e-9);
+}
+
+#[test]
+fn paused_time_does_not_count() {
+    let mut state = GameState::new(
+        GameConfig::builder()
+            .seed(Some(3))
+            .difficulty_ramp(Some(Duration::from_secs(1)))
+            .build(),
+    );
+    let pause = press(KeyCode::Char(' '), KeyModifiers::NONE);
+
+    state.update(pause, DT);
+    for _ in 0..40 {
+        state.update(HashMap::new(), DT);
+    }
+
+    let ramp = state.difficulty().unwrap();
+    assert_eq!(ramp.level(), 0);
+    assert_eq!(ramp.elapsed(), Duration::ZERO);
+}
+
+#[test]
+fn ramp_sharpens_the_ai() {
+    let base = AiStyle::Rookie.profile();
+    let mut ramp = DifficultyRamp::new(Duration::from_secs(30));
+
+    assert_eq!(ramp.advance(Duration::from_secs(29)), 0);
+    assert_eq!(ramp.time_to_next_level(), Duration::from_secs(1));
+    assert_eq!(ramp.ai_profile(base).reaction_time, base.reaction_time);
+    assert_eq!(ramp.advance(Duration::from_secs(61)), 3);
+    assert_eq!(ramp.level(), 3);
+    assert_eq!(ramp.elapsed(), Duration::from_secs(90));
+
+    let sharpened = ramp.ai_profile(base);
+    assert!(sharpened.reaction_time < base.reaction_time);
+    assert!(sharpened.reaction_distance > base.reaction_distance);
+    assert!(sharpened.aim_error < base.aim_error);
+    assert!(sharpened.predicts_bounces && sharpened.recovers_to_center);
+    assert!((ramp.ball_speed_factor() - BALL_SPEED_STEP.powi(3)).abs() < 1e-9);
+}
+
+#[test]
+fn first_goal_against_player1_ends_the_game() {
+    let mut state = GameState::with_mode(
+        GameConfig::builder().seed(Some(4)).build(),
+        Box::new(Survival),
+    );
+    let hud = |state: &GameState| -> String { state.render_to_grid()[2].iter().collect() };
+    assert!(hud(&state).starts_with("Survived: 00:00,  Level: 0,  next in 30s"));
+
+    // player1 does not move, so a ball gets past its paddle sooner or later
+    let mut events = Vec::new();
+    while !state.is_over() {
+        events.extend(state.update(HashMap::new(), DT));
+    }
+
+    assert_eq!(state.winner(), Some(PlayerSide::Right));
+    assert_eq!(state.scores().1, 1);
+    assert!(events.contains(&GameEvent::Won(PlayerSide::Right)));
+    let summary = Survival.summary(&state);
+    assert!(summary[0].starts_with("Survived 00:"), "{summary:?}");
+    assert!(summary[1].starts_with("Rallies: 1,"), "{summary:?}");
+    assert!(!hud(&state).contains("next in"));
+
+    state.update(press(KeyCode::Char('r'), KeyModifiers::CONTROL), DT);
+    assert_eq!(state.difficulty().unwrap().elapsed(), Duration::ZERO);
+    assert!(!state.is_over());
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use cli_pong::ai::AiStyle;
+use cli_pong::state::{step, GameConfig, GameEvent, GameState};
+use cli_pong::stats::PlayerSide;
+use cli_pong::taunt::{Taunt, Taunts, TAUNT_COOLDOWN, TAUNT_TIME};
+use common::press_chars;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+fn shows(state: &GameState, text: &str) -> bool {
+    state
+        .compose_frame()
+        .chars()
+        .iter()
+        .any(|row| row.iter().collect::<String>().contains(text))
+}
+
+#[test]
+fn a_taunt_is_shown_for_a_moment() {
+    let mut taunts = Taunts::default();
+
+    assert!(taunts.fire(PlayerSide::Left, Taunt::Wow));
+    assert_eq!(taunts.current(PlayerSide::Left), Some(Taunt::Wow));
+    assert_eq!(taunts.current(PlayerSide::Right), None);
+
+    taunts.update(TAUNT_TIME);
+    assert_eq!(taunts.current(PlayerSide::Left), None);
+}
+
+#[test]
+fn taunts_are_rate_limited_per_player() {
+    let mut taunts = Taunts::default();
+
+    assert!(taunts.fire(PlayerSide::Left, Taunt::GoodGame));
+    assert!(!taunts.fire(PlayerSide::Left, Taunt::TooEasy));
+    // the other player has a rate limit of its own
+    assert!(taunts.fire(PlayerSide::Right, Taunt::Close));
+
+    taunts.update(TAUNT_COOLDOWN - DT);
+    assert!(!taunts.fire(PlayerSide::Left, Taunt::TooEasy));
+    taunts.update(DT);
+    assert!(taunts.fire(PlayerSide::Left, Taunt::TooEasy));
+}
+
+#[test]
+fn the_taunt_keys_show_the_taunts_next_to_the_paddles() {
+    let state = GameState::new(GameConfig::builder().seed(Some(1)).build());
+
+    let (state, events) = step(&state, press_chars(&['1']), DT);
+    assert!(events.contains(&GameEvent::Taunted(PlayerSide::Left, Taunt::GoodGame)));
+    assert!(shows(&state, Taunt::GoodGame.message()));
+
+    let (state, events) = step(&state, press_chars(&['0']), DT);
+    assert!(events.contains(&GameEvent::Taunted(PlayerSide::Right, Taunt::Wow)));
+    assert!(shows(&state, Taunt::Wow.message()));
+
+    let mut state = state;
+    for _ in 0..TAUNT_TIME.as_millis() / DT.as_millis() {
+        state = step(&state, HashMap::new(), DT).0;
+    }
+    assert!(!shows(&state, Taunt::GoodGame.message()));
+}
+
+#[test]
+fn the_computer_does_not_taunt() {
+    let state = GameState::new(
+        GameConfig::builder()
+            .seed(Some(1))
+            .player2_ai(Some(AiStyle::Balanced.profile()))
+            .build(),
+    );
+
+    let (_, events) = step(&state, press_chars(&['7']), DT);
+
+    assert!(!events
+        .iter()
+        .any(|event| matches!(event, GameEvent::Taunted(..))));
+}
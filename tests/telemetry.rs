@@ -0,0 +1,135 @@
+mod common;
+
+use cli_pong::observer::GameObserver;
+use cli_pong::state::{GameConfig, GameEvent, GameState};
+use cli_pong::stats::PlayerSide;
+use cli_pong::storage::{Collection, Storage};
+use cli_pong::telemetry::{GameplayMetrics, Telemetry, TelemetryRecorder};
+use common::temp_dir;
+use std::fs;
+
+/// Plays a short game in the eyes of the recorder: a rally of three returns that ends with a goal,
+/// and a rally of one return that is served again.
+fn play(recorder: &mut TelemetryRecorder) {
+    let state = GameState::new(GameConfig::default());
+    let events = [
+        GameEvent::Hit(PlayerSide::Left),
+        GameEvent::Hit(PlayerSide::Right),
+        GameEvent::Hit(PlayerSide::Left),
+        GameEvent::Goal(PlayerSide::Left),
+        GameEvent::Hit(PlayerSide::Right),
+        GameEvent::PointRestarted,
+    ];
+    for event in events {
+        recorder.on_event(&state, event).unwrap();
+    }
+}
+
+#[test]
+fn nothing_is_recorded_before_the_player_opts_in() {
+    let storage = Storage::new(temp_dir("off"));
+
+    assert!(TelemetryRecorder::start(storage.clone(), "versus")
+        .unwrap()
+        .is_none());
+    assert!(!storage.path(Collection::Telemetry).exists());
+    assert_eq!(Telemetry::load(&storage).unwrap(), Telemetry::default());
+}
+
+#[test]
+fn finished_games_count_their_mode_and_rallies() {
+    let storage = Storage::new(temp_dir("finished"));
+    Telemetry::set_enabled(&storage, true).unwrap();
+
+    for mode in ["versus", "single", "single"] {
+        let mut recorder = TelemetryRecorder::start(storage.clone(), mode)
+            .unwrap()
+            .unwrap();
+        play(&mut recorder);
+        recorder.on_finish().unwrap();
+    }
+
+    let metrics = Telemetry::load(&storage).unwrap().metrics;
+    assert_eq!(metrics.sessions, 3);
+    assert_eq!(metrics.crash_free_sessions, 3);
+    assert_eq!(metrics.modes["single"], 2);
+    assert_eq!(metrics.modes["versus"], 1);
+    assert_eq!(metrics.rallies, 3);
+    assert_eq!(metrics.average_rally_length(), 3.0);
+}
+
+#[test]
+fn games_that_never_finish_are_not_crash_free() {
+    let storage = Storage::new(temp_dir("crash"));
+    Telemetry::set_enabled(&storage, true).unwrap();
+
+    let mut crashed = TelemetryRecorder::start(storage.clone(), "versus")
+        .unwrap()
+        .unwrap();
+    play(&mut crashed);
+    drop(crashed);
+    let mut finished = TelemetryRecorder::start(storage.clone(), "versus")
+        .unwrap()
+        .unwrap();
+    finished.on_finish().unwrap();
+
+    let metrics = Telemetry::load(&storage).unwrap().metrics;
+    assert_eq!(metrics.sessions, 2);
+    assert_eq!(metrics.crash_free_sessions, 1);
+    assert_eq!(metrics.crash_free_rate(), 0.5);
+    assert_eq!(metrics.rallies, 0);
+}
+
+#[test]
+fn turning_the_telemetry_off_stops_the_recording() {
+    let storage = Storage::new(temp_dir("disable"));
+    Telemetry::set_enabled(&storage, true).unwrap();
+    let mut recorder = TelemetryRecorder::start(storage.clone(), "versus")
+        .unwrap()
+        .unwrap();
+
+    Telemetry::set_enabled(&storage, false).unwrap();
+    play(&mut recorder);
+    recorder.on_finish().unwrap();
+
+    let telemetry = Telemetry::load(&storage).unwrap();
+    assert!(!telemetry.enabled);
+    assert_eq!(telemetry.metrics.sessions, 1);
+    assert_eq!(telemetry.metrics.crash_free_sessions, 0);
+    assert!(TelemetryRecorder::start(storage.clone(), "versus")
+        .unwrap()
+        .is_none());
+
+    let reset = Telemetry::reset(&storage).unwrap();
+    assert_eq!(reset.metrics, GameplayMetrics::default());
+}
+
+#[test]
+fn report_holds_only_the_metrics() {
+    let dir = temp_dir("export");
+    let storage = Storage::new(&dir);
+    Telemetry::set_enabled(&storage, true).unwrap();
+    let mut recorder = TelemetryRecorder::start(storage.clone(), "squash")
+        .unwrap()
+        .unwrap();
+    play(&mut recorder);
+    recorder.on_finish().unwrap();
+
+    let path = dir.join("report.json");
+    let telemetry = Telemetry::load(&storage).unwrap();
+    telemetry.export(&path).unwrap();
+
+    let report: GameplayMetrics =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(report, telemetry.metrics);
+    assert!(!fs::read_to_string(&path).unwrap().contains("enabled"));
+    assert_eq!(
+        telemetry.metrics.summary(),
+        vec![
+            "Games: 1",
+            "Crash-free games: 1 (100.0%)",
+            "Average rally: 3.0 returns over 1 rallies",
+            "Mode squash: 1 games",
+        ]
+    );
+}
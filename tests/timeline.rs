@@ -0,0 +1,142 @@
+mod common;
+
+use cli_pong::replay::{Replay, ReplayRecorder};
+use cli_pong::state::{Edge, GameConfig, GameEvent, GameState};
+use cli_pong::timeline::{PointTimeline, TimelineAction, TimelineView};
+use common::{ai_match, key, temp_dir};
+use crossterm::event::KeyCode;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DT: Duration = Duration::from_millis(50);
+
+/// Plays the game until it is over, records every update in a timeline and returns all events.
+fn play(config: GameConfig) -> (GameState, PointTimeline, Vec<GameEvent>) {
+    let mut state = GameState::new(config);
+    let mut timeline = PointTimeline::default();
+    let mut events = Vec::new();
+
+    for _ in 0..50_000 {
+        if state.is_over() {
+            return (state, timeline, events);
+        }
+        let update = state.update(HashMap::new(), DT);
+        timeline.record(&state, &update, DT);
+        events.extend(update);
+    }
+
+    panic!("the game was not over");
+}
+
+#[test]
+fn timeline_has_a_point_for_every_goal() {
+    let (state, timeline, events) = play(ai_match(1, 5));
+    let (player1_score, player2_score) = state.scores();
+    let points = timeline.points();
+
+    assert_eq!(points.len(), player1_score + player2_score);
+    assert_eq!(points.last().unwrap().score, state.scores());
+    let player1_points = points
+        .iter()
+        .filter(|point| point.scorer == Edge::Left)
+        .count();
+    assert_eq!(player1_points, player1_score);
+
+    let hits = events
+        .iter()
+        .filter(|event| matches!(event, GameEvent::Hit(_)))
+        .count();
+    assert_eq!(points.iter().map(|point| point.hits).sum::<usize>(), hits);
+    assert!(points.iter().all(|point| point.final_speed > 0.0));
+    assert!(points.iter().all(|point| point.duration > Duration::ZERO));
+}
+
+#[test]
+fn points_follow_each_other_without_gaps() {
+    let (_, timeline, _) = play(ai_match(2, 5));
+    let points = timeline.points();
+
+    assert_eq!(points[0].frames.start, 0);
+    for pair in points.windows(2) {
+        assert_eq!(pair[0].frames.end, pair[1].frames.start);
+    }
+    for point in points {
+        assert!(point.duration <= DT * point.frames.len() as u32);
+    }
+}
+
+#[test]
+fn frames_of_a_point_show_it_in_the_replay() {
+    let path = temp_dir("frames_of_a_point_show_it_in_the_replay").join("frames.replay");
+    let mut state = GameState::new(ai_match(3, 5));
+    let mut recorder = ReplayRecorder::create(&path, 60, 18, &[]).unwrap();
+    let mut timeline = PointTimeline::default();
+    while !state.is_over() {
+        let events = state.update(HashMap::new(), DT);
+        recorder.record(state.replay_frame(DT)).unwrap();
+        timeline.record(&state, &events, DT);
+    }
+    recorder.finish().unwrap();
+    let replay = Replay::load(&path).unwrap();
+
+    let mut previous = (0, 0);
+    for point in timeline.points() {
+        let first = replay.frames[point.frames.start];
+        let last = replay.frames[point.frames.end - 1];
+        assert_eq!((first.player1_score, first.player2_score), previous);
+        assert_eq!((last.player1_score, last.player2_score), point.score);
+        previous = point.score;
+    }
+}
+
+#[test]
+fn new_games_start_an_empty_timeline() {
+    let (state, mut timeline, _) = play(ai_match(4, 5));
+    assert!(!timeline.is_empty());
+
+    timeline.record(&state, &[GameEvent::NewGame], DT);
+
+    assert!(timeline.is_empty());
+}
+
+#[test]
+fn view_plays_the_frames_of_the_selected_point() {
+    let (_, timeline, _) = play(ai_match(1, 5));
+    let second = timeline.points()[1].clone();
+    let mut view = TimelineView::new(timeline);
+
+    assert_eq!(view.handle_key(key(KeyCode::Down)), TimelineAction::Stay);
+    assert_eq!(view.selected(), Some(&second));
+    assert_eq!(
+        view.handle_key(key(KeyCode::Enter)),
+        TimelineAction::Play(second.frames)
+    );
+    view.handle_key(key(KeyCode::Up));
+    view.handle_key(key(KeyCode::Up));
+    assert_eq!(view.selected().unwrap().frames.start, 0);
+    assert_eq!(view.handle_key(key(KeyCode::Esc)), TimelineAction::Quit);
+}
+
+#[test]
+fn view_lists_the_details_of_every_point() {
+    let (state, timeline, _) = play(ai_match(1, 5));
+    let count = timeline.points().len();
+    let first = timeline.points()[0].clone();
+    let rows: Vec<String> = TimelineView::new(timeline)
+        .compose_frame(80, 24)
+        .chars()
+        .iter()
+        .map(|row| row.iter().collect())
+        .collect();
+
+    assert_eq!(
+        rows[0].trim_end(),
+        format!("Timeline of the match: {count} points")
+    );
+    assert!(rows[2].contains("Scorer"));
+    assert!(rows[3].contains(first.scorer.player_name()));
+    assert!(rows[3].contains(&format!("{}:{}", first.score.0, first.score.1)));
+    let (player1_score, player2_score) = state.scores();
+    assert!(rows[2 + count].contains(&format!("{player1_score}:{player2_score}")));
+    assert!(rows[23].starts_with("enter: play point"));
+}
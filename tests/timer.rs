@@ -0,0 +1,67 @@
+use cli_pong::timer::{Timer, WorldClock};
+use std::time::Duration;
+
+fn millis(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn one_shot_timer_fires_once() {
+    let mut timer = Timer::once(millis(250));
+
+    assert_eq!(timer.advance(millis(100)), 0);
+    assert_eq!(timer.advance(millis(100)), 0);
+    assert_eq!(timer.advance(millis(100)), 1);
+    assert!(timer.is_finished());
+    assert_eq!(timer.advance(millis(1000)), 0);
+}
+
+#[test]
+fn repeating_timer_keeps_the_left_over_time() {
+    let mut timer = Timer::repeating(millis(300));
+    let fired: u32 = (0..10).map(|_| timer.advance(millis(100))).sum();
+
+    assert_eq!(fired, 3);
+    assert_eq!(timer.remaining(), millis(200));
+    assert!(!timer.is_finished());
+}
+
+#[test]
+fn repeating_timer_fires_several_times_in_a_long_update() {
+    let mut timer = Timer::repeating(millis(100));
+
+    assert_eq!(timer.advance(millis(350)), 3);
+    assert_eq!(timer.remaining(), millis(50));
+}
+
+#[test]
+fn timer_runs_on_simulated_time_only() {
+    let mut first = Timer::once(millis(500));
+    let mut second = Timer::once(millis(500));
+
+    // the same simulated time in frames of different lengths
+    let fired_first: u32 = (0..5).map(|_| first.advance(millis(100))).sum();
+    let fired_second: u32 = (0..20).map(|_| second.advance(millis(25))).sum();
+
+    assert_eq!((fired_first, fired_second), (1, 1));
+}
+
+#[test]
+fn reset_starts_the_period_again() {
+    let mut timer = Timer::once(millis(200));
+    timer.advance(millis(150));
+    timer.reset();
+
+    assert_eq!(timer.elapsed(), Duration::ZERO);
+    assert_eq!(timer.advance(millis(150)), 0);
+}
+
+#[test]
+fn world_clock_counts_ticks_and_time() {
+    let mut clock = WorldClock::default();
+    clock.advance(millis(100));
+    clock.advance(millis(50));
+
+    assert_eq!(clock.tick(), 2);
+    assert_eq!(clock.elapsed(), millis(150));
+}
@@ -0,0 +1,43 @@
+use cli_pong::state::{Position2D, Velocity2D};
+use cli_pong::units::{Cells, CellsPerSecond, Extent};
+use std::time::Duration;
+
+#[test]
+fn speed_times_duration_is_a_distance() {
+    let distance = CellsPerSecond::new(12.0) * Duration::from_millis(250);
+
+    assert_eq!(distance, Cells::new(3.0));
+    assert_eq!(
+        distance / Duration::from_millis(250),
+        CellsPerSecond::new(12.0)
+    );
+}
+
+#[test]
+fn distance_over_no_time_is_no_speed() {
+    assert_eq!(Cells::new(3.0) / Duration::ZERO, CellsPerSecond::ZERO);
+}
+
+#[test]
+fn cells_round_to_signed_indices() {
+    assert_eq!(Cells::new(-0.7).round_to_cell(), -1);
+    assert_eq!(Cells::new(2.5).round_to_cell(), 3);
+    assert_eq!(Cells::new(f64::MAX).round_to_cell(), i64::MAX);
+}
+
+#[test]
+fn extents_do_not_shrink_below_zero() {
+    let extent = Extent::new(2);
+
+    assert_eq!(extent.shrunk_by(5), Extent::new(0));
+    assert_eq!(extent.to_cells(), Cells::new(2.0));
+    assert_eq!(Extent::new(usize::MAX).to_offset(), i64::MAX);
+}
+
+#[test]
+fn positions_move_with_their_velocity() {
+    let position = Position2D::new(1.0, 2.0);
+    let moved = position.moved(Velocity2D::new(10.0, -4.0), Duration::from_millis(500));
+
+    assert_eq!((moved.x(), moved.y()), (6.0, 0.0));
+}
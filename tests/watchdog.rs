@@ -0,0 +1,124 @@
+mod common;
+
+use cli_pong::frame::Frame;
+use cli_pong::state::{step, GameConfig, GameState};
+use cli_pong::theme::Theme;
+use cli_pong::watchdog::{Degradation, FrameWatchdog};
+use common::game;
+use crossterm::style::Color;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const BUDGET: Duration = Duration::from_millis(50);
+const SLOW: Duration = Duration::from_millis(80);
+const FAST: Duration = Duration::from_millis(10);
+
+/// Feeds the given render time until the watchdog degrades or gives up after 100 frames.
+fn render_until_degraded(
+    watchdog: &mut FrameWatchdog,
+    render_time: Duration,
+) -> Option<Degradation> {
+    (0..100).find_map(|_| watchdog.frame_rendered(render_time))
+}
+
+#[test]
+fn fast_frames_keep_all_features() {
+    let mut watchdog = FrameWatchdog::new(BUDGET);
+
+    assert_eq!(render_until_degraded(&mut watchdog, FAST), None);
+    assert_eq!(watchdog.level(), None);
+}
+
+#[test]
+fn a_few_slow_frames_are_tolerated() {
+    let mut watchdog = FrameWatchdog::new(BUDGET);
+
+    for frame in 0..300 {
+        let render_time = if frame % 3 == 0 { SLOW } else { FAST };
+        assert_eq!(watchdog.frame_rendered(render_time), None);
+    }
+}
+
+#[test]
+fn consistently_slow_frames_degrade_one_feature_after_the_other() {
+    let mut watchdog = FrameWatchdog::new(BUDGET);
+
+    for expected in Degradation::ORDER {
+        assert_eq!(render_until_degraded(&mut watchdog, SLOW), Some(expected));
+    }
+    assert_eq!(render_until_degraded(&mut watchdog, SLOW), None);
+    assert_eq!(watchdog.level(), Some(Degradation::PartialRedraw));
+}
+
+#[test]
+fn degraded_game_drops_effects_and_patterns() {
+    let theme = Theme::built_in()
+        .into_iter()
+        .find(|theme| theme.name == "neon")
+        .unwrap();
+    let config = GameConfig::builder()
+        .seed(Some(2))
+        .theme(theme)
+        .ball_glow(true)
+        .build();
+    let mut state = GameState::new(config);
+    let has_pattern = |state: &GameState| {
+        state
+            .render_to_grid()
+            .iter()
+            .flatten()
+            .any(|&cell| cell == '\u{00B7}')
+    };
+    let glows = |state: &GameState| {
+        let frame = state.compose_frame();
+        (0..frame.height())
+            .flat_map(|y| frame.composed_row(y))
+            .any(|cell| matches!(cell.background, Some(Color::AnsiValue(232..))))
+    };
+    assert!(has_pattern(&state) && glows(&state));
+
+    state.degrade(Degradation::Effects);
+    assert!(has_pattern(&state) && !glows(&state));
+
+    state.degrade(Degradation::Pattern);
+    assert!(!has_pattern(&state));
+    assert!(state.is_degraded(Degradation::Effects));
+    assert!(!state.is_degraded(Degradation::Colors));
+}
+
+#[test]
+fn partial_redraw_only_writes_the_changed_cells() {
+    let (state, _) = step(&game(2), HashMap::new(), Duration::from_millis(100));
+    let previous = game(2).compose_frame();
+    let frame = state.compose_frame();
+
+    let mut full = Vec::new();
+    frame.flush(&mut full).unwrap();
+    let mut changes = Vec::new();
+    frame
+        .flush_changes(&mut changes, &previous, &Default::default())
+        .unwrap();
+
+    let changes = String::from_utf8(changes).unwrap();
+    assert!(changes.len() < full.len() / 4);
+    assert!(
+        changes.contains('\u{25CF}'),
+        "the moved ball is not redrawn"
+    );
+    assert!(!changes.contains("Goals"));
+}
+
+#[test]
+fn partial_redraw_of_a_resized_frame_writes_everything() {
+    let previous = Frame::new(40, 10);
+    let frame = Frame::new(30, 10);
+
+    let mut full = Vec::new();
+    frame.flush(&mut full).unwrap();
+    let mut changes = Vec::new();
+    frame
+        .flush_changes(&mut changes, &previous, &Default::default())
+        .unwrap();
+
+    assert_eq!(changes, full);
+}